@@ -106,3 +106,7 @@ RUST_CONF_OPENSSL_NO_DEPRECATED_3_0
 #ifdef OPENSSL_NO_SEED
 RUST_CONF_OPENSSL_NO_SEED
 #endif
+
+#ifdef OPENSSL_NO_SCTP
+RUST_CONF_OPENSSL_NO_SCTP
+#endif
@@ -37,3 +37,20 @@ pub const MBSTRING_UTF8: c_int = MBSTRING_FLAG;
 pub const MBSTRING_ASC: c_int = MBSTRING_FLAG | 1;
 pub const MBSTRING_BMP: c_int = MBSTRING_FLAG | 2;
 pub const MBSTRING_UNIV: c_int = MBSTRING_FLAG | 4;
+
+pub const ASN1_STRFLGS_ESC_2253: c_ulong = 1;
+pub const ASN1_STRFLGS_ESC_CTRL: c_ulong = 2;
+pub const ASN1_STRFLGS_ESC_MSB: c_ulong = 4;
+pub const ASN1_STRFLGS_ESC_QUOTE: c_ulong = 8;
+pub const ASN1_STRFLGS_UTF8_CONVERT: c_ulong = 0x10;
+pub const ASN1_STRFLGS_IGNORE_TYPE: c_ulong = 0x20;
+pub const ASN1_STRFLGS_SHOW_TYPE: c_ulong = 0x40;
+pub const ASN1_STRFLGS_DUMP_ALL: c_ulong = 0x80;
+pub const ASN1_STRFLGS_DUMP_UNKNOWN: c_ulong = 0x100;
+pub const ASN1_STRFLGS_DUMP_DER: c_ulong = 0x200;
+pub const ASN1_STRFLGS_RFC2253: c_ulong = ASN1_STRFLGS_ESC_2253
+    | ASN1_STRFLGS_ESC_CTRL
+    | ASN1_STRFLGS_ESC_MSB
+    | ASN1_STRFLGS_UTF8_CONVERT
+    | ASN1_STRFLGS_DUMP_UNKNOWN
+    | ASN1_STRFLGS_DUMP_DER;
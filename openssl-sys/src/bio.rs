@@ -1,4 +1,5 @@
 use libc::*;
+use std::ptr;
 
 use *;
 
@@ -6,9 +7,14 @@ pub const BIO_TYPE_NONE: c_int = 0;
 
 pub const BIO_CTRL_EOF: c_int = 2;
 pub const BIO_CTRL_INFO: c_int = 3;
+pub const BIO_CTRL_PENDING: c_int = 10;
 pub const BIO_CTRL_FLUSH: c_int = 11;
 pub const BIO_CTRL_DGRAM_QUERY_MTU: c_int = 40;
 pub const BIO_C_SET_BUF_MEM_EOF_RETURN: c_int = 130;
+pub const BIO_C_SHUTDOWN_WR: c_int = 142;
+
+pub const BIO_NOCLOSE: c_int = 0x00;
+pub const BIO_CLOSE: c_int = 0x01;
 
 pub unsafe fn BIO_set_retry_read(b: *mut BIO) {
     BIO_set_flags(b, BIO_FLAGS_READ | BIO_FLAGS_SHOULD_RETRY)
@@ -32,6 +38,27 @@ pub unsafe fn BIO_get_mem_data(b: *mut BIO, pp: *mut *mut c_char) -> c_long {
     BIO_ctrl(b, BIO_CTRL_INFO, 0, pp as *mut c_void)
 }
 
+pub unsafe fn BIO_set_mem_eof_return(b: *mut BIO, v: c_int) -> c_long {
+    BIO_ctrl(
+        b,
+        BIO_C_SET_BUF_MEM_EOF_RETURN,
+        v as c_long,
+        ptr::null_mut(),
+    )
+}
+
+pub unsafe fn BIO_pending(b: *mut BIO) -> c_long {
+    BIO_ctrl(b, BIO_CTRL_PENDING, 0, ptr::null_mut())
+}
+
+pub unsafe fn BIO_should_retry(b: *mut BIO) -> c_int {
+    BIO_test_flags(b, BIO_FLAGS_SHOULD_RETRY)
+}
+
+pub unsafe fn BIO_shutdown_wr(b: *mut BIO) -> c_long {
+    BIO_ctrl(b, BIO_C_SHUTDOWN_WR, 0, ptr::null_mut())
+}
+
 extern "C" {
     #[deprecated(note = "use BIO_meth_set_write__fixed_rust instead")]
     #[cfg(any(ossl110, libressl273))]
@@ -109,9 +109,11 @@ cfg_if! {
     if #[cfg(ossl110)] {
         pub const CRYPTO_EX_INDEX_SSL: c_int = 0;
         pub const CRYPTO_EX_INDEX_SSL_CTX: c_int = 1;
+        pub const CRYPTO_EX_INDEX_X509_STORE_CTX: c_int = 5;
     } else if #[cfg(libressl)] {
         pub const CRYPTO_EX_INDEX_SSL: c_int = 1;
         pub const CRYPTO_EX_INDEX_SSL_CTX: c_int = 2;
+        pub const CRYPTO_EX_INDEX_X509_STORE_CTX: c_int = 5;
     }
 }
 
@@ -0,0 +1,43 @@
+use libc::*;
+
+#[cfg(ossl110)]
+pub type sct_version_t = c_int;
+#[cfg(ossl110)]
+pub const SCT_VERSION_NOT_SET: sct_version_t = -1;
+#[cfg(ossl110)]
+pub const SCT_VERSION_V1: sct_version_t = 0;
+
+#[cfg(ossl110)]
+pub type ct_log_entry_type_t = c_int;
+#[cfg(ossl110)]
+pub const CT_LOG_ENTRY_TYPE_NOT_SET: ct_log_entry_type_t = -1;
+#[cfg(ossl110)]
+pub const CT_LOG_ENTRY_TYPE_X509: ct_log_entry_type_t = 0;
+#[cfg(ossl110)]
+pub const CT_LOG_ENTRY_TYPE_PRECERT: ct_log_entry_type_t = 1;
+
+#[cfg(ossl110)]
+pub type sct_validation_status_t = c_int;
+#[cfg(ossl110)]
+pub const SCT_VALIDATION_STATUS_NOT_SET: sct_validation_status_t = 0;
+#[cfg(ossl110)]
+pub const SCT_VALIDATION_STATUS_UNKNOWN_LOG: sct_validation_status_t = 1;
+#[cfg(ossl110)]
+pub const SCT_VALIDATION_STATUS_VALID: sct_validation_status_t = 2;
+#[cfg(ossl110)]
+pub const SCT_VALIDATION_STATUS_INVALID: sct_validation_status_t = 3;
+#[cfg(ossl110)]
+pub const SCT_VALIDATION_STATUS_UNVERIFIED: sct_validation_status_t = 4;
+#[cfg(ossl110)]
+pub const SCT_VALIDATION_STATUS_UNKNOWN_VERSION: sct_validation_status_t = 5;
+
+#[cfg(ossl110)]
+pub type sct_source_t = c_int;
+#[cfg(ossl110)]
+pub const SCT_SOURCE_UNKNOWN: sct_source_t = 0;
+#[cfg(ossl110)]
+pub const SCT_SOURCE_TLS_EXTENSION: sct_source_t = 1;
+#[cfg(ossl110)]
+pub const SCT_SOURCE_X509V3_EXTENSION: sct_source_t = 2;
+#[cfg(ossl110)]
+pub const SCT_SOURCE_OCSP_STAPLED_RESPONSE: sct_source_t = 3;
@@ -6,6 +6,8 @@ pub const ERR_TXT_STRING: c_int = 0x02;
 pub const ERR_LIB_SYS: c_int = 2;
 pub const ERR_LIB_PEM: c_int = 9;
 pub const ERR_LIB_ASN1: c_int = 13;
+pub const ERR_LIB_SSL: c_int = 20;
+pub const ERR_LIB_X509V3: c_int = 34;
 
 cfg_if! {
     if #[cfg(ossl300)] {
@@ -13,6 +13,7 @@ extern "C" {
 }
 
 stack!(stack_st_ASN1_OBJECT);
+stack!(stack_st_ASN1_INTEGER);
 
 extern "C" {
     pub fn ASN1_STRING_type_new(ty: c_int) -> *mut ASN1_STRING;
@@ -41,6 +42,12 @@ extern "C" {
     pub fn ASN1_TIME_free(tm: *mut ASN1_TIME);
     pub fn ASN1_TIME_print(b: *mut BIO, tm: *const ASN1_TIME) -> c_int;
     pub fn ASN1_TIME_set(from: *mut ASN1_TIME, to: time_t) -> *mut ASN1_TIME;
+    pub fn ASN1_TIME_adj(
+        s: *mut ASN1_TIME,
+        t: time_t,
+        offset_day: c_int,
+        offset_sec: c_long,
+    ) -> *mut ASN1_TIME;
 
     pub fn ASN1_INTEGER_free(x: *mut ASN1_INTEGER);
     pub fn ASN1_INTEGER_get(dest: *const ASN1_INTEGER) -> c_long;
@@ -48,13 +55,62 @@ extern "C" {
     pub fn BN_to_ASN1_INTEGER(bn: *const BIGNUM, ai: *mut ASN1_INTEGER) -> *mut ASN1_INTEGER;
     pub fn ASN1_INTEGER_to_BN(ai: *const ASN1_INTEGER, bn: *mut BIGNUM) -> *mut BIGNUM;
 
+    pub fn ASN1_ENUMERATED_new() -> *mut ASN1_ENUMERATED;
+    pub fn ASN1_ENUMERATED_free(a: *mut ASN1_ENUMERATED);
+    pub fn ASN1_ENUMERATED_get(a: *const ASN1_ENUMERATED) -> c_long;
+    pub fn ASN1_ENUMERATED_set(a: *mut ASN1_ENUMERATED, v: c_long) -> c_int;
+
     pub fn ASN1_TIME_set_string(s: *mut ASN1_TIME, str: *const c_char) -> c_int;
     #[cfg(ossl111)]
     pub fn ASN1_TIME_set_string_X509(s: *mut ASN1_TIME, str: *const c_char) -> c_int;
+
+    pub fn d2i_ASN1_TIME(
+        a: *mut *mut ASN1_TIME,
+        pp: *mut *const c_uchar,
+        length: c_long,
+    ) -> *mut ASN1_TIME;
+    pub fn d2i_ASN1_INTEGER(
+        a: *mut *mut ASN1_INTEGER,
+        pp: *mut *const c_uchar,
+        length: c_long,
+    ) -> *mut ASN1_INTEGER;
+    pub fn d2i_ASN1_BIT_STRING(
+        a: *mut *mut ASN1_BIT_STRING,
+        pp: *mut *const c_uchar,
+        length: c_long,
+    ) -> *mut ASN1_BIT_STRING;
+    pub fn d2i_ASN1_OBJECT(
+        a: *mut *mut ASN1_OBJECT,
+        pp: *mut *const c_uchar,
+        length: c_long,
+    ) -> *mut ASN1_OBJECT;
+
+    pub fn ASN1_TYPE_free(x: *mut ASN1_TYPE);
+    pub fn d2i_ASN1_TYPE(
+        a: *mut *mut ASN1_TYPE,
+        pp: *mut *const c_uchar,
+        length: c_long,
+    ) -> *mut ASN1_TYPE;
 }
 
 const_ptr_api! {
     extern "C" {
         pub fn ASN1_STRING_to_UTF8(out: *mut *mut c_uchar, s: #[const_ptr_if(any(ossl110, libressl280))] ASN1_STRING) -> c_int;
+        pub fn ASN1_TYPE_get(a: #[const_ptr_if(any(ossl110, libressl280))] ASN1_TYPE) -> c_int;
+        pub fn ASN1_TYPE_get_octetstring(
+            a: #[const_ptr_if(any(ossl110, libressl280))] ASN1_TYPE,
+            data: *mut c_uchar,
+            max_len: c_int,
+        ) -> c_int;
+        pub fn ASN1_STRING_type(x: #[const_ptr_if(any(ossl110, libressl280))] ASN1_STRING) -> c_int;
+        pub fn ASN1_TIME_to_generalizedtime(
+            t: #[const_ptr_if(any(ossl110, libressl280))] ASN1_TIME,
+            out: *mut *mut ASN1_GENERALIZEDTIME,
+        ) -> *mut ASN1_GENERALIZEDTIME;
+        pub fn i2d_ASN1_TYPE(a: #[const_ptr_if(ossl300)] ASN1_TYPE, pp: *mut *mut c_uchar) -> c_int;
+        pub fn i2d_ASN1_TIME(a: #[const_ptr_if(ossl300)] ASN1_TIME, pp: *mut *mut c_uchar) -> c_int;
+        pub fn i2d_ASN1_INTEGER(a: #[const_ptr_if(ossl300)] ASN1_INTEGER, pp: *mut *mut c_uchar) -> c_int;
+        pub fn i2d_ASN1_BIT_STRING(a: #[const_ptr_if(ossl300)] ASN1_BIT_STRING, pp: *mut *mut c_uchar) -> c_int;
+        pub fn i2d_ASN1_OBJECT(a: #[const_ptr_if(any(ossl110, libressl280))] ASN1_OBJECT, pp: *mut *mut c_uchar) -> c_int;
     }
 }
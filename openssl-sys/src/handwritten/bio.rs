@@ -4,6 +4,7 @@ use *;
 extern "C" {
     pub fn BIO_set_flags(b: *mut BIO, flags: c_int);
     pub fn BIO_clear_flags(b: *mut BIO, flags: c_int);
+    pub fn BIO_test_flags(b: *const BIO, flags: c_int) -> c_int;
 }
 
 pub type bio_info_cb =
@@ -60,6 +61,35 @@ const_ptr_api! {
 extern "C" {
     pub fn BIO_new_socket(sock: c_int, close_flag: c_int) -> *mut BIO;
 
+    #[cfg(all(ossl101, not(osslconf = "OPENSSL_NO_SCTP")))]
+    pub fn BIO_new_dgram_sctp(fd: c_int, close_flag: c_int) -> *mut BIO;
+    #[cfg(all(ossl101, not(osslconf = "OPENSSL_NO_SCTP")))]
+    pub fn BIO_dgram_is_sctp(bio: *mut BIO) -> c_int;
+    #[cfg(all(ossl101, not(osslconf = "OPENSSL_NO_SCTP")))]
+    pub fn BIO_dgram_sctp_wait_for_dry(b: *mut BIO) -> c_int;
+    #[cfg(all(ossl101, not(osslconf = "OPENSSL_NO_SCTP")))]
+    pub fn BIO_dgram_sctp_msg_waiting(b: *mut BIO) -> c_int;
+
+    pub fn BIO_new_bio_pair(
+        bio1: *mut *mut BIO,
+        writebuf1: size_t,
+        bio2: *mut *mut BIO,
+        writebuf2: size_t,
+    ) -> c_int;
+    pub fn BIO_ctrl_pending(b: *mut BIO) -> size_t;
+    pub fn BIO_ctrl_get_write_guarantee(b: *mut BIO) -> size_t;
+
+    #[cfg(ossl110)]
+    pub fn BIO_ADDR_new() -> *mut BIO_ADDR;
+    #[cfg(ossl110)]
+    pub fn BIO_ADDR_free(ap: *mut BIO_ADDR);
+    #[cfg(ossl110)]
+    pub fn BIO_ADDR_family(ap: *const BIO_ADDR) -> c_int;
+    #[cfg(ossl110)]
+    pub fn BIO_ADDR_rawaddress(ap: *const BIO_ADDR, p: *mut c_void, l: *mut size_t) -> c_int;
+    #[cfg(ossl110)]
+    pub fn BIO_ADDR_rawport(ap: *const BIO_ADDR) -> c_ushort;
+
     #[cfg(any(ossl110, libressl273))]
     pub fn BIO_meth_new(type_: c_int, name: *const c_char) -> *mut BIO_METHOD;
     #[cfg(any(ossl110, libressl273))]
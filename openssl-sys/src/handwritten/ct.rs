@@ -0,0 +1,61 @@
+use libc::*;
+use *;
+
+pub enum SCT {}
+pub enum CTLOG {}
+pub enum CTLOG_STORE {}
+pub enum CT_POLICY_EVAL_CTX {}
+
+stack!(stack_st_SCT);
+
+#[cfg(ossl110)]
+extern "C" {
+    pub fn SCT_free(sct: *mut SCT);
+
+    pub fn SCT_get_version(sct: *const SCT) -> sct_version_t;
+    pub fn SCT_get_log_entry_type(sct: *const SCT) -> ct_log_entry_type_t;
+    pub fn SCT_get0_log_id(sct: *const SCT, log_id: *mut *mut c_uchar) -> size_t;
+    pub fn SCT_get_timestamp(sct: *const SCT) -> u64;
+    pub fn SCT_get_signature_nid(sct: *const SCT) -> c_int;
+    pub fn SCT_get0_extensions(sct: *const SCT, ext: *mut *mut c_uchar) -> size_t;
+    pub fn SCT_get0_signature(sct: *const SCT, sig: *mut *mut c_uchar) -> size_t;
+    pub fn SCT_get_validation_status(sct: *const SCT) -> sct_validation_status_t;
+    pub fn SCT_set_source(sct: *mut SCT, source: sct_source_t) -> c_int;
+    pub fn SCT_validate(sct: *mut SCT, ctx: *const CT_POLICY_EVAL_CTX) -> c_int;
+
+    pub fn o2i_SCT_LIST(
+        a: *mut *mut stack_st_SCT,
+        pp: *mut *const c_uchar,
+        len: size_t,
+    ) -> *mut stack_st_SCT;
+    pub fn d2i_SCT_LIST(
+        a: *mut *mut stack_st_SCT,
+        pp: *mut *const c_uchar,
+        len: c_long,
+    ) -> *mut stack_st_SCT;
+
+    pub fn CTLOG_free(log: *mut CTLOG);
+    pub fn CTLOG_get0_name(log: *const CTLOG) -> *const c_char;
+    pub fn CTLOG_get0_log_id(log: *const CTLOG, log_id: *mut *const u8, log_id_len: *mut size_t);
+    pub fn CTLOG_get0_public_key(log: *const CTLOG) -> *mut EVP_PKEY;
+
+    pub fn CTLOG_STORE_new() -> *mut CTLOG_STORE;
+    pub fn CTLOG_STORE_free(store: *mut CTLOG_STORE);
+    pub fn CTLOG_STORE_load_file(store: *mut CTLOG_STORE, file: *const c_char) -> c_int;
+    pub fn CTLOG_STORE_load_default_file(store: *mut CTLOG_STORE) -> c_int;
+    pub fn CTLOG_STORE_get0_log_by_id(
+        store: *const CTLOG_STORE,
+        log_id: *const u8,
+        log_id_len: size_t,
+    ) -> *const CTLOG;
+
+    pub fn CT_POLICY_EVAL_CTX_new() -> *mut CT_POLICY_EVAL_CTX;
+    pub fn CT_POLICY_EVAL_CTX_free(ctx: *mut CT_POLICY_EVAL_CTX);
+    pub fn CT_POLICY_EVAL_CTX_set1_cert(ctx: *mut CT_POLICY_EVAL_CTX, cert: *mut X509) -> c_int;
+    pub fn CT_POLICY_EVAL_CTX_set1_issuer(ctx: *mut CT_POLICY_EVAL_CTX, issuer: *mut X509)
+        -> c_int;
+    pub fn CT_POLICY_EVAL_CTX_set_shared_CTLOG_STORE(
+        ctx: *mut CT_POLICY_EVAL_CTX,
+        log_store: *mut CTLOG_STORE,
+    );
+}
@@ -5,6 +5,7 @@ pub use self::bn::*;
 pub use self::cms::*;
 pub use self::conf::*;
 pub use self::crypto::*;
+pub use self::ct::*;
 pub use self::dh::*;
 pub use self::dsa::*;
 pub use self::ec::*;
@@ -38,6 +39,7 @@ mod bn;
 mod cms;
 mod conf;
 mod crypto;
+mod ct;
 mod dh;
 mod dsa;
 mod ec;
@@ -9,6 +9,8 @@ pub enum OCSP_REQUEST {}
 
 pub enum OCSP_BASICRESP {}
 
+pub enum OCSP_SINGLERESP {}
+
 const_ptr_api! {
     extern "C" {
         pub fn OCSP_cert_to_id(
@@ -31,6 +33,65 @@ extern "C" {
         thisupd: *mut *mut ASN1_GENERALIZEDTIME,
         nextupd: *mut *mut ASN1_GENERALIZEDTIME,
     ) -> c_int;
+    pub fn OCSP_resp_count(bs: *mut OCSP_BASICRESP) -> c_int;
+    pub fn OCSP_resp_get0(bs: *mut OCSP_BASICRESP, idx: c_int) -> *mut OCSP_SINGLERESP;
+    #[cfg(ossl110)]
+    pub fn OCSP_resp_get0_produced_at(bs: *const OCSP_BASICRESP) -> *const ASN1_GENERALIZEDTIME;
+    pub fn OCSP_single_get0_status(
+        single: *mut OCSP_SINGLERESP,
+        reason: *mut c_int,
+        revtime: *mut *mut ASN1_GENERALIZEDTIME,
+        thisupd: *mut *mut ASN1_GENERALIZEDTIME,
+        nextupd: *mut *mut ASN1_GENERALIZEDTIME,
+    ) -> c_int;
+    #[cfg(ossl110)]
+    pub fn OCSP_SINGLERESP_get0_id(x: *const OCSP_SINGLERESP) -> *const OCSP_CERTID;
+    pub fn OCSP_SINGLERESP_free(r: *mut OCSP_SINGLERESP);
+    pub fn OCSP_SINGLERESP_add_ext(
+        x: *mut OCSP_SINGLERESP,
+        ex: *mut X509_EXTENSION,
+        loc: c_int,
+    ) -> c_int;
+    pub fn OCSP_SINGLERESP_get1_ext_d2i(
+        x: *mut OCSP_SINGLERESP,
+        nid: c_int,
+        crit: *mut c_int,
+        idx: *mut c_int,
+    ) -> *mut c_void;
+    pub fn OCSP_id_get0_info(
+        piNameHash: *mut *mut ASN1_OCTET_STRING,
+        pmd: *mut *mut ASN1_OBJECT,
+        pikeyHash: *mut *mut ASN1_OCTET_STRING,
+        pserial: *mut *mut ASN1_INTEGER,
+        cid: *mut OCSP_CERTID,
+    ) -> c_int;
+
+    pub fn OCSP_basic_add1_status(
+        rsp: *mut OCSP_BASICRESP,
+        cid: *mut OCSP_CERTID,
+        status: c_int,
+        reason: c_int,
+        revtime: *mut ASN1_TIME,
+        thisupd: *mut ASN1_TIME,
+        nextupd: *mut ASN1_TIME,
+    ) -> *mut OCSP_SINGLERESP;
+    pub fn OCSP_basic_sign(
+        brsp: *mut OCSP_BASICRESP,
+        signer: *mut X509,
+        key: *mut EVP_PKEY,
+        dgst: *const EVP_MD,
+        certs: *mut stack_st_X509,
+        flags: c_ulong,
+    ) -> c_int;
+    pub fn OCSP_copy_nonce(resp: *mut OCSP_BASICRESP, req: *mut OCSP_REQUEST) -> c_int;
+
+    pub fn OCSP_request_onereq_count(req: *mut OCSP_REQUEST) -> c_int;
+    pub fn OCSP_request_onereq_get0(req: *mut OCSP_REQUEST, i: c_int) -> *mut OCSP_ONEREQ;
+    pub fn OCSP_onereq_get0_id(one: *mut OCSP_ONEREQ) -> *mut OCSP_CERTID;
+
+    pub fn OCSP_request_add1_nonce(req: *mut OCSP_REQUEST, val: *mut c_uchar, len: c_int) -> c_int;
+    pub fn OCSP_check_nonce(req: *mut OCSP_REQUEST, bs: *mut OCSP_BASICRESP) -> c_int;
+
     pub fn OCSP_check_validity(
         thisupd: *mut ASN1_GENERALIZEDTIME,
         nextupd: *mut ASN1_GENERALIZEDTIME,
@@ -3,6 +3,9 @@ use libc::*;
 use *;
 
 pub enum PKCS12 {}
+pub enum PKCS12_SAFEBAG {}
+
+stack!(stack_st_PKCS12_SAFEBAG);
 
 extern "C" {
     pub fn PKCS12_free(p12: *mut PKCS12);
@@ -51,3 +54,87 @@ const_ptr_api! {
         pub fn i2d_PKCS12_bio(b: *mut BIO, a: #[const_ptr_if(ossl300)] PKCS12) -> c_int;
     }
 }
+
+extern "C" {
+    pub fn PKCS12_SAFEBAG_free(bag: *mut PKCS12_SAFEBAG);
+
+    pub fn PKCS12_verify_mac(p12: *mut PKCS12, pass: *const c_char, passlen: c_int) -> c_int;
+    pub fn PKCS12_unpack_p7data(p7: *mut PKCS7) -> *mut stack_st_PKCS12_SAFEBAG;
+    pub fn PKCS12_unpack_p7encdata(
+        p7: *mut PKCS7,
+        pass: *const c_char,
+        passlen: c_int,
+    ) -> *mut stack_st_PKCS12_SAFEBAG;
+    pub fn PKCS12_get_friendlyname(bag: *mut PKCS12_SAFEBAG) -> *mut c_char;
+}
+const_ptr_api! {
+    extern "C" {
+        pub fn PKCS12_unpack_authsafes(p12: #[const_ptr_if(ossl110)] PKCS12) -> *mut stack_st_PKCS7;
+        pub fn PKCS12_decrypt_skey(
+            bag: #[const_ptr_if(ossl110)] PKCS12_SAFEBAG,
+            pass: *const c_char,
+            passlen: c_int,
+        ) -> *mut PKCS8_PRIV_KEY_INFO;
+    }
+}
+extern "C" {
+    #[cfg(ossl110)]
+    pub fn PKCS12_mac_present(p12: *const PKCS12) -> c_int;
+    #[cfg(ossl110)]
+    pub fn PKCS12_SAFEBAG_get_nid(bag: *const PKCS12_SAFEBAG) -> c_int;
+    #[cfg(ossl110)]
+    pub fn PKCS12_SAFEBAG_get_bag_nid(bag: *const PKCS12_SAFEBAG) -> c_int;
+    #[cfg(ossl110)]
+    pub fn PKCS12_SAFEBAG_get0_attr(
+        bag: *const PKCS12_SAFEBAG,
+        attr_nid: c_int,
+    ) -> *const ASN1_TYPE;
+    #[cfg(ossl110)]
+    pub fn PKCS12_SAFEBAG_get0_p8inf(bag: *const PKCS12_SAFEBAG) -> *const PKCS8_PRIV_KEY_INFO;
+    #[cfg(ossl110)]
+    pub fn PKCS12_SAFEBAG_get0_safes(bag: *const PKCS12_SAFEBAG) -> *const stack_st_PKCS12_SAFEBAG;
+    #[cfg(ossl110)]
+    pub fn PKCS12_SAFEBAG_get1_cert(bag: *const PKCS12_SAFEBAG) -> *mut X509;
+}
+
+extern "C" {
+    pub fn PKCS12_add_cert(
+        pbags: *mut *mut stack_st_PKCS12_SAFEBAG,
+        cert: *mut X509,
+    ) -> *mut PKCS12_SAFEBAG;
+    pub fn PKCS12_add_key(
+        pbags: *mut *mut stack_st_PKCS12_SAFEBAG,
+        key: *mut EVP_PKEY,
+        key_usage: c_int,
+        iter: c_int,
+        key_nid: c_int,
+        pass: *const c_char,
+    ) -> *mut PKCS12_SAFEBAG;
+    pub fn PKCS12_add_localkeyid(
+        bag: *mut PKCS12_SAFEBAG,
+        name: *mut c_uchar,
+        namelen: c_int,
+    ) -> c_int;
+    pub fn PKCS12_add_friendlyname_asc(
+        bag: *mut PKCS12_SAFEBAG,
+        name: *const c_char,
+        namelen: c_int,
+    ) -> c_int;
+    pub fn PKCS12_add_safe(
+        psafes: *mut *mut stack_st_PKCS7,
+        bags: *mut stack_st_PKCS12_SAFEBAG,
+        safe_nid: c_int,
+        iter: c_int,
+        pass: *const c_char,
+    ) -> c_int;
+    pub fn PKCS12_add_safes(safes: *mut stack_st_PKCS7, p7_nid: c_int) -> *mut PKCS12;
+
+    #[cfg(ossl110)]
+    pub fn PKCS12_get0_mac(
+        pmac: *mut *const ASN1_OCTET_STRING,
+        pmacalg: *mut *const X509_ALGOR,
+        psalt: *mut *const ASN1_OCTET_STRING,
+        piter: *mut *const ASN1_INTEGER,
+        p12: *const PKCS12,
+    );
+}
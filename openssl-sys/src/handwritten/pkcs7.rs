@@ -8,6 +8,8 @@ pub enum PKCS7_DIGEST {}
 pub enum PKCS7_ENCRYPT {}
 pub enum PKCS7 {}
 
+stack!(stack_st_PKCS7);
+
 extern "C" {
     pub fn d2i_PKCS7(a: *mut *mut PKCS7, pp: *mut *const c_uchar, length: c_long) -> *mut PKCS7;
 }
@@ -3,6 +3,8 @@ use *;
 
 pub enum SSL_METHOD {}
 pub enum SSL_CIPHER {}
+#[cfg(ossl102)]
+pub enum SSL_CONF_CTX {}
 cfg_if! {
     if #[cfg(any(ossl110, libressl280))] {
         pub enum SSL_SESSION {}
@@ -200,12 +202,18 @@ cfg_if! {
             pub fn SSL_CTX_get_options(ctx: *const SSL_CTX) -> u64;
             pub fn SSL_CTX_set_options(ctx: *mut SSL_CTX, op: u64) -> u64;
             pub fn SSL_CTX_clear_options(ctx: *mut SSL_CTX, op: u64) -> u64;
+            pub fn SSL_get_options(s: *const SSL) -> u64;
+            pub fn SSL_set_options(s: *mut SSL, op: u64) -> u64;
+            pub fn SSL_clear_options(s: *mut SSL, op: u64) -> u64;
         }
     } else if #[cfg(ossl110)] {
         extern "C" {
             pub fn SSL_CTX_get_options(ctx: *const SSL_CTX) -> c_ulong;
             pub fn SSL_CTX_set_options(ctx: *mut SSL_CTX, op: c_ulong) -> c_ulong;
             pub fn SSL_CTX_clear_options(ctx: *mut SSL_CTX, op: c_ulong) -> c_ulong;
+            pub fn SSL_get_options(s: *const SSL) -> c_ulong;
+            pub fn SSL_set_options(s: *mut SSL, op: c_ulong) -> c_ulong;
+            pub fn SSL_clear_options(s: *mut SSL, op: c_ulong) -> c_ulong;
         }
     }
 }
@@ -405,6 +413,94 @@ extern "C" {
     pub fn SSL_extension_supported(ext_type: c_uint) -> c_int;
 }
 
+#[cfg(ossl110)]
+extern "C" {
+    pub fn SSL_CTX_enable_ct(ctx: *mut SSL_CTX, validation_mode: c_int) -> c_int;
+    pub fn SSL_enable_ct(s: *mut SSL, validation_mode: c_int) -> c_int;
+    pub fn SSL_CTX_ct_is_enabled(ctx: *const SSL_CTX) -> c_int;
+    pub fn SSL_ct_is_enabled(s: *const SSL) -> c_int;
+    pub fn SSL_get0_peer_scts(s: *mut SSL) -> *const stack_st_SCT;
+    pub fn SSL_CTX_set_default_ctlog_list_file(ctx: *mut SSL_CTX) -> c_int;
+    pub fn SSL_CTX_set_ctlog_list_file(ctx: *mut SSL_CTX, path: *const c_char) -> c_int;
+}
+
+#[cfg(ossl110)]
+extern "C" {
+    pub fn SSL_CTX_dane_enable(ctx: *mut SSL_CTX) -> c_int;
+    pub fn SSL_CTX_dane_mtype_set(
+        ctx: *mut SSL_CTX,
+        md: *const EVP_MD,
+        mtype: u8,
+        ord: u8,
+    ) -> c_int;
+    pub fn SSL_dane_enable(s: *mut SSL, basedomain: *const c_char) -> c_int;
+    pub fn SSL_dane_tlsa_add(
+        s: *mut SSL,
+        usage: u8,
+        selector: u8,
+        mtype: u8,
+        data: *const c_uchar,
+        dlen: size_t,
+    ) -> c_int;
+    pub fn SSL_get0_dane_authority(
+        s: *mut SSL,
+        mcert: *mut *mut X509,
+        mspki: *mut *mut EVP_PKEY,
+    ) -> c_int;
+    pub fn SSL_get0_dane_tlsa(
+        s: *mut SSL,
+        usage: *mut u8,
+        selector: *mut u8,
+        mtype: *mut u8,
+        data: *mut *const c_uchar,
+        dlen: *mut size_t,
+    ) -> c_int;
+    pub fn SSL_dane_set_flags(ssl: *mut SSL, flags: c_ulong) -> c_ulong;
+    pub fn SSL_dane_clear_flags(ssl: *mut SSL, flags: c_ulong) -> c_ulong;
+}
+
+extern "C" {
+    pub fn SSL_CTX_set_msg_callback(
+        ctx: *mut SSL_CTX,
+        cb: Option<
+            unsafe extern "C" fn(
+                write_p: c_int,
+                version: c_int,
+                content_type: c_int,
+                buf: *const c_void,
+                len: size_t,
+                ssl: *mut SSL,
+                arg: *mut c_void,
+            ),
+        >,
+    );
+    pub fn SSL_set_msg_callback(
+        ssl: *mut SSL,
+        cb: Option<
+            unsafe extern "C" fn(
+                write_p: c_int,
+                version: c_int,
+                content_type: c_int,
+                buf: *const c_void,
+                len: size_t,
+                ssl: *mut SSL,
+                arg: *mut c_void,
+            ),
+        >,
+    );
+}
+
+extern "C" {
+    pub fn SSL_CTX_set_info_callback(
+        ctx: *mut SSL_CTX,
+        cb: Option<unsafe extern "C" fn(ssl: *const SSL, type_: c_int, val: c_int)>,
+    );
+    pub fn SSL_set_info_callback(
+        ssl: *mut SSL,
+        cb: Option<unsafe extern "C" fn(ssl: *const SSL, type_: c_int, val: c_int)>,
+    );
+}
+
 #[cfg(ossl111)]
 pub type SSL_CTX_keylog_cb_func =
     Option<unsafe extern "C" fn(ssl: *const SSL, line: *const c_char)>;
@@ -421,18 +517,66 @@ extern "C" {
     pub fn SSL_set_max_early_data(ctx: *mut SSL, max_early_data: u32) -> c_int;
     #[cfg(any(ossl111, libressl340))]
     pub fn SSL_get_max_early_data(ctx: *const SSL) -> u32;
+    #[cfg(ossl111)]
+    pub fn SSL_CTX_set_recv_max_early_data(ctx: *mut SSL_CTX, recv_max_early_data: u32) -> c_int;
+    #[cfg(ossl111)]
+    pub fn SSL_CTX_get_recv_max_early_data(ctx: *const SSL_CTX) -> u32;
+    #[cfg(ossl111)]
+    pub fn SSL_set_recv_max_early_data(ctx: *mut SSL, recv_max_early_data: u32) -> c_int;
+    #[cfg(ossl111)]
+    pub fn SSL_get_recv_max_early_data(ctx: *const SSL) -> u32;
+
+    #[cfg(ossl111)]
+    pub fn SSL_CTX_set_num_tickets(ctx: *mut SSL_CTX, num_tickets: size_t) -> c_int;
+    #[cfg(ossl111)]
+    pub fn SSL_CTX_get_num_tickets(ctx: *const SSL_CTX) -> size_t;
+    #[cfg(ossl111)]
+    pub fn SSL_set_num_tickets(s: *mut SSL, num_tickets: size_t) -> c_int;
+    #[cfg(ossl111)]
+    pub fn SSL_get_num_tickets(s: *const SSL) -> size_t;
+
+    #[cfg(ossl110)]
+    pub fn SSL_CTX_set_security_level(ctx: *mut SSL_CTX, level: c_int);
+    #[cfg(ossl110)]
+    pub fn SSL_CTX_get_security_level(ctx: *const SSL_CTX) -> c_int;
+    #[cfg(ossl110)]
+    pub fn SSL_set_security_level(s: *mut SSL, level: c_int);
+    #[cfg(ossl110)]
+    pub fn SSL_get_security_level(s: *const SSL) -> c_int;
+
+    #[cfg(ossl102)]
+    pub fn SSL_get_shared_sigalgs(
+        s: *mut SSL,
+        idx: c_int,
+        psign: *mut c_int,
+        phash: *mut c_int,
+        psignhash: *mut c_int,
+        rsig: *mut c_uchar,
+        rhash: *mut c_uchar,
+    ) -> c_int;
+    #[cfg(ossl111)]
+    pub fn SSL_get_peer_signature_type_nid(s: *const SSL, pnid: *mut c_int) -> c_int;
 
     pub fn SSL_get_finished(s: *const SSL, buf: *mut c_void, count: size_t) -> size_t;
     pub fn SSL_get_peer_finished(s: *const SSL, buf: *mut c_void, count: size_t) -> size_t;
 
     pub fn SSL_CTX_get_verify_mode(ctx: *const SSL_CTX) -> c_int;
     pub fn SSL_get_verify_mode(s: *const SSL) -> c_int;
+    pub fn SSL_CTX_get_verify_depth(ctx: *const SSL_CTX) -> c_int;
+    pub fn SSL_get_verify_depth(s: *const SSL) -> c_int;
+
+    pub fn SSL_CTX_set_timeout(ctx: *mut SSL_CTX, t: c_long) -> c_long;
+    pub fn SSL_CTX_get_timeout(ctx: *const SSL_CTX) -> c_long;
 }
 
 const_ptr_api! {
     extern "C" {
         #[cfg(ossl110)]
         pub fn SSL_is_init_finished(s: #[const_ptr_if(ossl111)] SSL) -> c_int;
+        #[cfg(ossl110)]
+        pub fn SSL_in_init(s: #[const_ptr_if(ossl111)] SSL) -> c_int;
+        #[cfg(ossl110)]
+        pub fn SSL_in_before(s: #[const_ptr_if(ossl111)] SSL) -> c_int;
     }
 }
 
@@ -469,6 +613,9 @@ extern "C" {
 
     pub fn SSL_get_current_cipher(ssl: *const SSL) -> *const SSL_CIPHER;
     pub fn SSL_CIPHER_get_bits(cipher: *const SSL_CIPHER, alg_bits: *mut c_int) -> c_int;
+    pub fn SSL_get_ciphers(ssl: *const SSL) -> *mut stack_st_SSL_CIPHER;
+    #[cfg(ossl110)]
+    pub fn SSL_CTX_get_ciphers(ctx: *const SSL_CTX) -> *mut stack_st_SSL_CIPHER;
 }
 const_ptr_api! {
     extern "C" {
@@ -500,6 +647,8 @@ extern "C" {
     );
     pub fn SSL_CTX_use_PrivateKey(ctx: *mut SSL_CTX, key: *mut EVP_PKEY) -> c_int;
     pub fn SSL_CTX_use_certificate(ctx: *mut SSL_CTX, cert: *mut X509) -> c_int;
+    pub fn SSL_use_PrivateKey(ssl: *mut SSL, pkey: *mut EVP_PKEY) -> c_int;
+    pub fn SSL_use_certificate(ssl: *mut SSL, x: *mut X509) -> c_int;
 
     pub fn SSL_CTX_use_PrivateKey_file(
         ctx: *mut SSL_CTX,
@@ -524,8 +673,15 @@ extern "C" {
 
     pub fn SSL_SESSION_get_time(s: *const SSL_SESSION) -> c_long;
     pub fn SSL_SESSION_get_timeout(s: *const SSL_SESSION) -> c_long;
+    pub fn SSL_SESSION_set_timeout(s: *mut SSL_SESSION, t: c_long) -> c_long;
     #[cfg(any(ossl110, libressl270))]
     pub fn SSL_SESSION_get_protocol_version(s: *const SSL_SESSION) -> c_int;
+    #[cfg(any(ossl110, libressl270))]
+    pub fn SSL_SESSION_get0_cipher(s: *const SSL_SESSION) -> *const SSL_CIPHER;
+    #[cfg(ossl111)]
+    pub fn SSL_SESSION_has_ticket(s: *const SSL_SESSION) -> c_int;
+    #[cfg(ossl111)]
+    pub fn SSL_SESSION_get_ticket_lifetime_hint(s: *const SSL_SESSION) -> c_ulong;
 
     #[cfg(any(ossl111, libressl340))]
     pub fn SSL_SESSION_set_max_early_data(ctx: *mut SSL_SESSION, max_early_data: u32) -> c_int;
@@ -533,6 +689,8 @@ extern "C" {
     pub fn SSL_SESSION_get_max_early_data(ctx: *const SSL_SESSION) -> u32;
 
     pub fn SSL_SESSION_get_id(s: *const SSL_SESSION, len: *mut c_uint) -> *const c_uchar;
+    #[cfg(ossl110)]
+    pub fn SSL_SESSION_get0_id_context(s: *const SSL_SESSION, len: *mut c_uint) -> *const c_uchar;
     #[cfg(any(ossl110, libressl273))]
     pub fn SSL_SESSION_up_ref(ses: *mut SSL_SESSION) -> c_int;
     pub fn SSL_SESSION_free(s: *mut SSL_SESSION);
@@ -558,6 +716,8 @@ extern "C" {
     pub fn SSL_get1_peer_certificate(ssl: *const SSL) -> *mut X509;
 
     pub fn SSL_get_peer_cert_chain(ssl: *const SSL) -> *mut stack_st_X509;
+    pub fn SSL_get_client_CA_list(ssl: *const SSL) -> *mut stack_st_X509_NAME;
+    pub fn SSL_CTX_get_client_CA_list(ctx: *const SSL_CTX) -> *mut stack_st_X509_NAME;
 
     pub fn SSL_CTX_set_verify(
         ctx: *mut SSL_CTX,
@@ -565,9 +725,12 @@ extern "C" {
         verify_callback: Option<extern "C" fn(c_int, *mut X509_STORE_CTX) -> c_int>,
     );
     pub fn SSL_CTX_set_verify_depth(ctx: *mut SSL_CTX, depth: c_int);
+    pub fn SSL_set_verify_depth(s: *mut SSL, depth: c_int);
 
     #[cfg(any(ossl111, libressl340))]
     pub fn SSL_CTX_set_post_handshake_auth(ctx: *mut SSL_CTX, val: c_int);
+    #[cfg(any(ossl111, libressl340))]
+    pub fn SSL_set_post_handshake_auth(s: *mut SSL, val: c_int);
 
     pub fn SSL_CTX_check_private_key(ctx: *const SSL_CTX) -> c_int;
 
@@ -576,6 +739,11 @@ extern "C" {
         sid_ctx: *const c_uchar,
         sid_ctx_len: c_uint,
     ) -> c_int;
+    pub fn SSL_set_session_id_context(
+        ssl: *mut SSL,
+        sid_ctx: *const c_uchar,
+        sid_ctx_len: c_uint,
+    ) -> c_int;
 
     pub fn SSL_new(ctx: *mut SSL_CTX) -> *mut SSL;
 
@@ -584,12 +752,28 @@ extern "C" {
 
     #[cfg(any(ossl102, libressl261))]
     pub fn SSL_get0_param(ssl: *mut SSL) -> *mut X509_VERIFY_PARAM;
+
+    #[cfg(any(ossl102, libressl261))]
+    pub fn SSL_CTX_set1_param(ctx: *mut SSL_CTX, vpm: *mut X509_VERIFY_PARAM) -> c_int;
 }
 
 #[cfg(ossl111)]
 pub type SSL_client_hello_cb_fn =
     Option<unsafe extern "C" fn(s: *mut SSL, al: *mut c_int, arg: *mut c_void) -> c_int>;
 extern "C" {
+    #[cfg(ossl102)]
+    pub fn SSL_CTX_set_cert_cb(
+        c: *mut SSL_CTX,
+        cb: Option<unsafe extern "C" fn(*mut SSL, *mut c_void) -> c_int>,
+        arg: *mut c_void,
+    );
+    #[cfg(ossl102)]
+    pub fn SSL_set_cert_cb(
+        s: *mut SSL,
+        cb: Option<unsafe extern "C" fn(*mut SSL, *mut c_void) -> c_int>,
+        arg: *mut c_void,
+    );
+
     #[cfg(ossl111)]
     pub fn SSL_CTX_set_client_hello_cb(
         c: *mut SSL_CTX,
@@ -629,9 +813,25 @@ extern "C" {
     pub fn SSL_accept(ssl: *mut SSL) -> c_int;
     #[cfg(ossl111)]
     pub fn SSL_stateless(s: *mut SSL) -> c_int;
+    #[cfg(ossl110)]
+    pub fn DTLSv1_listen(s: *mut SSL, client: *mut BIO_ADDR) -> c_int;
     pub fn SSL_connect(ssl: *mut SSL) -> c_int;
     pub fn SSL_read(ssl: *mut SSL, buf: *mut c_void, num: c_int) -> c_int;
+    #[cfg(any(ossl111, libressl350))]
+    pub fn SSL_read_ex(
+        ssl: *mut SSL,
+        buf: *mut c_void,
+        num: size_t,
+        readbytes: *mut size_t,
+    ) -> c_int;
     pub fn SSL_peek(ssl: *mut SSL, buf: *mut c_void, num: c_int) -> c_int;
+    #[cfg(any(ossl111, libressl350))]
+    pub fn SSL_peek_ex(
+        ssl: *mut SSL,
+        buf: *mut c_void,
+        num: size_t,
+        readbytes: *mut size_t,
+    ) -> c_int;
     #[cfg(any(ossl111, libressl340))]
     pub fn SSL_read_early_data(
         s: *mut ::SSL,
@@ -643,6 +843,13 @@ extern "C" {
 
 extern "C" {
     pub fn SSL_write(ssl: *mut SSL, buf: *const c_void, num: c_int) -> c_int;
+    #[cfg(any(ossl111, libressl350))]
+    pub fn SSL_write_ex(
+        ssl: *mut SSL,
+        buf: *const c_void,
+        num: size_t,
+        written: *mut size_t,
+    ) -> c_int;
     #[cfg(any(ossl111, libressl340))]
     pub fn SSL_write_early_data(
         s: *mut SSL,
@@ -698,6 +905,7 @@ cfg_if! {
 
 extern "C" {
     pub fn SSL_get_error(ssl: *const SSL, ret: c_int) -> c_int;
+    pub fn SSL_want(ssl: *const SSL) -> c_int;
     pub fn SSL_get_version(ssl: *const SSL) -> *const c_char;
 
     pub fn SSL_do_handshake(ssl: *mut SSL) -> c_int;
@@ -707,12 +915,29 @@ extern "C" {
 
     pub fn SSL_CTX_add_client_CA(ctx: *mut SSL_CTX, cacert: *mut X509) -> c_int;
 
+    pub fn SSL_set_client_CA_list(s: *mut SSL, list: *mut stack_st_X509_NAME);
+
+    pub fn SSL_add_client_CA(ssl: *mut SSL, x: *mut X509) -> c_int;
+
+    pub fn SSL_CTX_set_client_cert_cb(
+        ctx: *mut SSL_CTX,
+        client_cert_cb: Option<
+            unsafe extern "C" fn(
+                ssl: *mut SSL,
+                x509: *mut *mut X509,
+                pkey: *mut *mut EVP_PKEY,
+            ) -> c_int,
+        >,
+    );
+
     pub fn SSL_CTX_set_default_verify_paths(ctx: *mut SSL_CTX) -> c_int;
     pub fn SSL_CTX_load_verify_locations(
         ctx: *mut SSL_CTX,
         CAfile: *const c_char,
         CApath: *const c_char,
     ) -> c_int;
+    #[cfg(ossl300)]
+    pub fn SSL_CTX_load_verify_store(ctx: *mut SSL_CTX, CAstore: *const c_char) -> c_int;
 }
 
 const_ptr_api! {
@@ -881,6 +1106,23 @@ const_ptr_api! {
     }
 }
 
+extern "C" {
+    pub fn SSL_renegotiate(s: *mut SSL) -> c_int;
+    pub fn SSL_renegotiate_abbreviated(s: *mut SSL) -> c_int;
+    #[cfg(ossl111)]
+    pub fn SSL_key_update(s: *mut SSL, updatetype: c_int) -> c_int;
+    #[cfg(ossl111)]
+    pub fn SSL_get_key_update_type(s: *const SSL) -> c_int;
+    #[cfg(ossl111)]
+    pub fn SSL_verify_client_post_handshake(s: *mut SSL) -> c_int;
+}
+
+const_ptr_api! {
+    extern "C" {
+        pub fn SSL_renegotiate_pending(s: #[const_ptr_if(ossl111)] SSL) -> c_int;
+    }
+}
+
 const_ptr_api! {
     extern "C" {
         #[cfg(any(ossl102, libressl273))]
@@ -892,3 +1134,54 @@ extern "C" {
     #[cfg(ossl110)]
     pub fn OPENSSL_init_ssl(opts: u64, settings: *const OPENSSL_INIT_SETTINGS) -> c_int;
 }
+
+#[cfg(ossl320)]
+extern "C" {
+    pub fn SSL_add_expected_rpk(s: *mut SSL, rpk: *mut EVP_PKEY) -> c_int;
+    pub fn SSL_get0_peer_rpk(s: *const SSL) -> *mut EVP_PKEY;
+    pub fn SSL_SESSION_get0_peer_rpk(s: *mut SSL_SESSION) -> *mut EVP_PKEY;
+    pub fn SSL_get_negotiated_client_cert_type(s: *const SSL) -> c_int;
+    pub fn SSL_get_negotiated_server_cert_type(s: *const SSL) -> c_int;
+    pub fn SSL_set1_client_cert_type(s: *mut SSL, val: *const c_uchar, len: size_t) -> c_int;
+    pub fn SSL_set1_server_cert_type(s: *mut SSL, val: *const c_uchar, len: size_t) -> c_int;
+    pub fn SSL_CTX_set1_client_cert_type(
+        ctx: *mut SSL_CTX,
+        val: *const c_uchar,
+        len: size_t,
+    ) -> c_int;
+    pub fn SSL_CTX_set1_server_cert_type(
+        ctx: *mut SSL_CTX,
+        val: *const c_uchar,
+        len: size_t,
+    ) -> c_int;
+}
+
+#[cfg(ossl350)]
+extern "C" {
+    pub fn SSL_set_quic_tls_cbs(
+        s: *mut SSL,
+        qtdis: *const OSSL_DISPATCH,
+        arg: *mut c_void,
+    ) -> c_int;
+    pub fn SSL_set_quic_tls_transport_params(
+        s: *mut SSL,
+        params: *const c_uchar,
+        params_len: size_t,
+    ) -> c_int;
+    pub fn SSL_set_quic_tls_early_data_enabled(s: *mut SSL, enabled: c_int) -> c_int;
+}
+
+#[cfg(ossl102)]
+extern "C" {
+    pub fn SSL_CONF_CTX_new() -> *mut SSL_CONF_CTX;
+    pub fn SSL_CONF_CTX_finish(cctx: *mut SSL_CONF_CTX) -> c_int;
+    pub fn SSL_CONF_CTX_free(cctx: *mut SSL_CONF_CTX);
+    pub fn SSL_CONF_CTX_set_flags(cctx: *mut SSL_CONF_CTX, flags: c_uint) -> c_uint;
+    pub fn SSL_CONF_CTX_clear_flags(cctx: *mut SSL_CONF_CTX, flags: c_uint) -> c_uint;
+    pub fn SSL_CONF_CTX_set1_prefix(cctx: *mut SSL_CONF_CTX, pre: *const c_char) -> c_int;
+    pub fn SSL_CONF_CTX_set_ssl(cctx: *mut SSL_CONF_CTX, ssl: *mut SSL);
+    pub fn SSL_CONF_CTX_set_ssl_ctx(cctx: *mut SSL_CONF_CTX, ctx: *mut SSL_CTX);
+    pub fn SSL_CONF_cmd(cctx: *mut SSL_CONF_CTX, cmd: *const c_char, value: *const c_char)
+        -> c_int;
+    pub fn SSL_CONF_cmd_value_type(cctx: *mut SSL_CONF_CTX, cmd: *const c_char) -> c_int;
+}
@@ -4,6 +4,7 @@ use libc::*;
 use *;
 
 pub enum ASN1_INTEGER {}
+pub enum ASN1_ENUMERATED {}
 pub enum ASN1_GENERALIZEDTIME {}
 pub enum ASN1_STRING {}
 pub enum ASN1_BIT_STRING {}
@@ -39,6 +40,8 @@ cfg_if! {
         }
     }
 }
+#[cfg(ossl110)]
+pub enum BIO_ADDR {}
 cfg_if! {
     if #[cfg(any(ossl110, libressl350))] {
         pub enum BIGNUM {}
@@ -1076,3 +1079,10 @@ pub enum OSSL_PROVIDER {}
 
 #[cfg(ossl300)]
 pub enum OSSL_LIB_CTX {}
+
+#[cfg(ossl300)]
+#[repr(C)]
+pub struct OSSL_DISPATCH {
+    pub function_id: c_int,
+    pub function: Option<unsafe extern "C" fn()>,
+}
@@ -15,6 +15,8 @@ pub enum X509_EXTENSION {}
 
 stack!(stack_st_X509_EXTENSION);
 
+pub enum X509_ATTRIBUTE {}
+
 stack!(stack_st_X509_ATTRIBUTE);
 
 cfg_if! {
@@ -141,6 +143,20 @@ extern "C" {
 
     pub fn X509_sign(x: *mut X509, pkey: *mut EVP_PKEY, md: *const EVP_MD) -> c_int;
 
+    pub fn X509_check_trust(x: *mut X509, id: c_int, flags: c_int) -> c_int;
+}
+const_ptr_api! {
+    extern "C" {
+        pub fn X509_check_private_key(
+            x509: #[const_ptr_if(ossl110)] X509,
+            pkey: #[const_ptr_if(ossl110)] EVP_PKEY,
+        ) -> c_int;
+    }
+}
+extern "C" {
+    pub fn X509_trust_clear(x: *mut X509);
+    pub fn X509_reject_clear(x: *mut X509);
+
     pub fn X509_digest(
         x: *const X509,
         digest: *const EVP_MD,
@@ -236,6 +252,7 @@ extern "C" {
 const_ptr_api! {
     extern "C" {
         pub fn i2d_X509_CRL(x: #[const_ptr_if(ossl300)] X509_CRL, buf: *mut *mut u8) -> c_int;
+        pub fn i2d_X509_EXTENSION(x: #[const_ptr_if(ossl300)] X509_EXTENSION, buf: *mut *mut u8) -> c_int;
         #[cfg(any(ossl110, libressl270))]
         pub fn X509_CRL_dup(x: #[const_ptr_if(ossl300)] X509_CRL) -> *mut X509_CRL;
     }
@@ -270,6 +287,11 @@ extern "C" {
     pub fn X509_get_signature_nid(x: *const X509) -> c_int;
 
     pub fn X509_EXTENSION_free(ext: *mut X509_EXTENSION);
+    pub fn d2i_X509_EXTENSION(
+        a: *mut *mut X509_EXTENSION,
+        pp: *mut *const c_uchar,
+        length: c_long,
+    ) -> *mut X509_EXTENSION;
 
     pub fn X509_NAME_ENTRY_free(x: *mut X509_NAME_ENTRY);
 
@@ -291,6 +313,8 @@ const_ptr_api! {
 extern "C" {
     pub fn d2i_X509(a: *mut *mut X509, pp: *mut *const c_uchar, length: c_long) -> *mut X509;
     pub fn d2i_X509_bio(b: *mut BIO, a: *mut *mut X509) -> *mut X509;
+    #[cfg(ossl102)]
+    pub fn i2d_re_X509_tbs(x: *mut X509, pp: *mut *mut c_uchar) -> c_int;
 
     pub fn X509_get_pubkey(x: *mut X509) -> *mut EVP_PKEY;
 
@@ -352,6 +376,40 @@ const_ptr_api! {
             -> c_int;
     }
 }
+extern "C" {
+    pub fn X509_REQ_get_attr_count(req: *const X509_REQ) -> c_int;
+    pub fn X509_REQ_get_attr_by_NID(req: *const X509_REQ, nid: c_int, lastpos: c_int) -> c_int;
+    pub fn X509_REQ_get_attr(req: *const X509_REQ, loc: c_int) -> *mut X509_ATTRIBUTE;
+    pub fn X509_REQ_add1_attr_by_NID(
+        req: *mut X509_REQ,
+        nid: c_int,
+        ty: c_int,
+        bytes: *const c_uchar,
+        len: c_int,
+    ) -> c_int;
+    pub fn X509_REQ_add1_attr_by_OBJ(
+        req: *mut X509_REQ,
+        obj: *const ASN1_OBJECT,
+        ty: c_int,
+        bytes: *const c_uchar,
+        len: c_int,
+    ) -> c_int;
+
+    pub fn X509_ATTRIBUTE_free(attr: *mut X509_ATTRIBUTE);
+    pub fn X509_ATTRIBUTE_get0_object(attr: *mut X509_ATTRIBUTE) -> *mut ASN1_OBJECT;
+    pub fn X509_ATTRIBUTE_get0_type(attr: *mut X509_ATTRIBUTE, idx: c_int) -> *mut ASN1_TYPE;
+    pub fn X509_ATTRIBUTE_get0_data(
+        attr: *mut X509_ATTRIBUTE,
+        idx: c_int,
+        atrtype: c_int,
+        data: *mut c_void,
+    ) -> *mut c_void;
+}
+const_ptr_api! {
+    extern "C" {
+        pub fn X509_ATTRIBUTE_count(attr: #[const_ptr_if(any(ossl110, libressl280))] X509_ATTRIBUTE) -> c_int;
+    }
+}
 extern "C" {
     pub fn X509_set_pubkey(x: *mut X509, pkey: *mut EVP_PKEY) -> c_int;
     pub fn X509_REQ_verify(req: *mut X509_REQ, pkey: *mut EVP_PKEY) -> c_int;
@@ -452,12 +510,37 @@ const_ptr_api! {
             loc: c_int,
             set: c_int,
         ) -> c_int;
+        pub fn X509_NAME_add_entry_by_OBJ(
+            x: *mut X509_NAME,
+            obj: #[const_ptr_if(any(ossl110, libressl280))] ASN1_OBJECT,
+            ty: c_int,
+            bytes: #[const_ptr_if(any(ossl110, libressl280))] c_uchar,
+            len: c_int,
+            loc: c_int,
+            set: c_int,
+        ) -> c_int;
+        pub fn X509_NAME_add_entry(
+            x: *mut X509_NAME,
+            ne: #[const_ptr_if(any(ossl110, libressl280))] X509_NAME_ENTRY,
+            loc: c_int,
+            set: c_int,
+        ) -> c_int;
+        pub fn X509_NAME_ENTRY_create_by_OBJ(
+            ne: *mut *mut X509_NAME_ENTRY,
+            obj: #[const_ptr_if(any(ossl110, libressl280))] ASN1_OBJECT,
+            ty: c_int,
+            bytes: *const c_uchar,
+            len: c_int,
+        ) -> *mut X509_NAME_ENTRY;
         pub fn i2d_X509_NAME(n: #[const_ptr_if(ossl300)] X509_NAME, buf: *mut *mut u8) -> c_int;
         pub fn X509_NAME_ENTRY_get_object(ne: #[const_ptr_if(any(ossl110, libressl280))] X509_NAME_ENTRY) -> *mut ASN1_OBJECT;
         pub fn X509_NAME_ENTRY_get_data(ne: #[const_ptr_if(any(ossl110, libressl280))] X509_NAME_ENTRY) -> *mut ASN1_STRING;
     }
 }
 extern "C" {
+    pub fn X509_NAME_delete_entry(x: *mut X509_NAME, loc: c_int) -> *mut X509_NAME_ENTRY;
+    #[cfg(ossl110)]
+    pub fn X509_NAME_ENTRY_set(ne: *const X509_NAME_ENTRY) -> c_int;
     pub fn X509_NAME_add_entry_by_txt(
         x: *mut X509_NAME,
         field: *const c_char,
@@ -636,5 +719,19 @@ extern "C" {
 
 extern "C" {
     pub fn X509_print(bio: *mut BIO, x509: *mut X509) -> c_int;
+    pub fn X509_print_ex(bio: *mut BIO, x509: *mut X509, nmflag: c_ulong, cflag: c_ulong) -> c_int;
     pub fn X509_REQ_print(bio: *mut BIO, req: *mut X509_REQ) -> c_int;
 }
+
+const_ptr_api! {
+    extern "C" {
+        pub fn X509_NAME_print_ex(
+            out: *mut BIO,
+            nm: #[const_ptr_if(any(ossl110, libressl280))] X509_NAME,
+            indent: c_int,
+            flags: c_ulong,
+        ) -> c_int;
+        pub fn X509_add1_trust_object(x: *mut X509, obj: #[const_ptr_if(any(ossl110, libressl280))] ASN1_OBJECT) -> c_int;
+        pub fn X509_add1_reject_object(x: *mut X509, obj: #[const_ptr_if(any(ossl110, libressl280))] ASN1_OBJECT) -> c_int;
+    }
+}
@@ -4,7 +4,33 @@ use *;
 pub enum CONF_METHOD {}
 
 extern "C" {
+    pub fn GENERAL_NAME_new() -> *mut GENERAL_NAME;
     pub fn GENERAL_NAME_free(name: *mut GENERAL_NAME);
+    pub fn GENERAL_NAME_set0_value(a: *mut GENERAL_NAME, type_: c_int, value: *mut c_void);
+    pub fn GENERAL_NAME_set0_othername(
+        gen: *mut GENERAL_NAME,
+        oid: *mut ASN1_OBJECT,
+        value: *mut ASN1_TYPE,
+    ) -> c_int;
+
+    pub fn a2i_IPADDRESS(ipasc: *const c_char) -> *mut ASN1_OCTET_STRING;
+
+    pub fn d2i_GENERAL_NAME(
+        a: *mut *mut GENERAL_NAME,
+        pp: *mut *const c_uchar,
+        length: c_long,
+    ) -> *mut GENERAL_NAME;
+}
+
+const_ptr_api! {
+    extern "C" {
+        pub fn i2d_GENERAL_NAME(a: #[const_ptr_if(ossl300)] GENERAL_NAME, pp: *mut *mut c_uchar) -> c_int;
+        pub fn GENERAL_NAME_get0_otherName(
+            gen: #[const_ptr_if(ossl300)] GENERAL_NAME,
+            poid: *mut *mut ASN1_OBJECT,
+            pvalue: *mut *mut ASN1_TYPE,
+        ) -> c_int;
+    }
 }
 
 #[repr(C)]
@@ -19,6 +45,16 @@ extern "C" {
     pub fn ACCESS_DESCRIPTION_free(ad: *mut ACCESS_DESCRIPTION);
 }
 
+#[repr(C)]
+pub struct BASIC_CONSTRAINTS {
+    pub ca: c_int,
+    pub pathlen: *mut ASN1_INTEGER,
+}
+
+extern "C" {
+    pub fn BASIC_CONSTRAINTS_free(bc: *mut BASIC_CONSTRAINTS);
+}
+
 #[repr(C)]
 pub struct AUTHORITY_KEYID {
     pub keyid: *mut ASN1_OCTET_STRING,
@@ -30,6 +66,43 @@ extern "C" {
     pub fn AUTHORITY_KEYID_free(akid: *mut AUTHORITY_KEYID);
 }
 
+#[repr(C)]
+pub struct DIST_POINT_NAME {
+    pub type_: c_int,
+    // FIXME should be a union
+    pub name: *mut c_void,
+    pub dpname: *mut X509_NAME,
+}
+
+#[repr(C)]
+pub struct DIST_POINT {
+    pub distpoint: *mut DIST_POINT_NAME,
+    pub reasons: *mut ASN1_BIT_STRING,
+    pub CRLissuer: *mut stack_st_GENERAL_NAME,
+    pub dp_reasons: c_int,
+}
+
+stack!(stack_st_DIST_POINT);
+
+extern "C" {
+    pub fn DIST_POINT_free(dp: *mut DIST_POINT);
+    pub fn DIST_POINT_NAME_free(dpn: *mut DIST_POINT_NAME);
+}
+
+stack!(stack_st_POLICYQUALINFO);
+
+#[repr(C)]
+pub struct POLICYINFO {
+    pub policyid: *mut ASN1_OBJECT,
+    pub qualifiers: *mut stack_st_POLICYQUALINFO,
+}
+
+stack!(stack_st_POLICYINFO);
+
+extern "C" {
+    pub fn POLICYINFO_free(pi: *mut POLICYINFO);
+}
+
 const_ptr_api! {
     extern "C" {
         pub fn X509V3_EXT_nconf_nid(
@@ -49,9 +122,31 @@ const_ptr_api! {
 
 extern "C" {
     pub fn X509_check_issued(issuer: *mut X509, subject: *mut X509) -> c_int;
+    pub fn X509_check_purpose(x: *mut X509, id: c_int, ca: c_int) -> c_int;
     pub fn X509_verify(req: *mut X509, pkey: *mut EVP_PKEY) -> c_int;
 
+    #[cfg(any(ossl102, libressl261))]
+    pub fn X509_check_host(
+        x: *mut X509,
+        chk: *const c_char,
+        chklen: size_t,
+        flags: c_uint,
+        peername: *mut *mut c_char,
+    ) -> c_int;
+    #[cfg(any(ossl102, libressl261))]
+    pub fn X509_check_email(
+        x: *mut X509,
+        chk: *const c_char,
+        chklen: size_t,
+        flags: c_uint,
+    ) -> c_int;
+    #[cfg(any(ossl102, libressl261))]
+    pub fn X509_check_ip(x: *mut X509, chk: *const c_uchar, chklen: size_t, flags: c_uint)
+        -> c_int;
+
     pub fn X509V3_set_nconf(ctx: *mut X509V3_CTX, conf: *mut CONF);
+    #[cfg(ossl300)]
+    pub fn X509V3_set_issuer_pkey(ctx: *mut X509V3_CTX, pkey: *mut EVP_PKEY) -> c_int;
 
     pub fn X509V3_set_ctx(
         ctx: *mut X509V3_CTX,
@@ -101,4 +196,12 @@ extern "C" {
     pub fn X509_get_key_usage(x: *mut X509) -> u32;
     #[cfg(ossl110)]
     pub fn X509_get_extended_key_usage(x: *mut X509) -> u32;
+    #[cfg(ossl110)]
+    pub fn X509_get0_subject_key_id(x: *mut X509) -> *const ASN1_OCTET_STRING;
+    #[cfg(ossl111d)]
+    pub fn X509_get0_authority_key_id(x: *mut X509) -> *const ASN1_OCTET_STRING;
+    #[cfg(ossl111d)]
+    pub fn X509_get0_authority_issuer(x: *mut X509) -> *const stack_st_GENERAL_NAME;
+    #[cfg(ossl111d)]
+    pub fn X509_get0_authority_serial(x: *mut X509) -> *const ASN1_INTEGER;
 }
@@ -34,6 +34,7 @@ mod openssl {
     pub use self::bn::*;
     pub use self::cms::*;
     pub use self::crypto::*;
+    pub use self::ct::*;
     pub use self::dtls1::*;
     pub use self::ec::*;
     pub use self::err::*;
@@ -64,6 +65,7 @@ mod openssl {
     mod bn;
     mod cms;
     mod crypto;
+    mod ct;
     mod dtls1;
     mod ec;
     mod err;
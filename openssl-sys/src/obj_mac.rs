@@ -912,6 +912,16 @@ pub const NID_rc4_hmac_md5: c_int = 915;
 pub const NID_aes_128_cbc_hmac_sha1: c_int = 916;
 pub const NID_aes_192_cbc_hmac_sha1: c_int = 917;
 pub const NID_aes_256_cbc_hmac_sha1: c_int = 918;
+#[cfg(ossl110)]
+pub const NID_ct_precert_scts: c_int = 951;
+#[cfg(ossl110)]
+pub const NID_ct_precert_poison: c_int = 952;
+#[cfg(ossl110)]
+pub const NID_ct_precert_signer: c_int = 953;
+#[cfg(ossl110)]
+pub const NID_ct_cert_scts: c_int = 954;
+#[cfg(ossl110)]
+pub const NID_tlsfeature: c_int = 1020;
 #[cfg(ossl111)]
 pub const NID_X25519: c_int = 1034;
 #[cfg(ossl111)]
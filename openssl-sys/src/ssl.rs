@@ -120,6 +120,8 @@ cfg_if! {
         pub const SSL_OP_TLS_ROLLBACK_BUG: ssl_op_type!() = 0x00800000;
     }
 }
+#[cfg(ossl111)]
+pub const SSL_OP_NO_ANTI_REPLAY: ssl_op_type!() = 0x01000000;
 
 cfg_if! {
     if #[cfg(ossl101)] {
@@ -246,6 +248,66 @@ pub unsafe fn SSL_CTX_set_mode(ctx: *mut SSL_CTX, op: c_long) -> c_long {
     SSL_CTX_ctrl(ctx, SSL_CTRL_MODE, op, ptr::null_mut())
 }
 
+pub unsafe fn SSL_CTX_set_msg_callback_arg(ctx: *mut SSL_CTX, arg: *mut c_void) -> c_long {
+    SSL_CTX_ctrl(ctx, SSL_CTRL_SET_MSG_CALLBACK_ARG, 0, arg)
+}
+
+#[cfg(ossl110)]
+pub const SSL_CT_VALIDATION_PERMISSIVE: c_int = 0;
+#[cfg(ossl110)]
+pub const SSL_CT_VALIDATION_STRICT: c_int = 1;
+
+#[cfg(ossl110)]
+pub const DANE_FLAG_NO_DANE_EE_NAMECHECKS: c_ulong = 1 << 0;
+
+#[cfg(ossl350)]
+pub const OSSL_RECORD_PROTECTION_LEVEL_NONE: u32 = 0;
+#[cfg(ossl350)]
+pub const OSSL_RECORD_PROTECTION_LEVEL_EARLY: u32 = 1;
+#[cfg(ossl350)]
+pub const OSSL_RECORD_PROTECTION_LEVEL_HANDSHAKE: u32 = 2;
+#[cfg(ossl350)]
+pub const OSSL_RECORD_PROTECTION_LEVEL_APPLICATION: u32 = 3;
+
+#[cfg(ossl350)]
+pub const OSSL_FUNC_SSL_QUIC_TLS_CRYPTO_SEND: c_int = 2001;
+#[cfg(ossl350)]
+pub const OSSL_FUNC_SSL_QUIC_TLS_CRYPTO_RECV_RCD: c_int = 2002;
+#[cfg(ossl350)]
+pub const OSSL_FUNC_SSL_QUIC_TLS_CRYPTO_RELEASE_RCD: c_int = 2003;
+#[cfg(ossl350)]
+pub const OSSL_FUNC_SSL_QUIC_TLS_YIELD_SECRET: c_int = 2004;
+#[cfg(ossl350)]
+pub const OSSL_FUNC_SSL_QUIC_TLS_GOT_TRANSPORT_PARAMS: c_int = 2005;
+#[cfg(ossl350)]
+pub const OSSL_FUNC_SSL_QUIC_TLS_ALERT: c_int = 2006;
+
+#[cfg(ossl102)]
+pub const SSL_CONF_FLAG_CMDLINE: c_uint = 0x1;
+#[cfg(ossl102)]
+pub const SSL_CONF_FLAG_FILE: c_uint = 0x2;
+#[cfg(ossl102)]
+pub const SSL_CONF_FLAG_CLIENT: c_uint = 0x4;
+#[cfg(ossl102)]
+pub const SSL_CONF_FLAG_SERVER: c_uint = 0x8;
+#[cfg(ossl102)]
+pub const SSL_CONF_FLAG_SHOW_ERRORS: c_uint = 0x10;
+#[cfg(ossl102)]
+pub const SSL_CONF_FLAG_CERTIFICATE: c_uint = 0x20;
+#[cfg(ossl110)]
+pub const SSL_CONF_FLAG_REQUIRE_PRIVATE: c_uint = 0x40;
+
+#[cfg(ossl102)]
+pub const SSL_CONF_TYPE_UNKNOWN: c_int = 0x0;
+#[cfg(ossl102)]
+pub const SSL_CONF_TYPE_STRING: c_int = 0x1;
+#[cfg(ossl102)]
+pub const SSL_CONF_TYPE_FILE: c_int = 0x2;
+#[cfg(ossl102)]
+pub const SSL_CONF_TYPE_DIR: c_int = 0x3;
+#[cfg(ossl110)]
+pub const SSL_CONF_TYPE_NONE: c_int = 0x4;
+
 #[cfg(ossl111)]
 pub const SSL_COOKIE_LENGTH: c_int = 4096;
 
@@ -272,6 +334,18 @@ cfg_if! {
                 ptr::null_mut(),
             ) as c_ulong
         }
+
+        pub unsafe fn SSL_get_options(s: *const SSL) -> c_ulong {
+            SSL_ctrl(s as *mut _, SSL_CTRL_OPTIONS, 0, ptr::null_mut()) as c_ulong
+        }
+
+        pub unsafe fn SSL_set_options(s: *mut SSL, op: c_ulong) -> c_ulong {
+            SSL_ctrl(s, SSL_CTRL_OPTIONS, op as c_long, ptr::null_mut()) as c_ulong
+        }
+
+        pub unsafe fn SSL_clear_options(s: *mut SSL, op: c_ulong) -> c_ulong {
+            SSL_ctrl(s, SSL_CTRL_CLEAR_OPTIONS, op as c_long, ptr::null_mut()) as c_ulong
+        }
     }
 }
 
@@ -279,6 +353,22 @@ pub unsafe fn SSL_set_mtu(ssl: *mut SSL, mtu: c_long) -> c_long {
     SSL_ctrl(ssl, SSL_CTRL_SET_MTU, mtu, ptr::null_mut())
 }
 
+pub unsafe fn SSL_num_renegotiations(ssl: *mut SSL) -> c_long {
+    SSL_ctrl(ssl, SSL_CTRL_GET_NUM_RENEGOTIATIONS, 0, ptr::null_mut())
+}
+
+pub unsafe fn SSL_get_secure_renegotiation_support(ssl: *mut SSL) -> c_long {
+    SSL_ctrl(ssl, SSL_CTRL_GET_RI_SUPPORT, 0, ptr::null_mut())
+}
+
+pub unsafe fn DTLSv1_get_timeout(ssl: *mut SSL, tv: *mut timeval) -> c_long {
+    SSL_ctrl(ssl, DTLS_CTRL_GET_TIMEOUT, 0, tv as *mut c_void)
+}
+
+pub unsafe fn DTLSv1_handle_timeout(ssl: *mut SSL) -> c_long {
+    SSL_ctrl(ssl, DTLS_CTRL_HANDLE_TIMEOUT, 0, ptr::null_mut())
+}
+
 #[cfg(ossl110)]
 pub unsafe fn SSL_get_extms_support(ssl: *mut SSL) -> c_long {
     SSL_ctrl(ssl, SSL_CTRL_GET_EXTMS_SUPPORT, 0, ptr::null_mut())
@@ -294,6 +384,17 @@ pub const SSL_SESS_CACHE_NO_INTERNAL_STORE: c_long = 0x200;
 pub const SSL_SESS_CACHE_NO_INTERNAL: c_long =
     SSL_SESS_CACHE_NO_INTERNAL_LOOKUP | SSL_SESS_CACHE_NO_INTERNAL_STORE;
 
+#[cfg(ossl102)]
+pub const SSL_BUILD_CHAIN_FLAG_UNTRUSTED: c_long = 0x1;
+#[cfg(ossl102)]
+pub const SSL_BUILD_CHAIN_FLAG_NO_ROOT: c_long = 0x2;
+#[cfg(ossl102)]
+pub const SSL_BUILD_CHAIN_FLAG_CHECK: c_long = 0x4;
+#[cfg(ossl102)]
+pub const SSL_BUILD_CHAIN_FLAG_IGNORE_ERROR: c_long = 0x8;
+#[cfg(ossl102)]
+pub const SSL_BUILD_CHAIN_FLAG_CLEAR_ERROR: c_long = 0x10;
+
 pub const OPENSSL_NPN_UNSUPPORTED: c_int = 0;
 pub const OPENSSL_NPN_NEGOTIATED: c_int = 1;
 pub const OPENSSL_NPN_NO_OVERLAP: c_int = 2;
@@ -301,6 +402,27 @@ pub const OPENSSL_NPN_NO_OVERLAP: c_int = 2;
 pub const SSL_AD_ILLEGAL_PARAMETER: c_int = SSL3_AD_ILLEGAL_PARAMETER;
 pub const SSL_AD_DECODE_ERROR: c_int = TLS1_AD_DECODE_ERROR;
 pub const SSL_AD_UNRECOGNIZED_NAME: c_int = TLS1_AD_UNRECOGNIZED_NAME;
+pub const SSL_AD_NO_RENEGOTIATION: c_int = TLS1_AD_NO_RENEGOTIATION;
+
+pub const SSL_CB_LOOP: c_int = 0x01;
+pub const SSL_CB_EXIT: c_int = 0x02;
+pub const SSL_CB_READ: c_int = 0x04;
+pub const SSL_CB_WRITE: c_int = 0x08;
+pub const SSL_CB_ALERT: c_int = 0x4000;
+pub const SSL_CB_READ_ALERT: c_int = SSL_CB_ALERT | SSL_CB_READ;
+pub const SSL_CB_WRITE_ALERT: c_int = SSL_CB_ALERT | SSL_CB_WRITE;
+pub const SSL_CB_HANDSHAKE_START: c_int = 0x10;
+pub const SSL_CB_HANDSHAKE_DONE: c_int = 0x20;
+#[cfg(ossl111)]
+pub const SSL_KEY_UPDATE_NONE: c_int = -1;
+#[cfg(ossl111)]
+pub const SSL_KEY_UPDATE_NOT_REQUESTED: c_int = 0;
+#[cfg(ossl111)]
+pub const SSL_KEY_UPDATE_REQUESTED: c_int = 1;
+
+#[cfg(ossl102)]
+pub const SSL_R_BAD_VALUE: c_int = 384;
+
 pub const SSL_ERROR_NONE: c_int = 0;
 pub const SSL_ERROR_SSL: c_int = 1;
 pub const SSL_ERROR_SYSCALL: c_int = 5;
@@ -312,15 +434,24 @@ pub const SSL_ERROR_WANT_X509_LOOKUP: c_int = 4;
 pub const SSL_ERROR_ZERO_RETURN: c_int = 6;
 #[cfg(ossl111)]
 pub const SSL_ERROR_WANT_CLIENT_HELLO_CB: c_int = 11;
+#[cfg(ossl300)]
+pub const SSL_ERROR_WANT_RETRY_VERIFY: c_int = 12;
+#[cfg(ossl300)]
+pub const SSL_RETRY_VERIFY: c_int = 8;
 pub const SSL_VERIFY_NONE: c_int = 0;
 pub const SSL_VERIFY_PEER: c_int = 1;
 pub const SSL_VERIFY_FAIL_IF_NO_PEER_CERT: c_int = 2;
+#[cfg(ossl111)]
+pub const SSL_VERIFY_POST_HANDSHAKE: c_int = 8;
 pub const SSL_CTRL_SET_TMP_DH: c_int = 3;
 pub const SSL_CTRL_SET_TMP_ECDH: c_int = 4;
 #[cfg(any(libressl, all(ossl101, not(ossl110))))]
 pub const SSL_CTRL_GET_SESSION_REUSED: c_int = 8;
+pub const SSL_CTRL_GET_NUM_RENEGOTIATIONS: c_int = 10;
 pub const SSL_CTRL_EXTRA_CHAIN_CERT: c_int = 14;
+pub const SSL_CTRL_SET_MSG_CALLBACK_ARG: c_int = 16;
 pub const SSL_CTRL_SET_MTU: c_int = 17;
+pub const SSL_CTRL_SESS_NUMBER: c_int = 20;
 #[cfg(any(libressl, all(ossl101, not(ossl110))))]
 pub const SSL_CTRL_OPTIONS: c_int = 32;
 pub const SSL_CTRL_MODE: c_int = 33;
@@ -328,6 +459,7 @@ pub const SSL_CTRL_SET_READ_AHEAD: c_int = 41;
 pub const SSL_CTRL_SET_SESS_CACHE_SIZE: c_int = 42;
 pub const SSL_CTRL_GET_SESS_CACHE_SIZE: c_int = 43;
 pub const SSL_CTRL_SET_SESS_CACHE_MODE: c_int = 44;
+pub const SSL_CTRL_GET_SESS_CACHE_MODE: c_int = 45;
 pub const SSL_CTRL_SET_TLSEXT_SERVERNAME_CB: c_int = 53;
 pub const SSL_CTRL_SET_TLSEXT_SERVERNAME_ARG: c_int = 54;
 pub const SSL_CTRL_SET_TLSEXT_HOSTNAME: c_int = 55;
@@ -336,27 +468,60 @@ pub const SSL_CTRL_SET_TLSEXT_STATUS_REQ_CB_ARG: c_int = 64;
 pub const SSL_CTRL_SET_TLSEXT_STATUS_REQ_TYPE: c_int = 65;
 pub const SSL_CTRL_GET_TLSEXT_STATUS_REQ_OCSP_RESP: c_int = 70;
 pub const SSL_CTRL_SET_TLSEXT_STATUS_REQ_OCSP_RESP: c_int = 71;
+pub const DTLS_CTRL_GET_TIMEOUT: c_int = 73;
+pub const DTLS_CTRL_HANDLE_TIMEOUT: c_int = 74;
+pub const SSL_CTRL_GET_RI_SUPPORT: c_int = 76;
 #[cfg(any(libressl, all(ossl101, not(ossl110))))]
 pub const SSL_CTRL_CLEAR_OPTIONS: c_int = 77;
 pub const SSL_CTRL_GET_EXTRA_CHAIN_CERTS: c_int = 82;
+#[cfg(ossl102)]
+pub const SSL_CTRL_CHAIN: c_int = 88;
+#[cfg(ossl102)]
+pub const SSL_CTRL_CHAIN_CERT: c_int = 89;
+#[cfg(ossl111)]
+pub const SSL_CTRL_GET_GROUPS: c_int = 90;
 #[cfg(any(ossl111, libressl252))]
 pub const SSL_CTRL_SET_GROUPS_LIST: c_int = 92;
+#[cfg(ossl111)]
+pub const SSL_CTRL_GET_SHARED_GROUP: c_int = 93;
 #[cfg(any(libressl, all(ossl102, not(ossl110))))]
 pub const SSL_CTRL_SET_ECDH_AUTO: c_int = 94;
 #[cfg(ossl102)]
 pub const SSL_CTRL_SET_SIGALGS_LIST: c_int = 98;
 #[cfg(ossl102)]
+pub const SSL_CTRL_SET_CLIENT_SIGALGS_LIST: c_int = 102;
+#[cfg(ossl102)]
+pub const SSL_CTRL_BUILD_CERT_CHAIN: c_int = 105;
+#[cfg(ossl102)]
 pub const SSL_CTRL_SET_VERIFY_CERT_STORE: c_int = 106;
+#[cfg(ossl102)]
+pub const SSL_CTRL_GET_PEER_SIGNATURE_NID: c_int = 108;
+#[cfg(ossl102)]
+pub const SSL_CTRL_GET_SERVER_TMP_KEY: c_int = 109;
+#[cfg(ossl111)]
+pub const SSL_CTRL_GET_PEER_TMP_KEY: c_int = 109;
 #[cfg(ossl110)]
 pub const SSL_CTRL_GET_EXTMS_SUPPORT: c_int = 122;
 #[cfg(any(ossl110, libressl261))]
 pub const SSL_CTRL_SET_MIN_PROTO_VERSION: c_int = 123;
 #[cfg(any(ossl110, libressl261))]
 pub const SSL_CTRL_SET_MAX_PROTO_VERSION: c_int = 124;
+#[cfg(ossl110)]
+pub const SSL_CTRL_GET_TLSEXT_STATUS_REQ_TYPE: c_int = 127;
+#[cfg(ossl110)]
+pub const SSL_CTRL_GET_TLSEXT_STATUS_REQ_CB: c_int = 128;
 #[cfg(any(ossl110g, libressl270))]
 pub const SSL_CTRL_GET_MIN_PROTO_VERSION: c_int = 130;
 #[cfg(any(ossl110g, libressl270))]
 pub const SSL_CTRL_GET_MAX_PROTO_VERSION: c_int = 131;
+#[cfg(ossl111)]
+pub const SSL_CTRL_GET_TMP_KEY: c_int = 133;
+#[cfg(ossl300)]
+pub const SSL_CTRL_GET_NEGOTIATED_GROUP: c_int = 134;
+#[cfg(ossl300)]
+pub const SSL_CTRL_SET_RETRY_VERIFY: c_int = 136;
+#[cfg(ossl300)]
+pub const SSL_CTRL_GET_VERIFY_CERT_STORE: c_int = 137;
 
 pub unsafe fn SSL_CTX_set_tmp_dh(ctx: *mut SSL_CTX, dh: *mut DH) -> c_long {
     SSL_CTX_ctrl(ctx, SSL_CTRL_SET_TMP_DH, 0, dh as *mut c_void)
@@ -385,11 +550,36 @@ pub unsafe fn SSL_CTX_get_extra_chain_certs(
     SSL_CTX_ctrl(ctx, SSL_CTRL_GET_EXTRA_CHAIN_CERTS, 0, chain as *mut c_void)
 }
 
+#[cfg(ossl102)]
+pub unsafe fn SSL_add0_chain_cert(ssl: *mut SSL, x509: *mut X509) -> c_long {
+    SSL_ctrl(ssl, SSL_CTRL_CHAIN_CERT, 0, x509 as *mut c_void)
+}
+
+#[cfg(ossl102)]
+pub unsafe fn SSL_CTX_set0_chain(ctx: *mut SSL_CTX, sk: *mut stack_st_X509) -> c_long {
+    SSL_CTX_ctrl(ctx, SSL_CTRL_CHAIN, 0, sk as *mut c_void)
+}
+
+#[cfg(ossl102)]
+pub unsafe fn SSL_CTX_add0_chain_cert(ctx: *mut SSL_CTX, x509: *mut X509) -> c_long {
+    SSL_CTX_ctrl(ctx, SSL_CTRL_CHAIN_CERT, 0, x509 as *mut c_void)
+}
+
+#[cfg(ossl102)]
+pub unsafe fn SSL_CTX_build_cert_chain(ctx: *mut SSL_CTX, flags: c_long) -> c_long {
+    SSL_CTX_ctrl(ctx, SSL_CTRL_BUILD_CERT_CHAIN, flags, ptr::null_mut())
+}
+
 #[cfg(ossl102)]
 pub unsafe fn SSL_CTX_set0_verify_cert_store(ctx: *mut SSL_CTX, st: *mut X509_STORE) -> c_long {
     SSL_CTX_ctrl(ctx, SSL_CTRL_SET_VERIFY_CERT_STORE, 0, st as *mut c_void)
 }
 
+#[cfg(ossl300)]
+pub unsafe fn SSL_get0_verify_cert_store(ssl: *mut SSL, st: *mut *mut X509_STORE) -> c_long {
+    SSL_ctrl(ssl, SSL_CTRL_GET_VERIFY_CERT_STORE, 0, st as *mut c_void)
+}
+
 cfg_if! {
     if #[cfg(ossl111)] {
         pub unsafe fn SSL_CTX_set1_groups_list(ctx: *mut SSL_CTX, s: *const c_char) -> c_long {
@@ -407,6 +597,31 @@ cfg_if! {
     }
 }
 
+#[cfg(ossl111)]
+pub unsafe fn SSL_get1_groups(ssl: *mut SSL, glist: *mut c_int) -> c_long {
+    SSL_ctrl(ssl, SSL_CTRL_GET_GROUPS, 0, glist as *mut c_void)
+}
+
+#[cfg(ossl111)]
+pub unsafe fn SSL_get_shared_group(ssl: *mut SSL, n: c_long) -> c_long {
+    SSL_ctrl(ssl, SSL_CTRL_GET_SHARED_GROUP, n, ptr::null_mut())
+}
+
+#[cfg(ossl300)]
+pub unsafe fn SSL_get_negotiated_group(ssl: *mut SSL) -> c_long {
+    SSL_ctrl(ssl, SSL_CTRL_GET_NEGOTIATED_GROUP, 0, ptr::null_mut())
+}
+
+#[cfg(ossl300)]
+pub unsafe fn SSL_set_retry_verify(ssl: *mut SSL) -> c_int {
+    (SSL_ctrl(ssl, SSL_CTRL_SET_RETRY_VERIFY, 0, ptr::null_mut()) > 0) as c_int
+}
+
+#[cfg(ossl300)]
+pub unsafe fn SSL_want_retry_verify(ssl: *const SSL) -> c_int {
+    (SSL_want(ssl) == SSL_RETRY_VERIFY) as c_int
+}
+
 #[cfg(ossl102)]
 pub unsafe fn SSL_CTX_set1_sigalgs_list(ctx: *mut SSL_CTX, s: *const c_char) -> c_long {
     SSL_CTX_ctrl(
@@ -417,6 +632,36 @@ pub unsafe fn SSL_CTX_set1_sigalgs_list(ctx: *mut SSL_CTX, s: *const c_char) ->
     )
 }
 
+#[cfg(ossl102)]
+pub unsafe fn SSL_CTX_set1_client_sigalgs_list(ctx: *mut SSL_CTX, s: *const c_char) -> c_long {
+    SSL_CTX_ctrl(
+        ctx,
+        SSL_CTRL_SET_CLIENT_SIGALGS_LIST,
+        0,
+        s as *const c_void as *mut c_void,
+    )
+}
+
+#[cfg(ossl102)]
+pub unsafe fn SSL_get_peer_signature_nid(ssl: *mut SSL, pnid: *mut c_int) -> c_long {
+    SSL_ctrl(ssl, SSL_CTRL_GET_PEER_SIGNATURE_NID, 0, pnid as *mut c_void)
+}
+
+#[cfg(ossl102)]
+pub unsafe fn SSL_get_server_tmp_key(ssl: *mut SSL, key: *mut *mut EVP_PKEY) -> c_long {
+    SSL_ctrl(ssl, SSL_CTRL_GET_SERVER_TMP_KEY, 0, key as *mut c_void)
+}
+
+#[cfg(ossl111)]
+pub unsafe fn SSL_get_peer_tmp_key(ssl: *mut SSL, key: *mut *mut EVP_PKEY) -> c_long {
+    SSL_ctrl(ssl, SSL_CTRL_GET_PEER_TMP_KEY, 0, key as *mut c_void)
+}
+
+#[cfg(ossl111)]
+pub unsafe fn SSL_get_tmp_key(ssl: *mut SSL, key: *mut *mut EVP_PKEY) -> c_long {
+    SSL_ctrl(ssl, SSL_CTRL_GET_TMP_KEY, 0, key as *mut c_void)
+}
+
 #[cfg(any(libressl, all(ossl102, not(ossl110))))]
 pub unsafe fn SSL_CTX_set_ecdh_auto(ctx: *mut SSL_CTX, onoff: c_int) -> c_int {
     SSL_CTX_ctrl(
@@ -544,10 +789,18 @@ pub unsafe fn SSL_CTX_sess_get_cache_size(ctx: *mut SSL_CTX) -> c_long {
     SSL_CTX_ctrl(ctx, SSL_CTRL_GET_SESS_CACHE_SIZE, 0, ptr::null_mut())
 }
 
+pub unsafe fn SSL_CTX_sess_number(ctx: *mut SSL_CTX) -> c_long {
+    SSL_CTX_ctrl(ctx, SSL_CTRL_SESS_NUMBER, 0, ptr::null_mut())
+}
+
 pub unsafe fn SSL_CTX_set_session_cache_mode(ctx: *mut SSL_CTX, m: c_long) -> c_long {
     SSL_CTX_ctrl(ctx, SSL_CTRL_SET_SESS_CACHE_MODE, m, ptr::null_mut())
 }
 
+pub unsafe fn SSL_CTX_get_session_cache_mode(ctx: *mut SSL_CTX) -> c_long {
+    SSL_CTX_ctrl(ctx, SSL_CTRL_GET_SESS_CACHE_MODE, 0, ptr::null_mut())
+}
+
 pub unsafe fn SSL_CTX_set_read_ahead(ctx: *mut SSL_CTX, m: c_long) -> c_long {
     SSL_CTX_ctrl(ctx, SSL_CTRL_SET_READ_AHEAD, m, ptr::null_mut())
 }
@@ -3,3 +3,12 @@ use libc::*;
 pub const SSL3_VERSION: c_int = 0x300;
 
 pub const SSL3_AD_ILLEGAL_PARAMETER: c_int = 47;
+
+pub const SSL3_RT_CHANGE_CIPHER_SPEC: c_int = 20;
+pub const SSL3_RT_ALERT: c_int = 21;
+pub const SSL3_RT_HANDSHAKE: c_int = 22;
+pub const SSL3_RT_APPLICATION_DATA: c_int = 23;
+#[cfg(ossl102)]
+pub const SSL3_RT_HEADER: c_int = 0x100;
+#[cfg(ossl111)]
+pub const SSL3_RT_INNER_CONTENT_TYPE: c_int = 0x101;
@@ -11,11 +11,29 @@ pub const TLS1_2_VERSION: c_int = 0x303;
 pub const TLS1_3_VERSION: c_int = 0x304;
 
 pub const TLS1_AD_DECODE_ERROR: c_int = 50;
+pub const TLS1_AD_NO_RENEGOTIATION: c_int = 100;
 pub const TLS1_AD_UNRECOGNIZED_NAME: c_int = 112;
 
 pub const TLSEXT_NAMETYPE_host_name: c_int = 0;
 pub const TLSEXT_STATUSTYPE_ocsp: c_int = 1;
 
+pub const TLSEXT_TYPE_server_name: c_int = 0;
+pub const TLSEXT_TYPE_status_request: c_int = 5;
+#[cfg(ossl110)]
+pub const TLSEXT_TYPE_supported_groups: c_int = 10;
+pub const TLSEXT_TYPE_ec_point_formats: c_int = 11;
+pub const TLSEXT_TYPE_signature_algorithms: c_int = 13;
+pub const TLSEXT_TYPE_application_layer_protocol_negotiation: c_int = 16;
+#[cfg(ossl111)]
+pub const TLSEXT_TYPE_supported_versions: c_int = 43;
+#[cfg(ossl111)]
+pub const TLSEXT_TYPE_key_share: c_int = 51;
+
+#[cfg(ossl320)]
+pub const TLSEXT_cert_type_x509: c_int = 0;
+#[cfg(ossl320)]
+pub const TLSEXT_cert_type_rpk: c_int = 2;
+
 pub unsafe fn SSL_set_tlsext_host_name(s: *mut SSL, name: *mut c_char) -> c_long {
     SSL_ctrl(
         s,
@@ -34,6 +52,26 @@ pub unsafe fn SSL_set_tlsext_status_type(s: *mut SSL, type_: c_int) -> c_long {
     )
 }
 
+#[cfg(ossl110)]
+pub unsafe fn SSL_get_tlsext_status_type(s: *mut SSL) -> c_long {
+    SSL_ctrl(s, SSL_CTRL_GET_TLSEXT_STATUS_REQ_TYPE, 0, ptr::null_mut())
+}
+
+#[cfg(ossl110)]
+pub unsafe fn SSL_CTX_set_tlsext_status_type(ctx: *mut SSL_CTX, type_: c_int) -> c_long {
+    SSL_CTX_ctrl(
+        ctx,
+        SSL_CTRL_SET_TLSEXT_STATUS_REQ_TYPE,
+        type_ as c_long,
+        ptr::null_mut(),
+    )
+}
+
+#[cfg(ossl110)]
+pub unsafe fn SSL_CTX_get_tlsext_status_type(ctx: *mut SSL_CTX) -> c_long {
+    SSL_CTX_ctrl(ctx, SSL_CTRL_GET_TLSEXT_STATUS_REQ_TYPE, 0, ptr::null_mut())
+}
+
 pub unsafe fn SSL_get_tlsext_status_ocsp_resp(ssl: *mut SSL, resp: *mut *mut c_uchar) -> c_long {
     SSL_ctrl(
         ssl,
@@ -89,6 +127,14 @@ pub unsafe fn SSL_CTX_set_tlsext_status_cb(
     SSL_CTX_callback_ctrl__fixed_rust(ctx, SSL_CTRL_SET_TLSEXT_STATUS_REQ_CB, mem::transmute(cb))
 }
 
+#[cfg(ossl110)]
+pub unsafe fn SSL_CTX_get_tlsext_status_cb(
+    ctx: *mut SSL_CTX,
+    cb: *mut Option<unsafe extern "C" fn(*mut SSL, *mut c_void) -> c_int>,
+) -> c_long {
+    SSL_CTX_ctrl(ctx, SSL_CTRL_GET_TLSEXT_STATUS_REQ_CB, 0, cb as *mut c_void)
+}
+
 pub unsafe fn SSL_CTX_set_tlsext_status_arg(ctx: *mut SSL_CTX, arg: *mut c_void) -> c_long {
     SSL_CTX_ctrl(ctx, SSL_CTRL_SET_TLSEXT_STATUS_REQ_CB_ARG, 0, arg)
 }
@@ -6,7 +6,72 @@ pub const X509_FILETYPE_PEM: c_int = 1;
 pub const X509_FILETYPE_ASN1: c_int = 2;
 pub const X509_FILETYPE_DEFAULT: c_int = 3;
 
+#[cfg(ossl110)]
+pub const X509_TRUST_DEFAULT: c_int = 0;
+pub const X509_TRUST_COMPAT: c_int = 1;
+pub const X509_TRUST_SSL_CLIENT: c_int = 2;
+pub const X509_TRUST_SSL_SERVER: c_int = 3;
+pub const X509_TRUST_EMAIL: c_int = 4;
+pub const X509_TRUST_OBJECT_SIGN: c_int = 5;
+pub const X509_TRUST_OCSP_SIGN: c_int = 6;
+pub const X509_TRUST_OCSP_REQUEST: c_int = 7;
+pub const X509_TRUST_TSA: c_int = 8;
+
+pub const XN_FLAG_SEP_MASK: c_ulong = 0xf << 16;
+pub const XN_FLAG_COMPAT: c_ulong = 0;
+pub const XN_FLAG_SEP_COMMA_PLUS: c_ulong = 1 << 16;
+pub const XN_FLAG_SEP_CPLUS_SPC: c_ulong = 2 << 16;
+pub const XN_FLAG_SEP_SPLUS_SPC: c_ulong = 3 << 16;
+pub const XN_FLAG_SEP_MULTILINE: c_ulong = 4 << 16;
+pub const XN_FLAG_DN_REV: c_ulong = 1 << 20;
+pub const XN_FLAG_FN_MASK: c_ulong = 0x3 << 21;
+pub const XN_FLAG_FN_SN: c_ulong = 0;
+pub const XN_FLAG_FN_LN: c_ulong = 1 << 21;
+pub const XN_FLAG_FN_OID: c_ulong = 2 << 21;
+pub const XN_FLAG_FN_NONE: c_ulong = 3 << 21;
+pub const XN_FLAG_SPC_EQ: c_ulong = 1 << 23;
+pub const XN_FLAG_DUMP_UNKNOWN_FIELDS: c_ulong = 1 << 24;
+pub const XN_FLAG_FN_ALIGN: c_ulong = 1 << 25;
+pub const XN_FLAG_RFC2253: c_ulong = ASN1_STRFLGS_RFC2253
+    | XN_FLAG_SEP_COMMA_PLUS
+    | XN_FLAG_DN_REV
+    | XN_FLAG_FN_SN
+    | XN_FLAG_DUMP_UNKNOWN_FIELDS;
+pub const XN_FLAG_ONELINE: c_ulong = ASN1_STRFLGS_RFC2253
+    | ASN1_STRFLGS_ESC_QUOTE
+    | XN_FLAG_SEP_CPLUS_SPC
+    | XN_FLAG_SPC_EQ
+    | XN_FLAG_FN_SN;
+pub const XN_FLAG_MULTILINE: c_ulong = ASN1_STRFLGS_ESC_CTRL
+    | ASN1_STRFLGS_ESC_MSB
+    | XN_FLAG_SEP_MULTILINE
+    | XN_FLAG_SPC_EQ
+    | XN_FLAG_FN_LN
+    | XN_FLAG_FN_ALIGN;
+
+pub const X509_FLAG_COMPAT: c_ulong = 0;
+pub const X509_FLAG_NO_HEADER: c_ulong = 1;
+pub const X509_FLAG_NO_VERSION: c_ulong = 1 << 1;
+pub const X509_FLAG_NO_SERIAL: c_ulong = 1 << 2;
+pub const X509_FLAG_NO_SIGNAME: c_ulong = 1 << 3;
+pub const X509_FLAG_NO_ISSUER: c_ulong = 1 << 4;
+pub const X509_FLAG_NO_VALIDITY: c_ulong = 1 << 5;
+pub const X509_FLAG_NO_SUBJECT: c_ulong = 1 << 6;
+pub const X509_FLAG_NO_PUBKEY: c_ulong = 1 << 7;
+pub const X509_FLAG_NO_EXTENSIONS: c_ulong = 1 << 8;
+pub const X509_FLAG_NO_SIGDUMP: c_ulong = 1 << 9;
+pub const X509_FLAG_NO_AUX: c_ulong = 1 << 10;
+pub const X509_FLAG_NO_ATTRIBUTES: c_ulong = 1 << 11;
+#[cfg(ossl110)]
+pub const X509_FLAG_NO_IDS: c_ulong = 1 << 12;
+
+pub const X509_TRUST_TRUSTED: c_int = 1;
+pub const X509_TRUST_REJECTED: c_int = 2;
+pub const X509_TRUST_UNTRUSTED: c_int = 3;
+
 pub const ASN1_R_HEADER_TOO_LONG: c_int = 123;
+pub const ASN1_R_ILLEGAL_TIME_VALUE: c_int = 184;
+pub const ASN1_R_TOO_LONG: c_int = 155;
 
 cfg_if! {
     if #[cfg(not(any(ossl110, libressl350)))] {
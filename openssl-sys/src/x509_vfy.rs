@@ -133,6 +133,21 @@ pub const X509_V_FLAG_NO_ALT_CHAINS: c_ulong = 0x100000;
 #[cfg(ossl110)]
 pub const X509_V_FLAG_NO_CHECK_TIME: c_ulong = 0x200000;
 
+pub unsafe fn X509_LOOKUP_load_file(
+    ctx: *mut X509_LOOKUP,
+    name: *const c_char,
+    _type: c_int,
+) -> c_int {
+    const X509_L_FILE_LOAD: c_int = 1;
+    X509_LOOKUP_ctrl(
+        ctx,
+        X509_L_FILE_LOAD,
+        name,
+        _type as c_long,
+        std::ptr::null_mut(),
+    )
+}
+
 pub unsafe fn X509_LOOKUP_add_dir(
     ctx: *mut X509_LOOKUP,
     name: *const c_char,
@@ -147,3 +162,17 @@ pub unsafe fn X509_LOOKUP_add_dir(
         std::ptr::null_mut(),
     )
 }
+
+cfg_if! {
+    if #[cfg(ossl110)] {
+        pub unsafe fn X509_STORE_CTX_get_ex_new_index(
+            l: c_long,
+            p: *mut c_void,
+            newf: Option<CRYPTO_EX_new>,
+            dupf: Option<CRYPTO_EX_dup>,
+            freef: Option<CRYPTO_EX_free>,
+        ) -> c_int {
+            CRYPTO_get_ex_new_index(CRYPTO_EX_INDEX_X509_STORE_CTX, l, p, newf, dupf, freef)
+        }
+    }
+}
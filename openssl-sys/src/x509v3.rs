@@ -21,6 +21,38 @@ pub const GEN_URI: c_int = 6;
 pub const GEN_IPADD: c_int = 7;
 pub const GEN_RID: c_int = 8;
 
+// Values of `DIST_POINT_NAME::type_`. OpenSSL doesn't name these.
+pub const DIST_POINT_NAME_FULLNAME: c_int = 0;
+pub const DIST_POINT_NAME_RELATIVENAME: c_int = 1;
+
+pub const X509V3_EXT_UNKNOWN_MASK: c_ulong = 0xf << 16;
+pub const X509V3_EXT_DEFAULT: c_ulong = 0;
+pub const X509V3_EXT_ERROR_UNKNOWN: c_ulong = 1 << 16;
+pub const X509V3_EXT_PARSE_UNKNOWN: c_ulong = 2 << 16;
+pub const X509V3_EXT_DUMP_UNKNOWN: c_ulong = 3 << 16;
+
+pub const X509_PURPOSE_SSL_CLIENT: c_int = 1;
+pub const X509_PURPOSE_SSL_SERVER: c_int = 2;
+pub const X509_PURPOSE_NS_SSL_SERVER: c_int = 3;
+pub const X509_PURPOSE_SMIME_SIGN: c_int = 4;
+pub const X509_PURPOSE_SMIME_ENCRYPT: c_int = 5;
+pub const X509_PURPOSE_CRL_SIGN: c_int = 6;
+pub const X509_PURPOSE_ANY: c_int = 7;
+pub const X509_PURPOSE_OCSP_HELPER: c_int = 8;
+pub const X509_PURPOSE_TIMESTAMP_SIGN: c_int = 9;
+
+pub const CRL_REASON_NONE: c_int = -1;
+pub const CRL_REASON_UNSPECIFIED: c_int = 0;
+pub const CRL_REASON_KEY_COMPROMISE: c_int = 1;
+pub const CRL_REASON_CA_COMPROMISE: c_int = 2;
+pub const CRL_REASON_AFFILIATION_CHANGED: c_int = 3;
+pub const CRL_REASON_SUPERSEDED: c_int = 4;
+pub const CRL_REASON_CESSATION_OF_OPERATION: c_int = 5;
+pub const CRL_REASON_CERTIFICATE_HOLD: c_int = 6;
+pub const CRL_REASON_REMOVE_FROM_CRL: c_int = 8;
+pub const CRL_REASON_PRIVILEGE_WITHDRAWN: c_int = 9;
+pub const CRL_REASON_AA_COMPROMISE: c_int = 10;
+
 #[cfg(any(ossl102, libressl261))]
 pub const X509_CHECK_FLAG_ALWAYS_CHECK_SUBJECT: c_uint = 0x1;
 #[cfg(any(ossl102, libressl261))]
@@ -42,6 +74,8 @@ pub const X509V3_ADD_KEEP_EXISTING: c_ulong = 4;
 pub const X509V3_ADD_DELETE: c_ulong = 5;
 pub const X509V3_ADD_SILENT: c_ulong = 0x10;
 
+pub const X509V3_R_BAD_IP_ADDRESS: c_int = 118;
+
 pub const EXFLAG_BCONS: u32 = 0x1;
 pub const EXFLAG_KUSAGE: u32 = 0x2;
 pub const EXFLAG_XKUSAGE: u32 = 0x4;
@@ -34,11 +34,13 @@ use std::fmt;
 use std::ptr;
 use std::slice;
 use std::str;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use crate::bio::MemBio;
 use crate::bn::{BigNum, BigNumRef};
 use crate::error::ErrorStack;
 use crate::nid::Nid;
+use crate::stack::Stackable;
 use crate::string::OpensslString;
 use crate::{cvt, cvt_p};
 use openssl_macros::corresponds;
@@ -157,6 +159,8 @@ impl Asn1Type {
     }
 }
 
+const SECS_PER_DAY: i64 = 60 * 60 * 24;
+
 /// Difference between two ASN1 times.
 ///
 /// This `struct` is created by the [`diff`] method on [`Asn1TimeRef`]. See its
@@ -212,6 +216,29 @@ impl Asn1TimeRef {
         }
     }
 
+    /// Returns the time as the number of seconds since the Unix epoch.
+    ///
+    /// Times before the epoch are negative.
+    #[corresponds(ASN1_TIME_diff)]
+    #[cfg(ossl102)]
+    pub fn to_unix(&self) -> Result<i64, ErrorStack> {
+        let epoch = Asn1Time::from_unix(0)?;
+        let diff = epoch.diff(self)?;
+        Ok(i64::from(diff.days) * SECS_PER_DAY + i64::from(diff.secs))
+    }
+
+    /// Converts the time to a `SystemTime`.
+    #[corresponds(ASN1_TIME_diff)]
+    #[cfg(ossl102)]
+    pub fn to_system_time(&self) -> Result<SystemTime, ErrorStack> {
+        let secs = self.to_unix()?;
+        if secs >= 0 {
+            Ok(UNIX_EPOCH + Duration::from_secs(secs as u64))
+        } else {
+            Ok(UNIX_EPOCH - Duration::from_secs(secs.unsigned_abs()))
+        }
+    }
+
     /// Compare two times
     #[corresponds(ASN1_TIME_compare)]
     #[cfg(ossl102)]
@@ -226,6 +253,63 @@ impl Asn1TimeRef {
 
         Ok(Ordering::Equal)
     }
+
+    /// Returns the encoding of the time, either `Asn1Type::UTCTIME` or
+    /// `Asn1Type::GENERALIZEDTIME`.
+    #[corresponds(ASN1_STRING_type)]
+    pub fn asn1_type(&self) -> Asn1Type {
+        unsafe { Asn1Type::from_raw(ffi::ASN1_STRING_type(self.as_ptr() as *mut _)) }
+    }
+
+    /// Returns a copy of the time encoded as a GeneralizedTime.
+    ///
+    /// Times between 1950 and 2049 are normally encoded as a UTCTime, as required by RFC 5280.
+    /// Some profiles instead require GeneralizedTime to be used for all dates.
+    #[corresponds(ASN1_TIME_to_generalizedtime)]
+    pub fn to_generalized_time(&self) -> Result<Asn1Time, ErrorStack> {
+        unsafe {
+            let time = cvt_p(ffi::ASN1_TIME_to_generalizedtime(
+                self.as_ptr(),
+                ptr::null_mut(),
+            ))?;
+            Ok(Asn1Time::from_ptr(time as *mut ffi::ASN1_TIME))
+        }
+    }
+
+    /// Returns a copy of the time encoded as a UTCTime.
+    ///
+    /// UTCTime only represents the years 1950 to 2049, and an error is returned for other times.
+    #[corresponds(ASN1_TIME_set_string)]
+    pub fn to_utc_time(&self) -> Result<Asn1Time, ErrorStack> {
+        let generalized = self.to_generalized_time()?;
+        let data = unsafe { Asn1StringRef::from_ptr(generalized.as_ptr() as *mut _).as_slice() };
+        match data.get(..4) {
+            Some(year) if &b"1950"[..] <= year && year <= &b"2049"[..] => {}
+            _ => {
+                return Err(ErrorStack::from_reason(
+                    ffi::ERR_LIB_ASN1,
+                    ffi::ASN1_R_ILLEGAL_TIME_VALUE,
+                ))
+            }
+        }
+
+        unsafe {
+            // The century is implied by the two digit year of a UTCTime.
+            let s = CString::new(&data[2..]).unwrap();
+
+            let time = Asn1Time::new()?;
+            cvt(ffi::ASN1_TIME_set_string(time.as_ptr(), s.as_ptr()))?;
+
+            Ok(time)
+        }
+    }
+
+    to_der! {
+        /// Serializes the time into a DER-encoded ASN.1 `Time`.
+        #[corresponds(i2d_ASN1_TIME)]
+        to_der,
+        ffi::i2d_ASN1_TIME
+    }
 }
 
 #[cfg(ossl102)]
@@ -335,6 +419,33 @@ impl Asn1Time {
         }
     }
 
+    /// Creates a new time from a `SystemTime`.
+    ///
+    /// Sub-second precision is discarded. Times outside of the range representable in ASN.1,
+    /// years 0 through 9999, return an error.
+    #[corresponds(ASN1_TIME_adj)]
+    pub fn from_system_time(time: SystemTime) -> Result<Asn1Time, ErrorStack> {
+        let secs = match time.duration_since(UNIX_EPOCH) {
+            Ok(d) => d.as_secs() as i128,
+            Err(e) => -(e.duration().as_secs() as i128),
+        };
+        let days = secs.div_euclid(SECS_PER_DAY as i128);
+        let secs = secs.rem_euclid(SECS_PER_DAY as i128);
+        let days = days.clamp(c_int::min_value() as i128, c_int::max_value() as i128);
+
+        ffi::init();
+
+        unsafe {
+            let handle = cvt_p(ffi::ASN1_TIME_adj(
+                ptr::null_mut(),
+                0,
+                days as c_int,
+                secs as c_long,
+            ))?;
+            Ok(Asn1Time::from_ptr(handle))
+        }
+    }
+
     /// Creates a new time corresponding to the specified ASN1 time string.
     #[corresponds(ASN1_TIME_set_string)]
     #[allow(clippy::should_implement_trait)]
@@ -364,6 +475,14 @@ impl Asn1Time {
             Ok(time)
         }
     }
+
+    from_der! {
+        /// Deserializes a DER-encoded ASN.1 `Time`.
+        #[corresponds(d2i_ASN1_TIME)]
+        from_der,
+        Asn1Time,
+        ffi::d2i_ASN1_TIME
+    }
 }
 
 #[cfg(ossl102)]
@@ -509,6 +628,18 @@ impl Asn1Integer {
     pub fn from_bn(bn: &BigNumRef) -> Result<Self, ErrorStack> {
         bn.to_asn1_integer()
     }
+
+    from_der! {
+        /// Deserializes a DER-encoded ASN.1 `INTEGER`.
+        #[corresponds(d2i_ASN1_INTEGER)]
+        from_der,
+        Asn1Integer,
+        ffi::d2i_ASN1_INTEGER
+    }
+}
+
+impl Stackable for Asn1Integer {
+    type StackType = ffi::stack_st_ASN1_INTEGER;
 }
 
 impl Asn1IntegerRef {
@@ -535,6 +666,13 @@ impl Asn1IntegerRef {
     pub fn set(&mut self, value: i32) -> Result<(), ErrorStack> {
         unsafe { cvt(ffi::ASN1_INTEGER_set(self.as_ptr(), value as c_long)).map(|_| ()) }
     }
+
+    to_der! {
+        /// Serializes the integer into a DER-encoded ASN.1 `INTEGER`.
+        #[corresponds(i2d_ASN1_INTEGER)]
+        to_der,
+        ffi::i2d_ASN1_INTEGER
+    }
 }
 
 foreign_type_and_impl_send_sync! {
@@ -551,6 +689,16 @@ foreign_type_and_impl_send_sync! {
     pub struct Asn1BitStringRef;
 }
 
+impl Asn1BitString {
+    from_der! {
+        /// Deserializes a DER-encoded ASN.1 `BIT STRING`.
+        #[corresponds(d2i_ASN1_BIT_STRING)]
+        from_der,
+        Asn1BitString,
+        ffi::d2i_ASN1_BIT_STRING
+    }
+}
+
 impl Asn1BitStringRef {
     /// Returns the Asn1BitString as a slice.
     #[corresponds(ASN1_STRING_get0_data)]
@@ -568,6 +716,13 @@ impl Asn1BitStringRef {
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
+
+    to_der! {
+        /// Serializes the bit string into a DER-encoded ASN.1 `BIT STRING`.
+        #[corresponds(i2d_ASN1_BIT_STRING)]
+        to_der,
+        ffi::i2d_ASN1_BIT_STRING
+    }
 }
 
 foreign_type_and_impl_send_sync! {
@@ -617,6 +772,18 @@ impl Asn1Object {
             slice::from_raw_parts(ffi::OBJ_get0_data(self.as_ptr()), len)
         }
     }
+
+    from_der! {
+        /// Deserializes a DER-encoded ASN.1 `OBJECT IDENTIFIER`, including its tag and length.
+        #[corresponds(d2i_ASN1_OBJECT)]
+        from_der,
+        Asn1Object,
+        ffi::d2i_ASN1_OBJECT
+    }
+}
+
+impl Stackable for Asn1Object {
+    type StackType = ffi::stack_st_ASN1_OBJECT;
 }
 
 impl Asn1ObjectRef {
@@ -624,6 +791,14 @@ impl Asn1ObjectRef {
     pub fn nid(&self) -> Nid {
         unsafe { Nid::from_raw(ffi::OBJ_obj2nid(self.as_ptr())) }
     }
+
+    to_der! {
+        /// Serializes the OID into a DER-encoded ASN.1 `OBJECT IDENTIFIER`, including its tag
+        /// and length.
+        #[corresponds(i2d_ASN1_OBJECT)]
+        to_der,
+        ffi::i2d_ASN1_OBJECT
+    }
 }
 
 impl fmt::Display for Asn1ObjectRef {
@@ -695,6 +870,45 @@ mod tests {
         assert_eq!("Jan  1 00:00:00 1970 GMT", t.to_string());
     }
 
+    #[test]
+    fn time_from_system_time() {
+        let t = Asn1Time::from_system_time(UNIX_EPOCH + Duration::from_millis(86_400_500)).unwrap();
+        assert_eq!("Jan  2 00:00:00 1970 GMT", t.to_string());
+
+        let t = Asn1Time::from_system_time(UNIX_EPOCH - Duration::from_secs(1)).unwrap();
+        assert_eq!("Dec 31 23:59:59 1969 GMT", t.to_string());
+
+        let far = UNIX_EPOCH + Duration::from_secs(400_000_000_000);
+        assert!(Asn1Time::from_system_time(far).is_err());
+    }
+
+    #[test]
+    #[cfg(ossl102)]
+    fn time_to_system_time() {
+        let t = Asn1Time::from_str("99991231235959Z").unwrap();
+        assert_eq!(t.to_unix().unwrap(), 253_402_300_799);
+        assert_eq!(
+            t.to_system_time().unwrap(),
+            UNIX_EPOCH + Duration::from_secs(253_402_300_799)
+        );
+
+        let t = Asn1Time::from_str("19500101000000Z").unwrap();
+        assert_eq!(t.to_unix().unwrap(), -631_152_000);
+        assert_eq!(
+            t.to_system_time().unwrap(),
+            UNIX_EPOCH - Duration::from_secs(631_152_000)
+        );
+
+        let now = SystemTime::now();
+        let t = Asn1Time::from_system_time(now).unwrap();
+        let secs = now.duration_since(UNIX_EPOCH).unwrap().as_secs();
+        assert_eq!(t.to_unix().unwrap(), secs as i64);
+
+        let expiry = Asn1Time::days_from_now(10).unwrap();
+        let deadline = Asn1Time::from_system_time(now + Duration::from_secs(30 * 86_400)).unwrap();
+        assert!(expiry < deadline);
+    }
+
     #[test]
     #[cfg(ossl102)]
     fn time_eq() {
@@ -766,4 +980,68 @@ mod tests {
             &[0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01],
         );
     }
+
+    #[test]
+    fn der_round_trips() {
+        let time = Asn1Time::from_str("99991231235959Z").unwrap();
+        let der = time.to_der().unwrap();
+        assert_eq!(&der[..2], &[0x18, 15]);
+        let parsed = Asn1Time::from_der(&der).unwrap();
+        assert_eq!(parsed.to_string(), time.to_string());
+
+        let bn = BigNum::from_dec_str("-1234567890123456789").unwrap();
+        let integer = bn.to_asn1_integer().unwrap();
+        let parsed = Asn1Integer::from_der(&integer.to_der().unwrap()).unwrap();
+        assert_eq!(parsed.to_bn().unwrap(), bn);
+
+        let object = Asn1Object::from_str("2.999.1").unwrap();
+        let der = object.to_der().unwrap();
+        assert_eq!(der, [0x06, 0x03, 0x88, 0x37, 0x01]);
+        let parsed = Asn1Object::from_der(&der).unwrap();
+        assert_eq!(parsed.to_string(), "2.999.1");
+
+        let bits = Asn1BitString::from_der(&[0x03, 0x03, 0x00, 0xab, 0xcd]).unwrap();
+        assert_eq!(bits.as_slice(), &[0xab, 0xcd]);
+        assert_eq!(bits.to_der().unwrap(), [0x03, 0x03, 0x00, 0xab, 0xcd]);
+
+        assert!(Asn1Integer::from_der(&der).is_err());
+    }
+
+    #[test]
+    fn time_encoding() {
+        let time = Asn1Time::from_str("301231235959Z").unwrap();
+        assert_eq!(time.asn1_type(), Asn1Type::UTCTIME);
+        let generalized = time.to_generalized_time().unwrap();
+        assert_eq!(generalized.asn1_type(), Asn1Type::GENERALIZEDTIME);
+        assert_eq!(generalized.to_string(), time.to_string());
+        assert_eq!(&generalized.to_der().unwrap()[2..], b"20301231235959Z");
+
+        let time = Asn1Time::from_unix(2_556_143_999).unwrap();
+        assert_eq!(time.asn1_type(), Asn1Type::GENERALIZEDTIME);
+    }
+
+    #[test]
+    fn utc_time_encoding() {
+        let time = Asn1Time::from_str("20301231235959Z").unwrap();
+        assert_eq!(time.asn1_type(), Asn1Type::GENERALIZEDTIME);
+        let utc = time.to_utc_time().unwrap();
+        assert_eq!(utc.asn1_type(), Asn1Type::UTCTIME);
+        assert_eq!(utc.to_string(), time.to_string());
+        assert_eq!(&utc.to_der().unwrap()[2..], b"301231235959Z");
+
+        let time = Asn1Time::from_str("19500101000000Z").unwrap();
+        assert_eq!(
+            &time.to_utc_time().unwrap().to_der().unwrap()[2..],
+            b"500101000000Z"
+        );
+
+        assert!(Asn1Time::from_str("19491231235959Z")
+            .unwrap()
+            .to_utc_time()
+            .is_err());
+        assert!(Asn1Time::from_str("20500101000000Z")
+            .unwrap()
+            .to_utc_time()
+            .is_err());
+    }
 }
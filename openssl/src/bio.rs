@@ -63,6 +63,10 @@ impl MemBio {
         unsafe {
             let mut ptr = ptr::null_mut();
             let len = ffi::BIO_get_mem_data(self.0, &mut ptr);
+            // An empty BIO may not have allocated a buffer yet.
+            if len == 0 {
+                return &[];
+            }
             slice::from_raw_parts(ptr as *const _ as *const _, len as usize)
         }
     }
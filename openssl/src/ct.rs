@@ -0,0 +1,671 @@
+//! Certificate Transparency.
+//!
+//! Signed certificate timestamps (SCTs) are promises by a Certificate Transparency log that a
+//! certificate has been, or will be, publicly logged. They are defined in [RFC 6962].
+//!
+//! Requires OpenSSL 1.1.0 or newer.
+//!
+//! [RFC 6962]: https://tools.ietf.org/html/rfc6962
+use foreign_types::{ForeignType, ForeignTypeRef};
+use libc::{c_int, c_long};
+use std::cmp;
+use std::ffi::{CStr, CString};
+use std::path::Path;
+use std::ptr;
+use std::slice;
+use std::str;
+
+use crate::error::ErrorStack;
+use crate::nid::Nid;
+use crate::pkey::{PKeyRef, Public};
+use crate::stack::{Stack, Stackable};
+use crate::util::ForeignTypeRefExt;
+use crate::x509::X509Ref;
+use crate::{cvt, cvt_p};
+use openssl_macros::corresponds;
+
+/// The version of a signed certificate timestamp.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SctVersion(c_int);
+
+impl SctVersion {
+    /// The version is not known.
+    pub const NOT_SET: SctVersion = SctVersion(ffi::SCT_VERSION_NOT_SET);
+
+    /// Version 1, as defined in RFC 6962.
+    pub const V1: SctVersion = SctVersion(ffi::SCT_VERSION_V1);
+
+    /// Constructs an `SctVersion` from a raw OpenSSL value.
+    pub fn from_raw(raw: c_int) -> SctVersion {
+        SctVersion(raw)
+    }
+
+    /// Returns the raw OpenSSL value represented by this type.
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn as_raw(&self) -> c_int {
+        self.0
+    }
+}
+
+/// The type of log entry a signed certificate timestamp refers to.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct LogEntryType(c_int);
+
+impl LogEntryType {
+    /// The entry type is not known.
+    pub const NOT_SET: LogEntryType = LogEntryType(ffi::CT_LOG_ENTRY_TYPE_NOT_SET);
+
+    /// The log entry is a final certificate.
+    pub const X509: LogEntryType = LogEntryType(ffi::CT_LOG_ENTRY_TYPE_X509);
+
+    /// The log entry is a precertificate.
+    pub const PRECERT: LogEntryType = LogEntryType(ffi::CT_LOG_ENTRY_TYPE_PRECERT);
+
+    /// Constructs a `LogEntryType` from a raw OpenSSL value.
+    pub fn from_raw(raw: c_int) -> LogEntryType {
+        LogEntryType(raw)
+    }
+
+    /// Returns the raw OpenSSL value represented by this type.
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn as_raw(&self) -> c_int {
+        self.0
+    }
+}
+
+/// The result of validating a signed certificate timestamp.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SctValidationStatus(c_int);
+
+impl SctValidationStatus {
+    /// The SCT has not been validated.
+    pub const NOT_SET: SctValidationStatus =
+        SctValidationStatus(ffi::SCT_VALIDATION_STATUS_NOT_SET);
+
+    /// The SCT was issued by a log which is not in the configured log list.
+    pub const UNKNOWN_LOG: SctValidationStatus =
+        SctValidationStatus(ffi::SCT_VALIDATION_STATUS_UNKNOWN_LOG);
+
+    /// The SCT is valid.
+    pub const VALID: SctValidationStatus = SctValidationStatus(ffi::SCT_VALIDATION_STATUS_VALID);
+
+    /// The SCT's signature is invalid.
+    pub const INVALID: SctValidationStatus =
+        SctValidationStatus(ffi::SCT_VALIDATION_STATUS_INVALID);
+
+    /// The SCT could not be validated.
+    pub const UNVERIFIED: SctValidationStatus =
+        SctValidationStatus(ffi::SCT_VALIDATION_STATUS_UNVERIFIED);
+
+    /// The SCT has an unsupported version.
+    pub const UNKNOWN_VERSION: SctValidationStatus =
+        SctValidationStatus(ffi::SCT_VALIDATION_STATUS_UNKNOWN_VERSION);
+
+    /// Constructs an `SctValidationStatus` from a raw OpenSSL value.
+    pub fn from_raw(raw: c_int) -> SctValidationStatus {
+        SctValidationStatus(raw)
+    }
+
+    /// Returns the raw OpenSSL value represented by this type.
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn as_raw(&self) -> c_int {
+        self.0
+    }
+}
+
+/// Where a signed certificate timestamp was obtained from.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct SctSource(c_int);
+
+impl SctSource {
+    /// The source is not known.
+    pub const UNKNOWN: SctSource = SctSource(ffi::SCT_SOURCE_UNKNOWN);
+
+    /// The SCT was sent in the `signed_certificate_timestamp` TLS extension.
+    pub const TLS_EXTENSION: SctSource = SctSource(ffi::SCT_SOURCE_TLS_EXTENSION);
+
+    /// The SCT was embedded in the certificate.
+    pub const X509V3_EXTENSION: SctSource = SctSource(ffi::SCT_SOURCE_X509V3_EXTENSION);
+
+    /// The SCT was included in a stapled OCSP response.
+    pub const OCSP_STAPLED_RESPONSE: SctSource = SctSource(ffi::SCT_SOURCE_OCSP_STAPLED_RESPONSE);
+
+    /// Constructs an `SctSource` from a raw OpenSSL value.
+    pub fn from_raw(raw: c_int) -> SctSource {
+        SctSource(raw)
+    }
+
+    /// Returns the raw OpenSSL value represented by this type.
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn as_raw(&self) -> c_int {
+        self.0
+    }
+}
+
+foreign_type_and_impl_send_sync! {
+    type CType = ffi::SCT;
+    fn drop = ffi::SCT_free;
+
+    /// A signed certificate timestamp.
+    pub struct Sct;
+    /// Reference to an [`Sct`].
+    pub struct SctRef;
+}
+
+impl Stackable for Sct {
+    type StackType = ffi::stack_st_SCT;
+}
+
+impl Sct {
+    /// Parses a list of SCTs in the TLS encoding used by RFC 6962.
+    ///
+    /// This is the contents of the `signed_certificate_timestamp` TLS extension. The source of
+    /// the returned SCTs is not set.
+    #[corresponds(o2i_SCT_LIST)]
+    pub fn list_from_tls(data: &[u8]) -> Result<Stack<Sct>, ErrorStack> {
+        unsafe {
+            ffi::init();
+            let mut p = data.as_ptr();
+            let list = cvt_p(ffi::o2i_SCT_LIST(ptr::null_mut(), &mut p, data.len()))?;
+            Ok(Stack::from_ptr(list))
+        }
+    }
+
+    /// Parses a list of SCTs wrapped in a DER encoded `OCTET STRING`.
+    ///
+    /// This is the value of the SCT list extensions of certificates and OCSP responses. The
+    /// source of the returned SCTs is not set.
+    #[corresponds(d2i_SCT_LIST)]
+    pub fn list_from_der(der: &[u8]) -> Result<Stack<Sct>, ErrorStack> {
+        unsafe {
+            ffi::init();
+            let len = cmp::min(der.len(), c_long::max_value() as usize) as c_long;
+            let mut p = der.as_ptr();
+            let list = cvt_p(ffi::d2i_SCT_LIST(ptr::null_mut(), &mut p, len))?;
+            Ok(Stack::from_ptr(list))
+        }
+    }
+}
+
+impl SctRef {
+    /// Returns the version of the SCT.
+    #[corresponds(SCT_get_version)]
+    pub fn version(&self) -> SctVersion {
+        unsafe { SctVersion(ffi::SCT_get_version(self.as_ptr())) }
+    }
+
+    /// Returns the type of log entry the SCT refers to.
+    #[corresponds(SCT_get_log_entry_type)]
+    pub fn log_entry_type(&self) -> LogEntryType {
+        unsafe { LogEntryType(ffi::SCT_get_log_entry_type(self.as_ptr())) }
+    }
+
+    /// Returns the ID of the log which issued the SCT.
+    ///
+    /// For version 1 SCTs this is the SHA-256 hash of the log's public key.
+    #[corresponds(SCT_get0_log_id)]
+    pub fn log_id(&self) -> &[u8] {
+        unsafe {
+            let mut ptr = ptr::null_mut();
+            let len = ffi::SCT_get0_log_id(self.as_ptr(), &mut ptr);
+            bytes(ptr, len)
+        }
+    }
+
+    /// Returns the time at which the SCT was issued, in milliseconds since the Unix epoch.
+    #[corresponds(SCT_get_timestamp)]
+    pub fn timestamp(&self) -> u64 {
+        unsafe { ffi::SCT_get_timestamp(self.as_ptr()) }
+    }
+
+    /// Returns the algorithm used to sign the SCT.
+    #[corresponds(SCT_get_signature_nid)]
+    pub fn signature_nid(&self) -> Nid {
+        unsafe { Nid::from_raw(ffi::SCT_get_signature_nid(self.as_ptr())) }
+    }
+
+    /// Returns the log's signature over the SCT.
+    #[corresponds(SCT_get0_signature)]
+    pub fn signature(&self) -> &[u8] {
+        unsafe {
+            let mut ptr = ptr::null_mut();
+            let len = ffi::SCT_get0_signature(self.as_ptr(), &mut ptr);
+            bytes(ptr, len)
+        }
+    }
+
+    /// Returns the raw extensions of the SCT.
+    #[corresponds(SCT_get0_extensions)]
+    pub fn extensions(&self) -> &[u8] {
+        unsafe {
+            let mut ptr = ptr::null_mut();
+            let len = ffi::SCT_get0_extensions(self.as_ptr(), &mut ptr);
+            bytes(ptr, len)
+        }
+    }
+
+    /// Returns the result of validating the SCT.
+    ///
+    /// SCTs are validated by [`validate`], or during a TLS handshake with Certificate
+    /// Transparency enabled.
+    ///
+    /// [`validate`]: #method.validate
+    #[corresponds(SCT_get_validation_status)]
+    pub fn validation_status(&self) -> SctValidationStatus {
+        unsafe { SctValidationStatus(ffi::SCT_get_validation_status(self.as_ptr())) }
+    }
+
+    /// Records where the SCT was obtained from.
+    ///
+    /// This also sets the log entry type, which is needed to validate the SCT: SCTs embedded in a
+    /// certificate refer to its precertificate, while all others refer to the certificate itself.
+    #[corresponds(SCT_set_source)]
+    pub fn set_source(&mut self, source: SctSource) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::SCT_set_source(self.as_ptr(), source.0)).map(|_| ()) }
+    }
+
+    /// Validates the SCT for `cert` against the logs in `log_store`.
+    ///
+    /// `issuer` is the certificate which issued `cert`, and is required to validate SCTs for
+    /// precertificates. The result is returned and recorded in [`validation_status`]. An error
+    /// is only returned if validation could not be performed at all.
+    ///
+    /// [`validation_status`]: #method.validation_status
+    #[corresponds(SCT_validate)]
+    pub fn validate(
+        &mut self,
+        cert: &X509Ref,
+        issuer: Option<&X509Ref>,
+        log_store: &CtLogStoreRef,
+    ) -> Result<SctValidationStatus, ErrorStack> {
+        unsafe {
+            let ctx = PolicyEvalCtx(cvt_p(ffi::CT_POLICY_EVAL_CTX_new())?);
+            cvt(ffi::CT_POLICY_EVAL_CTX_set1_cert(ctx.0, cert.as_ptr()))?;
+            if let Some(issuer) = issuer {
+                cvt(ffi::CT_POLICY_EVAL_CTX_set1_issuer(ctx.0, issuer.as_ptr()))?;
+            }
+            ffi::CT_POLICY_EVAL_CTX_set_shared_CTLOG_STORE(ctx.0, log_store.as_ptr());
+
+            if ffi::SCT_validate(self.as_ptr(), ctx.0) < 0 {
+                return Err(ErrorStack::get());
+            }
+        }
+
+        Ok(self.validation_status())
+    }
+}
+
+struct PolicyEvalCtx(*mut ffi::CT_POLICY_EVAL_CTX);
+
+impl Drop for PolicyEvalCtx {
+    fn drop(&mut self) {
+        unsafe { ffi::CT_POLICY_EVAL_CTX_free(self.0) }
+    }
+}
+
+foreign_type_and_impl_send_sync! {
+    type CType = ffi::CTLOG;
+    fn drop = ffi::CTLOG_free;
+
+    /// A Certificate Transparency log.
+    pub struct CtLog;
+    /// Reference to a [`CtLog`].
+    pub struct CtLogRef;
+}
+
+impl CtLogRef {
+    /// Returns the name of the log.
+    ///
+    /// Returns `None` if the name is not valid UTF-8.
+    #[corresponds(CTLOG_get0_name)]
+    pub fn name(&self) -> Option<&str> {
+        unsafe {
+            let name = ffi::CTLOG_get0_name(self.as_ptr());
+            str::from_utf8(CStr::from_ptr(name).to_bytes()).ok()
+        }
+    }
+
+    /// Returns the ID of the log, the SHA-256 hash of its public key.
+    #[corresponds(CTLOG_get0_log_id)]
+    pub fn log_id(&self) -> &[u8] {
+        unsafe {
+            let mut ptr = ptr::null();
+            let mut len = 0;
+            ffi::CTLOG_get0_log_id(self.as_ptr(), &mut ptr, &mut len);
+            bytes(ptr, len)
+        }
+    }
+
+    /// Returns the public key of the log.
+    #[corresponds(CTLOG_get0_public_key)]
+    pub fn public_key(&self) -> &PKeyRef<Public> {
+        unsafe { PKeyRef::from_ptr(ffi::CTLOG_get0_public_key(self.as_ptr())) }
+    }
+}
+
+foreign_type_and_impl_send_sync! {
+    type CType = ffi::CTLOG_STORE;
+    fn drop = ffi::CTLOG_STORE_free;
+
+    /// A collection of Certificate Transparency logs.
+    ///
+    /// Logs are loaded from files in OpenSSL's log list format, in which each log is a section
+    /// containing a `description` and the base64 encoded DER public `key` of the log. The
+    /// `enabled_logs` entry lists the names of the sections to load:
+    ///
+    /// ```text
+    /// enabled_logs = example
+    ///
+    /// [example]
+    /// description = Example log
+    /// key = MFkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDQgAE...
+    /// ```
+    pub struct CtLogStore;
+    /// Reference to a [`CtLogStore`].
+    pub struct CtLogStoreRef;
+}
+
+impl CtLogStore {
+    /// Creates a new, empty, log store.
+    #[corresponds(CTLOG_STORE_new)]
+    pub fn new() -> Result<CtLogStore, ErrorStack> {
+        unsafe {
+            ffi::init();
+            cvt_p(ffi::CTLOG_STORE_new()).map(CtLogStore)
+        }
+    }
+}
+
+impl CtLogStoreRef {
+    /// Loads logs from a log list file.
+    #[corresponds(CTLOG_STORE_load_file)]
+    pub fn load_file<P: AsRef<Path>>(&mut self, file: P) -> Result<(), ErrorStack> {
+        let file = CString::new(file.as_ref().as_os_str().to_str().unwrap()).unwrap();
+        unsafe { cvt(ffi::CTLOG_STORE_load_file(self.as_ptr(), file.as_ptr())).map(|_| ()) }
+    }
+
+    /// Loads logs from the default log list file.
+    ///
+    /// This is the `ct_log_list.cnf` file in the OpenSSL directory, or the file named by the
+    /// `CTLOG_FILE` environment variable.
+    #[corresponds(CTLOG_STORE_load_default_file)]
+    pub fn load_default_file(&mut self) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::CTLOG_STORE_load_default_file(self.as_ptr())).map(|_| ()) }
+    }
+
+    /// Looks up a log by its ID.
+    #[corresponds(CTLOG_STORE_get0_log_by_id)]
+    pub fn log_by_id(&self, log_id: &[u8]) -> Option<&CtLogRef> {
+        unsafe {
+            let log = ffi::CTLOG_STORE_get0_log_by_id(self.as_ptr(), log_id.as_ptr(), log_id.len());
+            CtLogRef::from_const_ptr_opt(log)
+        }
+    }
+}
+
+unsafe fn bytes<'a>(ptr: *const u8, len: usize) -> &'a [u8] {
+    if ptr.is_null() || len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(ptr, len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::asn1::{Asn1Object, Asn1Time};
+    use crate::bn::BigNum;
+    use crate::ec::{EcGroup, EcKey};
+    use crate::hash::{hash, MessageDigest};
+    use crate::ocsp::{
+        OcspBasicResponse, OcspCertId, OcspCertStatus, OcspFlag, OcspResponse, OcspResponseStatus,
+        OcspRevokedStatus,
+    };
+    use crate::pkey::PKey;
+    use crate::sign::Signer;
+    use crate::x509::{X509Builder, X509Extension, X509Name, X509};
+
+    // Builds the signed entry of an SCT for `cert`.
+    fn x509_entry(cert: &X509Ref) -> Vec<u8> {
+        let cert = cert.to_der().unwrap();
+        let mut entry = vec![0, 0];
+        entry.extend_from_slice(&(cert.len() as u32).to_be_bytes()[1..]);
+        entry.extend_from_slice(&cert);
+        entry
+    }
+
+    // Builds the signed entry of an SCT for the precertificate `cert` issued by `issuer`.
+    fn precert_entry(cert: &X509Ref, issuer: &X509Ref) -> Vec<u8> {
+        let issuer_key = issuer.public_key().unwrap().public_key_to_der().unwrap();
+        let tbs = cert.precertificate_tbs().unwrap();
+        let mut entry = vec![0, 1];
+        entry.extend_from_slice(&hash(MessageDigest::sha256(), &issuer_key).unwrap());
+        entry.extend_from_slice(&(tbs.len() as u32).to_be_bytes()[1..]);
+        entry.extend_from_slice(&tbs);
+        entry
+    }
+
+    // Builds a TLS encoded SCT list holding a single SCT for `entry`, signed by `log_key`.
+    fn sct_list(entry: &[u8], log_key: &PKeyRef<crate::pkey::Private>, timestamp: u64) -> Vec<u8> {
+        let log_id = hash(
+            MessageDigest::sha256(),
+            &log_key.public_key_to_der().unwrap(),
+        )
+        .unwrap();
+
+        let mut signed = vec![0, 0];
+        signed.extend_from_slice(&timestamp.to_be_bytes());
+        signed.extend_from_slice(entry);
+        signed.extend_from_slice(&[0, 0]);
+
+        let mut signer = Signer::new(MessageDigest::sha256(), log_key).unwrap();
+        signer.update(&signed).unwrap();
+        let signature = signer.sign_to_vec().unwrap();
+
+        let mut sct = vec![0];
+        sct.extend_from_slice(&log_id);
+        sct.extend_from_slice(&timestamp.to_be_bytes());
+        sct.extend_from_slice(&[0, 0, 4, 3]);
+        sct.extend_from_slice(&(signature.len() as u16).to_be_bytes());
+        sct.extend_from_slice(&signature);
+
+        let mut list = ((sct.len() + 2) as u16).to_be_bytes().to_vec();
+        list.extend_from_slice(&(sct.len() as u16).to_be_bytes());
+        list.extend_from_slice(&sct);
+        list
+    }
+
+    #[test]
+    fn validate() {
+        let cert = X509::from_pem(include_bytes!("../test/cert.pem")).unwrap();
+        let other = X509::from_pem(include_bytes!("../test/root-ca.pem")).unwrap();
+        let log_key = PKey::private_key_from_pem(include_bytes!("../test/ct_log.pem")).unwrap();
+
+        let mut store = CtLogStore::new().unwrap();
+        store.load_file("test/ct_log_list.cnf").unwrap();
+
+        let list = sct_list(&x509_entry(&cert), &log_key, 1_600_000_000_000);
+        let mut scts = Sct::list_from_tls(&list).unwrap();
+        assert_eq!(scts.len(), 1);
+        let sct = scts.get_mut(0).unwrap();
+        assert_eq!(sct.version(), SctVersion::V1);
+        assert_eq!(sct.timestamp(), 1_600_000_000_000);
+        assert_eq!(sct.signature_nid(), Nid::ECDSA_WITH_SHA256);
+        assert!(sct.extensions().is_empty());
+        assert_eq!(sct.validation_status(), SctValidationStatus::NOT_SET);
+
+        let log = store.log_by_id(sct.log_id()).unwrap();
+        assert_eq!(log.name(), Some("Test log"));
+        assert_eq!(log.log_id(), sct.log_id());
+        assert!(log.public_key().public_eq(&log_key));
+        assert!(store.log_by_id(&[0; 32]).is_none());
+
+        sct.set_source(SctSource::TLS_EXTENSION).unwrap();
+        assert_eq!(sct.log_entry_type(), LogEntryType::X509);
+        assert_eq!(
+            sct.validate(&cert, None, &store).unwrap(),
+            SctValidationStatus::VALID
+        );
+        assert_eq!(
+            sct.validate(&other, None, &store).unwrap(),
+            SctValidationStatus::INVALID
+        );
+        assert_eq!(
+            sct.validate(&cert, None, &CtLogStore::new().unwrap())
+                .unwrap(),
+            SctValidationStatus::UNKNOWN_LOG
+        );
+
+        let mut der = vec![0x04, list.len() as u8];
+        der.extend_from_slice(&list);
+        let scts = Sct::list_from_der(&der).unwrap();
+        assert_eq!(scts[0].signature(), sct.signature());
+
+        assert!(Sct::list_from_tls(&list[1..]).is_err());
+        assert!(cert.scts().is_none());
+    }
+
+    #[test]
+    fn ocsp_stapled_response() {
+        let ca = X509::from_pem(include_bytes!("../test/root-ca.pem")).unwrap();
+        let ca_key = PKey::private_key_from_pem(include_bytes!("../test/root-ca.key")).unwrap();
+        let cert = X509::from_pem(include_bytes!("../test/cert.pem")).unwrap();
+        let log_key = PKey::private_key_from_pem(include_bytes!("../test/ct_log.pem")).unwrap();
+
+        let mut store = CtLogStore::new().unwrap();
+        store.load_file("test/ct_log_list.cnf").unwrap();
+
+        let list = sct_list(&x509_entry(&cert), &log_key, 1_600_000_000_000);
+        assert!(list.len() < 0x80);
+        let mut der = vec![0x04, list.len() as u8];
+        der.extend_from_slice(&list);
+        let oid = Asn1Object::from_str("1.3.6.1.4.1.11129.2.4.5").unwrap();
+        let extension = X509Extension::new_from_der(&oid, false, &der).unwrap();
+
+        let id = OcspCertId::from_cert(MessageDigest::sha1(), &cert, &ca).unwrap();
+        let now = Asn1Time::days_from_now(0).unwrap();
+        let mut basic = OcspBasicResponse::new().unwrap();
+        basic
+            .add_status(
+                &id,
+                OcspCertStatus::GOOD,
+                OcspRevokedStatus::NO_STATUS,
+                None,
+                &now,
+                None,
+            )
+            .unwrap()
+            .add_extension(&extension)
+            .unwrap();
+        basic
+            .sign(
+                &ca,
+                &ca_key,
+                MessageDigest::sha256(),
+                None,
+                OcspFlag::empty(),
+            )
+            .unwrap();
+        let response = OcspResponse::create(OcspResponseStatus::SUCCESSFUL, Some(&basic)).unwrap();
+        let response = OcspResponse::from_der(&response.to_der().unwrap()).unwrap();
+        let basic = response.basic().unwrap();
+
+        let single = basic.responses().next().unwrap();
+        let mut scts = single.scts().unwrap();
+        assert_eq!(scts.len(), 1);
+        let sct = scts.get_mut(0).unwrap();
+        assert_eq!(sct.timestamp(), 1_600_000_000_000);
+        sct.set_source(SctSource::OCSP_STAPLED_RESPONSE).unwrap();
+        assert_eq!(sct.log_entry_type(), LogEntryType::X509);
+        assert_eq!(
+            sct.validate(&cert, None, &store).unwrap(),
+            SctValidationStatus::VALID
+        );
+        assert_eq!(
+            sct.validate(&ca, None, &store).unwrap(),
+            SctValidationStatus::INVALID
+        );
+
+        let mut basic = OcspBasicResponse::new().unwrap();
+        let single = basic
+            .add_status(
+                &id,
+                OcspCertStatus::GOOD,
+                OcspRevokedStatus::NO_STATUS,
+                None,
+                &now,
+                None,
+            )
+            .unwrap();
+        assert!(single.scts().is_none());
+    }
+
+    #[test]
+    fn precertificate() {
+        let ca = X509::from_pem(include_bytes!("../test/root-ca.pem")).unwrap();
+        let ca_key = PKey::private_key_from_pem(include_bytes!("../test/root-ca.key")).unwrap();
+        let log_key = PKey::private_key_from_pem(include_bytes!("../test/ct_log.pem")).unwrap();
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+
+        let mut store = CtLogStore::new().unwrap();
+        store.load_file("test/ct_log_list.cnf").unwrap();
+
+        let mut name = X509Name::builder().unwrap();
+        name.append_entry_by_nid(Nid::COMMONNAME, "foobar.com")
+            .unwrap();
+        let name = name.build();
+
+        let mut builder = X509Builder::new().unwrap();
+        builder.set_version(2).unwrap();
+        builder
+            .set_serial_number(&BigNum::from_u32(7).unwrap().to_asn1_integer().unwrap())
+            .unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(ca.subject_name()).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(30).unwrap())
+            .unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder.append_ct_poison().unwrap();
+        builder.sign(&ca_key, MessageDigest::sha256()).unwrap();
+        let precert = builder.build();
+        assert!(precert.is_precertificate());
+        assert!(!ca.is_precertificate());
+
+        let list = sct_list(&precert_entry(&precert, &ca), &log_key, 1_600_000_000_000);
+        assert!(list.len() < 0x80);
+        let mut der = vec![0x04, list.len() as u8];
+        der.extend_from_slice(&list);
+
+        let mut builder = X509Builder::from_precertificate(&precert).unwrap();
+        let oid = Asn1Object::from_str("1.3.6.1.4.1.11129.2.4.2").unwrap();
+        builder.append_raw_extension(&oid, false, &der).unwrap();
+        builder.sign(&ca_key, MessageDigest::sha256()).unwrap();
+        let cert = builder.build();
+        assert!(!cert.is_precertificate());
+        assert_eq!(
+            cert.precertificate_tbs().unwrap(),
+            precert.precertificate_tbs().unwrap()
+        );
+
+        let mut scts = cert.scts().unwrap();
+        assert_eq!(scts.len(), 1);
+        let sct = scts.get_mut(0).unwrap();
+        sct.set_source(SctSource::X509V3_EXTENSION).unwrap();
+        assert_eq!(sct.log_entry_type(), LogEntryType::PRECERT);
+        assert_eq!(
+            sct.validate(&cert, Some(&ca), &store).unwrap(),
+            SctValidationStatus::VALID
+        );
+        assert_eq!(
+            sct.validate(&cert, Some(&cert), &store).unwrap(),
+            SctValidationStatus::INVALID
+        );
+    }
+}
@@ -57,6 +57,26 @@ impl ErrorStack {
 }
 
 impl ErrorStack {
+    /// Pushes an error with the given library and reason codes and returns the contents of the
+    /// OpenSSL error stack.
+    ///
+    /// This reports invalid input which is rejected before it reaches OpenSSL.
+    pub(crate) fn from_reason(lib: c_int, reason: c_int) -> ErrorStack {
+        let file = concat!(file!(), "\0").as_ptr() as *const c_char;
+        unsafe {
+            cfg_if! {
+                if #[cfg(ossl300)] {
+                    ffi::ERR_new();
+                    ffi::ERR_set_debug(file, line!() as c_int, ptr::null());
+                    ffi::ERR_set_error(lib, reason, ptr::null());
+                } else {
+                    ffi::ERR_put_error(lib, 0, reason, file, line!() as _);
+                }
+            }
+        }
+        ErrorStack::get()
+    }
+
     /// Returns the errors in the stack.
     pub fn errors(&self) -> &[Error] {
         &self.0
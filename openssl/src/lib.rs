@@ -142,6 +142,8 @@ pub mod cipher_ctx;
 #[cfg(all(not(boringssl), not(libressl), not(osslconf = "OPENSSL_NO_CMS")))]
 pub mod cms;
 pub mod conf;
+#[cfg(ossl110)]
+pub mod ct;
 pub mod derive;
 pub mod dh;
 pub mod dsa;
@@ -1068,6 +1068,16 @@ impl Nid {
     pub const AES_128_CBC_HMAC_SHA1: Nid = Nid(ffi::NID_aes_128_cbc_hmac_sha1);
     pub const AES_192_CBC_HMAC_SHA1: Nid = Nid(ffi::NID_aes_192_cbc_hmac_sha1);
     pub const AES_256_CBC_HMAC_SHA1: Nid = Nid(ffi::NID_aes_256_cbc_hmac_sha1);
+    #[cfg(ossl110)]
+    pub const CT_PRECERT_SCTS: Nid = Nid(ffi::NID_ct_precert_scts);
+    #[cfg(ossl110)]
+    pub const CT_PRECERT_POISON: Nid = Nid(ffi::NID_ct_precert_poison);
+    #[cfg(ossl110)]
+    pub const CT_PRECERT_SIGNER: Nid = Nid(ffi::NID_ct_precert_signer);
+    #[cfg(ossl110)]
+    pub const CT_CERT_SCTS: Nid = Nid(ffi::NID_ct_cert_scts);
+    #[cfg(ossl110)]
+    pub const TLSFEATURE: Nid = Nid(ffi::NID_tlsfeature);
     #[cfg(any(ossl111, libressl291))]
     pub const SM3: Nid = Nid(ffi::NID_sm3);
     #[cfg(ossl111)]
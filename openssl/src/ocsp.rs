@@ -4,13 +4,20 @@ use libc::{c_int, c_long, c_ulong};
 use std::mem;
 use std::ptr;
 
-use crate::asn1::Asn1GeneralizedTimeRef;
+use crate::asn1::{Asn1GeneralizedTimeRef, Asn1IntegerRef, Asn1TimeRef};
+#[cfg(ossl110)]
+use crate::ct::Sct;
 use crate::error::ErrorStack;
 use crate::hash::MessageDigest;
+use crate::pkey::{HasPrivate, PKeyRef};
+#[cfg(ossl110)]
+use crate::stack::Stack;
 use crate::stack::StackRef;
+#[cfg(ossl110)]
+use crate::util::ForeignTypeExt;
 use crate::util::ForeignTypeRefExt;
 use crate::x509::store::X509StoreRef;
-use crate::x509::{X509Ref, X509};
+use crate::x509::{X509ExtensionRef, X509Ref, X509};
 use crate::{cvt, cvt_p};
 use openssl_macros::corresponds;
 
@@ -107,6 +114,34 @@ impl OcspRevokedStatus {
     }
 }
 
+/// The result of comparing the nonces of an OCSP request and response.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct OcspNonceStatus(c_int);
+
+impl OcspNonceStatus {
+    /// The request and response contain different nonces.
+    pub const MISMATCH: OcspNonceStatus = OcspNonceStatus(0);
+    /// The request and response contain the same nonce.
+    pub const MATCH: OcspNonceStatus = OcspNonceStatus(1);
+    /// Neither the request nor the response contain a nonce.
+    pub const ABSENT: OcspNonceStatus = OcspNonceStatus(2);
+    /// Only the response contains a nonce.
+    pub const RESPONSE_ONLY: OcspNonceStatus = OcspNonceStatus(3);
+    /// Only the request contains a nonce.
+    ///
+    /// This is common, as many responders ignore nonces and send cached responses.
+    pub const REQUEST_ONLY: OcspNonceStatus = OcspNonceStatus(-1);
+
+    pub fn from_raw(raw: c_int) -> OcspNonceStatus {
+        OcspNonceStatus(raw)
+    }
+
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn as_raw(&self) -> c_int {
+        self.0
+    }
+}
+
 pub struct OcspStatus<'a> {
     /// The overall status of the response.
     pub status: OcspCertStatus,
@@ -150,7 +185,90 @@ foreign_type_and_impl_send_sync! {
     pub struct OcspBasicResponseRef;
 }
 
+impl OcspBasicResponse {
+    /// Creates a new, empty, basic response.
+    ///
+    /// Responses for individual certificates are added with [`add_status`], after which the
+    /// response is signed with [`sign`] and wrapped with [`OcspResponse::create`].
+    ///
+    /// [`add_status`]: struct.OcspBasicResponseRef.html#method.add_status
+    /// [`sign`]: struct.OcspBasicResponseRef.html#method.sign
+    /// [`OcspResponse::create`]: struct.OcspResponse.html#method.create
+    #[corresponds(OCSP_BASICRESP_new)]
+    pub fn new() -> Result<OcspBasicResponse, ErrorStack> {
+        unsafe {
+            ffi::init();
+
+            cvt_p(ffi::OCSP_BASICRESP_new()).map(OcspBasicResponse)
+        }
+    }
+}
+
 impl OcspBasicResponseRef {
+    /// Adds the status of the certificate identified by `id`.
+    ///
+    /// `reason` and `revocation_time` are only used if `status` is `REVOKED`, in which case
+    /// `revocation_time` is required. `reason` may be `NO_STATUS` to omit the revocation reason.
+    #[corresponds(OCSP_basic_add1_status)]
+    pub fn add_status(
+        &mut self,
+        id: &OcspCertIdRef,
+        status: OcspCertStatus,
+        reason: OcspRevokedStatus,
+        revocation_time: Option<&Asn1TimeRef>,
+        this_update: &Asn1TimeRef,
+        next_update: Option<&Asn1TimeRef>,
+    ) -> Result<&mut OcspSingleResponseRef, ErrorStack> {
+        unsafe {
+            let single = cvt_p(ffi::OCSP_basic_add1_status(
+                self.as_ptr(),
+                id.as_ptr(),
+                status.as_raw(),
+                reason.as_raw(),
+                revocation_time.map_or(ptr::null_mut(), |t| t.as_ptr()),
+                this_update.as_ptr(),
+                next_update.map_or(ptr::null_mut(), |t| t.as_ptr()),
+            ))?;
+            Ok(OcspSingleResponseRef::from_ptr_mut(single))
+        }
+    }
+
+    /// Copies the nonce of `request` into the response, if it has one.
+    #[corresponds(OCSP_copy_nonce)]
+    pub fn copy_nonce(&mut self, request: &OcspRequestRef) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::OCSP_copy_nonce(self.as_ptr(), request.as_ptr())).map(|_| ()) }
+    }
+
+    /// Signs the response with the responder's certificate and private key.
+    ///
+    /// The responder's certificate is included in the response along with `certs`, unless
+    /// `flags` contains `NO_CERTS`. The responder is identified by name unless `flags` contains
+    /// `RESPID_KEY`, in which case the hash of its public key is used.
+    #[corresponds(OCSP_basic_sign)]
+    pub fn sign<T>(
+        &mut self,
+        signer: &X509Ref,
+        key: &PKeyRef<T>,
+        digest: MessageDigest,
+        certs: Option<&StackRef<X509>>,
+        flags: OcspFlag,
+    ) -> Result<(), ErrorStack>
+    where
+        T: HasPrivate,
+    {
+        unsafe {
+            cvt(ffi::OCSP_basic_sign(
+                self.as_ptr(),
+                signer.as_ptr(),
+                key.as_ptr(),
+                digest.as_ptr(),
+                certs.map_or(ptr::null_mut(), |c| c.as_ptr()),
+                flags.bits(),
+            ))
+            .map(|_| ())
+        }
+    }
+
     /// Verifies the validity of the response.
     ///
     /// The `certs` parameter contains a set of certificates that will be searched when locating the
@@ -197,7 +315,7 @@ impl OcspBasicResponseRef {
 
                 Some(OcspStatus {
                     status: OcspCertStatus(status),
-                    reason: OcspRevokedStatus(status),
+                    reason: OcspRevokedStatus(reason),
                     revocation_time,
                     this_update: Asn1GeneralizedTimeRef::from_ptr(this_update),
                     next_update: Asn1GeneralizedTimeRef::from_ptr(next_update),
@@ -207,6 +325,181 @@ impl OcspBasicResponseRef {
             }
         }
     }
+
+    /// Returns the time at which the response was signed.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(OCSP_resp_get0_produced_at)]
+    #[cfg(ossl110)]
+    pub fn produced_at(&self) -> &Asn1GeneralizedTimeRef {
+        unsafe {
+            let time = ffi::OCSP_resp_get0_produced_at(self.as_ptr());
+            Asn1GeneralizedTimeRef::from_const_ptr_opt(time).expect("produced at must not be null")
+        }
+    }
+
+    /// Returns an iterator over the responses for the individual certificates.
+    pub fn responses(&self) -> OcspSingleResponses<'_> {
+        OcspSingleResponses {
+            response: self,
+            idx: 0,
+        }
+    }
+}
+
+/// An iterator over the single responses of an `OcspBasicResponse`.
+pub struct OcspSingleResponses<'a> {
+    response: &'a OcspBasicResponseRef,
+    idx: c_int,
+}
+
+impl<'a> Iterator for OcspSingleResponses<'a> {
+    type Item = &'a OcspSingleResponseRef;
+
+    fn next(&mut self) -> Option<&'a OcspSingleResponseRef> {
+        unsafe {
+            if self.idx >= ffi::OCSP_resp_count(self.response.as_ptr()) {
+                return None;
+            }
+            let single = ffi::OCSP_resp_get0(self.response.as_ptr(), self.idx);
+            self.idx += 1;
+            Some(OcspSingleResponseRef::from_ptr(single))
+        }
+    }
+}
+
+foreign_type_and_impl_send_sync! {
+    type CType = ffi::OCSP_SINGLERESP;
+    fn drop = ffi::OCSP_SINGLERESP_free;
+
+    /// The status of a single certificate in an OCSP response.
+    pub struct OcspSingleResponse;
+    pub struct OcspSingleResponseRef;
+}
+
+impl OcspSingleResponseRef {
+    /// Returns the ID of the certificate this response refers to.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(OCSP_SINGLERESP_get0_id)]
+    #[cfg(ossl110)]
+    pub fn cert_id(&self) -> &OcspCertIdRef {
+        unsafe {
+            let id = ffi::OCSP_SINGLERESP_get0_id(self.as_ptr());
+            OcspCertIdRef::from_const_ptr_opt(id).expect("certificate ID must not be null")
+        }
+    }
+
+    /// Returns the signed certificate timestamps stapled to this response, if they exist.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(OCSP_SINGLERESP_get1_ext_d2i)]
+    #[cfg(ossl110)]
+    pub fn scts(&self) -> Option<Stack<Sct>> {
+        unsafe {
+            let stack = ffi::OCSP_SINGLERESP_get1_ext_d2i(
+                self.as_ptr(),
+                ffi::NID_ct_cert_scts,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            Stack::from_ptr_opt(stack as *mut _)
+        }
+    }
+
+    /// Adds an extension to the response.
+    #[corresponds(OCSP_SINGLERESP_add_ext)]
+    pub fn add_extension(&mut self, extension: &X509ExtensionRef) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::OCSP_SINGLERESP_add_ext(
+                self.as_ptr(),
+                extension.as_ptr(),
+                -1,
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Returns the status of the certificate.
+    #[corresponds(OCSP_single_get0_status)]
+    pub fn cert_status(&self) -> OcspCertStatus {
+        OcspCertStatus(self.get0_status().status)
+    }
+
+    /// If the certificate is revoked, returns the reason for the revocation.
+    #[corresponds(OCSP_single_get0_status)]
+    pub fn revocation_reason(&self) -> OcspRevokedStatus {
+        OcspRevokedStatus(self.get0_status().reason)
+    }
+
+    /// If the certificate is revoked, returns the time at which it was revoked.
+    #[corresponds(OCSP_single_get0_status)]
+    pub fn revocation_time(&self) -> Option<&Asn1GeneralizedTimeRef> {
+        unsafe { Asn1GeneralizedTimeRef::from_const_ptr_opt(self.get0_status().revocation_time) }
+    }
+
+    /// Returns the time at which the status was known to be correct.
+    #[corresponds(OCSP_single_get0_status)]
+    pub fn this_update(&self) -> &Asn1GeneralizedTimeRef {
+        unsafe {
+            Asn1GeneralizedTimeRef::from_const_ptr_opt(self.get0_status().this_update)
+                .expect("this update must not be null")
+        }
+    }
+
+    /// Returns the time at or before which newer information about the status will be available,
+    /// if the responder provides one.
+    #[corresponds(OCSP_single_get0_status)]
+    pub fn next_update(&self) -> Option<&Asn1GeneralizedTimeRef> {
+        unsafe { Asn1GeneralizedTimeRef::from_const_ptr_opt(self.get0_status().next_update) }
+    }
+
+    /// Checks validity of the `this_update` and `next_update` fields.
+    ///
+    /// See [`OcspStatus::check_validity`] for details.
+    ///
+    /// [`OcspStatus::check_validity`]: struct.OcspStatus.html#method.check_validity
+    #[corresponds(OCSP_check_validity)]
+    pub fn check_validity(&self, nsec: u32, maxsec: Option<u32>) -> Result<(), ErrorStack> {
+        let status = self.get0_status();
+        unsafe {
+            cvt(ffi::OCSP_check_validity(
+                status.this_update,
+                status.next_update,
+                nsec as c_long,
+                maxsec.map(|n| n as c_long).unwrap_or(-1),
+            ))
+            .map(|_| ())
+        }
+    }
+
+    fn get0_status(&self) -> SingleStatus {
+        unsafe {
+            let mut status = SingleStatus {
+                status: ffi::V_OCSP_CERTSTATUS_UNKNOWN,
+                reason: ffi::OCSP_REVOKED_STATUS_NOSTATUS,
+                revocation_time: ptr::null_mut(),
+                this_update: ptr::null_mut(),
+                next_update: ptr::null_mut(),
+            };
+            status.status = ffi::OCSP_single_get0_status(
+                self.as_ptr(),
+                &mut status.reason,
+                &mut status.revocation_time,
+                &mut status.this_update,
+                &mut status.next_update,
+            );
+            status
+        }
+    }
+}
+
+struct SingleStatus {
+    status: c_int,
+    reason: c_int,
+    revocation_time: *mut ffi::ASN1_GENERALIZEDTIME,
+    this_update: *mut ffi::ASN1_GENERALIZEDTIME,
+    next_update: *mut ffi::ASN1_GENERALIZEDTIME,
 }
 
 foreign_type_and_impl_send_sync! {
@@ -236,6 +529,24 @@ impl OcspCertId {
     }
 }
 
+impl OcspCertIdRef {
+    /// Returns the serial number of the certificate.
+    #[corresponds(OCSP_id_get0_info)]
+    pub fn serial_number(&self) -> &Asn1IntegerRef {
+        unsafe {
+            let mut serial = ptr::null_mut();
+            ffi::OCSP_id_get0_info(
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                &mut serial,
+                self.as_ptr(),
+            );
+            Asn1IntegerRef::from_const_ptr_opt(serial).expect("serial number must not be null")
+        }
+    }
+}
+
 foreign_type_and_impl_send_sync! {
     type CType = ffi::OCSP_RESPONSE;
     fn drop = ffi::OCSP_RESPONSE_free;
@@ -339,6 +650,37 @@ impl OcspRequestRef {
             Ok(OcspOneReqRef::from_ptr_mut(ptr))
         }
     }
+
+    /// Adds a nonce to the request, protecting against replayed responses.
+    ///
+    /// If `nonce` is `None`, a random 16 byte nonce is generated.
+    #[corresponds(OCSP_request_add1_nonce)]
+    pub fn add_nonce(&mut self, nonce: Option<&[u8]>) -> Result<(), ErrorStack> {
+        unsafe {
+            let (ptr, len) = match nonce {
+                Some(nonce) => {
+                    assert!(nonce.len() <= c_int::max_value() as usize);
+                    (nonce.as_ptr() as *mut _, nonce.len() as c_int)
+                }
+                None => (ptr::null_mut(), 0),
+            };
+            cvt(ffi::OCSP_request_add1_nonce(self.as_ptr(), ptr, len)).map(|_| ())
+        }
+    }
+
+    /// Returns an iterator over the requests for the individual certificates.
+    pub fn requests(&self) -> OcspOneReqs<'_> {
+        OcspOneReqs {
+            request: self,
+            idx: 0,
+        }
+    }
+
+    /// Compares the nonce of the request with that of `response`.
+    #[corresponds(OCSP_check_nonce)]
+    pub fn check_nonce(&self, response: &OcspBasicResponseRef) -> OcspNonceStatus {
+        unsafe { OcspNonceStatus(ffi::OCSP_check_nonce(self.as_ptr(), response.as_ptr())) }
+    }
 }
 
 foreign_type_and_impl_send_sync! {
@@ -348,3 +690,262 @@ foreign_type_and_impl_send_sync! {
     pub struct OcspOneReq;
     pub struct OcspOneReqRef;
 }
+
+impl OcspOneReqRef {
+    /// Returns the ID of the certificate whose status is requested.
+    #[corresponds(OCSP_onereq_get0_id)]
+    pub fn cert_id(&self) -> &OcspCertIdRef {
+        unsafe { OcspCertIdRef::from_ptr(ffi::OCSP_onereq_get0_id(self.as_ptr())) }
+    }
+}
+
+/// An iterator over the single requests of an `OcspRequest`.
+pub struct OcspOneReqs<'a> {
+    request: &'a OcspRequestRef,
+    idx: c_int,
+}
+
+impl<'a> Iterator for OcspOneReqs<'a> {
+    type Item = &'a OcspOneReqRef;
+
+    fn next(&mut self) -> Option<&'a OcspOneReqRef> {
+        unsafe {
+            if self.idx >= ffi::OCSP_request_onereq_count(self.request.as_ptr()) {
+                return None;
+            }
+            let one = ffi::OCSP_request_onereq_get0(self.request.as_ptr(), self.idx);
+            self.idx += 1;
+            Some(OcspOneReqRef::from_ptr(one))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::asn1::Asn1Time;
+    use crate::pkey::PKey;
+    use crate::stack::Stack;
+    use crate::x509::store::X509StoreBuilder;
+
+    fn cert(pem: &[u8]) -> X509 {
+        X509::from_pem(pem).unwrap()
+    }
+
+    #[test]
+    fn basic_response() {
+        let ca = cert(include_bytes!("../test/root-ca.pem"));
+        let good = cert(include_bytes!("../test/cert.pem"));
+        let revoked = cert(include_bytes!("../test/alt_name_cert.pem"));
+
+        let response = OcspResponse::from_der(include_bytes!("../test/ocsp_resp.der")).unwrap();
+        assert_eq!(response.status(), OcspResponseStatus::SUCCESSFUL);
+        let basic = response.basic().unwrap();
+        #[cfg(ossl110)]
+        assert!(basic.produced_at().to_string().ends_with("GMT"));
+
+        let responses = basic.responses().collect::<Vec<_>>();
+        assert_eq!(responses.len(), 2);
+
+        assert_eq!(responses[0].cert_status(), OcspCertStatus::GOOD);
+        assert_eq!(
+            responses[0].revocation_reason(),
+            OcspRevokedStatus::NO_STATUS
+        );
+        assert!(responses[0].revocation_time().is_none());
+        assert!(responses[0].next_update().is_some());
+        responses[0].check_validity(300, None).unwrap();
+        #[cfg(ossl110)]
+        assert_eq!(
+            responses[0].cert_id().serial_number().to_bn().unwrap(),
+            good.serial_number().to_bn().unwrap()
+        );
+
+        assert_eq!(responses[1].cert_status(), OcspCertStatus::REVOKED);
+        assert_eq!(
+            responses[1].revocation_reason(),
+            OcspRevokedStatus::KEY_COMPROMISE
+        );
+        assert!(responses[1].revocation_time().is_some());
+
+        let id = OcspCertId::from_cert(MessageDigest::sha1(), &revoked, &ca).unwrap();
+        assert_eq!(
+            id.serial_number().to_bn().unwrap(),
+            revoked.serial_number().to_bn().unwrap()
+        );
+        let status = basic.find_status(&id).unwrap();
+        assert_eq!(status.status, OcspCertStatus::REVOKED);
+        assert_eq!(status.reason, OcspRevokedStatus::KEY_COMPROMISE);
+    }
+
+    #[test]
+    fn nonce() {
+        let response = OcspResponse::from_der(include_bytes!("../test/ocsp_resp.der")).unwrap();
+        let basic = response.basic().unwrap();
+
+        let request = OcspRequest::from_der(include_bytes!("../test/ocsp_req.der")).unwrap();
+        assert_eq!(request.check_nonce(&basic), OcspNonceStatus::MATCH);
+
+        let mut request = OcspRequest::new().unwrap();
+        assert_eq!(request.check_nonce(&basic), OcspNonceStatus::RESPONSE_ONLY);
+        request.add_nonce(None).unwrap();
+        assert_eq!(request.check_nonce(&basic), OcspNonceStatus::MISMATCH);
+
+        let mut request = OcspRequest::new().unwrap();
+        request.add_nonce(Some(b"0123456789abcdef")).unwrap();
+        let request = OcspRequest::from_der(&request.to_der().unwrap()).unwrap();
+        assert_eq!(request.check_nonce(&basic), OcspNonceStatus::MISMATCH);
+    }
+
+    #[test]
+    fn responder() {
+        let ca = cert(include_bytes!("../test/root-ca.pem"));
+        let ca_key = PKey::private_key_from_pem(include_bytes!("../test/root-ca.key")).unwrap();
+        let good = cert(include_bytes!("../test/cert.pem"));
+        let revoked = cert(include_bytes!("../test/alt_name_cert.pem"));
+
+        let mut request = OcspRequest::new().unwrap();
+        for subject in [&good, &revoked] {
+            let id = OcspCertId::from_cert(MessageDigest::sha1(), subject, &ca).unwrap();
+            request.add_id(id).unwrap();
+        }
+        request.add_nonce(None).unwrap();
+        let request = OcspRequest::from_der(&request.to_der().unwrap()).unwrap();
+
+        let now = Asn1Time::days_from_now(0).unwrap();
+        let next = Asn1Time::days_from_now(7).unwrap();
+        let revoked_at = Asn1Time::from_unix(1_577_836_800).unwrap();
+
+        let mut basic = OcspBasicResponse::new().unwrap();
+        for (i, one) in request.requests().enumerate() {
+            let id = one.cert_id();
+            if i == 0 {
+                basic
+                    .add_status(
+                        id,
+                        OcspCertStatus::GOOD,
+                        OcspRevokedStatus::NO_STATUS,
+                        None,
+                        &now,
+                        Some(&next),
+                    )
+                    .unwrap();
+            } else {
+                basic
+                    .add_status(
+                        id,
+                        OcspCertStatus::REVOKED,
+                        OcspRevokedStatus::KEY_COMPROMISE,
+                        Some(&revoked_at),
+                        &now,
+                        None,
+                    )
+                    .unwrap();
+            }
+        }
+        basic.copy_nonce(&request).unwrap();
+        basic
+            .sign(
+                &ca,
+                &ca_key,
+                MessageDigest::sha256(),
+                None,
+                OcspFlag::empty(),
+            )
+            .unwrap();
+
+        let response = OcspResponse::create(OcspResponseStatus::SUCCESSFUL, Some(&basic)).unwrap();
+        let response = OcspResponse::from_der(&response.to_der().unwrap()).unwrap();
+        let basic = response.basic().unwrap();
+
+        let mut store = X509StoreBuilder::new().unwrap();
+        store.add_cert(ca.clone()).unwrap();
+        let store = store.build();
+        let certs = Stack::new().unwrap();
+        // The test CA has expired, so only the signature is checked.
+        basic.verify(&certs, &store, OcspFlag::NO_VERIFY).unwrap();
+        assert_eq!(request.check_nonce(&basic), OcspNonceStatus::MATCH);
+
+        let responses = basic.responses().collect::<Vec<_>>();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0].cert_status(), OcspCertStatus::GOOD);
+        responses[0].check_validity(300, None).unwrap();
+        assert_eq!(responses[1].cert_status(), OcspCertStatus::REVOKED);
+        assert_eq!(
+            responses[1].revocation_reason(),
+            OcspRevokedStatus::KEY_COMPROMISE
+        );
+        assert!(responses[1].next_update().is_none());
+
+        let other_key = PKey::private_key_from_pem(include_bytes!("../test/key.pem")).unwrap();
+        let mut other = OcspBasicResponse::new().unwrap();
+        assert!(other
+            .sign(
+                &ca,
+                &other_key,
+                MessageDigest::sha256(),
+                None,
+                OcspFlag::empty()
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn find_status_revocation_reason() {
+        let ca = cert(include_bytes!("../test/root-ca.pem"));
+        let ca_key = PKey::private_key_from_pem(include_bytes!("../test/root-ca.key")).unwrap();
+        let good = cert(include_bytes!("../test/cert.pem"));
+        let revoked = cert(include_bytes!("../test/alt_name_cert.pem"));
+        let good_id = OcspCertId::from_cert(MessageDigest::sha1(), &good, &ca).unwrap();
+        let revoked_id = OcspCertId::from_cert(MessageDigest::sha1(), &revoked, &ca).unwrap();
+
+        let now = Asn1Time::days_from_now(0).unwrap();
+        let revoked_at = Asn1Time::from_unix(1_577_836_800).unwrap();
+
+        // The reason must differ from the numeric value of the REVOKED status.
+        let mut basic = OcspBasicResponse::new().unwrap();
+        basic
+            .add_status(
+                &good_id,
+                OcspCertStatus::GOOD,
+                OcspRevokedStatus::NO_STATUS,
+                None,
+                &now,
+                None,
+            )
+            .unwrap();
+        basic
+            .add_status(
+                &revoked_id,
+                OcspCertStatus::REVOKED,
+                OcspRevokedStatus::CA_COMPROMISE,
+                Some(&revoked_at),
+                &now,
+                None,
+            )
+            .unwrap();
+        basic
+            .sign(
+                &ca,
+                &ca_key,
+                MessageDigest::sha256(),
+                None,
+                OcspFlag::empty(),
+            )
+            .unwrap();
+
+        let response = OcspResponse::create(OcspResponseStatus::SUCCESSFUL, Some(&basic)).unwrap();
+        let basic = response.basic().unwrap();
+
+        let status = basic.find_status(&good_id).unwrap();
+        assert_eq!(status.status, OcspCertStatus::GOOD);
+        assert_eq!(status.reason, OcspRevokedStatus::NO_STATUS);
+        assert!(status.revocation_time.is_none());
+
+        let status = basic.find_status(&revoked_id).unwrap();
+        assert_eq!(status.status, OcspCertStatus::REVOKED);
+        assert_eq!(status.reason, OcspRevokedStatus::CA_COMPROMISE);
+        assert!(status.revocation_time.is_some());
+    }
+}
@@ -2,6 +2,8 @@
 
 use foreign_types::{ForeignType, ForeignTypeRef};
 use libc::c_int;
+#[cfg(ossl110)]
+use std::ffi::CStr;
 use std::ffi::CString;
 use std::ptr;
 
@@ -9,9 +11,19 @@ use crate::error::ErrorStack;
 #[cfg(not(boringssl))]
 use crate::hash::MessageDigest;
 use crate::nid::Nid;
+#[cfg(ossl110)]
+use crate::pkcs7::Pkcs7;
 use crate::pkey::{HasPrivate, PKey, PKeyRef, Private};
 use crate::stack::Stack;
+#[cfg(ossl110)]
+use crate::stack::{StackRef, Stackable};
+#[cfg(ossl110)]
+use crate::string::OpensslString;
 use crate::util::ForeignTypeExt;
+#[cfg(ossl110)]
+use crate::util::ForeignTypeRefExt;
+#[cfg(ossl110)]
+use crate::x509::X509AlgorithmRef;
 use crate::x509::{X509Ref, X509};
 use crate::{cvt, cvt_p};
 use openssl_macros::corresponds;
@@ -24,6 +36,107 @@ foreign_type_and_impl_send_sync! {
     pub struct Pkcs12Ref;
 }
 
+#[cfg(ossl110)]
+foreign_type_and_impl_send_sync! {
+    type CType = ffi::PKCS12_SAFEBAG;
+    fn drop = ffi::PKCS12_SAFEBAG_free;
+
+    /// A single item of a PKCS #12 archive, such as a private key or a certificate, along with its
+    /// attributes.
+    pub struct Pkcs12SafeBag;
+    /// Reference to `Pkcs12SafeBag`.
+    pub struct Pkcs12SafeBagRef;
+}
+
+#[cfg(ossl110)]
+impl Stackable for Pkcs12SafeBag {
+    type StackType = ffi::stack_st_PKCS12_SAFEBAG;
+}
+
+#[cfg(ossl110)]
+impl Pkcs12SafeBagRef {
+    /// Returns the type of the bag, such as `Nid::KEYBAG` or `Nid::CERTBAG`.
+    #[corresponds(PKCS12_SAFEBAG_get_nid)]
+    pub fn bag_type(&self) -> Nid {
+        unsafe { Nid::from_raw(ffi::PKCS12_SAFEBAG_get_nid(self.as_ptr())) }
+    }
+
+    /// Returns the value of the bag's `friendlyName` attribute.
+    #[corresponds(PKCS12_get_friendlyname)]
+    pub fn friendly_name(&self) -> Option<OpensslString> {
+        unsafe {
+            let ptr = ffi::PKCS12_get_friendlyname(self.as_ptr());
+            if ptr.is_null() {
+                None
+            } else {
+                Some(OpensslString::from_ptr(ptr))
+            }
+        }
+    }
+
+    /// Returns the value of the bag's `localKeyID` attribute.
+    ///
+    /// A private key and its certificate are conventionally given the same key ID.
+    #[corresponds(PKCS12_SAFEBAG_get0_attr)]
+    pub fn local_key_id(&self) -> Option<Vec<u8>> {
+        unsafe {
+            let attr = ffi::PKCS12_SAFEBAG_get0_attr(self.as_ptr(), ffi::NID_localKeyID);
+            if attr.is_null() || ffi::ASN1_TYPE_get(attr) != ffi::V_ASN1_OCTET_STRING {
+                return None;
+            }
+            let len = ffi::ASN1_TYPE_get_octetstring(attr, ptr::null_mut(), 0);
+            let mut buf = vec![0; len as usize];
+            ffi::ASN1_TYPE_get_octetstring(attr, buf.as_mut_ptr(), len);
+            Some(buf)
+        }
+    }
+
+    /// Returns the X509 certificate held by a certificate bag.
+    ///
+    /// `None` is returned for other kinds of bags.
+    #[corresponds(PKCS12_SAFEBAG_get1_cert)]
+    pub fn cert(&self) -> Option<X509> {
+        unsafe {
+            if self.bag_type() != Nid::CERTBAG
+                || ffi::PKCS12_SAFEBAG_get_bag_nid(self.as_ptr()) != ffi::NID_x509Certificate
+            {
+                return None;
+            }
+            X509::from_ptr_opt(ffi::PKCS12_SAFEBAG_get1_cert(self.as_ptr()))
+        }
+    }
+
+    /// Returns the private key held by a key bag, decrypting it with `pass` if it is shrouded.
+    ///
+    /// `None` is returned for other kinds of bags.
+    #[corresponds(PKCS12_decrypt_skey)]
+    pub fn private_key(&self, pass: &str) -> Result<Option<PKey<Private>>, ErrorStack> {
+        unsafe {
+            let bag_type = self.bag_type();
+            if bag_type == Nid::KEYBAG {
+                let p8 = ffi::PKCS12_SAFEBAG_get0_p8inf(self.as_ptr());
+                cvt_p(ffi::EVP_PKCS82PKEY(p8)).map(|pkey| Some(PKey::from_ptr(pkey)))
+            } else if bag_type == Nid::PKCS8SHROUDEDKEYBAG {
+                let pass = CString::new(pass).unwrap();
+                let p8 = cvt_p(ffi::PKCS12_decrypt_skey(self.as_ptr(), pass.as_ptr(), -1))?;
+                let pkey = cvt_p(ffi::EVP_PKCS82PKEY(p8));
+                ffi::PKCS8_PRIV_KEY_INFO_free(p8);
+                pkey.map(|pkey| Some(PKey::from_ptr(pkey)))
+            } else {
+                Ok(None)
+            }
+        }
+    }
+
+    /// Returns the bags nested in a safe contents bag.
+    ///
+    /// `None` is returned for other kinds of bags.
+    #[corresponds(PKCS12_SAFEBAG_get0_safes)]
+    pub fn safe_contents(&self) -> Option<&StackRef<Pkcs12SafeBag>> {
+        unsafe { StackRef::from_const_ptr_opt(ffi::PKCS12_SAFEBAG_get0_safes(self.as_ptr())) }
+    }
+}
+
 impl Pkcs12Ref {
     to_der! {
         /// Serializes the `Pkcs12` to its standard DER encoding.
@@ -32,7 +145,30 @@ impl Pkcs12Ref {
         ffi::i2d_PKCS12
     }
 
+    /// Returns the digest algorithm of the MAC protecting the archive, if it has one.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(PKCS12_get0_mac)]
+    #[cfg(ossl110)]
+    pub fn mac_algorithm(&self) -> Option<&X509AlgorithmRef> {
+        unsafe {
+            let mut algorithm = ptr::null();
+            ffi::PKCS12_get0_mac(
+                ptr::null_mut(),
+                &mut algorithm,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                self.as_ptr(),
+            );
+            X509AlgorithmRef::from_const_ptr_opt(algorithm)
+        }
+    }
+
     /// Extracts the contents of the `Pkcs12`.
+    ///
+    /// Only the first private key and its certificate are returned. The order of the certificates
+    /// in `chain` depends on the version of OpenSSL; use `parse_entries` to preserve the order of the
+    /// archive.
     #[corresponds(PKCS12_parse)]
     pub fn parse(&self, pass: &str) -> Result<ParsedPkcs12, ErrorStack> {
         unsafe {
@@ -58,6 +194,122 @@ impl Pkcs12Ref {
             Ok(ParsedPkcs12 { pkey, cert, chain })
         }
     }
+
+    /// Returns the bags of the archive in the order they are stored.
+    ///
+    /// The MAC of the archive is verified, if present, and encrypted contents are decrypted with
+    /// `pass`. Bags nested in safe contents bags are returned as they are; see
+    /// `Pkcs12SafeBagRef::safe_contents`.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(PKCS12_unpack_authsafes)]
+    #[cfg(ossl110)]
+    pub fn safe_bags(&self, pass: &str) -> Result<Stack<Pkcs12SafeBag>, ErrorStack> {
+        unsafe {
+            let pass = CString::new(pass.as_bytes()).unwrap();
+
+            if ffi::PKCS12_mac_present(self.as_ptr()) == 1 {
+                cvt(ffi::PKCS12_verify_mac(self.as_ptr(), pass.as_ptr(), -1))?;
+            }
+
+            let authsafes = cvt_p(ffi::PKCS12_unpack_authsafes(self.as_ptr()))?;
+            let authsafes = Stack::<Pkcs7>::from_ptr(authsafes);
+
+            let mut bags = Stack::new()?;
+            for authsafe in &authsafes {
+                // Each content is either plain data or encrypted with the password.
+                let contents = match cvt_p(ffi::PKCS12_unpack_p7data(authsafe.as_ptr())) {
+                    Ok(contents) => contents,
+                    Err(_) => cvt_p(ffi::PKCS12_unpack_p7encdata(
+                        authsafe.as_ptr(),
+                        pass.as_ptr(),
+                        -1,
+                    ))?,
+                };
+                for bag in Stack::<Pkcs12SafeBag>::from_ptr(contents) {
+                    bags.push(bag)?;
+                }
+            }
+
+            Ok(bags)
+        }
+    }
+
+    /// Extracts all private keys and certificates of the `Pkcs12`, along with their attributes.
+    ///
+    /// Each private key is returned with the certificate sharing its `localKeyID`, or failing that,
+    /// its public key. Entries holding a private key come first, followed by the remaining
+    /// certificates in the order they are stored in the archive.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[cfg(ossl110)]
+    pub fn parse_entries(&self, pass: &str) -> Result<Vec<Pkcs12Entry>, ErrorStack> {
+        fn collect<'a>(bags: &'a StackRef<Pkcs12SafeBag>, out: &mut Vec<&'a Pkcs12SafeBagRef>) {
+            for bag in bags {
+                match bag.safe_contents() {
+                    Some(contents) => collect(contents, out),
+                    None => out.push(bag),
+                }
+            }
+        }
+
+        let bags = self.safe_bags(pass)?;
+        let mut leaves = vec![];
+        collect(&bags, &mut leaves);
+
+        let mut entries = vec![];
+        for bag in &leaves {
+            if let Some(pkey) = bag.private_key(pass)? {
+                entries.push(Pkcs12Entry {
+                    pkey: Some(pkey),
+                    cert: None,
+                    friendly_name: bag.friendly_name().map(|name| name.to_string()),
+                    local_key_id: bag.local_key_id(),
+                });
+            }
+        }
+        let keys = entries.len();
+
+        for bag in &leaves {
+            let cert = match bag.cert() {
+                Some(cert) => cert,
+                None => continue,
+            };
+            let friendly_name = bag.friendly_name().map(|name| name.to_string());
+            let local_key_id = bag.local_key_id();
+
+            let mut index = None;
+            if local_key_id.is_some() {
+                index = entries[..keys]
+                    .iter()
+                    .position(|e| e.cert.is_none() && e.local_key_id == local_key_id);
+            }
+            if index.is_none() {
+                let public_key = cert.public_key()?;
+                index = entries[..keys].iter().position(|e| {
+                    e.cert.is_none() && matches!(e.pkey, Some(ref k) if k.public_eq(&public_key))
+                });
+            }
+
+            match index {
+                Some(index) => {
+                    let entry = &mut entries[index];
+                    entry.cert = Some(cert);
+                    if entry.friendly_name.is_none() {
+                        entry.friendly_name = friendly_name;
+                    }
+                }
+                None => entries.push(Pkcs12Entry {
+                    pkey: None,
+                    cert: Some(cert),
+                    friendly_name,
+                    local_key_id,
+                }),
+            }
+        }
+
+        Ok(entries)
+    }
 }
 
 impl Pkcs12 {
@@ -88,11 +340,26 @@ impl Pkcs12 {
             mac_iter: ffi::PKCS12_DEFAULT_ITER,
             #[cfg(not(boringssl))]
             mac_md: None,
+            #[cfg(ossl110)]
+            local_key_id: None,
             ca: None,
         }
     }
 }
 
+/// A private key or certificate extracted from a PKCS #12 archive by `Pkcs12Ref::parse_entries`.
+#[cfg(ossl110)]
+pub struct Pkcs12Entry {
+    /// The private key, if any.
+    pub pkey: Option<PKey<Private>>,
+    /// The certificate, if any.
+    pub cert: Option<X509>,
+    /// The `friendlyName` attribute of the key or certificate.
+    pub friendly_name: Option<String>,
+    /// The `localKeyID` attribute of the key or certificate.
+    pub local_key_id: Option<Vec<u8>>,
+}
+
 pub struct ParsedPkcs12 {
     pub pkey: PKey<Private>,
     pub cert: X509,
@@ -106,17 +373,24 @@ pub struct Pkcs12Builder {
     mac_iter: c_int,
     #[cfg(not(boringssl))]
     mac_md: Option<MessageDigest>,
+    #[cfg(ossl110)]
+    local_key_id: Option<Vec<u8>>,
     ca: Option<Stack<X509>>,
 }
 
 impl Pkcs12Builder {
     /// The encryption algorithm that should be used for the key
+    ///
+    /// Either a PKCS #12 PBE algorithm such as `Nid::PBE_WITHSHA1AND3_KEY_TRIPLEDES_CBC` or a
+    /// cipher such as `Nid::AES_256_CBC` may be used. Ciphers are applied with PBES2.
     pub fn key_algorithm(&mut self, nid: Nid) -> &mut Self {
         self.nid_key = nid;
         self
     }
 
     /// The encryption algorithm that should be used for the cert
+    ///
+    /// This accepts the same algorithms as `key_algorithm`.
     pub fn cert_algorithm(&mut self, nid: Nid) -> &mut Self {
         self.nid_cert = nid;
         self
@@ -144,6 +418,37 @@ impl Pkcs12Builder {
         self
     }
 
+    /// Stores the key and certificates without encryption.
+    ///
+    /// The key is stored in a plain key bag rather than a shrouded one.
+    #[cfg(not(boringssl))]
+    pub fn no_encryption(&mut self) -> &mut Self {
+        self.nid_key = Nid::from_raw(-1);
+        self.nid_cert = Nid::from_raw(-1);
+        self
+    }
+
+    /// Omits the MAC protecting the integrity of the archive.
+    ///
+    /// Combined with `no_encryption`, this builds an archive which does not use a password at all.
+    /// Such archives should be parsed with an empty password.
+    #[cfg(not(boringssl))]
+    pub fn no_mac(&mut self) -> &mut Self {
+        self.mac_iter = -1;
+        self
+    }
+
+    /// Sets the `localKeyID` attribute linking the key to its certificate.
+    ///
+    /// Defaults to the SHA-1 digest of the certificate.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[cfg(ossl110)]
+    pub fn local_key_id(&mut self, key_id: &[u8]) -> &mut Self {
+        self.local_key_id = Some(key_id.to_vec());
+        self
+    }
+
     /// An additional set of certificates to include in the archive beyond the one provided to
     /// `build`.
     pub fn ca(&mut self, ca: Stack<X509>) -> &mut Self {
@@ -159,6 +464,9 @@ impl Pkcs12Builder {
     /// * `friendly_name` - user defined name for the certificate
     /// * `pkey` - key to store
     /// * `cert` - certificate to store
+    ///
+    /// The key and certificate are linked by a `localKeyID` attribute holding the SHA-1 digest of
+    /// the certificate, unless another one was set with `local_key_id`.
     #[corresponds(PKCS12_create)]
     pub fn build<T>(
         self,
@@ -188,24 +496,35 @@ impl Pkcs12Builder {
             // https://www.openssl.org/docs/man1.0.2/crypto/PKCS12_create.html
             let keytype = 0;
 
-            let pkcs12 = cvt_p(ffi::PKCS12_create(
-                pass.as_ptr() as *const _ as *mut _,
-                friendly_name.as_ptr() as *const _ as *mut _,
-                pkey,
-                cert,
-                ca,
-                nid_key,
-                nid_cert,
-                self.iter,
-                self.mac_iter,
-                keytype,
-            ))
-            .map(Pkcs12)?;
+            #[cfg(ossl110)]
+            let local_key_id = self.local_key_id.as_deref();
+            #[cfg(not(ossl110))]
+            let local_key_id: Option<&[u8]> = None;
+
+            let pkcs12 = match local_key_id {
+                #[cfg(ossl110)]
+                Some(key_id) => {
+                    self.create_with_local_key_id(&pass, &friendly_name, pkey, cert, key_id)?
+                }
+                _ => cvt_p(ffi::PKCS12_create(
+                    pass.as_ptr() as *const _ as *mut _,
+                    friendly_name.as_ptr() as *const _ as *mut _,
+                    pkey,
+                    cert,
+                    ca,
+                    nid_key,
+                    nid_cert,
+                    self.iter,
+                    self.mac_iter,
+                    keytype,
+                ))
+                .map(Pkcs12)?,
+            };
 
             #[cfg(not(boringssl))]
             // BoringSSL does not support overriding the MAC and will always
             // use SHA-1
-            {
+            if self.mac_iter != -1 {
                 let md_type = self
                     .mac_md
                     .map(|md_type| md_type.as_ptr())
@@ -225,14 +544,98 @@ impl Pkcs12Builder {
             Ok(pkcs12)
         }
     }
+
+    // PKCS12_create always derives the localKeyID attribute from the certificate, so the archive
+    // is assembled the same way by hand instead. The MAC is added by the caller.
+    #[cfg(ossl110)]
+    unsafe fn create_with_local_key_id(
+        &self,
+        pass: &CStr,
+        friendly_name: &CStr,
+        pkey: *mut ffi::EVP_PKEY,
+        cert: *mut ffi::X509,
+        key_id: &[u8],
+    ) -> Result<Pkcs12, ErrorStack> {
+        // Before 3.0.0, PKCS12_add_safe applies the same default as PKCS12_create for the cert.
+        let nid_key = match self.nid_key {
+            Nid::UNDEF if cfg!(ossl300) => ffi::NID_aes_256_cbc,
+            Nid::UNDEF => ffi::NID_pbe_WithSHA1And3_Key_TripleDES_CBC,
+            nid => nid.as_raw(),
+        };
+        let nid_cert = match self.nid_cert {
+            Nid::UNDEF if cfg!(ossl300) => ffi::NID_aes_256_cbc,
+            nid => nid.as_raw(),
+        };
+
+        cvt(ffi::X509_check_private_key(cert, pkey))?;
+
+        let cert_bags = Stack::<Pkcs12SafeBag>::new()?;
+        let mut bags = cert_bags.as_ptr();
+        let bag = cvt_p(ffi::PKCS12_add_cert(&mut bags, cert))?;
+        cvt(ffi::PKCS12_add_friendlyname_asc(
+            bag,
+            friendly_name.as_ptr(),
+            -1,
+        ))?;
+        cvt(ffi::PKCS12_add_localkeyid(
+            bag,
+            key_id.as_ptr() as *mut _,
+            key_id.len() as c_int,
+        ))?;
+        if let Some(ca) = &self.ca {
+            for ca in ca {
+                cvt_p(ffi::PKCS12_add_cert(&mut bags, ca.as_ptr()))?;
+            }
+        }
+
+        let key_bags = Stack::<Pkcs12SafeBag>::new()?;
+        let mut bags = key_bags.as_ptr();
+        let bag = cvt_p(ffi::PKCS12_add_key(
+            &mut bags,
+            pkey,
+            0,
+            self.iter,
+            nid_key,
+            pass.as_ptr(),
+        ))?;
+        cvt(ffi::PKCS12_add_friendlyname_asc(
+            bag,
+            friendly_name.as_ptr(),
+            -1,
+        ))?;
+        cvt(ffi::PKCS12_add_localkeyid(
+            bag,
+            key_id.as_ptr() as *mut _,
+            key_id.len() as c_int,
+        ))?;
+
+        let safes = Stack::<Pkcs7>::new()?;
+        let mut psafes = safes.as_ptr();
+        cvt(ffi::PKCS12_add_safe(
+            &mut psafes,
+            cert_bags.as_ptr(),
+            nid_cert,
+            self.iter,
+            pass.as_ptr(),
+        ))?;
+        cvt(ffi::PKCS12_add_safe(
+            &mut psafes,
+            key_bags.as_ptr(),
+            -1,
+            0,
+            ptr::null(),
+        ))?;
+
+        cvt_p(ffi::PKCS12_add_safes(safes.as_ptr(), 0)).map(Pkcs12)
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use crate::asn1::Asn1Time;
+    use crate::asn1::{Asn1Object, Asn1Time};
     use crate::hash::MessageDigest;
     use crate::nid::Nid;
-    use crate::pkey::PKey;
+    use crate::pkey::{PKey, Private};
     use crate::rsa::Rsa;
     use crate::x509::extension::KeyUsage;
     use crate::x509::{X509Name, X509};
@@ -274,6 +677,33 @@ mod test {
         }
     }
 
+    fn identity(subject_name: &str) -> (PKey<Private>, X509) {
+        let rsa = Rsa::generate(2048).unwrap();
+        let pkey = PKey::from_rsa(rsa).unwrap();
+
+        let mut name = X509Name::builder().unwrap();
+        name.append_entry_by_nid(Nid::COMMONNAME, subject_name)
+            .unwrap();
+        let name = name.build();
+
+        let key_usage = KeyUsage::new().digital_signature().build().unwrap();
+
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(365).unwrap())
+            .unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(&name).unwrap();
+        builder.append_extension(key_usage).unwrap();
+        builder.set_pubkey(&pkey).unwrap();
+        builder.sign(&pkey, MessageDigest::sha256()).unwrap();
+        (pkey, builder.build())
+    }
+
     #[test]
     fn create() {
         let subject_name = "ns.example.com";
@@ -317,4 +747,183 @@ mod test {
         );
         assert!(parsed.pkey.public_eq(&pkey));
     }
+
+    #[test]
+    fn create_with_algorithms() {
+        let (pkey, cert) = identity("ns.example.com");
+
+        let mut pkcs12_builder = Pkcs12::builder();
+        pkcs12_builder
+            .key_algorithm(Nid::AES_256_CBC)
+            .cert_algorithm(Nid::AES_256_CBC)
+            .key_iter(10_000)
+            .mac_iter(10_000)
+            .mac_md(MessageDigest::sha256());
+        let pkcs12 = pkcs12_builder
+            .build("mypass", "ns.example.com", &pkey, &cert)
+            .unwrap();
+        let der = pkcs12.to_der().unwrap();
+
+        let parsed = Pkcs12::from_der(&der).unwrap().parse("mypass").unwrap();
+        assert_eq!(parsed.cert.to_der().unwrap(), cert.to_der().unwrap());
+        assert!(parsed.pkey.public_eq(&pkey));
+        assert!(Pkcs12::from_der(&der).unwrap().parse("wrong").is_err());
+
+        // Both the key and the certificate are encrypted with PBES2 and AES-256-CBC.
+        let count = |oid: &str| {
+            let oid = Asn1Object::from_str(oid).unwrap().to_der().unwrap();
+            der.windows(oid.len()).filter(|w| *w == &*oid).count()
+        };
+        assert_eq!(count("1.2.840.113549.1.5.13"), 2);
+        assert_eq!(count("2.16.840.1.101.3.4.1.42"), 2);
+
+        #[cfg(ossl110)]
+        assert_eq!(pkcs12.mac_algorithm().unwrap().object().nid(), Nid::SHA256);
+    }
+
+    #[test]
+    #[cfg(ossl110)]
+    fn create_with_local_key_id() {
+        let (pkey, cert) = identity("ns.example.com");
+        let ca = X509::from_pem(include_bytes!("../test/root-ca.pem")).unwrap();
+        let mut chain = Stack::new().unwrap();
+        chain.push(ca.clone()).unwrap();
+
+        let mut pkcs12_builder = Pkcs12::builder();
+        pkcs12_builder.local_key_id(b"key id").ca(chain);
+        let pkcs12 = pkcs12_builder
+            .build("mypass", "ns.example.com", &pkey, &cert)
+            .unwrap();
+
+        let bags = pkcs12.safe_bags("mypass").unwrap();
+        assert_eq!(bags.len(), 3);
+        assert_eq!(bags[0].cert().unwrap(), cert);
+        assert_eq!(bags[0].local_key_id().as_deref(), Some(&b"key id"[..]));
+        assert_eq!(bags[1].cert().unwrap(), ca);
+        assert_eq!(bags[1].local_key_id(), None);
+        assert_eq!(bags[2].bag_type(), Nid::PKCS8SHROUDEDKEYBAG);
+        assert_eq!(bags[2].local_key_id().as_deref(), Some(&b"key id"[..]));
+        assert_eq!(
+            bags[2].friendly_name().unwrap().to_string(),
+            "ns.example.com"
+        );
+
+        let parsed = pkcs12.parse("mypass").unwrap();
+        assert_eq!(parsed.cert.to_der().unwrap(), cert.to_der().unwrap());
+        assert!(parsed.pkey.public_eq(&pkey));
+        assert_eq!(parsed.chain.unwrap().len(), 1);
+        assert!(pkcs12.mac_algorithm().is_some());
+        assert!(pkcs12.parse("wrong").is_err());
+    }
+
+    #[test]
+    #[cfg(ossl110)]
+    fn create_without_password() {
+        let (pkey, cert) = identity("ns.example.com");
+
+        let mut pkcs12_builder = Pkcs12::builder();
+        pkcs12_builder.no_encryption().no_mac();
+        let pkcs12 = pkcs12_builder
+            .build("", "ns.example.com", &pkey, &cert)
+            .unwrap();
+        let der = pkcs12.to_der().unwrap();
+
+        let cert_der = cert.to_der().unwrap();
+        assert!(der.windows(cert_der.len()).any(|w| w == &*cert_der));
+        assert!(pkcs12.mac_algorithm().is_none());
+
+        let parsed = Pkcs12::from_der(&der).unwrap().parse("").unwrap();
+        assert_eq!(parsed.cert.to_der().unwrap(), cert_der);
+        assert!(parsed.pkey.public_eq(&pkey));
+    }
+
+    #[test]
+    #[cfg(ossl110)]
+    fn parse_entries() {
+        #[cfg(ossl300)]
+        let _provider = crate::provider::Provider::try_load(None, "legacy", true).unwrap();
+
+        let der = include_bytes!("../test/identity.p12");
+        let pkcs12 = Pkcs12::from_der(der).unwrap();
+        let entries = pkcs12.parse_entries("mypass").unwrap();
+        assert_eq!(entries.len(), 2);
+
+        let cert = entries[0].cert.as_ref().unwrap();
+        assert_eq!(
+            hex::encode(cert.digest(MessageDigest::sha1()).unwrap()),
+            "59172d9313e84459bcff27f967e79e6e9217e584"
+        );
+        assert!(entries[0].pkey.is_some());
+
+        assert!(entries[1].pkey.is_none());
+        let cert = entries[1].cert.as_ref().unwrap();
+        assert_eq!(
+            hex::encode(cert.digest(MessageDigest::sha1()).unwrap()),
+            "c0cbdf7cdd03c9773e5468e1f6d2da7d5cbb1875"
+        );
+
+        assert!(pkcs12.parse_entries("wrong").is_err());
+    }
+
+    #[test]
+    #[cfg(ossl110)]
+    fn safe_bags_and_chain_order() {
+        let (pkey, cert) = identity("ns.example.com");
+        let ca1 = X509::from_pem(include_bytes!("../test/root-ca.pem")).unwrap();
+        let ca2 = X509::from_pem(include_bytes!("../test/alt_name_cert.pem")).unwrap();
+        let mut ca = Stack::new().unwrap();
+        ca.push(ca1.clone()).unwrap();
+        ca.push(ca2.clone()).unwrap();
+
+        let mut pkcs12_builder = Pkcs12::builder();
+        pkcs12_builder
+            .key_algorithm(Nid::AES_256_CBC)
+            .cert_algorithm(Nid::AES_256_CBC)
+            .ca(ca);
+        let pkcs12 = pkcs12_builder
+            .build("mypass", "ns.example.com", &pkey, &cert)
+            .unwrap();
+
+        let bags = pkcs12.safe_bags("mypass").unwrap();
+        let bag_types = bags.iter().map(|b| b.bag_type()).collect::<Vec<_>>();
+        assert_eq!(
+            bag_types,
+            [
+                Nid::CERTBAG,
+                Nid::CERTBAG,
+                Nid::CERTBAG,
+                Nid::PKCS8SHROUDEDKEYBAG
+            ]
+        );
+        let key_id = cert.digest(MessageDigest::sha1()).unwrap().to_vec();
+        assert_eq!(bags[0].local_key_id(), Some(key_id.clone()));
+        assert_eq!(
+            bags[0].friendly_name().unwrap().to_string(),
+            "ns.example.com"
+        );
+        assert!(bags[0].private_key("mypass").unwrap().is_none());
+        assert_eq!(bags[1].local_key_id(), None);
+        assert!(bags[3].cert().is_none());
+        assert!(bags[3].private_key("wrong").is_err());
+        assert!(bags[3].safe_contents().is_none());
+
+        let entries = pkcs12.parse_entries("mypass").unwrap();
+        let certs = entries
+            .iter()
+            .map(|e| e.cert.as_ref().unwrap().to_der().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            certs,
+            [
+                cert.to_der().unwrap(),
+                ca1.to_der().unwrap(),
+                ca2.to_der().unwrap()
+            ]
+        );
+        assert!(entries[0].pkey.as_ref().unwrap().public_eq(&pkey));
+        assert_eq!(entries[0].friendly_name.as_deref(), Some("ns.example.com"));
+        assert_eq!(entries[0].local_key_id, Some(key_id));
+        assert!(entries[1].pkey.is_none());
+        assert!(entries[1].friendly_name.is_none());
+    }
 }
@@ -61,4 +61,78 @@ impl SrtpProfileId {
     pub fn as_raw(&self) -> c_ulong {
         self.0
     }
+
+    /// Returns the length in bytes of the SRTP master key used by this profile.
+    ///
+    /// Returns `None` for profiles which do not define a master key length.
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn master_key_len(&self) -> Option<usize> {
+        self.key_salt_len().map(|(key, _)| key)
+    }
+
+    /// Returns the length in bytes of the SRTP master salt used by this profile.
+    ///
+    /// Returns `None` for profiles which do not define a master salt length.
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn master_salt_len(&self) -> Option<usize> {
+        self.key_salt_len().map(|(_, salt)| salt)
+    }
+
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    fn key_salt_len(&self) -> Option<(usize, usize)> {
+        match self.0 {
+            ffi::SRTP_AES128_CM_SHA1_80
+            | ffi::SRTP_AES128_CM_SHA1_32
+            | ffi::SRTP_AES128_F8_SHA1_80
+            | ffi::SRTP_AES128_F8_SHA1_32 => Some((16, 14)),
+            #[cfg(ossl110)]
+            ffi::SRTP_AEAD_AES_128_GCM => Some((16, 12)),
+            #[cfg(ossl110)]
+            ffi::SRTP_AEAD_AES_256_GCM => Some((32, 12)),
+            _ => None,
+        }
+    }
+}
+
+/// SRTP master keys and salts derived from a DTLS-SRTP handshake.
+///
+/// The keying material is laid out as described in [RFC 5764 section 4.2].
+///
+/// [RFC 5764 section 4.2]: https://tools.ietf.org/html/rfc5764#section-4.2
+pub struct SrtpKeyingMaterial {
+    material: Vec<u8>,
+    key_len: usize,
+    salt_len: usize,
+}
+
+impl SrtpKeyingMaterial {
+    pub(crate) fn new(material: Vec<u8>, key_len: usize, salt_len: usize) -> SrtpKeyingMaterial {
+        debug_assert_eq!(material.len(), 2 * (key_len + salt_len));
+        SrtpKeyingMaterial {
+            material,
+            key_len,
+            salt_len,
+        }
+    }
+
+    /// Returns the master key used to protect packets sent by the client.
+    pub fn client_write_key(&self) -> &[u8] {
+        &self.material[..self.key_len]
+    }
+
+    /// Returns the master key used to protect packets sent by the server.
+    pub fn server_write_key(&self) -> &[u8] {
+        &self.material[self.key_len..2 * self.key_len]
+    }
+
+    /// Returns the master salt used to protect packets sent by the client.
+    pub fn client_write_salt(&self) -> &[u8] {
+        let start = 2 * self.key_len;
+        &self.material[start..start + self.salt_len]
+    }
+
+    /// Returns the master salt used to protect packets sent by the server.
+    pub fn server_write_salt(&self) -> &[u8] {
+        &self.material[2 * self.key_len + self.salt_len..]
+    }
 }
@@ -0,0 +1,131 @@
+use libc::{c_int, c_void};
+use std::cmp;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::ptr;
+
+use crate::error::ErrorStack;
+use crate::{cvt, init};
+use openssl_macros::corresponds;
+
+/// One end of a pair of connected in-memory BIOs.
+///
+/// Data written to one end of the pair can be read from the other, making a `BioPair` an
+/// in-process pipe. Because it implements `Read` and `Write`, one end can be used as the
+/// underlying stream of an [`SslStream`] while the application moves the encrypted data
+/// between the other end and its own transport.
+///
+/// Each end buffers a limited amount of data. Writes which do not fit and reads from an empty
+/// end fail with `io::ErrorKind::WouldBlock`, so the stream must be driven in the same way as a
+/// nonblocking socket. Reads return `Ok(0)` once the other end has been dropped or shut down
+/// for writing and all buffered data has been consumed.
+///
+/// The two ends share state, so they cannot be moved to different threads.
+///
+/// [`SslStream`]: struct.SslStream.html
+pub struct BioPair(*mut ffi::BIO);
+
+impl fmt::Debug for BioPair {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("BioPair")
+            .field("pending", &self.pending())
+            .field("write_guarantee", &self.write_guarantee())
+            .finish()
+    }
+}
+
+impl Drop for BioPair {
+    fn drop(&mut self) {
+        unsafe {
+            ffi::BIO_free_all(self.0);
+        }
+    }
+}
+
+impl BioPair {
+    /// Creates a connected pair of BIOs, each of which buffers up to `buf_size` bytes written
+    /// to it.
+    ///
+    /// A `buf_size` of 0 selects OpenSSL's default of 17KB.
+    #[corresponds(BIO_new_bio_pair)]
+    pub fn new(buf_size: usize) -> Result<(BioPair, BioPair), ErrorStack> {
+        init();
+
+        unsafe {
+            let mut bio1 = ptr::null_mut();
+            let mut bio2 = ptr::null_mut();
+            cvt(ffi::BIO_new_bio_pair(
+                &mut bio1, buf_size, &mut bio2, buf_size,
+            ))?;
+            Ok((BioPair(bio1), BioPair(bio2)))
+        }
+    }
+
+    /// Returns the number of bytes which can be read from this end.
+    #[corresponds(BIO_ctrl_pending)]
+    pub fn pending(&self) -> usize {
+        unsafe { ffi::BIO_ctrl_pending(self.0) }
+    }
+
+    /// Returns the number of bytes which can currently be written to this end.
+    #[corresponds(BIO_ctrl_get_write_guarantee)]
+    pub fn write_guarantee(&self) -> usize {
+        unsafe { ffi::BIO_ctrl_get_write_guarantee(self.0) }
+    }
+
+    /// Closes this end for writing.
+    ///
+    /// Once the buffered data has been consumed, reads from the other end return `Ok(0)`.
+    #[corresponds(BIO_shutdown_wr)]
+    pub fn shutdown_write(&mut self) {
+        unsafe {
+            ffi::BIO_shutdown_wr(self.0);
+        }
+    }
+
+    /// Returns a pointer to the raw OpenSSL value.
+    pub fn as_ptr(&self) -> *mut ffi::BIO {
+        self.0
+    }
+
+    fn error(&self) -> io::Error {
+        if unsafe { ffi::BIO_should_retry(self.0) } != 0 {
+            io::Error::from(io::ErrorKind::WouldBlock)
+        } else {
+            io::Error::new(io::ErrorKind::Other, ErrorStack::get())
+        }
+    }
+}
+
+impl Read for BioPair {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let len = cmp::min(c_int::max_value() as usize, buf.len()) as c_int;
+        match unsafe { ffi::BIO_read(self.0, buf.as_mut_ptr() as *mut c_void, len) } {
+            n if n >= 0 => Ok(n as usize),
+            _ => Err(self.error()),
+        }
+    }
+}
+
+impl Write for BioPair {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let len = cmp::min(c_int::max_value() as usize, buf.len()) as c_int;
+        match unsafe { ffi::BIO_write(self.0, buf.as_ptr() as *const c_void, len) } {
+            n if n > 0 => Ok(n as usize),
+            0 => Err(io::Error::from(io::ErrorKind::BrokenPipe)),
+            _ => Err(self.error()),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
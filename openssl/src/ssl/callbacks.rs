@@ -3,7 +3,6 @@ use foreign_types::ForeignType;
 use foreign_types::ForeignTypeRef;
 #[cfg(any(ossl111, not(osslconf = "OPENSSL_NO_PSK")))]
 use libc::c_char;
-#[cfg(ossl111)]
 use libc::size_t;
 use libc::{c_int, c_uchar, c_uint, c_void};
 #[cfg(any(ossl111, not(osslconf = "OPENSSL_NO_PSK")))]
@@ -19,20 +18,26 @@ use crate::dh::Dh;
 #[cfg(all(ossl101, not(ossl110)))]
 use crate::ec::EcKey;
 use crate::error::ErrorStack;
-use crate::pkey::Params;
+use crate::pkey::{PKey, Params, Private};
 #[cfg(any(ossl102, libressl261))]
 use crate::ssl::AlpnError;
+#[cfg(ossl102)]
+use crate::ssl::CertCallbackResponse;
+#[cfg(not(boringssl))]
+use crate::ssl::RenegotiationAttempted;
 use crate::ssl::{
-    try_get_session_ctx_index, SniError, Ssl, SslAlert, SslContext, SslContextRef, SslRef,
-    SslSession, SslSessionRef,
+    try_get_session_ctx_index, SniError, Ssl, SslAlert, SslContentType, SslContext, SslContextRef,
+    SslRef, SslSession, SslSessionRef, SslVersion,
 };
 #[cfg(ossl111)]
 use crate::ssl::{ClientHelloResponse, ExtensionContext};
+#[cfg(ossl350)]
+use crate::ssl::{QuicProtectionLevel, QuicTlsState};
 #[cfg(ossl111)]
 use crate::util::ForeignTypeRefExt;
 #[cfg(ossl111)]
 use crate::x509::X509Ref;
-use crate::x509::{X509StoreContext, X509StoreContextRef};
+use crate::x509::{X509StoreContext, X509StoreContextRef, X509};
 
 pub extern "C" fn raw_verify<F>(preverify_ok: c_int, x509_ctx: *mut ffi::X509_STORE_CTX) -> c_int
 where
@@ -441,6 +446,81 @@ where
     callback(ssl, line);
 }
 
+#[cfg(not(boringssl))]
+pub unsafe extern "C" fn raw_info(ssl: *const ffi::SSL, where_: c_int, ret: c_int) {
+    if where_ & ffi::SSL_CB_WRITE_ALERT == ffi::SSL_CB_WRITE_ALERT
+        && ret & 0xff == ffi::SSL_AD_NO_RENEGOTIATION
+    {
+        let ssl = SslRef::from_ptr_mut(ssl as *mut _);
+        let index = Ssl::cached_ex_index::<RenegotiationAttempted>();
+        if ssl.ex_data(index).is_none() {
+            ssl.set_ex_data(index, RenegotiationAttempted);
+        }
+    }
+}
+
+pub unsafe extern "C" fn raw_msg<F>(
+    write_p: c_int,
+    version: c_int,
+    content_type: c_int,
+    buf: *const c_void,
+    len: size_t,
+    ssl: *mut ffi::SSL,
+    arg: *mut c_void,
+) where
+    F: Fn(&mut SslRef, bool, SslVersion, SslContentType, &[u8]) + 'static + Sync + Send,
+{
+    if arg.is_null() {
+        return;
+    }
+    let ssl = SslRef::from_ptr_mut(ssl);
+    let callback = arg as *const F;
+    let buf = msg_slice(buf, len);
+
+    (*callback)(
+        ssl,
+        write_p != 0,
+        SslVersion(version),
+        SslContentType::from_raw(content_type),
+        buf,
+    );
+}
+
+pub unsafe extern "C" fn ssl_raw_msg<F>(
+    write_p: c_int,
+    version: c_int,
+    content_type: c_int,
+    buf: *const c_void,
+    len: size_t,
+    ssl: *mut ffi::SSL,
+    _arg: *mut c_void,
+) where
+    F: Fn(&mut SslRef, bool, SslVersion, SslContentType, &[u8]) + 'static + Sync + Send,
+{
+    let ssl = SslRef::from_ptr_mut(ssl);
+    let callback = ssl
+        .ex_data(Ssl::cached_ex_index::<Arc<F>>())
+        .expect("BUG: ssl msg callback missing")
+        .clone();
+    let buf = msg_slice(buf, len);
+
+    callback(
+        ssl,
+        write_p != 0,
+        SslVersion(version),
+        SslContentType::from_raw(content_type),
+        buf,
+    );
+}
+
+unsafe fn msg_slice<'a>(buf: *const c_void, len: size_t) -> &'a [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(buf as *const u8, len)
+    }
+}
+
 #[cfg(ossl111)]
 pub unsafe extern "C" fn raw_stateless_cookie_generate<F>(
     ssl: *mut ffi::SSL,
@@ -670,6 +750,48 @@ where
     }
 }
 
+pub unsafe extern "C" fn raw_client_cert<F>(
+    ssl: *mut ffi::SSL,
+    x509: *mut *mut ffi::X509,
+    pkey: *mut *mut ffi::EVP_PKEY,
+) -> c_int
+where
+    F: Fn(&mut SslRef) -> Option<(X509, PKey<Private>)> + 'static + Sync + Send,
+{
+    let ssl = SslRef::from_ptr_mut(ssl);
+    let callback = ssl
+        .ssl_context()
+        .ex_data(SslContext::cached_ex_index::<F>())
+        .expect("BUG: client cert callback missing") as *const F;
+
+    match (*callback)(ssl) {
+        Some((cert, key)) => {
+            *x509 = cert.as_ptr();
+            *pkey = key.as_ptr();
+            mem::forget((cert, key));
+            1
+        }
+        None => 0,
+    }
+}
+
+#[cfg(ossl102)]
+pub unsafe extern "C" fn raw_cert<F>(ssl: *mut ffi::SSL, arg: *mut c_void) -> c_int
+where
+    F: Fn(&mut SslRef) -> Result<CertCallbackResponse, ErrorStack> + 'static + Sync + Send,
+{
+    let ssl = SslRef::from_ptr_mut(ssl);
+    let callback = arg as *const F;
+
+    match (*callback)(ssl) {
+        Ok(response) => response.0,
+        Err(e) => {
+            e.put();
+            0
+        }
+    }
+}
+
 #[cfg(ossl111)]
 pub unsafe extern "C" fn raw_client_hello<F>(
     ssl: *mut ffi::SSL,
@@ -696,3 +818,191 @@ where
         }
     }
 }
+
+#[cfg(ossl350)]
+unsafe extern "C" fn raw_quic_crypto_send(
+    _: *mut ffi::SSL,
+    buf: *const c_uchar,
+    buf_len: size_t,
+    consumed: *mut size_t,
+    arg: *mut c_void,
+) -> c_int {
+    let state = &mut *(arg as *mut QuicTlsState);
+    let data = msg_slice(buf as *const c_void, buf_len);
+    match state.callbacks.send_crypto_data(data) {
+        Ok(()) => {
+            *consumed = buf_len;
+            1
+        }
+        Err(e) => {
+            e.put();
+            0
+        }
+    }
+}
+
+#[cfg(ossl350)]
+unsafe extern "C" fn raw_quic_crypto_recv_rcd(
+    _: *mut ffi::SSL,
+    buf: *mut *const c_uchar,
+    bytes_read: *mut size_t,
+    arg: *mut c_void,
+) -> c_int {
+    let state = &mut *(arg as *mut QuicTlsState);
+    match state.records[state.read_level.as_raw() as usize].front() {
+        Some(record) => {
+            *buf = record.as_ptr();
+            *bytes_read = record.len();
+        }
+        None => {
+            *buf = ptr::null();
+            *bytes_read = 0;
+        }
+    }
+    1
+}
+
+#[cfg(ossl350)]
+unsafe extern "C" fn raw_quic_crypto_release_rcd(
+    _: *mut ffi::SSL,
+    bytes_read: size_t,
+    arg: *mut c_void,
+) -> c_int {
+    let state = &mut *(arg as *mut QuicTlsState);
+    let records = &mut state.records[state.read_level.as_raw() as usize];
+    if let Some(record) = records.front_mut() {
+        if bytes_read >= record.len() {
+            records.pop_front();
+        } else {
+            record.drain(..bytes_read);
+        }
+    }
+    1
+}
+
+#[cfg(ossl350)]
+unsafe extern "C" fn raw_quic_yield_secret(
+    _: *mut ffi::SSL,
+    prot_level: u32,
+    direction: c_int,
+    secret: *const c_uchar,
+    secret_len: size_t,
+    arg: *mut c_void,
+) -> c_int {
+    let state = &mut *(arg as *mut QuicTlsState);
+    let level = QuicProtectionLevel::from_raw(prot_level);
+    let write = direction == 1;
+    let secret = msg_slice(secret as *const c_void, secret_len);
+    match state.callbacks.set_secret(level, write, secret) {
+        Ok(()) => {
+            if !write {
+                state.read_level = level;
+            }
+            1
+        }
+        Err(e) => {
+            e.put();
+            0
+        }
+    }
+}
+
+#[cfg(ossl350)]
+unsafe extern "C" fn raw_quic_got_transport_params(
+    _: *mut ffi::SSL,
+    params: *const c_uchar,
+    params_len: size_t,
+    arg: *mut c_void,
+) -> c_int {
+    let state = &mut *(arg as *mut QuicTlsState);
+    let params = msg_slice(params as *const c_void, params_len);
+    match state.callbacks.transport_params(params) {
+        Ok(()) => 1,
+        Err(e) => {
+            e.put();
+            0
+        }
+    }
+}
+
+#[cfg(ossl350)]
+unsafe extern "C" fn raw_quic_alert(
+    _: *mut ffi::SSL,
+    alert_code: c_uchar,
+    arg: *mut c_void,
+) -> c_int {
+    let state = &mut *(arg as *mut QuicTlsState);
+    match state.callbacks.alert(alert_code) {
+        Ok(()) => 1,
+        Err(e) => {
+            e.put();
+            0
+        }
+    }
+}
+
+#[cfg(ossl350)]
+type QuicCryptoSend =
+    unsafe extern "C" fn(*mut ffi::SSL, *const c_uchar, size_t, *mut size_t, *mut c_void) -> c_int;
+#[cfg(ossl350)]
+type QuicCryptoRecvRcd =
+    unsafe extern "C" fn(*mut ffi::SSL, *mut *const c_uchar, *mut size_t, *mut c_void) -> c_int;
+#[cfg(ossl350)]
+type QuicCryptoReleaseRcd = unsafe extern "C" fn(*mut ffi::SSL, size_t, *mut c_void) -> c_int;
+#[cfg(ossl350)]
+type QuicYieldSecret =
+    unsafe extern "C" fn(*mut ffi::SSL, u32, c_int, *const c_uchar, size_t, *mut c_void) -> c_int;
+#[cfg(ossl350)]
+type QuicGotTransportParams =
+    unsafe extern "C" fn(*mut ffi::SSL, *const c_uchar, size_t, *mut c_void) -> c_int;
+#[cfg(ossl350)]
+type QuicAlert = unsafe extern "C" fn(*mut ffi::SSL, c_uchar, *mut c_void) -> c_int;
+
+#[cfg(ossl350)]
+pub static QUIC_TLS_DISPATCH: [ffi::OSSL_DISPATCH; 7] = unsafe {
+    [
+        ffi::OSSL_DISPATCH {
+            function_id: ffi::OSSL_FUNC_SSL_QUIC_TLS_CRYPTO_SEND,
+            function: Some(mem::transmute::<QuicCryptoSend, unsafe extern "C" fn()>(
+                raw_quic_crypto_send,
+            )),
+        },
+        ffi::OSSL_DISPATCH {
+            function_id: ffi::OSSL_FUNC_SSL_QUIC_TLS_CRYPTO_RECV_RCD,
+            function: Some(mem::transmute::<QuicCryptoRecvRcd, unsafe extern "C" fn()>(
+                raw_quic_crypto_recv_rcd,
+            )),
+        },
+        ffi::OSSL_DISPATCH {
+            function_id: ffi::OSSL_FUNC_SSL_QUIC_TLS_CRYPTO_RELEASE_RCD,
+            function: Some(
+                mem::transmute::<QuicCryptoReleaseRcd, unsafe extern "C" fn()>(
+                    raw_quic_crypto_release_rcd,
+                ),
+            ),
+        },
+        ffi::OSSL_DISPATCH {
+            function_id: ffi::OSSL_FUNC_SSL_QUIC_TLS_YIELD_SECRET,
+            function: Some(mem::transmute::<QuicYieldSecret, unsafe extern "C" fn()>(
+                raw_quic_yield_secret,
+            )),
+        },
+        ffi::OSSL_DISPATCH {
+            function_id: ffi::OSSL_FUNC_SSL_QUIC_TLS_GOT_TRANSPORT_PARAMS,
+            function: Some(mem::transmute::<
+                QuicGotTransportParams,
+                unsafe extern "C" fn(),
+            >(raw_quic_got_transport_params)),
+        },
+        ffi::OSSL_DISPATCH {
+            function_id: ffi::OSSL_FUNC_SSL_QUIC_TLS_ALERT,
+            function: Some(mem::transmute::<QuicAlert, unsafe extern "C" fn()>(
+                raw_quic_alert,
+            )),
+        },
+        ffi::OSSL_DISPATCH {
+            function_id: 0,
+            function: None,
+        },
+    ]
+};
@@ -0,0 +1,132 @@
+use bitflags::bitflags;
+use libc::{c_int, c_uint};
+use std::ffi::CString;
+use std::marker::PhantomData;
+
+use crate::error::ErrorStack;
+use crate::ssl::{SslContextBuilder, SslRef};
+use crate::{cvt, cvt_p, init};
+use foreign_types::ForeignTypeRef;
+use openssl_macros::corresponds;
+
+bitflags! {
+    /// Options controlling how configuration commands are interpreted.
+    pub struct SslConfFlags: c_uint {
+        /// Commands are recognized in their command line form, e.g. `-min_protocol`.
+        const CMDLINE = ffi::SSL_CONF_FLAG_CMDLINE;
+        /// Commands are recognized in their configuration file form, e.g. `MinProtocol`.
+        const FILE = ffi::SSL_CONF_FLAG_FILE;
+        /// Commands which apply to clients are recognized.
+        const CLIENT = ffi::SSL_CONF_FLAG_CLIENT;
+        /// Commands which apply to servers are recognized.
+        const SERVER = ffi::SSL_CONF_FLAG_SERVER;
+        /// Commands which load certificates and private keys are recognized.
+        const CERTIFICATE = ffi::SSL_CONF_FLAG_CERTIFICATE;
+        /// Finishing the configuration fails if a certificate has no matching private key.
+        ///
+        /// Requires OpenSSL 1.1.0 or newer.
+        #[cfg(ossl110)]
+        const REQUIRE_PRIVATE = ffi::SSL_CONF_FLAG_REQUIRE_PRIVATE;
+    }
+}
+
+/// A context for applying OpenSSL configuration commands to an `SslContextBuilder` or `Ssl`.
+///
+/// Commands take the same names and values as those accepted by the `openssl` command line
+/// tools and OpenSSL configuration files, such as `MinProtocol = TLSv1.2` or
+/// `Ciphersuites = TLS_AES_256_GCM_SHA384`. This makes it possible to expose TLS settings in
+/// an application's own configuration without wrapping each of them individually.
+///
+/// An `SslConf` is created with [`SslContextBuilder::conf`] or [`SslRef::conf`]. Once all
+/// commands have been applied, [`finish`] must be called.
+///
+/// Requires OpenSSL 1.0.2 or newer.
+///
+/// [`SslContextBuilder::conf`]: struct.SslContextBuilder.html#method.conf
+/// [`SslRef::conf`]: struct.SslRef.html#method.conf
+/// [`finish`]: #method.finish
+pub struct SslConf<'a> {
+    ptr: *mut ffi::SSL_CONF_CTX,
+    _p: PhantomData<&'a mut ()>,
+}
+
+unsafe impl<'a> Send for SslConf<'a> {}
+unsafe impl<'a> Sync for SslConf<'a> {}
+
+impl<'a> Drop for SslConf<'a> {
+    fn drop(&mut self) {
+        unsafe { ffi::SSL_CONF_CTX_free(self.ptr) }
+    }
+}
+
+impl<'a> SslConf<'a> {
+    fn new(flags: SslConfFlags) -> Result<SslConf<'a>, ErrorStack> {
+        init();
+
+        unsafe {
+            let ptr = cvt_p(ffi::SSL_CONF_CTX_new())?;
+            // Always report failed commands on the error stack so they can be returned.
+            ffi::SSL_CONF_CTX_set_flags(ptr, flags.bits() | ffi::SSL_CONF_FLAG_SHOW_ERRORS);
+            Ok(SslConf {
+                ptr,
+                _p: PhantomData,
+            })
+        }
+    }
+
+    pub(crate) fn for_context(
+        ctx: &'a mut SslContextBuilder,
+        flags: SslConfFlags,
+    ) -> Result<SslConf<'a>, ErrorStack> {
+        let conf = SslConf::new(flags)?;
+        unsafe { ffi::SSL_CONF_CTX_set_ssl_ctx(conf.ptr, ctx.as_ptr()) };
+        Ok(conf)
+    }
+
+    pub(crate) fn for_ssl(
+        ssl: &'a mut SslRef,
+        flags: SslConfFlags,
+    ) -> Result<SslConf<'a>, ErrorStack> {
+        let conf = SslConf::new(flags)?;
+        unsafe { ffi::SSL_CONF_CTX_set_ssl(conf.ptr, ssl.as_ptr()) };
+        Ok(conf)
+    }
+
+    /// Sets a prefix which commands must start with to be recognized.
+    ///
+    /// For example, with a prefix of `TLS` the command `TLSMinProtocol` is interpreted as
+    /// `MinProtocol`.
+    #[corresponds(SSL_CONF_CTX_set1_prefix)]
+    pub fn set_prefix(&mut self, prefix: &str) -> Result<(), ErrorStack> {
+        let prefix = CString::new(prefix).unwrap();
+        unsafe { cvt(ffi::SSL_CONF_CTX_set1_prefix(self.ptr, prefix.as_ptr())).map(|_| ()) }
+    }
+
+    /// Applies a configuration command.
+    ///
+    /// Commands which take no value, which are only recognized with `SslConfFlags::CMDLINE`,
+    /// ignore `value`. An error is returned if the command is not recognized, is missing a value,
+    /// or if the value is invalid.
+    #[corresponds(SSL_CONF_cmd)]
+    pub fn cmd(&mut self, cmd: &str, value: &str) -> Result<(), ErrorStack> {
+        let cmd = CString::new(cmd).unwrap();
+        let value = CString::new(value).unwrap();
+        unsafe {
+            let r = ffi::SSL_CONF_cmd(self.ptr, cmd.as_ptr(), value.as_ptr());
+            if r > 0 {
+                Ok(())
+            } else {
+                Err(ErrorStack::get())
+            }
+        }
+    }
+
+    /// Finishes applying configuration commands.
+    ///
+    /// This performs any processing which depends on several commands, such as loading the
+    /// private keys of configured certificates.
+    #[corresponds(SSL_CONF_CTX_finish)]
+    pub fn finish(self) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::SSL_CONF_CTX_finish(self.ptr) as c_int).map(|_| ()) }
+    }
+}
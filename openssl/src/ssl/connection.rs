@@ -0,0 +1,193 @@
+use foreign_types::ForeignTypeRef;
+use libc::{c_int, c_void};
+use std::cmp;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::slice;
+
+use crate::error::ErrorStack;
+use crate::ssl::{Error, ShutdownResult, Ssl, SslRef};
+use crate::{cvt_p, init};
+use openssl_macros::corresponds;
+
+/// A TLS session whose encrypted data is exchanged through in-memory buffers.
+///
+/// Unlike [`SslStream`], an `SslConnection` is not attached to an underlying stream. Instead, the
+/// application is responsible for moving TLS records between the connection and the transport:
+/// records received from the peer are passed to [`read_tls`], and records produced by the
+/// connection are taken with [`write_tls`]. This makes it possible to drive a session from
+/// completion-based or fully asynchronous I/O.
+///
+/// Operations which need more data from the peer fail with [`ErrorCode::WANT_READ`]. When that
+/// happens, flush any pending records with [`write_tls`], feed the connection more data with
+/// [`read_tls`], and retry the operation.
+///
+/// [`SslStream`]: struct.SslStream.html
+/// [`read_tls`]: #method.read_tls
+/// [`write_tls`]: #method.write_tls
+/// [`ErrorCode::WANT_READ`]: struct.ErrorCode.html#associatedconstant.WANT_READ
+pub struct SslConnection {
+    ssl: Ssl,
+}
+
+impl fmt::Debug for SslConnection {
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("SslConnection")
+            .field("ssl", &self.ssl)
+            .finish()
+    }
+}
+
+impl SslConnection {
+    /// Creates a new `SslConnection`, attaching a pair of memory BIOs to the `Ssl`.
+    ///
+    /// `set_connect_state` or `set_accept_state` must be called on the `Ssl` before the handshake
+    /// is started.
+    #[corresponds(SSL_set_bio)]
+    pub fn new(ssl: Ssl) -> Result<SslConnection, ErrorStack> {
+        init();
+
+        unsafe {
+            let rbio = cvt_p(ffi::BIO_new(ffi::BIO_s_mem()))?;
+            let wbio = match cvt_p(ffi::BIO_new(ffi::BIO_s_mem())) {
+                Ok(wbio) => wbio,
+                Err(e) => {
+                    ffi::BIO_free_all(rbio);
+                    return Err(e);
+                }
+            };
+            // An empty read BIO signals that more data is needed rather than EOF.
+            ffi::BIO_set_mem_eof_return(rbio, -1);
+            ffi::SSL_set_bio(ssl.as_ptr(), rbio, wbio);
+        }
+
+        Ok(SslConnection { ssl })
+    }
+
+    /// Returns a shared reference to the `Ssl` object associated with this connection.
+    pub fn ssl(&self) -> &SslRef {
+        &self.ssl
+    }
+
+    /// Returns a mutable reference to the `Ssl` object associated with this connection.
+    pub fn ssl_mut(&mut self) -> &mut SslRef {
+        &mut self.ssl
+    }
+
+    /// Reads TLS records received from the peer from `rd` into the connection.
+    ///
+    /// At most one call to `rd.read` is made. The number of bytes consumed is returned.
+    pub fn read_tls<R: Read>(&mut self, rd: &mut R) -> io::Result<usize> {
+        let mut buf = [0; 16 * 1024];
+        let len = rd.read(&mut buf)?;
+        if len > 0 {
+            let ret = unsafe {
+                ffi::BIO_write(
+                    ffi::SSL_get_rbio(self.ssl.as_ptr()),
+                    buf.as_ptr() as *const c_void,
+                    len as c_int,
+                )
+            };
+            if ret != len as c_int {
+                return Err(io::Error::new(io::ErrorKind::Other, ErrorStack::get()));
+            }
+        }
+        Ok(len)
+    }
+
+    /// Writes TLS records produced by the connection to `wr`.
+    ///
+    /// At most one call to `wr.write` is made. Only the bytes accepted by `wr` are removed from
+    /// the connection; the rest remain pending. The number of bytes written is returned.
+    pub fn write_tls<W: Write>(&mut self, wr: &mut W) -> io::Result<usize> {
+        let wbio = unsafe { ffi::SSL_get_wbio(self.ssl.as_ptr()) };
+        let pending = unsafe {
+            let mut ptr = std::ptr::null_mut();
+            let len = ffi::BIO_get_mem_data(wbio, &mut ptr);
+            if len <= 0 {
+                return Ok(0);
+            }
+            slice::from_raw_parts(ptr as *const u8, len as usize)
+        };
+
+        let len = wr.write(pending)?;
+
+        let mut discard = [0; 4096];
+        let mut remaining = len;
+        while remaining > 0 {
+            let n = cmp::min(remaining, discard.len());
+            unsafe {
+                ffi::BIO_read(wbio, discard.as_mut_ptr() as *mut c_void, n as c_int);
+            }
+            remaining -= n;
+        }
+
+        Ok(len)
+    }
+
+    /// Returns the number of bytes of TLS records waiting to be taken with [`write_tls`].
+    ///
+    /// [`write_tls`]: #method.write_tls
+    pub fn pending_tls(&self) -> usize {
+        unsafe { ffi::BIO_pending(ffi::SSL_get_wbio(self.ssl.as_ptr())) as usize }
+    }
+
+    /// Initiates or continues the handshake.
+    #[corresponds(SSL_do_handshake)]
+    pub fn do_handshake(&mut self) -> Result<(), Error> {
+        let ret = unsafe { ffi::SSL_do_handshake(self.ssl.as_ptr()) };
+        if ret > 0 {
+            Ok(())
+        } else {
+            Err(self.make_error(ret))
+        }
+    }
+
+    /// Reads decrypted application data.
+    #[corresponds(SSL_read)]
+    pub fn ssl_read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        match self.ssl.read(buf) {
+            Ok(n) => Ok(n),
+            Err(ret) => Err(self.make_error(ret)),
+        }
+    }
+
+    /// Encrypts application data, producing TLS records to be taken with [`write_tls`].
+    ///
+    /// [`write_tls`]: #method.write_tls
+    #[corresponds(SSL_write)]
+    pub fn ssl_write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        match self.ssl.write(buf) {
+            Ok(n) => Ok(n),
+            Err(ret) => Err(self.make_error(ret)),
+        }
+    }
+
+    /// Shuts down the session.
+    ///
+    /// See [`SslStream::shutdown`] for details.
+    ///
+    /// [`SslStream::shutdown`]: struct.SslStream.html#method.shutdown
+    #[corresponds(SSL_shutdown)]
+    pub fn shutdown(&mut self) -> Result<ShutdownResult, Error> {
+        match unsafe { ffi::SSL_shutdown(self.ssl.as_ptr()) } {
+            0 => Ok(ShutdownResult::Sent),
+            1 => Ok(ShutdownResult::Received),
+            n => Err(self.make_error(n)),
+        }
+    }
+
+    fn make_error(&mut self, ret: c_int) -> Error {
+        // memory BIOs don't fail with I/O errors
+        let code = self.ssl.get_error(ret);
+        Error::from_code(code, || None)
+    }
+}
@@ -1,16 +1,27 @@
 use cfg_if::cfg_if;
+use once_cell::sync::OnceCell;
+use std::collections::HashMap;
 use std::io::{Read, Write};
 use std::ops::{Deref, DerefMut};
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 
 use crate::dh::Dh;
 use crate::error::ErrorStack;
+use crate::ex_data::Index;
+use crate::hash::{hash, MessageDigest};
 #[cfg(any(ossl111, libressl340))]
 use crate::ssl::SslVersion;
+#[cfg(ossl110)]
+use crate::ssl::StatusType;
 use crate::ssl::{
-    HandshakeError, Ssl, SslContext, SslContextBuilder, SslContextRef, SslMethod, SslMode,
-    SslOptions, SslRef, SslStream, SslVerifyMode,
+    HandshakeError, NameType, SniError, Ssl, SslContext, SslContextBuilder, SslContextRef,
+    SslMethod, SslMode, SslOptions, SslRef, SslSession, SslSessionCacheMode, SslStream,
+    SslVerifyMode,
 };
 use crate::version;
+use crate::x509::{X509Ref, X509StoreContextRef, X509VerifyResult};
+use openssl_macros::corresponds;
 
 const FFDHE_2048: &str = "
 -----BEGIN DH PARAMETERS-----
@@ -70,6 +81,14 @@ impl SslConnector {
     /// Creates a new builder for TLS connections.
     ///
     /// The default configuration is subject to change, and is currently derived from Python.
+    ///
+    /// On OpenSSL 1.1.0 and newer, a status callback is installed which enforces OCSP
+    /// Must-Staple for connections configured with
+    /// [`ConnectConfiguration::set_require_ocsp_must_staple`]. It accepts every other connection
+    /// without looking at the stapled response. If it is replaced with `set_status_callback`,
+    /// connections requiring Must-Staple can no longer be configured.
+    ///
+    /// [`ConnectConfiguration::set_require_ocsp_must_staple`]: struct.ConnectConfiguration.html#method.set_require_ocsp_must_staple
     pub fn builder(method: SslMethod) -> Result<SslConnectorBuilder, ErrorStack> {
         let mut ctx = ctx(method)?;
         ctx.set_default_verify_paths()?;
@@ -77,6 +96,8 @@ impl SslConnector {
             "DEFAULT:!aNULL:!eNULL:!MD5:!3DES:!DES:!RC4:!IDEA:!SEED:!aDSS:!SRP:!PSK",
         )?;
         setup_verify(&mut ctx);
+        #[cfg(ossl110)]
+        must_staple::install(&mut ctx)?;
 
         Ok(SslConnectorBuilder(ctx))
     }
@@ -97,6 +118,12 @@ impl SslConnector {
             ssl,
             sni: true,
             verify_hostname: true,
+            accept_invalid_certs: false,
+            port: None,
+            verify_callback: None,
+            pinned_public_keys: vec![],
+            wildcard_policy: WildcardPolicy::new(),
+            require_ocsp_must_staple: false,
         })
     }
 
@@ -112,9 +139,80 @@ impl SslConnector {
 }
 
 /// A builder for `SslConnector`s.
+///
+/// All of the methods of `SslContextBuilder` are available through `Deref`, so common settings
+/// such as [`set_ca_file`] and [`set_min_proto_version`] can be called on the builder directly.
+///
+/// ```no_run
+/// use openssl::ssl::{SslConnector, SslMethod, SslVersion};
+///
+/// let mut builder = SslConnector::builder(SslMethod::tls()).unwrap();
+/// builder.set_ca_file("ca.pem").unwrap();
+/// builder.set_min_proto_version(Some(SslVersion::TLS1_2)).unwrap();
+/// let connector = builder.build();
+/// ```
+///
+/// [`set_ca_file`]: struct.SslContextBuilder.html#method.set_ca_file
+/// [`set_min_proto_version`]: struct.SslContextBuilder.html#method.set_min_proto_version
 pub struct SslConnectorBuilder(SslContextBuilder);
 
 impl SslConnectorBuilder {
+    /// Adds a certificate to the set trusted for verifying servers.
+    ///
+    /// This is in addition to the system's default trusted certificates, which are loaded by
+    /// `SslConnector::builder`.
+    #[corresponds(X509_STORE_add_cert)]
+    pub fn add_root_certificate(&mut self, cert: &X509Ref) -> Result<(), ErrorStack> {
+        self.cert_store_mut().add_cert(cert.to_owned())
+    }
+
+    /// Enables a client-side session cache keyed by the server's host name and port.
+    ///
+    /// Sessions negotiated by connections made through the connector are stored in the cache, and
+    /// later connections to the same server automatically attempt to resume them, saving a round
+    /// trip and the cost of a full handshake. The host name is the domain passed to `connect` or
+    /// `into_ssl`, and the port is the one set with [`ConnectConfiguration::set_port`], if any.
+    ///
+    /// At most `capacity` sessions are kept, with the oldest being evicted first, and sessions are
+    /// not offered for resumption once they are older than `ttl`. As with any session, OpenSSL
+    /// only allows a session to be resumed if the connection it came from was closed with
+    /// `shutdown`.
+    ///
+    /// The server's certificate is not verified when a session is resumed, so sessions are only
+    /// shared between connections which verify the certificate in the same way: with the same
+    /// verification mode, hostname verification and wildcard policy, pinned public keys, and OCSP
+    /// Must-Staple setting. Connections with a callback set by
+    /// [`ConnectConfiguration::verify_callback`] do not use the cache.
+    ///
+    /// This replaces any callback set with `set_new_session_callback` and enables client-side
+    /// session caching with `set_session_cache_mode`.
+    ///
+    /// [`ConnectConfiguration::set_port`]: struct.ConnectConfiguration.html#method.set_port
+    /// [`ConnectConfiguration::verify_callback`]: struct.ConnectConfiguration.html#method.verify_callback
+    pub fn set_client_session_cache(
+        &mut self,
+        capacity: usize,
+        ttl: Duration,
+    ) -> Result<(), ErrorStack> {
+        let cache_idx = *session_cache_idx()?;
+        let key_idx = *session_key_idx()?;
+
+        let cache = Arc::new(ClientSessionCache {
+            capacity,
+            ttl,
+            sessions: Mutex::new(HashMap::new()),
+        });
+        self.set_ex_data(cache_idx, cache.clone());
+        self.set_session_cache_mode(SslSessionCacheMode::CLIENT);
+        self.set_new_session_callback(move |ssl, session| {
+            if let Some(key) = ssl.ex_data(key_idx) {
+                cache.insert(key.clone(), session);
+            }
+        });
+
+        Ok(())
+    }
+
     /// Consumes the builder, returning an `SslConnector`.
     pub fn build(self) -> SslConnector {
         SslConnector(self.0.build())
@@ -140,8 +238,16 @@ pub struct ConnectConfiguration {
     ssl: Ssl,
     sni: bool,
     verify_hostname: bool,
+    accept_invalid_certs: bool,
+    port: Option<u16>,
+    verify_callback: Option<VerifyCallback>,
+    pinned_public_keys: Vec<[u8; 32]>,
+    wildcard_policy: WildcardPolicy,
+    require_ocsp_must_staple: bool,
 }
 
+type VerifyCallback = Box<dyn Fn(bool, &mut X509StoreContextRef) -> bool + 'static + Sync + Send>;
+
 impl ConnectConfiguration {
     /// A builder-style version of `set_use_server_name_indication`.
     pub fn use_server_name_indication(mut self, use_sni: bool) -> ConnectConfiguration {
@@ -175,6 +281,144 @@ impl ConnectConfiguration {
         self.verify_hostname = verify_hostname;
     }
 
+    /// A builder-style version of `set_wildcard_policy`.
+    pub fn wildcard_policy(mut self, policy: WildcardPolicy) -> ConnectConfiguration {
+        self.set_wildcard_policy(policy);
+        self
+    }
+
+    /// Sets the rules used to match wildcard names in the server's certificate during hostname
+    /// verification.
+    ///
+    /// Defaults to `WildcardPolicy::new()`.
+    pub fn set_wildcard_policy(&mut self, policy: WildcardPolicy) {
+        self.wildcard_policy = policy;
+    }
+
+    /// A builder-style version of `set_require_ocsp_must_staple`.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[cfg(ossl110)]
+    pub fn require_ocsp_must_staple(mut self, require: bool) -> ConnectConfiguration {
+        self.set_require_ocsp_must_staple(require);
+        self
+    }
+
+    /// Configures the connection to enforce the OCSP Must-Staple certificate extension.
+    ///
+    /// If enabled, the server is asked for a stapled OCSP response. When the server's certificate
+    /// carries the TLS Feature extension (RFC 7633) with `status_request`, the handshake fails
+    /// unless the server staples a response which is signed by a trusted responder, is currently
+    /// valid, and reports the certificate as good. The handshake also fails if the extension
+    /// can't be decoded. Certificates without the extension are not affected.
+    ///
+    /// The response is verified against the certificate store the connection is verified with.
+    /// Before OpenSSL 3.0.0, the context's certificate store is used instead.
+    ///
+    /// The check is performed by a status callback installed by `SslConnector::builder`. If that
+    /// callback has been replaced with `set_status_callback`, the connection fails to be set up.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[cfg(ossl110)]
+    pub fn set_require_ocsp_must_staple(&mut self, require: bool) {
+        self.require_ocsp_must_staple = require;
+    }
+
+    /// Sets a certificate verification callback for this connection only.
+    ///
+    /// This overrides any callback configured on the `SslConnector`, allowing a single connector
+    /// to be shared between destinations which need different checks, such as certificate
+    /// pinning. The callback is passed the result of OpenSSL's verification, including hostname
+    /// verification if enabled, and returns whether the certificate should be accepted. See
+    /// [`SslContextBuilder::set_verify_callback`] for details.
+    ///
+    /// [`SslContextBuilder::set_verify_callback`]: struct.SslContextBuilder.html#method.set_verify_callback
+    pub fn verify_callback<F>(mut self, verify: F) -> ConnectConfiguration
+    where
+        F: Fn(bool, &mut X509StoreContextRef) -> bool + 'static + Sync + Send,
+    {
+        self.verify_callback = Some(Box::new(verify));
+        self
+    }
+
+    /// A builder-style version of `set_pinned_public_keys`.
+    pub fn pinned_public_keys(mut self, pins: Vec<[u8; 32]>) -> ConnectConfiguration {
+        self.set_pinned_public_keys(pins);
+        self
+    }
+
+    /// Requires the server's verified certificate chain to contain one of a set of public keys.
+    ///
+    /// Each pin is the SHA-256 digest of a DER-encoded SubjectPublicKeyInfo, as used by HTTP
+    /// Public Key Pinning. The check is made in addition to normal certificate and hostname
+    /// verification, and the handshake fails with `X509VerifyResult::APPLICATION_VERIFICATION` if
+    /// no certificate in the chain matches. Pinning an intermediate or root key in addition to
+    /// the leaf key allows the server's certificate to be renewed without updating the pins.
+    ///
+    /// Pins can only be checked against a verified chain, so `set_danger_accept_invalid_certs`
+    /// has no effect if any are set. Sessions resumed from the connector's client session cache
+    /// are only offered to connections with the same pins, but a session set directly with
+    /// `SslRef::set_session` is resumed without checking them.
+    ///
+    /// Defaults to no pins, which disables the check.
+    pub fn set_pinned_public_keys(&mut self, pins: Vec<[u8; 32]>) {
+        self.pinned_public_keys = pins;
+    }
+
+    /// A builder-style version of `set_danger_accept_invalid_certs`.
+    pub fn danger_accept_invalid_certs(
+        mut self,
+        accept_invalid_certs: bool,
+    ) -> ConnectConfiguration {
+        self.set_danger_accept_invalid_certs(accept_invalid_certs);
+        self
+    }
+
+    /// Configures the connection to succeed even if the server's certificate is invalid.
+    ///
+    /// The certificate chain is still verified, and the result is recorded rather than aborting
+    /// the handshake. Once connected, the presented chain is available from
+    /// `SslRef::peer_cert_chain` and the reason verification failed, if any, from
+    /// `SslRef::verify_result`. This is intended for diagnostic tools which need to report why a
+    /// server's certificate would be rejected.
+    ///
+    /// A callback set with [`verify_callback`] is still called for each certificate in the chain,
+    /// but its return value no longer aborts the handshake. This has no effect if pinned public
+    /// keys are set with [`set_pinned_public_keys`], as they require a verified chain.
+    ///
+    /// Defaults to `false`.
+    ///
+    /// # Warning
+    ///
+    /// You should think very carefully before you use this method. If invalid certificates are
+    /// accepted, the connection provides no protection against man-in-the-middle attacks, and
+    /// should not be used to exchange any sensitive data.
+    ///
+    /// [`verify_callback`]: #method.verify_callback
+    /// [`set_pinned_public_keys`]: #method.set_pinned_public_keys
+    pub fn set_danger_accept_invalid_certs(&mut self, accept_invalid_certs: bool) {
+        self.accept_invalid_certs = accept_invalid_certs;
+    }
+
+    /// A builder-style version of `set_port`.
+    pub fn port(mut self, port: u16) -> ConnectConfiguration {
+        self.set_port(port);
+        self
+    }
+
+    /// Sets the port of the server being connected to.
+    ///
+    /// It is combined with the domain to look up sessions in the connector's client session cache,
+    /// so that different services on the same host do not share sessions. It has no effect if
+    /// the cache is not enabled with [`SslConnectorBuilder::set_client_session_cache`].
+    ///
+    /// [`SslConnectorBuilder::set_client_session_cache`]: struct.SslConnectorBuilder.html#method.set_client_session_cache
+    pub fn set_port(&mut self, port: u16) {
+        self.port = Some(port);
+    }
+
     /// Returns an `Ssl` configured to connect to the provided domain.
     ///
     /// The domain is used for SNI and hostname verification if enabled.
@@ -183,8 +427,73 @@ impl ConnectConfiguration {
             self.ssl.set_hostname(domain)?;
         }
 
+        let mut public_suffixes = vec![];
         if self.verify_hostname {
-            setup_verify_hostname(&mut self.ssl, domain)?;
+            setup_verify_hostname(&mut self.ssl, domain, &self.wildcard_policy)?;
+            public_suffixes = std::mem::take(&mut self.wildcard_policy.public_suffixes);
+        }
+
+        // pins are only meaningful if the chain they're found in has been verified
+        let mode = if self.accept_invalid_certs && self.pinned_public_keys.is_empty() {
+            SslVerifyMode::NONE
+        } else {
+            self.ssl.verify_mode()
+        };
+
+        // the checks made by a per-connection callback can't be compared, so its sessions aren't
+        // cached
+        let session_key = match self.verify_callback {
+            Some(_) => None,
+            None => Some(SessionKey {
+                domain: domain.to_string(),
+                port: self.port,
+                verify_mode: mode,
+                verify_hostname: self.verify_hostname,
+                wildcard_policy: self.wildcard_policy.clone(),
+                pinned_public_keys: self.pinned_public_keys.clone(),
+                require_ocsp_must_staple: self.require_ocsp_must_staple,
+            }),
+        };
+
+        let pins = std::mem::take(&mut self.pinned_public_keys);
+        let verify = self.verify_callback.take();
+        if verify.is_some() || !pins.is_empty() || !public_suffixes.is_empty() {
+            // the callback is installed along with the mode so that it still runs in danger mode
+            setup_verify_callback(&mut self.ssl, mode, move |preverify_ok, x509_ctx| {
+                let preverify_ok = verify_public_suffixes(preverify_ok, x509_ctx, &public_suffixes);
+                let preverify_ok = verify_pins(preverify_ok, x509_ctx, &pins);
+                match &verify {
+                    Some(verify) => verify(preverify_ok, x509_ctx),
+                    None => preverify_ok,
+                }
+            });
+        } else {
+            self.ssl.set_verify(mode);
+        }
+
+        #[cfg(ossl110)]
+        if self.require_ocsp_must_staple {
+            if !must_staple::is_installed(self.ssl.ssl_context()) {
+                return Err(ErrorStack::from_reason(
+                    ffi::ERR_LIB_SSL,
+                    ffi::SSL_R_BAD_VALUE,
+                ));
+            }
+            self.ssl.set_status_type(StatusType::OCSP)?;
+            self.ssl.set_ex_data(*must_staple::try_get_idx()?, ());
+        }
+
+        let cache = self
+            .ssl
+            .ssl_context()
+            .ex_data(*session_cache_idx()?)
+            .cloned();
+        if let (Some(cache), Some(key)) = (cache, session_key) {
+            if let Some(session) = cache.get(&key) {
+                // The session was negotiated with this connector's context.
+                unsafe { self.ssl.set_session(&session)? };
+            }
+            self.ssl.set_ex_data(*session_key_idx()?, key);
         }
 
         Ok(self.ssl)
@@ -266,6 +575,43 @@ impl SslAcceptor {
         Ok(SslAcceptorBuilder(ctx))
     }
 
+    /// Creates a new builder configured to connect to legacy clients, including those which only
+    /// support TLS 1.0 or 1.1. This should only be used when such clients must be supported.
+    ///
+    /// This corresponds to the old configuration of version 5 of Mozilla's server side TLS
+    /// recommendations, with the exception that 3DES cipher suites are not enabled. See its
+    /// [documentation][docs] for more details on specifics.
+    ///
+    /// On OpenSSL 3.0 and newer, the security level is lowered to 0 since TLS 1.0 and 1.1 are
+    /// otherwise rejected.
+    ///
+    /// [docs]: https://wiki.mozilla.org/Security/Server_Side_TLS
+    pub fn mozilla_old(method: SslMethod) -> Result<SslAcceptorBuilder, ErrorStack> {
+        let mut ctx = ctx(method)?;
+        ctx.set_options(SslOptions::CIPHER_SERVER_PREFERENCE);
+        let dh = Dh::params_from_pem(FFDHE_2048.as_bytes())?;
+        ctx.set_tmp_dh(&dh)?;
+        setup_curves(&mut ctx)?;
+        let mut ciphers = String::from(
+            "ECDHE-ECDSA-AES128-GCM-SHA256:ECDHE-RSA-AES128-GCM-SHA256:ECDHE-ECDSA-AES256-GCM-SHA384:\
+             ECDHE-RSA-AES256-GCM-SHA384:ECDHE-ECDSA-CHACHA20-POLY1305:ECDHE-RSA-CHACHA20-POLY1305:\
+             DHE-RSA-AES128-GCM-SHA256:DHE-RSA-AES256-GCM-SHA384:DHE-RSA-CHACHA20-POLY1305:\
+             ECDHE-ECDSA-AES128-SHA256:ECDHE-RSA-AES128-SHA256:ECDHE-ECDSA-AES128-SHA:\
+             ECDHE-RSA-AES128-SHA:ECDHE-ECDSA-AES256-SHA384:ECDHE-RSA-AES256-SHA384:\
+             ECDHE-ECDSA-AES256-SHA:ECDHE-RSA-AES256-SHA:DHE-RSA-AES128-SHA256:DHE-RSA-AES256-SHA256:\
+             AES128-GCM-SHA256:AES256-GCM-SHA384:AES128-SHA256:AES256-SHA256:AES128-SHA:AES256-SHA",
+        );
+        if cfg!(ossl300) {
+            ciphers.push_str(":@SECLEVEL=0");
+        }
+        ctx.set_cipher_list(&ciphers)?;
+        #[cfg(any(ossl111, libressl340))]
+        ctx.set_ciphersuites(
+            "TLS_AES_128_GCM_SHA256:TLS_AES_256_GCM_SHA384:TLS_CHACHA20_POLY1305_SHA256",
+        )?;
+        Ok(SslAcceptorBuilder(ctx))
+    }
+
     /// Creates a new builder configured to connect to non-legacy clients. This should generally be
     /// considered a reasonable default choice.
     ///
@@ -342,6 +688,27 @@ impl SslAcceptor {
 pub struct SslAcceptorBuilder(SslContextBuilder);
 
 impl SslAcceptorBuilder {
+    /// Selects the context used for each connection from the server name sent by the client.
+    ///
+    /// This installs a server name indication callback which looks the name up in `contexts` and
+    /// switches the connection to the matching context, making its certificate and private key
+    /// the ones presented to the client. Connections which send no server name, or one with no
+    /// match, use the acceptor's own certificate.
+    ///
+    /// This replaces any callback set with `set_servername_callback`.
+    pub fn set_server_name_contexts(&mut self, contexts: ServerNameContexts) {
+        self.set_servername_callback(move |ssl, _| {
+            let ctx = ssl
+                .servername(NameType::HOST_NAME)
+                .and_then(|name| contexts.get(name));
+            if let Some(ctx) = ctx {
+                ssl.set_ssl_context(ctx)
+                    .map_err(|_| SniError::ALERT_FATAL)?;
+            }
+            Ok(())
+        });
+    }
+
     /// Consumes the builder, returning a `SslAcceptor`.
     pub fn build(self) -> SslAcceptor {
         SslAcceptor(self.0.build())
@@ -362,6 +729,419 @@ impl DerefMut for SslAcceptorBuilder {
     }
 }
 
+/// The rules used to match wildcard names, such as `*.example.com`, in a server's certificate
+/// during hostname verification.
+///
+/// By default a wildcard may only make up the entire leftmost label of a name, and matches exactly
+/// one label of the hostname.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct WildcardPolicy {
+    wildcards: bool,
+    partial_wildcards: bool,
+    public_suffixes: Vec<String>,
+}
+
+impl Default for WildcardPolicy {
+    fn default() -> WildcardPolicy {
+        WildcardPolicy::new()
+    }
+}
+
+impl WildcardPolicy {
+    /// Returns the default policy.
+    pub fn new() -> WildcardPolicy {
+        WildcardPolicy {
+            wildcards: true,
+            partial_wildcards: false,
+            public_suffixes: vec![],
+        }
+    }
+
+    /// Configures whether wildcard names are matched at all.
+    ///
+    /// Defaults to `true`.
+    pub fn set_allow_wildcards(&mut self, allow: bool) {
+        self.wildcards = allow;
+    }
+
+    /// Configures whether a wildcard may make up part of a label, such as `www*.example.com`.
+    ///
+    /// Partial wildcards never match in internationalized (`xn--`) labels.
+    ///
+    /// Defaults to `false`.
+    pub fn set_allow_partial_wildcards(&mut self, allow: bool) {
+        self.partial_wildcards = allow;
+    }
+
+    /// Adds a public suffix, such as `co.uk`, directly below which wildcards are not matched.
+    ///
+    /// With `co.uk` added, a certificate for `*.co.uk` will not be accepted for `example.co.uk`.
+    /// Suffixes are typically taken from the [Public Suffix List].
+    ///
+    /// Public suffixes are not supported with LibreSSL 2.6.1 or newer, and are ignored there.
+    ///
+    /// [Public Suffix List]: https://publicsuffix.org/
+    pub fn add_public_suffix(&mut self, suffix: &str) {
+        let suffix = suffix.trim_matches('.').to_ascii_lowercase();
+        self.public_suffixes.push(suffix);
+    }
+}
+
+// Returns true if `pattern` is a wildcard name directly below one of `public_suffixes`.
+#[cfg_attr(libressl261, allow(dead_code))]
+fn is_public_suffix_wildcard(pattern: &str, public_suffixes: &[String]) -> bool {
+    let pattern = pattern.strip_suffix('.').unwrap_or(pattern);
+    match pattern.split_once('.') {
+        Some((label, suffix)) if label.contains('*') => public_suffixes
+            .iter()
+            .any(|public| public.eq_ignore_ascii_case(suffix)),
+        _ => false,
+    }
+}
+
+fn verify_pins(preverify_ok: bool, x509_ctx: &mut X509StoreContextRef, pins: &[[u8; 32]]) -> bool {
+    // the whole chain is only known once the leaf certificate is reached
+    if !preverify_ok || pins.is_empty() || x509_ctx.error_depth() != 0 {
+        return preverify_ok;
+    }
+
+    let matched = match x509_ctx.chain() {
+        Some(chain) => chain.iter().any(|cert| {
+            let spki = match cert.public_key().and_then(|key| key.public_key_to_der()) {
+                Ok(spki) => spki,
+                Err(_) => return false,
+            };
+            match hash(MessageDigest::sha256(), &spki) {
+                Ok(digest) => pins.iter().any(|pin| pin[..] == digest[..]),
+                Err(_) => false,
+            }
+        }),
+        None => false,
+    };
+
+    if !matched {
+        x509_ctx.set_error(X509VerifyResult::APPLICATION_VERIFICATION);
+    }
+    matched
+}
+
+#[cfg(ossl110)]
+mod must_staple {
+    use foreign_types::ForeignTypeRef;
+    use libc::c_long;
+    use once_cell::sync::OnceCell;
+    use std::ptr;
+
+    use crate::asn1::Asn1Integer;
+    use crate::error::ErrorStack;
+    use crate::ex_data::Index;
+    use crate::hash::MessageDigest;
+    use crate::nid::Nid;
+    use crate::ocsp::{OcspCertId, OcspCertStatus, OcspFlag, OcspResponse, OcspResponseStatus};
+    use crate::ssl::{Ssl, SslContextBuilder, SslContextRef, SslRef};
+    use crate::stack::Stack;
+    use crate::util::ForeignTypeExt;
+    use crate::x509::store::X509StoreRef;
+    use crate::x509::{X509Ref, X509VerifyResult};
+
+    // the status_request TLS feature, RFC 7633
+    const STATUS_REQUEST: c_long = 5;
+
+    static MUST_STAPLE_IDX: OnceCell<Index<Ssl, ()>> = OnceCell::new();
+    static STATUS_CALLBACK: OnceCell<usize> = OnceCell::new();
+
+    pub fn try_get_idx() -> Result<&'static Index<Ssl, ()>, ErrorStack> {
+        MUST_STAPLE_IDX.get_or_try_init(Ssl::new_ex_index)
+    }
+
+    pub fn install(ctx: &mut SslContextBuilder) -> Result<(), ErrorStack> {
+        ctx.set_status_callback(status_callback)?;
+        STATUS_CALLBACK.get_or_init(|| installed_callback(ctx.as_ptr()));
+        Ok(())
+    }
+
+    // Returns `false` if the callback has been replaced with `set_status_callback`.
+    pub fn is_installed(ctx: &SslContextRef) -> bool {
+        STATUS_CALLBACK.get() == Some(&installed_callback(ctx.as_ptr()))
+    }
+
+    fn installed_callback(ctx: *mut ffi::SSL_CTX) -> usize {
+        let mut callback = None;
+        unsafe {
+            ffi::SSL_CTX_get_tlsext_status_cb(ctx, &mut callback);
+        }
+        callback.map_or(0, |callback| callback as usize)
+    }
+
+    // The store used to verify the connection, if one has been set for verification only.
+    fn verify_store(ssl: &SslRef) -> &X509StoreRef {
+        #[cfg(ossl300)]
+        unsafe {
+            let mut store = ptr::null_mut();
+            ffi::SSL_get0_verify_cert_store(ssl.as_ptr(), &mut store);
+            if !store.is_null() {
+                return X509StoreRef::from_ptr(store);
+            }
+        }
+        ssl.ssl_context().cert_store()
+    }
+
+    pub fn status_callback(ssl: &mut SslRef) -> Result<bool, ErrorStack> {
+        if ssl.ex_data(*try_get_idx()?).is_none() {
+            return Ok(true);
+        }
+
+        let chain = match ssl.verified_chain() {
+            Some(chain) => chain,
+            None => return Ok(true),
+        };
+        let cert = match chain.get(0) {
+            Some(cert) => cert,
+            None => return Ok(true),
+        };
+        match requires_status_request(cert) {
+            Some(true) => {}
+            Some(false) => return Ok(true),
+            None => return Ok(false),
+        }
+
+        let response = match ssl.ocsp_status() {
+            Some(response) => response,
+            None => return Ok(false),
+        };
+        let response = match OcspResponse::from_der(response) {
+            Ok(response) => response,
+            Err(_) => return Ok(false),
+        };
+        if response.status() != OcspResponseStatus::SUCCESSFUL {
+            return Ok(false);
+        }
+        let basic = match response.basic() {
+            Ok(basic) => basic,
+            Err(_) => return Ok(false),
+        };
+
+        let mut certs = Stack::new()?;
+        for cert in chain {
+            certs.push(cert.to_owned())?;
+        }
+        if basic
+            .verify(&certs, verify_store(ssl), OcspFlag::empty())
+            .is_err()
+        {
+            return Ok(false);
+        }
+
+        let issuer = chain
+            .iter()
+            .find(|issuer| issuer.issued(cert) == X509VerifyResult::OK);
+        let issuer = match issuer {
+            Some(issuer) => issuer,
+            None => return Ok(false),
+        };
+        let id = OcspCertId::from_cert(MessageDigest::sha1(), cert, issuer)?;
+        let ok = match basic.find_status(&id) {
+            Some(status) => {
+                status.status == OcspCertStatus::GOOD && status.check_validity(300, None).is_ok()
+            }
+            None => false,
+        };
+
+        Ok(ok)
+    }
+
+    // Returns `None` if the TLS Feature extension is present but can't be decoded.
+    fn requires_status_request(cert: &X509Ref) -> Option<bool> {
+        let mut crit = 0;
+        let features = unsafe {
+            let features = ffi::X509_get_ext_d2i(
+                cert.as_ptr(),
+                Nid::TLSFEATURE.as_raw(),
+                &mut crit,
+                ptr::null_mut(),
+            );
+            Stack::<Asn1Integer>::from_ptr_opt(features as *mut _)
+        };
+
+        match features {
+            Some(features) => Some(features.iter().any(
+                |feature| unsafe { ffi::ASN1_INTEGER_get(feature.as_ptr()) } == STATUS_REQUEST,
+            )),
+            // the extension is absent
+            None if crit == -1 => Some(false),
+            None => None,
+        }
+    }
+}
+
+/// Identifies the server a cached session was negotiated with and how its certificate was
+/// verified.
+///
+/// Resuming a session skips certificate verification, so a session may only be resumed by a
+/// connection which would have verified the certificate in the same way.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct SessionKey {
+    domain: String,
+    port: Option<u16>,
+    verify_mode: SslVerifyMode,
+    verify_hostname: bool,
+    wildcard_policy: WildcardPolicy,
+    pinned_public_keys: Vec<[u8; 32]>,
+    require_ocsp_must_staple: bool,
+}
+
+static SESSION_CACHE_IDX: OnceCell<Index<SslContext, Arc<ClientSessionCache>>> = OnceCell::new();
+static SESSION_KEY_IDX: OnceCell<Index<Ssl, SessionKey>> = OnceCell::new();
+
+fn session_cache_idx() -> Result<&'static Index<SslContext, Arc<ClientSessionCache>>, ErrorStack> {
+    SESSION_CACHE_IDX.get_or_try_init(SslContext::new_ex_index)
+}
+
+fn session_key_idx() -> Result<&'static Index<Ssl, SessionKey>, ErrorStack> {
+    SESSION_KEY_IDX.get_or_try_init(Ssl::new_ex_index)
+}
+
+struct ClientSessionCache {
+    capacity: usize,
+    ttl: Duration,
+    sessions: Mutex<HashMap<SessionKey, (SslSession, Instant)>>,
+}
+
+impl ClientSessionCache {
+    fn insert(&self, key: SessionKey, session: SslSession) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        let mut sessions = self.sessions.lock().unwrap();
+        if !sessions.contains_key(&key) && sessions.len() >= self.capacity {
+            let ttl = self.ttl;
+            sessions.retain(|_, (_, created)| created.elapsed() < ttl);
+            if sessions.len() >= self.capacity {
+                let oldest = sessions
+                    .iter()
+                    .min_by_key(|(_, (_, created))| *created)
+                    .map(|(key, _)| key.clone());
+                if let Some(oldest) = oldest {
+                    sessions.remove(&oldest);
+                }
+            }
+        }
+        sessions.insert(key, (session, Instant::now()));
+    }
+
+    fn get(&self, key: &SessionKey) -> Option<SslSession> {
+        let mut sessions = self.sessions.lock().unwrap();
+        let expired = match sessions.get(key) {
+            Some((session, created)) if created.elapsed() < self.ttl => {
+                return Some(session.clone());
+            }
+            Some(_) => true,
+            None => false,
+        };
+        if expired {
+            sessions.remove(key);
+        }
+        None
+    }
+}
+
+/// A map from server name patterns to the `SslContext`s which serve them.
+///
+/// Patterns are either exact host names, such as `example.com`, or wildcards of the form
+/// `*.example.com`, which match names with exactly one additional leading label, such as
+/// `www.example.com` but not `example.com` or `a.b.example.com`. Matching is case-insensitive,
+/// and exact patterns take precedence over wildcards.
+///
+/// Used with [`SslAcceptorBuilder::set_server_name_contexts`].
+///
+/// [`SslAcceptorBuilder::set_server_name_contexts`]: struct.SslAcceptorBuilder.html#method.set_server_name_contexts
+#[derive(Clone, Default)]
+pub struct ServerNameContexts {
+    exact: HashMap<String, SslContext>,
+    wildcard: HashMap<String, SslContext>,
+}
+
+impl ServerNameContexts {
+    /// Creates an empty map.
+    pub fn new() -> ServerNameContexts {
+        ServerNameContexts::default()
+    }
+
+    /// Associates a server name pattern with a context, replacing any previous context for the
+    /// same pattern.
+    ///
+    /// Typically each context is built with its own certificate and private key, for example from
+    /// an [`SslAcceptor`] builder.
+    pub fn insert(&mut self, pattern: &str, ctx: SslContext) {
+        let pattern = normalize_server_name(pattern);
+        match pattern.strip_prefix("*.") {
+            Some(suffix) => self.wildcard.insert(suffix.to_string(), ctx),
+            None => self.exact.insert(pattern, ctx),
+        };
+    }
+
+    /// Returns the context which serves `name`, if any.
+    pub fn get(&self, name: &str) -> Option<&SslContextRef> {
+        let name = normalize_server_name(name);
+        if let Some(ctx) = self.exact.get(&name) {
+            return Some(ctx);
+        }
+
+        let (label, suffix) = name.split_once('.')?;
+        if label.is_empty() {
+            return None;
+        }
+        self.wildcard.get(suffix).map(|ctx| &**ctx)
+    }
+}
+
+fn normalize_server_name(name: &str) -> String {
+    name.strip_suffix('.').unwrap_or(name).to_ascii_lowercase()
+}
+
+/// A shared, replaceable `SslAcceptor`.
+///
+/// Clones of a `ReloadableSslAcceptor` share the same inner acceptor, which can be swapped out with
+/// [`replace`] while connections are being accepted. This makes it possible to roll over to a
+/// renewed certificate and private key without restarting listeners. New connections use the
+/// acceptor which is current when they are accepted, while existing connections keep using the
+/// one they were created with.
+///
+/// Note that sessions cached by, or tickets issued with, the previous acceptor's context will not
+/// be resumed after a replacement.
+///
+/// [`replace`]: #method.replace
+#[derive(Clone)]
+pub struct ReloadableSslAcceptor(Arc<RwLock<SslAcceptor>>);
+
+impl ReloadableSslAcceptor {
+    /// Creates a new `ReloadableSslAcceptor` which initially uses `acceptor`.
+    pub fn new(acceptor: SslAcceptor) -> ReloadableSslAcceptor {
+        ReloadableSslAcceptor(Arc::new(RwLock::new(acceptor)))
+    }
+
+    /// Returns the acceptor currently used for new connections.
+    pub fn current(&self) -> SslAcceptor {
+        self.0.read().unwrap().clone()
+    }
+
+    /// Replaces the acceptor used for new connections, returning the previous one.
+    ///
+    /// The change is visible to all clones of this `ReloadableSslAcceptor`.
+    pub fn replace(&self, acceptor: SslAcceptor) -> SslAcceptor {
+        std::mem::replace(&mut *self.0.write().unwrap(), acceptor)
+    }
+
+    /// Initiates a server-side TLS session on a stream with the current acceptor.
+    pub fn accept<S>(&self, stream: S) -> Result<SslStream<S>, HandshakeError<S>>
+    where
+        S: Read + Write,
+    {
+        self.current().accept(stream)
+    }
+}
+
 cfg_if! {
     if #[cfg(ossl110)] {
         #[allow(clippy::unnecessary_wraps)]
@@ -389,33 +1169,107 @@ cfg_if! {
             ctx.set_verify(SslVerifyMode::PEER);
         }
 
-        fn setup_verify_hostname(ssl: &mut SslRef, domain: &str) -> Result<(), ErrorStack> {
+        fn setup_verify_callback<F>(ssl: &mut SslRef, mode: SslVerifyMode, verify: F)
+        where
+            F: Fn(bool, &mut X509StoreContextRef) -> bool + 'static + Sync + Send,
+        {
+            ssl.set_verify_callback(mode, verify);
+        }
+
+        fn setup_verify_hostname(
+            ssl: &mut SslRef,
+            domain: &str,
+            policy: &WildcardPolicy,
+        ) -> Result<(), ErrorStack> {
             use crate::x509::verify::X509CheckFlags;
 
             let param = ssl.param_mut();
-            param.set_hostflags(X509CheckFlags::NO_PARTIAL_WILDCARDS);
+            param.set_hostflags(if !policy.wildcards {
+                X509CheckFlags::NO_WILDCARDS
+            } else if policy.partial_wildcards {
+                X509CheckFlags::empty()
+            } else {
+                X509CheckFlags::NO_PARTIAL_WILDCARDS
+            });
             match domain.parse() {
                 Ok(ip) => param.set_ip(ip),
                 Err(_) => param.set_host(domain),
             }
         }
+
+        #[cfg(ossl102)]
+        fn verify_public_suffixes(
+            preverify_ok: bool,
+            x509_ctx: &mut X509StoreContextRef,
+            public_suffixes: &[String],
+        ) -> bool {
+            if !preverify_ok || public_suffixes.is_empty() || x509_ctx.error_depth() != 0 {
+                return preverify_ok;
+            }
+
+            // OpenSSL records which name in the certificate matched the hostname
+            let ok = match x509_ctx.verify_param().peername() {
+                Some(name) => !is_public_suffix_wildcard(name, public_suffixes),
+                None => true,
+            };
+
+            if !ok {
+                x509_ctx.set_error(X509VerifyResult::APPLICATION_VERIFICATION);
+            }
+
+            ok
+        }
+
+        #[cfg(not(ossl102))]
+        fn verify_public_suffixes(
+            preverify_ok: bool,
+            _: &mut X509StoreContextRef,
+            _: &[String],
+        ) -> bool {
+            preverify_ok
+        }
     } else {
         fn setup_verify(ctx: &mut SslContextBuilder) {
             ctx.set_verify_callback(SslVerifyMode::PEER, verify::verify_callback);
         }
 
-        fn setup_verify_hostname(ssl: &mut Ssl, domain: &str) -> Result<(), ErrorStack> {
+        fn setup_verify_callback<F>(ssl: &mut SslRef, mode: SslVerifyMode, verify: F)
+        where
+            F: Fn(bool, &mut X509StoreContextRef) -> bool + 'static + Sync + Send,
+        {
+            // the per-connection callback replaces the context's, so keep checking the hostname
+            ssl.set_verify_callback(mode, move |preverify_ok, x509_ctx| {
+                let preverify_ok = verify::verify_callback(preverify_ok, x509_ctx);
+                verify(preverify_ok, x509_ctx)
+            });
+        }
+
+        fn setup_verify_hostname(
+            ssl: &mut Ssl,
+            domain: &str,
+            policy: &WildcardPolicy,
+        ) -> Result<(), ErrorStack> {
             let domain = domain.to_string();
             let hostname_idx = verify::try_get_hostname_idx()?;
-            ssl.set_ex_data(*hostname_idx, domain);
+            ssl.set_ex_data(*hostname_idx, (domain, policy.clone()));
             Ok(())
         }
 
+        // public suffixes are checked while matching the hostname
+        fn verify_public_suffixes(
+            preverify_ok: bool,
+            _: &mut X509StoreContextRef,
+            _: &[String],
+        ) -> bool {
+            preverify_ok
+        }
+
         mod verify {
             use std::net::IpAddr;
             use std::str;
             use once_cell::sync::OnceCell;
 
+            use super::{is_public_suffix_wildcard, WildcardPolicy};
             use crate::error::ErrorStack;
             use crate::ex_data::Index;
             use crate::nid::Nid;
@@ -426,9 +1280,10 @@ cfg_if! {
                 X509VerifyResult,
             };
 
-            static HOSTNAME_IDX: OnceCell<Index<Ssl, String>> = OnceCell::new();
+            static HOSTNAME_IDX: OnceCell<Index<Ssl, (String, WildcardPolicy)>> = OnceCell::new();
 
-            pub fn try_get_hostname_idx() -> Result<&'static Index<Ssl, String>, ErrorStack> {
+            pub fn try_get_hostname_idx(
+            ) -> Result<&'static Index<Ssl, (String, WildcardPolicy)>, ErrorStack> {
                 HOSTNAME_IDX.get_or_try_init(Ssl::new_ex_index)
             }
 
@@ -446,7 +1301,7 @@ cfg_if! {
                         .and_then(|idx| x509_ctx.ex_data(idx))
                         .and_then(|ssl| ssl.ex_data(*hostname_idx)),
                 ) {
-                    (Some(x509), Some(domain)) => verify_hostname(domain, &x509),
+                    (Some(x509), Some((domain, policy))) => verify_hostname(domain, &x509, policy),
                     _ => true,
                 };
 
@@ -457,14 +1312,18 @@ cfg_if! {
                 ok
             }
 
-            fn verify_hostname(domain: &str, cert: &X509Ref) -> bool {
+            fn verify_hostname(domain: &str, cert: &X509Ref, policy: &WildcardPolicy) -> bool {
                 match cert.subject_alt_names() {
-                    Some(names) => verify_subject_alt_names(domain, names),
-                    None => verify_subject_name(domain, &cert.subject_name()),
+                    Some(names) => verify_subject_alt_names(domain, names, policy),
+                    None => verify_subject_name(domain, &cert.subject_name(), policy),
                 }
             }
 
-            fn verify_subject_alt_names(domain: &str, names: Stack<GeneralName>) -> bool {
+            fn verify_subject_alt_names(
+                domain: &str,
+                names: Stack<GeneralName>,
+                policy: &WildcardPolicy,
+            ) -> bool {
                 let ip = domain.parse();
 
                 for name in &names {
@@ -478,7 +1337,7 @@ cfg_if! {
                         }
                         Err(_) => {
                             if let Some(pattern) = name.dnsname() {
-                                if matches_dns(pattern, domain) {
+                                if matches_dns(pattern, domain, policy) {
                                     return true;
                                 }
                             }
@@ -489,7 +1348,11 @@ cfg_if! {
                 false
             }
 
-            fn verify_subject_name(domain: &str, subject_name: &X509NameRef) -> bool {
+            fn verify_subject_name(
+                domain: &str,
+                subject_name: &X509NameRef,
+                policy: &WildcardPolicy,
+            ) -> bool {
                 match subject_name.entries_by_nid(Nid::COMMONNAME).next() {
                     Some(pattern) => {
                         let pattern = match str::from_utf8(pattern.data().as_slice()) {
@@ -504,14 +1367,14 @@ cfg_if! {
                                 .parse::<IpAddr>()
                                 .ok()
                                 .map_or(false, |pattern| pattern == ip),
-                            Err(_) => matches_dns(pattern, domain),
+                            Err(_) => matches_dns(pattern, domain, policy),
                         }
                     }
                     None => false,
                 }
             }
 
-            fn matches_dns(mut pattern: &str, mut hostname: &str) -> bool {
+            fn matches_dns(mut pattern: &str, mut hostname: &str, policy: &WildcardPolicy) -> bool {
                 // first strip trailing . off of pattern and hostname to normalize
                 if pattern.ends_with('.') {
                     pattern = &pattern[..pattern.len() - 1];
@@ -520,10 +1383,19 @@ cfg_if! {
                     hostname = &hostname[..hostname.len() - 1];
                 }
 
-                matches_wildcard(pattern, hostname).unwrap_or_else(|| pattern.eq_ignore_ascii_case(hostname))
+                matches_wildcard(pattern, hostname, policy)
+                    .unwrap_or_else(|| pattern.eq_ignore_ascii_case(hostname))
             }
 
-            fn matches_wildcard(pattern: &str, hostname: &str) -> Option<bool> {
+            fn matches_wildcard(
+                pattern: &str,
+                hostname: &str,
+                policy: &WildcardPolicy,
+            ) -> Option<bool> {
+                if !policy.wildcards {
+                    return None;
+                }
+
                 let wildcard_location = match pattern.find('*') {
                     Some(l) => l,
                     None => return None,
@@ -539,17 +1411,32 @@ cfg_if! {
                 //
                 // This is a bit dubious, as it doesn't disallow other TLDs like *.co.uk.
                 // Chrome has a black- and white-list for this, but Firefox (via NSS) does
-                // the same thing we do here.
-                //
-                // The Public Suffix (https://www.publicsuffix.org/) list could
-                // potentially be used here, but it's both huge and updated frequently
-                // enough that management would be a PITA.
+                // the same thing we do here. Callers can provide their own list of public
+                // suffixes through the wildcard policy.
                 if dot_idxs.next().is_none() {
                     return None;
                 }
 
-                // Wildcards can only be in the first component, and must be the entire first label
-                if wildcard_location != 0 || wildcard_end != wildcard_location + 1 {
+                // Wildcards can only be in the first component
+                if wildcard_location > wildcard_end {
+                    return None;
+                }
+
+                let label = &pattern[..wildcard_end];
+                if label != "*" {
+                    // Partial wildcards must be allowed, may only contain a single '*', and are
+                    // never matched in punycode labels
+                    if !policy.partial_wildcards
+                        || label.matches('*').count() != 1
+                        || label
+                            .get(..4)
+                            .map_or(false, |p| p.eq_ignore_ascii_case("xn--"))
+                    {
+                        return None;
+                    }
+                }
+
+                if is_public_suffix_wildcard(pattern, &policy.public_suffixes) {
                     return None;
                 }
 
@@ -558,10 +1445,21 @@ cfg_if! {
                     None => return None,
                 };
 
+                let hostname_label = &hostname[..hostname_label_end];
+                let prefix = &label[..wildcard_location];
+                let suffix = &label[wildcard_location + 1..];
+                let label_matches = hostname_label.len() >= prefix.len() + suffix.len()
+                    && hostname_label
+                        .get(..prefix.len())
+                        .map_or(false, |p| p.eq_ignore_ascii_case(prefix))
+                    && hostname_label
+                        .get(hostname_label.len() - suffix.len()..)
+                        .map_or(false, |s| s.eq_ignore_ascii_case(suffix));
+
                 let pattern_after_wildcard = &pattern[wildcard_end..];
                 let hostname_after_wildcard = &hostname[hostname_label_end..];
 
-                Some(pattern_after_wildcard.eq_ignore_ascii_case(hostname_after_wildcard))
+                Some(label_matches && pattern_after_wildcard.eq_ignore_ascii_case(hostname_after_wildcard))
             }
 
             fn matches_ip(expected: &IpAddr, actual: &[u8]) -> bool {
@@ -573,7 +1471,11 @@ cfg_if! {
 
             #[test]
             fn test_dns_match() {
-                use crate::ssl::connector::verify::matches_dns;
+                use crate::ssl::connector::verify::matches_dns as matches_dns_with;
+                use crate::ssl::connector::WildcardPolicy;
+
+                let matches_dns =
+                    |pattern, hostname| matches_dns_with(pattern, hostname, &WildcardPolicy::new());
                 assert!(matches_dns("website.tld", "website.tld")); // A name should match itself.
                 assert!(matches_dns("website.tld", "wEbSiTe.tLd")); // DNS name matching ignores case of hostname.
                 assert!(matches_dns("wEbSiTe.TlD", "website.tld")); // DNS name matching ignores case of subject.
@@ -599,6 +1501,31 @@ cfg_if! {
                 assert!(!matches_dns("Xn--*.example.com", "subdomain.example.com")); // And we can't bypass a punycode test with weird casing.
                 assert!(!matches_dns("XN--*.example.com", "subdomain.example.com")); // And we can't bypass a punycode test with weird casing.
             }
+
+            #[test]
+            fn test_dns_match_policy() {
+                use crate::ssl::connector::verify::matches_dns;
+                use crate::ssl::connector::WildcardPolicy;
+
+                let mut policy = WildcardPolicy::new();
+                policy.set_allow_wildcards(false);
+                assert!(!matches_dns("*.example.com", "subdomain.example.com", &policy)); // Wildcards can be disabled.
+                assert!(matches_dns("example.com", "example.com", &policy)); // Exact names still match.
+
+                let mut policy = WildcardPolicy::new();
+                policy.set_allow_partial_wildcards(true);
+                assert!(matches_dns("prefix*.example.com", "prefix.example.com", &policy)); // Partial wildcards can be enabled.
+                assert!(matches_dns("prefix*.example.com", "prefixdomain.example.com", &policy));
+                assert!(matches_dns("*suffix.example.com", "domainsuffix.example.com", &policy));
+                assert!(!matches_dns("prefix*.example.com", "p.example.com", &policy)); // The rest of the label must still match.
+                assert!(!matches_dns("*suffix.example.com", "s.example.com", &policy));
+                assert!(!matches_dns("xn--*.example.com", "xn--bcher-kva.example.com", &policy)); // But never in punycode labels.
+
+                let mut policy = WildcardPolicy::new();
+                policy.add_public_suffix("co.uk");
+                assert!(!matches_dns("*.co.uk", "example.co.uk", &policy)); // Wildcards directly below a public suffix do not match.
+                assert!(matches_dns("*.example.co.uk", "www.example.co.uk", &policy)); // But can below a registered domain.
+            }
         }
     }
 }
@@ -32,12 +32,22 @@ impl ErrorCode {
     /// An error occurred in the SSL library.
     pub const SSL: ErrorCode = ErrorCode(ffi::SSL_ERROR_SSL);
 
+    /// The certificate callback indicated that it needed to be retried.
+    pub const WANT_X509_LOOKUP: ErrorCode = ErrorCode(ffi::SSL_ERROR_WANT_X509_LOOKUP);
+
     /// The client hello callback indicated that it needed to be retried.
     ///
     /// Requires OpenSSL 1.1.1 or newer.
     #[cfg(ossl111)]
     pub const WANT_CLIENT_HELLO_CB: ErrorCode = ErrorCode(ffi::SSL_ERROR_WANT_CLIENT_HELLO_CB);
 
+    /// The certificate verification callback suspended the handshake with
+    /// `SslRef::set_retry_verify`.
+    ///
+    /// Requires OpenSSL 3.0.0 or newer.
+    #[cfg(ossl300)]
+    pub const WANT_RETRY_VERIFY: ErrorCode = ErrorCode(ffi::SSL_ERROR_WANT_RETRY_VERIFY);
+
     pub fn from_raw(raw: c_int) -> ErrorCode {
         ErrorCode(raw)
     }
@@ -62,6 +72,31 @@ pub struct Error {
 }
 
 impl Error {
+    /// Creates the error for a failed operation which `SSL_get_error` classified as `code`.
+    ///
+    /// `io_error` is used to find the underlying I/O error of `SYSCALL` errors which leave
+    /// OpenSSL's error stack empty, and of `WANT_READ` and `WANT_WRITE` errors.
+    pub(crate) fn from_code<F>(code: ErrorCode, io_error: F) -> Error
+    where
+        F: FnOnce() -> Option<io::Error>,
+    {
+        let cause = match code {
+            ErrorCode::SSL => Some(InnerError::Ssl(ErrorStack::get())),
+            ErrorCode::SYSCALL => {
+                let errs = ErrorStack::get();
+                if errs.errors().is_empty() {
+                    io_error().map(InnerError::Io)
+                } else {
+                    Some(InnerError::Ssl(errs))
+                }
+            }
+            ErrorCode::WANT_READ | ErrorCode::WANT_WRITE => io_error().map(InnerError::Io),
+            _ => None,
+        };
+
+        Error { code, cause }
+    }
+
     pub fn code(&self) -> ErrorCode {
         self.code
     }
@@ -142,6 +177,9 @@ pub enum HandshakeError<S> {
     Failure(MidHandshakeSslStream<S>),
     /// The handshake encountered a `WouldBlock` error midway through.
     ///
+    /// The stream's `code` indicates whether the handshake is waiting to read or to write. The
+    /// handshake can be resumed with `MidHandshakeSslStream::handshake`.
+    ///
     /// This error will never be returned for blocking streams.
     WouldBlock(MidHandshakeSslStream<S>),
 }
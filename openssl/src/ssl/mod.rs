@@ -57,6 +57,8 @@
 //!     }
 //! }
 //! ```
+#[cfg(ossl110)]
+use crate::ct::Sct;
 use crate::dh::{Dh, DhRef};
 #[cfg(all(ossl101, not(ossl110)))]
 use crate::ec::EcKey;
@@ -67,12 +69,15 @@ use crate::ex_data::Index;
 use crate::hash::MessageDigest;
 #[cfg(any(ossl110, libressl270))]
 use crate::nid::Nid;
-use crate::pkey::{HasPrivate, PKeyRef, Params, Private};
-use crate::srtp::{SrtpProtectionProfile, SrtpProtectionProfileRef};
+#[cfg(ossl320)]
+use crate::pkey::HasPublic;
+#[cfg(ossl102)]
+use crate::pkey::Public;
+use crate::pkey::{HasPrivate, PKey, PKeyRef, Params, Private};
+use crate::srtp::{SrtpKeyingMaterial, SrtpProtectionProfile, SrtpProtectionProfileRef};
 use crate::ssl::bio::BioMethod;
 use crate::ssl::callbacks::*;
-use crate::ssl::error::InnerError;
-use crate::stack::{Stack, StackRef};
+use crate::stack::{Stack, StackRef, Stackable};
 use crate::util::{ForeignTypeExt, ForeignTypeRefExt};
 use crate::x509::store::{X509Store, X509StoreBuilderRef, X509StoreRef};
 #[cfg(any(ossl102, libressl261))]
@@ -88,12 +93,16 @@ use openssl_macros::corresponds;
 use std::any::TypeId;
 use std::cmp;
 use std::collections::HashMap;
+#[cfg(ossl350)]
+use std::collections::VecDeque;
 use std::ffi::{CStr, CString};
 use std::fmt;
 use std::io;
 use std::io::prelude::*;
 use std::marker::PhantomData;
 use std::mem::{self, ManuallyDrop};
+#[cfg(ossl110)]
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
 use std::ops::{Deref, DerefMut};
 use std::panic::resume_unwind;
 use std::path::Path;
@@ -101,16 +110,30 @@ use std::ptr;
 use std::slice;
 use std::str;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+pub use crate::ssl::bio_pair::BioPair;
+#[cfg(ossl102)]
+pub use crate::ssl::conf::{SslConf, SslConfFlags};
+pub use crate::ssl::connection::SslConnection;
 pub use crate::ssl::connector::{
-    ConnectConfiguration, SslAcceptor, SslAcceptorBuilder, SslConnector, SslConnectorBuilder,
+    ConnectConfiguration, ReloadableSslAcceptor, ServerNameContexts, SslAcceptor,
+    SslAcceptorBuilder, SslConnector, SslConnectorBuilder, WildcardPolicy,
 };
 pub use crate::ssl::error::{Error, ErrorCode, HandshakeError};
+#[cfg(all(unix, ossl101, not(osslconf = "OPENSSL_NO_SCTP")))]
+pub use crate::ssl::sctp::DtlsSctpStream;
 
 mod bio;
+mod bio_pair;
 mod callbacks;
+#[cfg(ossl102)]
+mod conf;
+mod connection;
 mod connector;
 mod error;
+#[cfg(all(unix, ossl101, not(osslconf = "OPENSSL_NO_SCTP")))]
+mod sctp;
 #[cfg(test)]
 mod test;
 
@@ -263,6 +286,16 @@ bitflags! {
         #[cfg(ossl111)]
         const ENABLE_MIDDLEBOX_COMPAT = ffi::SSL_OP_ENABLE_MIDDLEBOX_COMPAT as SslOptionsRepr;
 
+        /// Disable the built-in replay protection for TLSv1.3 early data.
+        ///
+        /// By default, servers only accept early data from a session ticket once, tracked through
+        /// the session cache. Setting this option allows applications with their own replay
+        /// protection to accept early data without relying on the session cache.
+        ///
+        /// Requires OpenSSL 1.1.1 or newer.
+        #[cfg(ossl111)]
+        const NO_ANTI_REPLAY = ffi::SSL_OP_NO_ANTI_REPLAY as SslOptionsRepr;
+
         /// Prioritize ChaCha ciphers when preferred by clients.
         ///
         /// Temporarily reprioritize ChaCha20-Poly1305 ciphers to the top of the server cipher list
@@ -395,6 +428,13 @@ bitflags! {
         ///
         /// This should be paired with `SSL_VERIFY_PEER`. It has no effect on the client side.
         const FAIL_IF_NO_PEER_CERT = ffi::SSL_VERIFY_FAIL_IF_NO_PEER_CERT;
+
+        /// On the server side, do not request a certificate from the client during the initial
+        /// TLSv1.3 handshake, but only when `SslStream::verify_client_post_handshake` is called.
+        ///
+        /// This should be paired with `SSL_VERIFY_PEER`. It has no effect on the client side.
+        #[cfg(ossl111)]
+        const POST_HANDSHAKE = ffi::SSL_VERIFY_POST_HANDSHAKE;
     }
 }
 
@@ -444,6 +484,31 @@ bitflags! {
     }
 }
 
+#[cfg(ossl102)]
+bitflags! {
+    /// Options controlling how a certificate chain is built by
+    /// [`SslContextBuilder::build_cert_chain`].
+    ///
+    /// [`SslContextBuilder::build_cert_chain`]: struct.SslContextBuilder.html#method.build_cert_chain
+    pub struct SslBuildChainFlags: c_long {
+        /// Use the existing chain certificates as untrusted intermediates rather than the
+        /// certificate store.
+        const UNTRUSTED = ffi::SSL_BUILD_CHAIN_FLAG_UNTRUSTED;
+
+        /// Omit the root certificate from the built chain.
+        const NO_ROOT = ffi::SSL_BUILD_CHAIN_FLAG_NO_ROOT;
+
+        /// Fully verify the built chain rather than only checking that it can be constructed.
+        const CHECK = ffi::SSL_BUILD_CHAIN_FLAG_CHECK;
+
+        /// Ignore verification errors while building the chain.
+        const IGNORE_ERROR = ffi::SSL_BUILD_CHAIN_FLAG_IGNORE_ERROR;
+
+        /// Clear the error stack of verification errors ignored with `IGNORE_ERROR`.
+        const CLEAR_ERROR = ffi::SSL_BUILD_CHAIN_FLAG_CLEAR_ERROR;
+    }
+}
+
 #[cfg(ossl111)]
 bitflags! {
     /// Which messages and under which conditions an extension should be added or expected.
@@ -462,18 +527,200 @@ bitflags! {
         const TLS1_3_ONLY = ffi::SSL_EXT_TLS1_3_ONLY;
         /// Ignore this extension during parsing if we are resuming
         const IGNORE_ON_RESUMPTION = ffi::SSL_EXT_IGNORE_ON_RESUMPTION;
+        /// The extension is sent in the ClientHello
         const CLIENT_HELLO = ffi::SSL_EXT_CLIENT_HELLO;
         /// Really means TLS1.2 or below
         const TLS1_2_SERVER_HELLO = ffi::SSL_EXT_TLS1_2_SERVER_HELLO;
+        /// The extension is sent in a TLS1.3 ServerHello
         const TLS1_3_SERVER_HELLO = ffi::SSL_EXT_TLS1_3_SERVER_HELLO;
+        /// The extension is sent in the TLS1.3 EncryptedExtensions message
         const TLS1_3_ENCRYPTED_EXTENSIONS = ffi::SSL_EXT_TLS1_3_ENCRYPTED_EXTENSIONS;
+        /// The extension is sent in a TLS1.3 HelloRetryRequest
         const TLS1_3_HELLO_RETRY_REQUEST = ffi::SSL_EXT_TLS1_3_HELLO_RETRY_REQUEST;
+        /// The extension is sent for each certificate of a TLS1.3 Certificate message
         const TLS1_3_CERTIFICATE = ffi::SSL_EXT_TLS1_3_CERTIFICATE;
+        /// The extension is sent in a TLS1.3 NewSessionTicket
         const TLS1_3_NEW_SESSION_TICKET = ffi::SSL_EXT_TLS1_3_NEW_SESSION_TICKET;
+        /// The extension is sent in a TLS1.3 CertificateRequest
         const TLS1_3_CERTIFICATE_REQUEST = ffi::SSL_EXT_TLS1_3_CERTIFICATE_REQUEST;
     }
 }
 
+/// How strictly Certificate Transparency is enforced during a handshake.
+///
+/// Requires OpenSSL 1.1.0 or newer.
+#[cfg(ossl110)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CtValidationMode(c_int);
+
+#[cfg(ossl110)]
+impl CtValidationMode {
+    /// Signed certificate timestamps are collected and validated, but the handshake does not fail
+    /// if none are valid.
+    pub const PERMISSIVE: CtValidationMode = CtValidationMode(ffi::SSL_CT_VALIDATION_PERMISSIVE);
+
+    /// The handshake fails unless the peer presents at least one valid signed certificate
+    /// timestamp.
+    pub const STRICT: CtValidationMode = CtValidationMode(ffi::SSL_CT_VALIDATION_STRICT);
+
+    /// Constructs a `CtValidationMode` from a raw OpenSSL value.
+    pub fn from_raw(raw: c_int) -> CtValidationMode {
+        CtValidationMode(raw)
+    }
+
+    /// Returns the raw OpenSSL value represented by this type.
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn as_raw(&self) -> c_int {
+        self.0
+    }
+}
+
+#[cfg(ossl110)]
+bitflags! {
+    /// Options controlling DANE verification.
+    pub struct DaneFlags: libc::c_ulong {
+        /// Skips peer name checks when a DANE-EE(3) TLSA record matches the leaf certificate, as
+        /// required by RFC 7672 for SMTP.
+        const NO_DANE_EE_NAMECHECKS = ffi::DANE_FLAG_NO_DANE_EE_NAMECHECKS;
+    }
+}
+
+/// The TLSA record which matched the peer's certificate chain during DANE verification.
+///
+/// Requires OpenSSL 1.1.0 or newer.
+#[cfg(ossl110)]
+pub struct DaneTlsa<'a> {
+    /// The depth in the peer's chain of the certificate which matched the record.
+    pub depth: u32,
+
+    /// The certificate usage field of the record.
+    pub usage: u8,
+
+    /// The selector field of the record.
+    pub selector: u8,
+
+    /// The matching type field of the record.
+    pub mtype: u8,
+
+    /// The certificate association data of the record.
+    pub data: &'a [u8],
+}
+
+/// An encryption level of a QUIC connection.
+///
+/// Requires OpenSSL 3.5.0 or newer.
+#[cfg(ossl350)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct QuicProtectionLevel(u32);
+
+#[cfg(ossl350)]
+impl QuicProtectionLevel {
+    /// Packets are not protected. Used for the Initial packets of a connection.
+    pub const NONE: QuicProtectionLevel =
+        QuicProtectionLevel(ffi::OSSL_RECORD_PROTECTION_LEVEL_NONE);
+
+    /// Packets are protected with 0-RTT keys.
+    pub const EARLY: QuicProtectionLevel =
+        QuicProtectionLevel(ffi::OSSL_RECORD_PROTECTION_LEVEL_EARLY);
+
+    /// Packets are protected with handshake keys.
+    pub const HANDSHAKE: QuicProtectionLevel =
+        QuicProtectionLevel(ffi::OSSL_RECORD_PROTECTION_LEVEL_HANDSHAKE);
+
+    /// Packets are protected with 1-RTT keys.
+    pub const APPLICATION: QuicProtectionLevel =
+        QuicProtectionLevel(ffi::OSSL_RECORD_PROTECTION_LEVEL_APPLICATION);
+
+    /// Constructs a `QuicProtectionLevel` from a raw OpenSSL value.
+    pub fn from_raw(raw: u32) -> QuicProtectionLevel {
+        QuicProtectionLevel(raw)
+    }
+
+    /// Returns the raw OpenSSL value represented by this type.
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn as_raw(&self) -> u32 {
+        self.0
+    }
+}
+
+/// A type of credential used to authenticate an endpoint, as negotiated by the extensions
+/// defined in [RFC 7250].
+///
+/// Requires OpenSSL 3.2.0 or newer.
+///
+/// [RFC 7250]: https://tools.ietf.org/html/rfc7250
+#[cfg(ossl320)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CertificateType(u8);
+
+#[cfg(ossl320)]
+impl CertificateType {
+    /// An X.509 certificate.
+    pub const X509: CertificateType = CertificateType(ffi::TLSEXT_cert_type_x509 as u8);
+
+    /// A raw public key, encoded as a SubjectPublicKeyInfo structure.
+    pub const RPK: CertificateType = CertificateType(ffi::TLSEXT_cert_type_rpk as u8);
+
+    /// Constructs a `CertificateType` from a raw OpenSSL value.
+    pub fn from_raw(raw: u8) -> CertificateType {
+        CertificateType(raw)
+    }
+
+    /// Returns the raw OpenSSL value represented by this type.
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn as_raw(&self) -> u8 {
+        self.0
+    }
+}
+
+/// The interface between a TLS handshake and the QUIC implementation carrying it.
+///
+/// When an `Ssl` is configured with [`SslRef::set_quic_tls`], it no longer produces or consumes
+/// TLS records. Handshake messages are instead passed to these methods to be sent in CRYPTO
+/// frames, and handshake messages received from the peer are passed to the `Ssl` with
+/// [`SslRef::provide_quic_data`].
+///
+/// Returning an error from any method aborts the handshake.
+///
+/// Requires OpenSSL 3.5.0 or newer.
+///
+/// [`SslRef::set_quic_tls`]: struct.SslRef.html#method.set_quic_tls
+/// [`SslRef::provide_quic_data`]: struct.SslRef.html#method.provide_quic_data
+#[cfg(ossl350)]
+pub trait QuicTlsCallbacks: 'static + Sync + Send {
+    /// Sends handshake data to the peer.
+    ///
+    /// The data is sent at the encryption level of the most recently installed write secret, or
+    /// in Initial packets if no write secret has been installed yet.
+    fn send_crypto_data(&mut self, data: &[u8]) -> Result<(), ErrorStack>;
+
+    /// Installs the secret for an encryption level.
+    ///
+    /// `write` is `true` if the secret protects packets sent to the peer, and `false` if it
+    /// protects packets received from the peer.
+    fn set_secret(
+        &mut self,
+        level: QuicProtectionLevel,
+        write: bool,
+        secret: &[u8],
+    ) -> Result<(), ErrorStack>;
+
+    /// Receives the peer's QUIC transport parameters.
+    fn transport_params(&mut self, params: &[u8]) -> Result<(), ErrorStack>;
+
+    /// Receives the TLS alert to send to the peer in a CONNECTION_CLOSE frame when the handshake
+    /// fails.
+    fn alert(&mut self, alert: u8) -> Result<(), ErrorStack>;
+}
+
+#[cfg(ossl350)]
+struct QuicTlsState {
+    callbacks: Box<dyn QuicTlsCallbacks>,
+    read_level: QuicProtectionLevel,
+    records: [VecDeque<Vec<u8>>; 4],
+    transport_params: Vec<u8>,
+}
+
 /// An identifier of the format of a certificate or key file.
 #[derive(Copy, Clone)]
 pub struct SslFiletype(c_int);
@@ -502,7 +749,7 @@ impl SslFiletype {
 }
 
 /// An identifier of a certificate status type.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, PartialEq, Eq)]
 pub struct StatusType(c_int);
 
 impl StatusType {
@@ -521,6 +768,46 @@ impl StatusType {
     }
 }
 
+/// An identifier of a TLS extension type.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct TlsExtType(u16);
+
+impl TlsExtType {
+    /// The server name indication extension.
+    pub const SERVER_NAME: TlsExtType = TlsExtType(ffi::TLSEXT_TYPE_server_name as u16);
+    /// The certificate status request extension.
+    pub const STATUS_REQUEST: TlsExtType = TlsExtType(ffi::TLSEXT_TYPE_status_request as u16);
+    /// The supported groups extension.
+    #[cfg(ossl110)]
+    pub const SUPPORTED_GROUPS: TlsExtType = TlsExtType(ffi::TLSEXT_TYPE_supported_groups as u16);
+    /// The EC point formats extension.
+    pub const EC_POINT_FORMATS: TlsExtType = TlsExtType(ffi::TLSEXT_TYPE_ec_point_formats as u16);
+    /// The signature algorithms extension.
+    pub const SIGNATURE_ALGORITHMS: TlsExtType =
+        TlsExtType(ffi::TLSEXT_TYPE_signature_algorithms as u16);
+    /// The application layer protocol negotiation extension.
+    pub const ALPN: TlsExtType =
+        TlsExtType(ffi::TLSEXT_TYPE_application_layer_protocol_negotiation as u16);
+    /// The supported versions extension.
+    #[cfg(ossl111)]
+    pub const SUPPORTED_VERSIONS: TlsExtType =
+        TlsExtType(ffi::TLSEXT_TYPE_supported_versions as u16);
+    /// The key share extension.
+    #[cfg(ossl111)]
+    pub const KEY_SHARE: TlsExtType = TlsExtType(ffi::TLSEXT_TYPE_key_share as u16);
+
+    /// Constructs a `TlsExtType` from a raw value.
+    pub fn from_raw(raw: u16) -> TlsExtType {
+        TlsExtType(raw)
+    }
+
+    /// Returns the raw value represented by this type.
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn as_raw(&self) -> u16 {
+        self.0
+    }
+}
+
 /// An identifier of a session name type.
 #[derive(Copy, Clone)]
 pub struct NameType(c_int);
@@ -549,7 +836,7 @@ fn try_get_session_ctx_index() -> Result<&'static Index<Ssl, SslContext>, ErrorS
     SESSION_CTX_INDEX.get_or_try_init(Ssl::new_ex_index)
 }
 
-unsafe extern "C" fn free_data_box<T>(
+pub(crate) unsafe extern "C" fn free_data_box<T>(
     _parent: *mut c_void,
     ptr: *mut c_void,
     _ad: *mut ffi::CRYPTO_EX_DATA,
@@ -585,8 +872,14 @@ impl SslAlert {
     pub const UNRECOGNIZED_NAME: SslAlert = SslAlert(ffi::SSL_AD_UNRECOGNIZED_NAME);
     pub const ILLEGAL_PARAMETER: SslAlert = SslAlert(ffi::SSL_AD_ILLEGAL_PARAMETER);
     pub const DECODE_ERROR: SslAlert = SslAlert(ffi::SSL_AD_DECODE_ERROR);
+    /// Alert 100 - `no_renegotiation`.
+    pub const NO_RENEGOTIATION: SslAlert = SslAlert(ffi::SSL_AD_NO_RENEGOTIATION);
 }
 
+/// Marks an `Ssl` which refused a renegotiation requested by its peer.
+#[cfg(not(boringssl))]
+pub(crate) struct RenegotiationAttempted;
+
 /// An error returned from an ALPN selection callback.
 ///
 /// Requires OpenSSL 1.0.2 or LibreSSL 2.6.1 or newer.
@@ -622,6 +915,93 @@ impl ClientHelloResponse {
     pub const RETRY: ClientHelloResponse = ClientHelloResponse(ffi::SSL_CLIENT_HELLO_RETRY);
 }
 
+/// The result of a certificate callback.
+///
+/// Requires OpenSSL 1.0.2 or newer.
+#[cfg(ossl102)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CertCallbackResponse(c_int);
+
+#[cfg(ossl102)]
+impl CertCallbackResponse {
+    /// Continue the handshake.
+    pub const SUCCESS: CertCallbackResponse = CertCallbackResponse(1);
+
+    /// Return from the handshake with an `ErrorCode::WANT_X509_LOOKUP` error.
+    ///
+    /// The callback is invoked again when the handshake is resumed.
+    pub const RETRY: CertCallbackResponse = CertCallbackResponse(-1);
+}
+
+/// The type of a TLSv1.3 key update.
+///
+/// Requires OpenSSL 1.1.1 or newer.
+#[cfg(ossl111)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct KeyUpdateType(c_int);
+
+#[cfg(ossl111)]
+impl KeyUpdateType {
+    /// Update the local sending keys only.
+    pub const NOT_REQUESTED: KeyUpdateType = KeyUpdateType(ffi::SSL_KEY_UPDATE_NOT_REQUESTED);
+
+    /// Update the local sending keys, and request that the peer update its sending keys as well.
+    pub const REQUESTED: KeyUpdateType = KeyUpdateType(ffi::SSL_KEY_UPDATE_REQUESTED);
+
+    /// Constructs a `KeyUpdateType` from a raw OpenSSL value.
+    pub fn from_raw(raw: c_int) -> KeyUpdateType {
+        KeyUpdateType(raw)
+    }
+
+    /// Returns the raw OpenSSL value represented by this type.
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn as_raw(&self) -> c_int {
+        self.0
+    }
+}
+
+/// The content type of a record passed to a message callback.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct SslContentType(c_int);
+
+impl SslContentType {
+    /// A ChangeCipherSpec record.
+    pub const CHANGE_CIPHER_SPEC: SslContentType = SslContentType(ffi::SSL3_RT_CHANGE_CIPHER_SPEC);
+
+    /// An alert record.
+    pub const ALERT: SslContentType = SslContentType(ffi::SSL3_RT_ALERT);
+
+    /// A handshake record.
+    pub const HANDSHAKE: SslContentType = SslContentType(ffi::SSL3_RT_HANDSHAKE);
+
+    /// An application data record.
+    pub const APPLICATION_DATA: SslContentType = SslContentType(ffi::SSL3_RT_APPLICATION_DATA);
+
+    /// A pseudo content type indicating that the message is a raw record header.
+    ///
+    /// Requires OpenSSL 1.0.2 or newer.
+    #[cfg(ossl102)]
+    pub const HEADER: SslContentType = SslContentType(ffi::SSL3_RT_HEADER);
+
+    /// A pseudo content type indicating that the message is the inner content type of a TLSv1.3
+    /// record.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[cfg(ossl111)]
+    pub const INNER_CONTENT_TYPE: SslContentType = SslContentType(ffi::SSL3_RT_INNER_CONTENT_TYPE);
+
+    /// Constructs an `SslContentType` from a raw OpenSSL value.
+    pub fn from_raw(raw: c_int) -> SslContentType {
+        SslContentType(raw)
+    }
+
+    /// Returns the raw OpenSSL value represented by this type.
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn as_raw(&self) -> c_int {
+        self.0
+    }
+}
+
 /// An SSL/TLS protocol version.
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub struct SslVersion(c_int);
@@ -646,6 +1026,54 @@ impl SslVersion {
     pub const TLS1_3: SslVersion = SslVersion(ffi::TLS1_3_VERSION);
 }
 
+// Versions of OpenSSL without SSL_CTX_set_min_proto_version can only restrict the protocol
+// version with options disabling each version individually.
+#[cfg(not(any(ossl110, libressl261, boringssl)))]
+const PROTO_VERSION_OPTIONS: [(SslVersion, SslOptions); 4] = [
+    (SslVersion::SSL3, SslOptions::NO_SSLV3),
+    (SslVersion::TLS1, SslOptions::NO_TLSV1),
+    (SslVersion::TLS1_1, SslOptions::NO_TLSV1_1),
+    (SslVersion::TLS1_2, SslOptions::NO_TLSV1_2),
+];
+
+#[cfg(not(any(ossl110, libressl261, boringssl)))]
+const PROTO_VERSION_OPTIONS_MASK: SslOptions = SslOptions {
+    bits: SslOptions::NO_SSLV3.bits
+        | SslOptions::NO_TLSV1.bits
+        | SslOptions::NO_TLSV1_1.bits
+        | SslOptions::NO_TLSV1_2.bits,
+};
+
+#[cfg(not(any(ossl110, libressl261, boringssl)))]
+fn proto_version_options(min: Option<SslVersion>, max: Option<SslVersion>) -> SslOptions {
+    let mut options = SslOptions::empty();
+    for &(version, option) in &PROTO_VERSION_OPTIONS {
+        if min.map_or(false, |min| version.0 < min.0) || max.map_or(false, |max| version.0 > max.0)
+        {
+            options |= option;
+        }
+    }
+    options
+}
+
+#[cfg(not(any(ossl110, libressl261, boringssl)))]
+fn min_proto_version_from_options(options: SslOptions) -> Option<SslVersion> {
+    PROTO_VERSION_OPTIONS
+        .iter()
+        .position(|&(_, option)| !options.contains(option))
+        .filter(|&i| i > 0)
+        .map(|i| PROTO_VERSION_OPTIONS[i].0)
+}
+
+#[cfg(not(any(ossl110, libressl261, boringssl)))]
+fn max_proto_version_from_options(options: SslOptions) -> Option<SslVersion> {
+    PROTO_VERSION_OPTIONS
+        .iter()
+        .rposition(|&(_, option)| !options.contains(option))
+        .filter(|&i| i < PROTO_VERSION_OPTIONS.len() - 1)
+        .map(|i| PROTO_VERSION_OPTIONS[i].0)
+}
+
 cfg_if! {
     if #[cfg(boringssl)] {
         type SslCacheTy = i64;
@@ -668,10 +1096,29 @@ cfg_if! {
 /// [`SslContextBuilder::set_alpn_protos`] for details.
 ///
 /// It will select the first protocol supported by the server which is also supported by the client.
+/// `None` is returned if there is no such protocol, or if either list is empty or malformed.
+///
+/// This is intended to be used from the callback passed to
+/// [`SslContextBuilder::set_alpn_select_callback`]:
+///
+/// ```
+/// use openssl::ssl::{self, AlpnError, SslContext, SslMethod};
+///
+/// let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+/// ctx.set_alpn_select_callback(|_, client| {
+///     ssl::select_next_proto(b"\x02h2\x08http/1.1", client).ok_or(AlpnError::NOACK)
+/// });
+/// ```
 ///
 /// [`SslContextBuilder::set_alpn_protos`]: struct.SslContextBuilder.html#method.set_alpn_protos
+/// [`SslContextBuilder::set_alpn_select_callback`]: struct.SslContextBuilder.html#method.set_alpn_select_callback
 #[corresponds(SSL_select_next_proto)]
-pub fn select_next_proto<'a>(server: &[u8], client: &'a [u8]) -> Option<&'a [u8]> {
+pub fn select_next_proto<'a>(server: &'a [u8], client: &'a [u8]) -> Option<&'a [u8]> {
+    // Older versions of OpenSSL read out of bounds when given an empty or malformed list.
+    if !is_alpn_wire_format(server) || !is_alpn_wire_format(client) {
+        return None;
+    }
+
     unsafe {
         let mut out = ptr::null_mut();
         let mut outlen = 0;
@@ -691,6 +1138,22 @@ pub fn select_next_proto<'a>(server: &[u8], client: &'a [u8]) -> Option<&'a [u8]
     }
 }
 
+fn is_alpn_wire_format(mut protos: &[u8]) -> bool {
+    if protos.is_empty() {
+        return false;
+    }
+
+    while let Some((&len, rest)) = protos.split_first() {
+        let len = usize::from(len);
+        if len == 0 || len > rest.len() {
+            return false;
+        }
+        protos = &rest[len..];
+    }
+
+    true
+}
+
 /// A builder for `SslContext`s.
 pub struct SslContextBuilder(SslContext);
 
@@ -786,6 +1249,19 @@ impl SslContextBuilder {
         }
     }
 
+    /// Enables or disables post-handshake authentication on the client side.
+    ///
+    /// TLSv1.3 servers may only request a client certificate after the handshake if the client
+    /// has advertised support for post-handshake authentication. This should be paired with
+    /// configuring a certificate and private key, or a certificate selection callback.
+    ///
+    /// Requires OpenSSL 1.1.1 or LibreSSL 3.4.0 or newer.
+    #[corresponds(SSL_CTX_set_post_handshake_auth)]
+    #[cfg(any(ossl111, libressl340))]
+    pub fn set_post_handshake_auth(&mut self, enable: bool) {
+        unsafe { ffi::SSL_CTX_set_post_handshake_auth(self.as_ptr(), enable as c_int) }
+    }
+
     /// Sets a custom certificate store for verifying peer certificates.
     ///
     /// Requires OpenSSL 1.0.2 or newer.
@@ -802,6 +1278,12 @@ impl SslContextBuilder {
     }
 
     /// Replaces the context's certificate store.
+    ///
+    /// This installs a store built with `X509StoreBuilder`, including its trusted certificates,
+    /// CRLs, and verification flags. Additional certificates can still be added through
+    /// [`cert_store_mut`] until the context is built.
+    ///
+    /// [`cert_store_mut`]: #method.cert_store_mut
     #[corresponds(SSL_CTX_set_cert_store)]
     pub fn set_cert_store(&mut self, cert_store: X509Store) {
         unsafe {
@@ -895,6 +1377,39 @@ impl SslContextBuilder {
         unsafe { cvt(ffi::SSL_CTX_set_default_verify_paths(self.as_ptr())).map(|_| ()) }
     }
 
+    /// Use the default locations of trusted certificates and the platform's native trust store
+    /// for verification.
+    ///
+    /// This behaves like [`set_default_verify_paths`], and additionally loads the operating
+    /// system's trusted root certificates where OpenSSL is able to. This is only the case for the
+    /// Windows system certificate store with OpenSSL 3.2.0 or newer; the macOS Keychain is not
+    /// supported.
+    ///
+    /// Returns `true` if the platform's trust store was loaded, and `false` if only the default
+    /// locations are used.
+    ///
+    /// This is useful for statically linked binaries, whose default locations often do not exist
+    /// on the machine they run on.
+    ///
+    /// [`set_default_verify_paths`]: #method.set_default_verify_paths
+    pub fn set_default_verify_paths_or_platform(&mut self) -> Result<bool, ErrorStack> {
+        self.set_default_verify_paths()?;
+
+        cfg_if! {
+            if #[cfg(all(windows, ossl320))] {
+                unsafe {
+                    cvt(ffi::SSL_CTX_load_verify_store(
+                        self.as_ptr(),
+                        "org.openssl.winstore:\0".as_ptr() as *const c_char,
+                    ))?;
+                }
+                Ok(true)
+            } else {
+                Ok(false)
+            }
+        }
+    }
+
     /// Loads trusted root certificates from a file.
     ///
     /// The file should contain a sequence of PEM-formatted CA certificates.
@@ -1014,6 +1529,51 @@ impl SslContextBuilder {
         }
     }
 
+    /// Replaces the certificate chain of the current certificate.
+    ///
+    /// Unlike `add_extra_chain_cert`, the chain is associated with the certificate most recently
+    /// set with `set_certificate`, so servers with several certificates can use a different
+    /// chain for each.
+    ///
+    /// Requires OpenSSL 1.0.2 or newer.
+    #[corresponds(SSL_CTX_set0_chain)]
+    #[cfg(ossl102)]
+    pub fn set_chain(&mut self, chain: Stack<X509>) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::SSL_CTX_set0_chain(self.as_ptr(), chain.as_ptr()) as c_int)?;
+            mem::forget(chain);
+            Ok(())
+        }
+    }
+
+    /// Appends a certificate to the certificate chain of the current certificate.
+    ///
+    /// Requires OpenSSL 1.0.2 or newer.
+    #[corresponds(SSL_CTX_add0_chain_cert)]
+    #[cfg(ossl102)]
+    pub fn add_chain_cert(&mut self, cert: X509) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::SSL_CTX_add0_chain_cert(self.as_ptr(), cert.as_ptr()) as c_int)?;
+            mem::forget(cert);
+            Ok(())
+        }
+    }
+
+    /// Builds the certificate chain of the current certificate.
+    ///
+    /// The chain is constructed from the certificate store, replacing any existing chain, so
+    /// that intermediates are sent in the correct order. Certificates must have been loaded with
+    /// `set_certificate` and the relevant CAs added to the store beforehand.
+    ///
+    /// Requires OpenSSL 1.0.2 or newer.
+    #[corresponds(SSL_CTX_build_cert_chain)]
+    #[cfg(ossl102)]
+    pub fn build_cert_chain(&mut self, flags: SslBuildChainFlags) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::SSL_CTX_build_cert_chain(self.as_ptr(), flags.bits()) as c_int).map(|_| ())
+        }
+    }
+
     /// Loads the private key from a file.
     #[corresponds(SSL_CTX_use_PrivateKey_file)]
     pub fn set_private_key_file<P: AsRef<Path>>(
@@ -1123,16 +1683,26 @@ impl SslContextBuilder {
     /// A value of `None` will enable protocol versions down the the lowest version supported by
     /// OpenSSL.
     ///
-    /// Requires OpenSSL 1.1.0 or LibreSSL 2.6.1 or newer.
+    /// On OpenSSL versions before 1.1.0 and LibreSSL versions before 2.6.1, this is implemented by
+    /// setting the `NO_SSLV3`, `NO_TLSV1`, `NO_TLSV1_1`, and `NO_TLSV1_2` options.
     #[corresponds(SSL_CTX_set_min_proto_version)]
-    #[cfg(any(ossl110, libressl261))]
+    #[cfg(not(boringssl))]
     pub fn set_min_proto_version(&mut self, version: Option<SslVersion>) -> Result<(), ErrorStack> {
-        unsafe {
-            cvt(ffi::SSL_CTX_set_min_proto_version(
-                self.as_ptr(),
-                version.map_or(0, |v| v.0 as _),
-            ))
-            .map(|_| ())
+        cfg_if! {
+            if #[cfg(any(ossl110, libressl261))] {
+                unsafe {
+                    cvt(ffi::SSL_CTX_set_min_proto_version(
+                        self.as_ptr(),
+                        version.map_or(0, |v| v.0 as _),
+                    ))
+                    .map(|_| ())
+                }
+            } else {
+                let max = max_proto_version_from_options(self.options());
+                self.clear_options(PROTO_VERSION_OPTIONS_MASK);
+                self.set_options(proto_version_options(version, max));
+                Ok(())
+            }
         }
     }
 
@@ -1141,16 +1711,26 @@ impl SslContextBuilder {
     /// A value of `None` will enable protocol versions down the the highest version supported by
     /// OpenSSL.
     ///
-    /// Requires OpenSSL 1.1.0 or or LibreSSL 2.6.1 or newer.
+    /// On OpenSSL versions before 1.1.0 and LibreSSL versions before 2.6.1, this is implemented by
+    /// setting the `NO_SSLV3`, `NO_TLSV1`, `NO_TLSV1_1`, and `NO_TLSV1_2` options.
     #[corresponds(SSL_CTX_set_max_proto_version)]
-    #[cfg(any(ossl110, libressl261))]
+    #[cfg(not(boringssl))]
     pub fn set_max_proto_version(&mut self, version: Option<SslVersion>) -> Result<(), ErrorStack> {
-        unsafe {
-            cvt(ffi::SSL_CTX_set_max_proto_version(
-                self.as_ptr(),
-                version.map_or(0, |v| v.0 as _),
-            ))
-            .map(|_| ())
+        cfg_if! {
+            if #[cfg(any(ossl110, libressl261))] {
+                unsafe {
+                    cvt(ffi::SSL_CTX_set_max_proto_version(
+                        self.as_ptr(),
+                        version.map_or(0, |v| v.0 as _),
+                    ))
+                    .map(|_| ())
+                }
+            } else {
+                let min = min_proto_version_from_options(self.options());
+                self.clear_options(PROTO_VERSION_OPTIONS_MASK);
+                self.set_options(proto_version_options(min, version));
+                Ok(())
+            }
         }
     }
 
@@ -1159,16 +1739,22 @@ impl SslContextBuilder {
     /// A value of `None` indicates that all versions down the the lowest version supported by
     /// OpenSSL are enabled.
     ///
-    /// Requires OpenSSL 1.1.0g or LibreSSL 2.7.0 or newer.
+    /// Requires OpenSSL 1.1.0g or LibreSSL 2.7.0 or newer, or OpenSSL before 1.1.0.
     #[corresponds(SSL_CTX_get_min_proto_version)]
-    #[cfg(any(ossl110g, libressl270))]
+    #[cfg(any(ossl110g, libressl270, not(any(ossl110, libressl261, boringssl))))]
     pub fn min_proto_version(&mut self) -> Option<SslVersion> {
-        unsafe {
-            let r = ffi::SSL_CTX_get_min_proto_version(self.as_ptr());
-            if r == 0 {
-                None
+        cfg_if! {
+            if #[cfg(any(ossl110g, libressl270))] {
+                unsafe {
+                    let r = ffi::SSL_CTX_get_min_proto_version(self.as_ptr());
+                    if r == 0 {
+                        None
+                    } else {
+                        Some(SslVersion(r))
+                    }
+                }
             } else {
-                Some(SslVersion(r))
+                min_proto_version_from_options(self.options())
             }
         }
     }
@@ -1178,16 +1764,22 @@ impl SslContextBuilder {
     /// A value of `None` indicates that all versions down the the highest version supported by
     /// OpenSSL are enabled.
     ///
-    /// Requires OpenSSL 1.1.0g or LibreSSL 2.7.0 or newer.
+    /// Requires OpenSSL 1.1.0g or LibreSSL 2.7.0 or newer, or OpenSSL before 1.1.0.
     #[corresponds(SSL_CTX_get_max_proto_version)]
-    #[cfg(any(ossl110g, libressl270))]
+    #[cfg(any(ossl110g, libressl270, not(any(ossl110, libressl261, boringssl))))]
     pub fn max_proto_version(&mut self) -> Option<SslVersion> {
-        unsafe {
-            let r = ffi::SSL_CTX_get_max_proto_version(self.as_ptr());
-            if r == 0 {
-                None
+        cfg_if! {
+            if #[cfg(any(ossl110g, libressl270))] {
+                unsafe {
+                    let r = ffi::SSL_CTX_get_max_proto_version(self.as_ptr());
+                    if r == 0 {
+                        None
+                    } else {
+                        Some(SslVersion(r))
+                    }
+                }
             } else {
-                Some(SslVersion(r))
+                max_proto_version_from_options(self.options())
             }
         }
     }
@@ -1299,6 +1891,17 @@ impl SslContextBuilder {
         unsafe { X509VerifyParamRef::from_ptr_mut(ffi::SSL_CTX_get0_param(self.as_ptr())) }
     }
 
+    /// Sets the X509 verification configuration.
+    ///
+    /// Only the parameters which have been set in `param` override those of the context.
+    ///
+    /// Requires OpenSSL 1.0.2 or newer.
+    #[corresponds(SSL_CTX_set1_param)]
+    #[cfg(any(ossl102, libressl261))]
+    pub fn set_verify_param(&mut self, param: &X509VerifyParamRef) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::SSL_CTX_set1_param(self.as_ptr(), param.as_ptr())).map(|_| ()) }
+    }
+
     /// Sets the callback dealing with OCSP stapling.
     ///
     /// On the client side, this callback is responsible for validating the OCSP status response
@@ -1326,7 +1929,21 @@ impl SslContextBuilder {
         }
     }
 
-    /// Sets the callback for providing an identity and pre-shared key for a TLS-PSK client.
+    /// Sets the status response a client wishes the server to reply with.
+    ///
+    /// This applies to every `Ssl` created from the context afterwards.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_CTX_set_tlsext_status_type)]
+    #[cfg(ossl110)]
+    pub fn set_status_type(&mut self, type_: StatusType) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::SSL_CTX_set_tlsext_status_type(self.as_ptr(), type_.as_raw()) as c_int)
+                .map(|_| ())
+        }
+    }
+
+    /// Sets the callback for providing an identity and pre-shared key for a TLS-PSK client.
     ///
     /// The callback will be called with the SSL context, an identity hint if one was provided
     /// by the server, a mutable slice for each of the identity and pre-shared key bytes. The
@@ -1457,6 +2074,40 @@ impl SslContextBuilder {
         }
     }
 
+    /// Sets the message callback.
+    ///
+    /// The callback is invoked for every protocol message sent or received, and is passed whether
+    /// the message was written (`true`) or read (`false`), the protocol version, the record content
+    /// type, and the raw message bytes. Handshake messages are delivered whole, so the first byte of
+    /// a `SslContentType::HANDSHAKE` message is its handshake type. This is intended for debugging
+    /// and tracing.
+    #[corresponds(SSL_CTX_set_msg_callback)]
+    pub fn set_msg_callback<F>(&mut self, callback: F)
+    where
+        F: Fn(&mut SslRef, bool, SslVersion, SslContentType, &[u8]) + 'static + Sync + Send,
+    {
+        unsafe {
+            // The callback and its argument are copied into each `Ssl` when it is created, so it
+            // stays in place if the `Ssl` is later switched to another context.
+            let ptr = self.set_ex_data_inner(SslContext::cached_ex_index::<F>(), callback);
+            ffi::SSL_CTX_set_msg_callback_arg(self.as_ptr(), ptr);
+            ffi::SSL_CTX_set_msg_callback(self.as_ptr(), Some(callbacks::raw_msg::<F>));
+        }
+    }
+
+    /// Records renegotiations requested by the peer which are refused, so that they can be
+    /// detected with [`SslRef::peer_renegotiation_attempted`].
+    ///
+    /// This is done with an info callback, which replaces any set on the context directly through
+    /// OpenSSL.
+    ///
+    /// [`SslRef::peer_renegotiation_attempted`]: struct.SslRef.html#method.peer_renegotiation_attempted
+    #[corresponds(SSL_CTX_set_info_callback)]
+    #[cfg(not(boringssl))]
+    pub fn track_peer_renegotiation(&mut self) {
+        unsafe { ffi::SSL_CTX_set_info_callback(self.as_ptr(), Some(callbacks::raw_info)) }
+    }
+
     /// Sets the session caching mode use for connections made with the context.
     ///
     /// Returns the previous session caching mode.
@@ -1468,6 +2119,20 @@ impl SslContextBuilder {
         }
     }
 
+    /// Enables or disables stateless session resumption with session tickets.
+    ///
+    /// Tickets are enabled by default. Clients can disable them to avoid presenting a ticket
+    /// which links their connections together, and servers can disable them to only resume
+    /// sessions from the session cache. This is a convenience wrapper around
+    /// `SslOptions::NO_TICKET`.
+    pub fn set_session_tickets_enabled(&mut self, enabled: bool) {
+        if enabled {
+            self.clear_options(SslOptions::NO_TICKET);
+        } else {
+            self.set_options(SslOptions::NO_TICKET);
+        }
+    }
+
     /// Sets the callback for generating an application cookie for TLS1.3
     /// stateless handshakes.
     ///
@@ -1546,22 +2211,65 @@ impl SslContextBuilder {
     /// Sets the extra data at the specified index.
     ///
     /// This can be used to provide data to callbacks registered with the context. Use the
-    /// `SslContext::new_ex_index` method to create an `Index`.
+    /// `SslContext::new_ex_index` method to create an `Index`. Any previous value at the index
+    /// is dropped.
     #[corresponds(SSL_CTX_set_ex_data)]
     pub fn set_ex_data<T>(&mut self, index: Index<SslContext, T>, data: T) {
         self.set_ex_data_inner(index, data);
     }
 
     fn set_ex_data_inner<T>(&mut self, index: Index<SslContext, T>, data: T) -> *mut c_void {
+        match self.ex_data_mut(index) {
+            Some(v) => {
+                *v = data;
+                v as *mut T as *mut c_void
+            }
+            None => unsafe {
+                let data = Box::into_raw(Box::new(data)) as *mut c_void;
+                ffi::SSL_CTX_set_ex_data(self.as_ptr(), index.as_raw(), data);
+                data
+            },
+        }
+    }
+
+    /// Returns a reference to the extra data at the specified index.
+    #[corresponds(SSL_CTX_get_ex_data)]
+    pub fn ex_data<T>(&self, index: Index<SslContext, T>) -> Option<&T> {
+        unsafe {
+            let data = ffi::SSL_CTX_get_ex_data(self.as_ptr(), index.as_raw());
+            if data.is_null() {
+                None
+            } else {
+                Some(&*(data as *const T))
+            }
+        }
+    }
+
+    /// Returns a mutable reference to the extra data at the specified index.
+    #[corresponds(SSL_CTX_get_ex_data)]
+    pub fn ex_data_mut<T>(&mut self, index: Index<SslContext, T>) -> Option<&mut T> {
         unsafe {
-            let data = Box::into_raw(Box::new(data)) as *mut c_void;
-            ffi::SSL_CTX_set_ex_data(self.as_ptr(), index.as_raw(), data);
-            data
+            let data = ffi::SSL_CTX_get_ex_data(self.as_ptr(), index.as_raw());
+            if data.is_null() {
+                None
+            } else {
+                Some(&mut *(data as *mut T))
+            }
         }
     }
 
     /// Adds a custom extension for a TLS/DTLS client or server for all supported protocol versions.
     ///
+    /// `context` specifies the messages in which the extension may appear. `add_cb` is invoked
+    /// when constructing one of those messages, and returns the extension's payload, `Ok(None)`
+    /// to omit the extension, or an alert to abort the handshake. `parse_cb` is invoked when the
+    /// extension is received from the peer, and returns an alert to abort the handshake if the
+    /// payload is unacceptable. For extensions in the TLS 1.3 Certificate message, both callbacks
+    /// are additionally passed the certificate's index in the chain and the certificate itself.
+    ///
+    /// A server only sends an extension in response to a client which included it in its
+    /// ClientHello.
+    ///
     /// Requires OpenSSL 1.1.1 or newer.
     #[corresponds(SSL_CTX_add_custom_ext)]
     #[cfg(ossl111)]
@@ -1629,6 +2337,73 @@ impl SslContextBuilder {
         }
     }
 
+    /// Sets the maximum amount of early data that will be read on incoming connections before
+    /// failing the handshake.
+    ///
+    /// This may be larger than the value set with [`set_max_early_data`], which is the amount
+    /// advertised to clients, to tolerate a change in the advertised value. Defaults to 16384.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    ///
+    /// [`set_max_early_data`]: #method.set_max_early_data
+    #[corresponds(SSL_CTX_set_recv_max_early_data)]
+    #[cfg(ossl111)]
+    pub fn set_recv_max_early_data(&mut self, bytes: u32) -> Result<(), ErrorStack> {
+        if unsafe { ffi::SSL_CTX_set_recv_max_early_data(self.as_ptr(), bytes) } == 1 {
+            Ok(())
+        } else {
+            Err(ErrorStack::get())
+        }
+    }
+
+    /// Enables or disables the built-in replay protection for TLSv1.3 early data.
+    ///
+    /// Replay protection is enabled by default. This is a convenience wrapper around
+    /// `SslOptions::NO_ANTI_REPLAY`.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[cfg(ossl111)]
+    pub fn set_anti_replay_enabled(&mut self, enabled: bool) {
+        if enabled {
+            self.clear_options(SslOptions::NO_ANTI_REPLAY);
+        } else {
+            self.set_options(SslOptions::NO_ANTI_REPLAY);
+        }
+    }
+
+    /// Sets the number of TLSv1.3 session tickets that will be sent to clients after a full
+    /// handshake.
+    ///
+    /// Defaults to 2. Setting this to 0 disables the issuance of session tickets.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[corresponds(SSL_CTX_set_num_tickets)]
+    #[cfg(ossl111)]
+    pub fn set_num_tickets(&mut self, num_tickets: usize) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::SSL_CTX_set_num_tickets(self.as_ptr(), num_tickets)).map(|_| ()) }
+    }
+
+    /// Sets the security level for the context.
+    ///
+    /// The security level controls the minimum strength of keys, signature algorithms, and
+    /// protocol versions that will be accepted. Levels range from 0 (permit anything) to 5.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_CTX_set_security_level)]
+    #[cfg(ossl110)]
+    pub fn set_security_level(&mut self, level: u32) {
+        unsafe { ffi::SSL_CTX_set_security_level(self.as_ptr(), level as c_int) }
+    }
+
+    /// Returns the security level of the context.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_CTX_get_security_level)]
+    #[cfg(ossl110)]
+    pub fn security_level(&self) -> u32 {
+        unsafe { ffi::SSL_CTX_get_security_level(self.as_ptr()) as u32 }
+    }
+
     /// Sets a callback which will be invoked just after the client's hello message is received.
     ///
     /// Requires OpenSSL 1.1.1 or newer.
@@ -1651,6 +2426,60 @@ impl SslContextBuilder {
         }
     }
 
+    /// Sets a callback which is invoked during the handshake to select the certificate to use.
+    ///
+    /// On the server side, the callback is invoked after the ClientHello has been processed, so the
+    /// requested server name and the peer's supported signature algorithms are available. On the
+    /// client side, it is invoked when the server requests a client certificate. The callback may
+    /// use `SslRef::set_certificate`, `SslRef::set_private_key` and `SslRef::add_chain_cert` to
+    /// configure the certificate for the connection. Returning an error aborts the handshake.
+    ///
+    /// If the certificate is not available yet, for example because it is being loaded from a
+    /// database, the callback can return `CertCallbackResponse::RETRY` to suspend the handshake.
+    /// The handshake then fails with `ErrorCode::WANT_X509_LOOKUP`, and the callback is invoked
+    /// again once it is resumed.
+    ///
+    /// Requires OpenSSL 1.0.2 or newer.
+    #[corresponds(SSL_CTX_set_cert_cb)]
+    #[cfg(ossl102)]
+    pub fn set_cert_callback<F>(&mut self, callback: F)
+    where
+        F: Fn(&mut SslRef) -> Result<CertCallbackResponse, ErrorStack> + 'static + Sync + Send,
+    {
+        unsafe {
+            let ptr = self.set_ex_data_inner(SslContext::cached_ex_index::<F>(), callback);
+            ffi::SSL_CTX_set_cert_cb(self.as_ptr(), Some(raw_cert::<F>), ptr);
+        }
+    }
+
+    /// Returns a context for applying OpenSSL configuration commands to this builder.
+    ///
+    /// Requires OpenSSL 1.0.2 or newer.
+    #[corresponds(SSL_CONF_CTX_set_ssl_ctx)]
+    #[cfg(ossl102)]
+    pub fn conf(&mut self, flags: SslConfFlags) -> Result<SslConf<'_>, ErrorStack> {
+        SslConf::for_context(self, flags)
+    }
+
+    /// Sets a callback which is invoked on the client side to select a client certificate.
+    ///
+    /// The callback is invoked when the server requests a client certificate and none has been
+    /// configured on the context or connection. The CA names sent by the server are available
+    /// from [`SslRef::client_ca_list`]. The callback returns the certificate and private key to
+    /// present to the server, or `None` to continue the handshake without a client certificate.
+    ///
+    /// [`SslRef::client_ca_list`]: struct.SslRef.html#method.client_ca_list
+    #[corresponds(SSL_CTX_set_client_cert_cb)]
+    pub fn set_client_cert_callback<F>(&mut self, callback: F)
+    where
+        F: Fn(&mut SslRef) -> Option<(X509, PKey<Private>)> + 'static + Sync + Send,
+    {
+        unsafe {
+            self.set_ex_data(SslContext::cached_ex_index::<F>(), callback);
+            ffi::SSL_CTX_set_client_cert_cb(self.as_ptr(), Some(raw_client_cert::<F>));
+        }
+    }
+
     /// Sets the context's session cache size limit, returning the previous limit.
     ///
     /// A value of 0 means that the cache size is unbounded.
@@ -1662,6 +2491,15 @@ impl SslContextBuilder {
         }
     }
 
+    /// Sets the timeout of sessions created by the context, in seconds, returning the previous
+    /// timeout.
+    ///
+    /// Expired sessions are not resumed, and are removed from the session cache.
+    #[corresponds(SSL_CTX_set_timeout)]
+    pub fn set_session_timeout(&mut self, timeout: u32) -> u32 {
+        unsafe { ffi::SSL_CTX_set_timeout(self.as_ptr(), timeout as _) as u32 }
+    }
+
     /// Sets the context's supported signature algorithms.
     ///
     /// Requires OpenSSL 1.0.2 or newer.
@@ -1675,6 +2513,23 @@ impl SslContextBuilder {
         }
     }
 
+    /// Sets the signature algorithms the context will accept in client certificates.
+    ///
+    /// If this is not set, the list set by [`set_sigalgs_list`] is used instead.
+    ///
+    /// Requires OpenSSL 1.0.2 or newer.
+    ///
+    /// [`set_sigalgs_list`]: #method.set_sigalgs_list
+    #[corresponds(SSL_CTX_set1_client_sigalgs_list)]
+    #[cfg(ossl102)]
+    pub fn set_client_sigalgs_list(&mut self, sigalgs: &str) -> Result<(), ErrorStack> {
+        let sigalgs = CString::new(sigalgs).unwrap();
+        unsafe {
+            cvt(ffi::SSL_CTX_set1_client_sigalgs_list(self.as_ptr(), sigalgs.as_ptr()) as c_int)
+                .map(|_| ())
+        }
+    }
+
     /// Sets the context's supported elliptic curve groups.
     ///
     /// Requires OpenSSL 1.1.1 or LibreSSL 2.5.1 or newer.
@@ -1687,6 +2542,127 @@ impl SslContextBuilder {
         }
     }
 
+    /// Enables Certificate Transparency validation of the peer's certificate.
+    ///
+    /// Signed certificate timestamps are validated against the log list loaded by
+    /// [`set_default_ctlog_list_file`] or [`set_ctlog_list_file`]. Validation is only performed
+    /// when peer verification is enabled.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    ///
+    /// [`set_default_ctlog_list_file`]: #method.set_default_ctlog_list_file
+    /// [`set_ctlog_list_file`]: #method.set_ctlog_list_file
+    #[corresponds(SSL_CTX_enable_ct)]
+    #[cfg(ossl110)]
+    pub fn enable_ct(&mut self, mode: CtValidationMode) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::SSL_CTX_enable_ct(self.as_ptr(), mode.as_raw())).map(|_| ()) }
+    }
+
+    /// Loads the Certificate Transparency log list from the default location.
+    ///
+    /// This is the `ct_log_list.cnf` file in the OpenSSL directory, or the file named by the
+    /// `CTLOG_FILE` environment variable.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_CTX_set_default_ctlog_list_file)]
+    #[cfg(ossl110)]
+    pub fn set_default_ctlog_list_file(&mut self) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::SSL_CTX_set_default_ctlog_list_file(self.as_ptr())).map(|_| ()) }
+    }
+
+    /// Loads the Certificate Transparency log list from a file.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_CTX_set_ctlog_list_file)]
+    #[cfg(ossl110)]
+    pub fn set_ctlog_list_file<P: AsRef<Path>>(&mut self, file: P) -> Result<(), ErrorStack> {
+        let file = CString::new(file.as_ref().as_os_str().to_str().unwrap()).unwrap();
+        unsafe {
+            cvt(ffi::SSL_CTX_set_ctlog_list_file(
+                self.as_ptr(),
+                file.as_ptr(),
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Enables DANE verification for connections created from this context.
+    ///
+    /// DANE must additionally be enabled on each connection with [`SslRef::dane_enable`].
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    ///
+    /// [`SslRef::dane_enable`]: struct.SslRef.html#method.dane_enable
+    #[corresponds(SSL_CTX_dane_enable)]
+    #[cfg(ossl110)]
+    pub fn dane_enable(&mut self) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::SSL_CTX_dane_enable(self.as_ptr())).map(|_| ()) }
+    }
+
+    /// Configures the digest used for a TLSA matching type.
+    ///
+    /// `ord` sets the preference of the matching type, with higher values preferred. Passing
+    /// `None` for the digest disables the matching type.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_CTX_dane_mtype_set)]
+    #[cfg(ossl110)]
+    pub fn set_dane_mtype(
+        &mut self,
+        digest: Option<MessageDigest>,
+        mtype: u8,
+        ord: u8,
+    ) -> Result<(), ErrorStack> {
+        let md = digest.map_or(ptr::null(), |md| md.as_ptr());
+        unsafe { cvt(ffi::SSL_CTX_dane_mtype_set(self.as_ptr(), md, mtype, ord)).map(|_| ()) }
+    }
+
+    /// Sets the types of credentials that clients may authenticate with, in order of preference.
+    ///
+    /// By default, only X.509 certificates are used. Including [`CertificateType::RPK`] allows
+    /// a bare public key to be used instead. Servers request the client's credential type, and
+    /// clients offer the types they can present.
+    ///
+    /// Requires OpenSSL 3.2.0 or newer.
+    ///
+    /// [`CertificateType::RPK`]: struct.CertificateType.html#associatedconstant.RPK
+    #[corresponds(SSL_CTX_set1_client_cert_type)]
+    #[cfg(ossl320)]
+    pub fn set_client_cert_types(&mut self, types: &[CertificateType]) -> Result<(), ErrorStack> {
+        let types = types.iter().map(|t| t.0).collect::<Vec<_>>();
+        unsafe {
+            cvt(ffi::SSL_CTX_set1_client_cert_type(
+                self.as_ptr(),
+                types.as_ptr(),
+                types.len(),
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Sets the types of credentials that servers may authenticate with, in order of preference.
+    ///
+    /// By default, only X.509 certificates are used. Clients offer the types they accept, and
+    /// servers select one they can present. A client accepting raw public keys must be able to
+    /// authenticate them, for example with [`SslRef::add_expected_rpk`].
+    ///
+    /// Requires OpenSSL 3.2.0 or newer.
+    ///
+    /// [`SslRef::add_expected_rpk`]: struct.SslRef.html#method.add_expected_rpk
+    #[corresponds(SSL_CTX_set1_server_cert_type)]
+    #[cfg(ossl320)]
+    pub fn set_server_cert_types(&mut self, types: &[CertificateType]) -> Result<(), ErrorStack> {
+        let types = types.iter().map(|t| t.0).collect::<Vec<_>>();
+        unsafe {
+            cvt(ffi::SSL_CTX_set1_server_cert_type(
+                self.as_ptr(),
+                types.as_ptr(),
+                types.len(),
+            ))
+            .map(|_| ())
+        }
+    }
+
     /// Consumes the builder, returning a new `SslContext`.
     pub fn build(self) -> SslContext {
         self.0
@@ -1837,6 +2813,34 @@ impl SslContextRef {
         unsafe { ffi::SSL_CTX_get_max_early_data(self.as_ptr()) }
     }
 
+    /// Gets the maximum amount of early data that will be read on incoming connections.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[corresponds(SSL_CTX_get_recv_max_early_data)]
+    #[cfg(ossl111)]
+    pub fn recv_max_early_data(&self) -> u32 {
+        unsafe { ffi::SSL_CTX_get_recv_max_early_data(self.as_ptr()) }
+    }
+
+    /// Gets the number of TLSv1.3 session tickets that will be sent to clients after a full
+    /// handshake.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[corresponds(SSL_CTX_get_num_tickets)]
+    #[cfg(ossl111)]
+    pub fn num_tickets(&self) -> usize {
+        unsafe { ffi::SSL_CTX_get_num_tickets(self.as_ptr()) }
+    }
+
+    /// Returns the security level of the context.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_CTX_get_security_level)]
+    #[cfg(ossl110)]
+    pub fn security_level(&self) -> u32 {
+        unsafe { ffi::SSL_CTX_get_security_level(self.as_ptr()) as u32 }
+    }
+
     /// Adds a session to the context's cache.
     ///
     /// Returns `true` if the session was successfully added to the cache, and `false` if it was already present.
@@ -1872,6 +2876,18 @@ impl SslContextRef {
         unsafe { ffi::SSL_CTX_sess_get_cache_size(self.as_ptr()) as i64 }
     }
 
+    /// Returns the number of sessions currently in the context's session cache.
+    #[corresponds(SSL_CTX_sess_number)]
+    pub fn session_cache_len(&self) -> usize {
+        unsafe { ffi::SSL_CTX_sess_number(self.as_ptr()) as usize }
+    }
+
+    /// Returns the timeout of sessions created by the context, in seconds.
+    #[corresponds(SSL_CTX_get_timeout)]
+    pub fn session_timeout(&self) -> u32 {
+        unsafe { ffi::SSL_CTX_get_timeout(self.as_ptr()) as u32 }
+    }
+
     /// Returns the verify mode that was set on this context from [`SslContextBuilder::set_verify`].
     ///
     /// [`SslContextBuilder::set_verify`]: struct.SslContextBuilder.html#method.set_verify
@@ -1880,6 +2896,49 @@ impl SslContextRef {
         let mode = unsafe { ffi::SSL_CTX_get_verify_mode(self.as_ptr()) };
         SslVerifyMode::from_bits(mode).expect("SSL_CTX_get_verify_mode returned invalid mode")
     }
+
+    /// Returns the certificate verification depth set on this context.
+    #[corresponds(SSL_CTX_get_verify_depth)]
+    pub fn verify_depth(&self) -> u32 {
+        unsafe { ffi::SSL_CTX_get_verify_depth(self.as_ptr()) as u32 }
+    }
+
+    /// Returns the list of CA names sent to clients when requesting a client certificate.
+    #[corresponds(SSL_CTX_get_client_CA_list)]
+    pub fn client_ca_list(&self) -> Option<&StackRef<X509Name>> {
+        unsafe {
+            let ptr = ffi::SSL_CTX_get_client_CA_list(self.as_ptr());
+            StackRef::from_const_ptr_opt(ptr)
+        }
+    }
+
+    /// Returns the session caching mode used for connections made with the context.
+    #[corresponds(SSL_CTX_get_session_cache_mode)]
+    pub fn session_cache_mode(&self) -> SslSessionCacheMode {
+        let bits = unsafe { ffi::SSL_CTX_get_session_cache_mode(self.as_ptr()) };
+        SslSessionCacheMode { bits }
+    }
+
+    /// Returns the ciphers enabled for the context, in order of preference.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_CTX_get_ciphers)]
+    #[cfg(ossl110)]
+    pub fn ciphers(&self) -> Option<&StackRef<SslCipher>> {
+        unsafe {
+            let ptr = ffi::SSL_CTX_get_ciphers(self.as_ptr());
+            StackRef::from_const_ptr_opt(ptr)
+        }
+    }
+
+    /// Determines if Certificate Transparency validation is enabled for the context.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_CTX_ct_is_enabled)]
+    #[cfg(ossl110)]
+    pub fn ct_is_enabled(&self) -> bool {
+        unsafe { ffi::SSL_CTX_ct_is_enabled(self.as_ptr()) == 1 }
+    }
 }
 
 /// Information about the state of a cipher.
@@ -1891,6 +2950,47 @@ pub struct CipherBits {
     pub algorithm: i32,
 }
 
+/// A signature algorithm supported by both endpoints of a connection.
+pub struct SharedSigalg {
+    /// The signature algorithm, e.g. `Nid::RSAENCRYPTION` or `Nid::RSASSAPSS`.
+    pub sign: Nid,
+
+    /// The digest used with the signature algorithm, if any.
+    pub hash: Option<Nid>,
+
+    /// The combined signature and digest algorithm, if one exists.
+    pub sign_hash: Option<Nid>,
+
+    /// The TLS SignatureScheme code point.
+    pub code: u16,
+}
+
+/// A summary of the negotiated parameters of a connection.
+///
+/// This is returned by [`SslRef::connection_info`], and bundles the values typically recorded
+/// when logging a connection.
+///
+/// [`SslRef::connection_info`]: struct.SslRef.html#method.connection_info
+#[derive(Debug)]
+pub struct ConnectionInfo<'a> {
+    /// The negotiated protocol version.
+    pub version: Option<SslVersion>,
+
+    /// The OpenSSL name of the negotiated cipher.
+    pub cipher: Option<&'static str>,
+
+    /// The protocol selected by ALPN, if any.
+    ///
+    /// This is always `None` before OpenSSL 1.0.2 and LibreSSL 2.6.1.
+    pub alpn_protocol: Option<&'a [u8]>,
+
+    /// Whether a previous session was resumed.
+    pub session_reused: bool,
+
+    /// The peer's certificate, if present.
+    pub peer_certificate: Option<X509>,
+}
+
 /// Information about a cipher.
 pub struct SslCipher(*mut ffi::SSL_CIPHER);
 
@@ -1923,6 +3023,10 @@ impl DerefMut for SslCipher {
     }
 }
 
+impl Stackable for SslCipher {
+    type StackType = ffi::stack_st_SSL_CIPHER;
+}
+
 /// Reference to an [`SslCipher`].
 ///
 /// [`SslCipher`]: struct.SslCipher.html
@@ -2078,15 +3182,46 @@ impl SslSessionRef {
         }
     }
 
-    /// Returns the length of the master key.
-    #[corresponds(SSL_SESSION_get_master_key)]
-    pub fn master_key_len(&self) -> usize {
-        unsafe { SSL_SESSION_get_master_key(self.as_ptr(), ptr::null_mut(), 0) }
-    }
+    /// Returns the session ID context the session was established with.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_SESSION_get0_id_context)]
+    #[cfg(ossl110)]
+    pub fn id_context(&self) -> &[u8] {
+        unsafe {
+            let mut len = 0;
+            let p = ffi::SSL_SESSION_get0_id_context(self.as_ptr(), &mut len);
+            slice::from_raw_parts(p as *const u8, len as usize)
+        }
+    }
+
+    /// Returns the raw public key the peer authenticated with, if any.
+    ///
+    /// Requires OpenSSL 3.2.0 or newer.
+    #[corresponds(SSL_SESSION_get0_peer_rpk)]
+    #[cfg(ossl320)]
+    pub fn peer_rpk(&self) -> Option<&PKeyRef<Public>> {
+        unsafe {
+            let ptr = ffi::SSL_SESSION_get0_peer_rpk(self.as_ptr());
+            PKeyRef::from_const_ptr_opt(ptr)
+        }
+    }
+
+    /// Returns the length of the master key.
+    #[corresponds(SSL_SESSION_get_master_key)]
+    pub fn master_key_len(&self) -> usize {
+        unsafe { SSL_SESSION_get_master_key(self.as_ptr(), ptr::null_mut(), 0) }
+    }
 
     /// Copies the master key into the provided buffer.
     ///
     /// Returns the number of bytes written, or the size of the master key if the buffer is empty.
+    ///
+    /// For TLSv1.2 and earlier, the master key and [`SslRef::client_random`] are sufficient to
+    /// decrypt the connection, for example by writing a `CLIENT_RANDOM` line in the NSS key log
+    /// format.
+    ///
+    /// [`SslRef::client_random`]: struct.SslRef.html#method.client_random
     #[corresponds(SSL_SESSION_get_master_key)]
     pub fn master_key(&self, buf: &mut [u8]) -> usize {
         unsafe { SSL_SESSION_get_master_key(self.as_ptr(), buf.as_mut_ptr(), buf.len()) }
@@ -2117,6 +3252,14 @@ impl SslSessionRef {
         unsafe { ffi::SSL_SESSION_get_timeout(self.as_ptr()).into() }
     }
 
+    /// Sets the session's timeout, in seconds.
+    #[corresponds(SSL_SESSION_set_timeout)]
+    pub fn set_timeout(&mut self, timeout: u32) {
+        unsafe {
+            ffi::SSL_SESSION_set_timeout(self.as_ptr(), timeout as _);
+        }
+    }
+
     /// Returns the session's TLS protocol version.
     ///
     /// Requires OpenSSL 1.1.0 or LibreSSL 2.7.0 or newer.
@@ -2129,6 +3272,38 @@ impl SslSessionRef {
         }
     }
 
+    /// Returns the cipher negotiated for the session.
+    ///
+    /// Requires OpenSSL 1.1.0 or LibreSSL 2.7.0 or newer.
+    #[corresponds(SSL_SESSION_get0_cipher)]
+    #[cfg(any(ossl110, libressl270))]
+    pub fn cipher(&self) -> Option<&SslCipherRef> {
+        unsafe {
+            let ptr = ffi::SSL_SESSION_get0_cipher(self.as_ptr());
+            SslCipherRef::from_const_ptr_opt(ptr)
+        }
+    }
+
+    /// Determines if the session has a session ticket which can be used for resumption.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[corresponds(SSL_SESSION_has_ticket)]
+    #[cfg(ossl111)]
+    pub fn has_ticket(&self) -> bool {
+        unsafe { ffi::SSL_SESSION_has_ticket(self.as_ptr()) == 1 }
+    }
+
+    /// Returns the lifetime hint of the session ticket, in seconds.
+    ///
+    /// A value of 0 indicates that the server did not specify a lifetime.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[corresponds(SSL_SESSION_get_ticket_lifetime_hint)]
+    #[cfg(ossl111)]
+    pub fn ticket_lifetime_hint(&self) -> u64 {
+        unsafe { ffi::SSL_SESSION_get_ticket_lifetime_hint(self.as_ptr()) as u64 }
+    }
+
     to_der! {
         /// Serializes the session into a DER-encoded structure.
         #[corresponds(i2d_SSL_SESSION)]
@@ -2266,19 +3441,95 @@ impl SslRef {
         unsafe { ffi::SSL_get_rbio(self.as_ptr()) }
     }
 
-    fn read(&mut self, buf: &mut [u8]) -> c_int {
-        let len = cmp::min(c_int::max_value() as usize, buf.len()) as c_int;
-        unsafe { ffi::SSL_read(self.as_ptr(), buf.as_ptr() as *mut c_void, len) }
+    // The _ex variants take size_t lengths and return 1 on success, so buffers longer than
+    // c_int::MAX aren't truncated and the byte count is never conflated with the error sentinel.
+    // On failure, the raw return value is handed back for use with SSL_get_error.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, c_int> {
+        cfg_if! {
+            if #[cfg(any(ossl111, libressl350))] {
+                let mut readbytes = 0;
+                let ret = unsafe {
+                    ffi::SSL_read_ex(
+                        self.as_ptr(),
+                        buf.as_mut_ptr() as *mut c_void,
+                        buf.len(),
+                        &mut readbytes,
+                    )
+                };
+                if ret > 0 {
+                    Ok(readbytes)
+                } else {
+                    Err(ret)
+                }
+            } else {
+                let len = cmp::min(c_int::max_value() as usize, buf.len()) as c_int;
+                let ret = unsafe { ffi::SSL_read(self.as_ptr(), buf.as_ptr() as *mut c_void, len) };
+                if ret > 0 {
+                    Ok(ret as usize)
+                } else {
+                    Err(ret)
+                }
+            }
+        }
     }
 
-    fn peek(&mut self, buf: &mut [u8]) -> c_int {
-        let len = cmp::min(c_int::max_value() as usize, buf.len()) as c_int;
-        unsafe { ffi::SSL_peek(self.as_ptr(), buf.as_ptr() as *mut c_void, len) }
+    fn peek(&mut self, buf: &mut [u8]) -> Result<usize, c_int> {
+        cfg_if! {
+            if #[cfg(any(ossl111, libressl350))] {
+                let mut readbytes = 0;
+                let ret = unsafe {
+                    ffi::SSL_peek_ex(
+                        self.as_ptr(),
+                        buf.as_mut_ptr() as *mut c_void,
+                        buf.len(),
+                        &mut readbytes,
+                    )
+                };
+                if ret > 0 {
+                    Ok(readbytes)
+                } else {
+                    Err(ret)
+                }
+            } else {
+                let len = cmp::min(c_int::max_value() as usize, buf.len()) as c_int;
+                let ret = unsafe { ffi::SSL_peek(self.as_ptr(), buf.as_ptr() as *mut c_void, len) };
+                if ret > 0 {
+                    Ok(ret as usize)
+                } else {
+                    Err(ret)
+                }
+            }
+        }
     }
 
-    fn write(&mut self, buf: &[u8]) -> c_int {
-        let len = cmp::min(c_int::max_value() as usize, buf.len()) as c_int;
-        unsafe { ffi::SSL_write(self.as_ptr(), buf.as_ptr() as *const c_void, len) }
+    fn write(&mut self, buf: &[u8]) -> Result<usize, c_int> {
+        cfg_if! {
+            if #[cfg(any(ossl111, libressl350))] {
+                let mut written = 0;
+                let ret = unsafe {
+                    ffi::SSL_write_ex(
+                        self.as_ptr(),
+                        buf.as_ptr() as *const c_void,
+                        buf.len(),
+                        &mut written,
+                    )
+                };
+                if ret > 0 {
+                    Ok(written)
+                } else {
+                    Err(ret)
+                }
+            } else {
+                let len = cmp::min(c_int::max_value() as usize, buf.len()) as c_int;
+                let ret =
+                    unsafe { ffi::SSL_write(self.as_ptr(), buf.as_ptr() as *const c_void, len) };
+                if ret > 0 {
+                    Ok(ret as usize)
+                } else {
+                    Err(ret)
+                }
+            }
+        }
     }
 
     fn get_error(&self, ret: c_int) -> ErrorCode {
@@ -2306,12 +3557,28 @@ impl SslRef {
     }
 
     /// Returns the verify mode that was set using `set_verify`.
-    #[corresponds(SSL_set_verify_mode)]
+    #[corresponds(SSL_get_verify_mode)]
     pub fn verify_mode(&self) -> SslVerifyMode {
         let mode = unsafe { ffi::SSL_get_verify_mode(self.as_ptr()) };
         SslVerifyMode::from_bits(mode).expect("SSL_get_verify_mode returned invalid mode")
     }
 
+    /// Like [`SslContextBuilder::set_verify_depth`].
+    ///
+    /// This overrides the depth set on the context for this connection only.
+    ///
+    /// [`SslContextBuilder::set_verify_depth`]: struct.SslContextBuilder.html#method.set_verify_depth
+    #[corresponds(SSL_set_verify_depth)]
+    pub fn set_verify_depth(&mut self, depth: u32) {
+        unsafe { ffi::SSL_set_verify_depth(self.as_ptr(), depth as c_int) }
+    }
+
+    /// Returns the certificate verification depth of the connection.
+    #[corresponds(SSL_get_verify_depth)]
+    pub fn verify_depth(&self) -> u32 {
+        unsafe { ffi::SSL_get_verify_depth(self.as_ptr()) as u32 }
+    }
+
     /// Like [`SslContextBuilder::set_verify_callback`].
     ///
     /// [`SslContextBuilder::set_verify_callback`]: struct.SslContextBuilder.html#method.set_verify_callback
@@ -2327,6 +3594,158 @@ impl SslRef {
         }
     }
 
+    /// Like [`SslContextBuilder::set_options`].
+    ///
+    /// [`SslContextBuilder::set_options`]: struct.SslContextBuilder.html#method.set_options
+    #[corresponds(SSL_set_options)]
+    pub fn set_options(&mut self, option: SslOptions) -> SslOptions {
+        let bits = unsafe { ffi::SSL_set_options(self.as_ptr(), option.bits()) } as SslOptionsRepr;
+        SslOptions { bits }
+    }
+
+    /// Like [`SslContextBuilder::options`].
+    ///
+    /// [`SslContextBuilder::options`]: struct.SslContextBuilder.html#method.options
+    #[corresponds(SSL_get_options)]
+    pub fn options(&self) -> SslOptions {
+        let bits = unsafe { ffi::SSL_get_options(self.as_ptr()) } as SslOptionsRepr;
+        SslOptions { bits }
+    }
+
+    /// Like [`SslContextBuilder::clear_options`].
+    ///
+    /// [`SslContextBuilder::clear_options`]: struct.SslContextBuilder.html#method.clear_options
+    #[corresponds(SSL_clear_options)]
+    pub fn clear_options(&mut self, option: SslOptions) -> SslOptions {
+        let bits =
+            unsafe { ffi::SSL_clear_options(self.as_ptr(), option.bits()) } as SslOptionsRepr;
+        SslOptions { bits }
+    }
+
+    /// Like [`SslContextBuilder::set_min_proto_version`].
+    ///
+    /// [`SslContextBuilder::set_min_proto_version`]: struct.SslContextBuilder.html#method.set_min_proto_version
+    #[corresponds(SSL_set_min_proto_version)]
+    #[cfg(not(boringssl))]
+    pub fn set_min_proto_version(&mut self, version: Option<SslVersion>) -> Result<(), ErrorStack> {
+        cfg_if! {
+            if #[cfg(any(ossl110, libressl261))] {
+                unsafe {
+                    cvt(ffi::SSL_set_min_proto_version(
+                        self.as_ptr(),
+                        version.map_or(0, |v| v.0 as _),
+                    ))
+                    .map(|_| ())
+                }
+            } else {
+                let max = max_proto_version_from_options(self.options());
+                self.clear_options(PROTO_VERSION_OPTIONS_MASK);
+                self.set_options(proto_version_options(version, max));
+                Ok(())
+            }
+        }
+    }
+
+    /// Like [`SslContextBuilder::set_max_proto_version`].
+    ///
+    /// [`SslContextBuilder::set_max_proto_version`]: struct.SslContextBuilder.html#method.set_max_proto_version
+    #[corresponds(SSL_set_max_proto_version)]
+    #[cfg(not(boringssl))]
+    pub fn set_max_proto_version(&mut self, version: Option<SslVersion>) -> Result<(), ErrorStack> {
+        cfg_if! {
+            if #[cfg(any(ossl110, libressl261))] {
+                unsafe {
+                    cvt(ffi::SSL_set_max_proto_version(
+                        self.as_ptr(),
+                        version.map_or(0, |v| v.0 as _),
+                    ))
+                    .map(|_| ())
+                }
+            } else {
+                let min = min_proto_version_from_options(self.options());
+                self.clear_options(PROTO_VERSION_OPTIONS_MASK);
+                self.set_options(proto_version_options(min, version));
+                Ok(())
+            }
+        }
+    }
+
+    /// Like [`SslContextBuilder::min_proto_version`].
+    ///
+    /// Requires OpenSSL 1.1.0g or LibreSSL 2.7.0 or newer, or OpenSSL before 1.1.0.
+    ///
+    /// [`SslContextBuilder::min_proto_version`]: struct.SslContextBuilder.html#method.min_proto_version
+    #[corresponds(SSL_get_min_proto_version)]
+    #[cfg(any(ossl110g, libressl270, not(any(ossl110, libressl261, boringssl))))]
+    pub fn min_proto_version(&self) -> Option<SslVersion> {
+        cfg_if! {
+            if #[cfg(any(ossl110g, libressl270))] {
+                unsafe {
+                    let r = ffi::SSL_get_min_proto_version(self.as_ptr());
+                    if r == 0 {
+                        None
+                    } else {
+                        Some(SslVersion(r))
+                    }
+                }
+            } else {
+                min_proto_version_from_options(self.options())
+            }
+        }
+    }
+
+    /// Like [`SslContextBuilder::max_proto_version`].
+    ///
+    /// Requires OpenSSL 1.1.0g or LibreSSL 2.7.0 or newer, or OpenSSL before 1.1.0.
+    ///
+    /// [`SslContextBuilder::max_proto_version`]: struct.SslContextBuilder.html#method.max_proto_version
+    #[corresponds(SSL_get_max_proto_version)]
+    #[cfg(any(ossl110g, libressl270, not(any(ossl110, libressl261, boringssl))))]
+    pub fn max_proto_version(&self) -> Option<SslVersion> {
+        cfg_if! {
+            if #[cfg(any(ossl110g, libressl270))] {
+                unsafe {
+                    let r = ffi::SSL_get_max_proto_version(self.as_ptr());
+                    if r == 0 {
+                        None
+                    } else {
+                        Some(SslVersion(r))
+                    }
+                }
+            } else {
+                max_proto_version_from_options(self.options())
+            }
+        }
+    }
+
+    /// Like [`SslContextBuilder::set_client_ca_list`].
+    ///
+    /// [`SslContextBuilder::set_client_ca_list`]: struct.SslContextBuilder.html#method.set_client_ca_list
+    #[corresponds(SSL_set_client_CA_list)]
+    pub fn set_client_ca_list(&mut self, list: Stack<X509Name>) {
+        unsafe {
+            ffi::SSL_set_client_CA_list(self.as_ptr(), list.as_ptr());
+            mem::forget(list);
+        }
+    }
+
+    /// Like [`SslContextBuilder::add_client_ca`].
+    ///
+    /// [`SslContextBuilder::add_client_ca`]: struct.SslContextBuilder.html#method.add_client_ca
+    #[corresponds(SSL_add_client_CA)]
+    pub fn add_client_ca(&mut self, cacert: &X509Ref) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::SSL_add_client_CA(self.as_ptr(), cacert.as_ptr())).map(|_| ()) }
+    }
+
+    /// Like [`SslContextBuilder::set_post_handshake_auth`].
+    ///
+    /// Requires OpenSSL 1.1.1 or LibreSSL 3.4.0 or newer.
+    #[corresponds(SSL_set_post_handshake_auth)]
+    #[cfg(any(ossl111, libressl340))]
+    pub fn set_post_handshake_auth(&mut self, enable: bool) {
+        unsafe { ffi::SSL_set_post_handshake_auth(self.as_ptr(), enable as c_int) }
+    }
+
     /// Like [`SslContextBuilder::set_tmp_dh`].
     ///
     /// [`SslContextBuilder::set_tmp_dh`]: struct.SslContextBuilder.html#method.set_tmp_dh
@@ -2353,6 +3772,21 @@ impl SslRef {
         }
     }
 
+    /// Like [`SslContextBuilder::set_msg_callback`].
+    ///
+    /// [`SslContextBuilder::set_msg_callback`]: struct.SslContextBuilder.html#method.set_msg_callback
+    #[corresponds(SSL_set_msg_callback)]
+    pub fn set_msg_callback<F>(&mut self, callback: F)
+    where
+        F: Fn(&mut SslRef, bool, SslVersion, SslContentType, &[u8]) + 'static + Sync + Send,
+    {
+        unsafe {
+            // this needs to be in an Arc since the callback can register a new callback!
+            self.set_ex_data(Ssl::cached_ex_index(), Arc::new(callback));
+            ffi::SSL_set_msg_callback(self.as_ptr(), Some(ssl_raw_msg::<F>));
+        }
+    }
+
     /// Like [`SslContextBuilder::set_tmp_ecdh`].
     ///
     /// [`SslContextBuilder::set_tmp_ecdh`]: struct.SslContextBuilder.html#method.set_tmp_ecdh
@@ -2423,6 +3857,189 @@ impl SslRef {
         }
     }
 
+    /// Returns the ciphers enabled for the session, in order of preference.
+    #[corresponds(SSL_get_ciphers)]
+    pub fn ciphers(&self) -> Option<&StackRef<SslCipher>> {
+        unsafe {
+            let ptr = ffi::SSL_get_ciphers(self.as_ptr());
+            StackRef::from_const_ptr_opt(ptr)
+        }
+    }
+
+    /// Returns the signature algorithms supported by both the local and peer endpoints, in order
+    /// of preference.
+    ///
+    /// Requires OpenSSL 1.0.2 or newer.
+    #[corresponds(SSL_get_shared_sigalgs)]
+    #[cfg(ossl102)]
+    pub fn shared_sigalgs(&self) -> Vec<SharedSigalg> {
+        unsafe {
+            let count = ffi::SSL_get_shared_sigalgs(
+                self.as_ptr(),
+                0,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+
+            (0..count)
+                .map(|idx| {
+                    let mut sign = 0;
+                    let mut hash = 0;
+                    let mut sign_hash = 0;
+                    let mut rsig = 0;
+                    let mut rhash = 0;
+                    ffi::SSL_get_shared_sigalgs(
+                        self.as_ptr(),
+                        idx,
+                        &mut sign,
+                        &mut hash,
+                        &mut sign_hash,
+                        &mut rsig,
+                        &mut rhash,
+                    );
+                    let nid = |raw| match raw {
+                        ffi::NID_undef => None,
+                        raw => Some(Nid::from_raw(raw)),
+                    };
+                    SharedSigalg {
+                        sign: Nid::from_raw(sign),
+                        hash: nid(hash),
+                        sign_hash: nid(sign_hash),
+                        code: (rhash as u16) << 8 | rsig as u16,
+                    }
+                })
+                .collect()
+        }
+    }
+
+    /// Returns the groups supported by the client, in the order it sent them.
+    ///
+    /// This is only meaningful on the server side once the ClientHello has been received.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[corresponds(SSL_get1_groups)]
+    #[cfg(ossl111)]
+    pub fn peer_groups(&self) -> Vec<Nid> {
+        unsafe {
+            let count = ffi::SSL_get1_groups(self.as_ptr(), ptr::null_mut());
+            if count <= 0 {
+                return vec![];
+            }
+
+            let mut groups = vec![0; count as usize];
+            ffi::SSL_get1_groups(self.as_ptr(), groups.as_mut_ptr());
+            groups.into_iter().map(Nid::from_raw).collect()
+        }
+    }
+
+    /// Returns the groups supported by both endpoints, in order of preference.
+    ///
+    /// This is only meaningful on the server side once the ClientHello has been received.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[corresponds(SSL_get_shared_group)]
+    #[cfg(ossl111)]
+    pub fn shared_groups(&self) -> Vec<Nid> {
+        unsafe {
+            let count = ffi::SSL_get_shared_group(self.as_ptr(), -1);
+            (0..count)
+                .map(|n| Nid::from_raw(ffi::SSL_get_shared_group(self.as_ptr(), n) as c_int))
+                .collect()
+        }
+    }
+
+    /// Returns the group used for the key exchange of the connection, if any.
+    ///
+    /// Requires OpenSSL 3.0.0 or newer.
+    #[corresponds(SSL_get_negotiated_group)]
+    #[cfg(ossl300)]
+    pub fn negotiated_group(&self) -> Option<Nid> {
+        let nid = unsafe { ffi::SSL_get_negotiated_group(self.as_ptr()) };
+        if nid > 0 {
+            Some(Nid::from_raw(nid as c_int))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the ephemeral public key sent by the peer for the key exchange, if any.
+    ///
+    /// This can be used to check the group and size of the key actually used, for example to
+    /// reject connections using small Diffie-Hellman parameters. Before OpenSSL 1.1.1, only the
+    /// server's key is available, so this always returns `None` on the server side.
+    ///
+    /// Requires OpenSSL 1.0.2 or newer.
+    #[corresponds(SSL_get_peer_tmp_key)]
+    #[cfg(ossl102)]
+    pub fn peer_tmp_key(&self) -> Option<PKey<Public>> {
+        unsafe {
+            let mut key = ptr::null_mut();
+            cfg_if! {
+                if #[cfg(ossl111)] {
+                    let r = ffi::SSL_get_peer_tmp_key(self.as_ptr(), &mut key);
+                } else {
+                    let r = ffi::SSL_get_server_tmp_key(self.as_ptr(), &mut key);
+                }
+            }
+            if r > 0 {
+                PKey::from_ptr_opt(key)
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Returns the ephemeral key generated locally for the key exchange, if any.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[corresponds(SSL_get_tmp_key)]
+    #[cfg(ossl111)]
+    pub fn tmp_key(&self) -> Option<PKey<Private>> {
+        unsafe {
+            let mut key = ptr::null_mut();
+            if ffi::SSL_get_tmp_key(self.as_ptr(), &mut key) > 0 {
+                PKey::from_ptr_opt(key)
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Returns the digest used by the peer to sign its handshake messages, if known.
+    ///
+    /// Requires OpenSSL 1.0.2 or newer.
+    #[corresponds(SSL_get_peer_signature_nid)]
+    #[cfg(ossl102)]
+    pub fn peer_signature_nid(&self) -> Option<Nid> {
+        unsafe {
+            let mut nid = 0;
+            if ffi::SSL_get_peer_signature_nid(self.as_ptr(), &mut nid) > 0 {
+                Some(Nid::from_raw(nid))
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Returns the signature algorithm used by the peer to sign its handshake messages, if known.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[corresponds(SSL_get_peer_signature_type_nid)]
+    #[cfg(ossl111)]
+    pub fn peer_signature_type_nid(&self) -> Option<Nid> {
+        unsafe {
+            let mut nid = 0;
+            if ffi::SSL_get_peer_signature_type_nid(self.as_ptr(), &mut nid) > 0 {
+                Some(Nid::from_raw(nid))
+            } else {
+                None
+            }
+        }
+    }
+
     /// Returns a short string describing the state of the session.
     #[corresponds(SSL_state_string)]
     pub fn state_string(&self) -> &'static str {
@@ -2478,6 +4095,19 @@ impl SslRef {
         }
     }
 
+    /// Returns the list of CA names associated with the connection.
+    ///
+    /// On the client side, these are the CA names sent by the server in its certificate request,
+    /// which can be used to select an appropriate client certificate. On the server side, this is
+    /// the list which will be sent to the client.
+    #[corresponds(SSL_get_client_CA_list)]
+    pub fn client_ca_list(&self) -> Option<&StackRef<X509Name>> {
+        unsafe {
+            let ptr = ffi::SSL_get_client_CA_list(self.as_ptr());
+            StackRef::from_const_ptr_opt(ptr)
+        }
+    }
+
     /// Returns the verified certificate chain of the peer, including the leaf certificate.
     ///
     /// If verification was not successful (i.e. [`verify_result`] does not return
@@ -2496,11 +4126,440 @@ impl SslRef {
         }
     }
 
-    /// Like [`SslContext::certificate`].
-    #[corresponds(SSL_get_certificate)]
-    pub fn certificate(&self) -> Option<&X509Ref> {
-        unsafe {
-            let ptr = ffi::SSL_get_certificate(self.as_ptr());
+    /// Like [`SslContextBuilder::enable_ct`].
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    ///
+    /// [`SslContextBuilder::enable_ct`]: struct.SslContextBuilder.html#method.enable_ct
+    #[corresponds(SSL_enable_ct)]
+    #[cfg(ossl110)]
+    pub fn enable_ct(&mut self, mode: CtValidationMode) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::SSL_enable_ct(self.as_ptr(), mode.as_raw())).map(|_| ()) }
+    }
+
+    /// Determines if Certificate Transparency validation is enabled for this connection.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_ct_is_enabled)]
+    #[cfg(ossl110)]
+    pub fn ct_is_enabled(&self) -> bool {
+        unsafe { ffi::SSL_ct_is_enabled(self.as_ptr()) == 1 }
+    }
+
+    /// Returns the signed certificate timestamps presented by the peer.
+    ///
+    /// This includes SCTs from the TLS extension, the OCSP response, and the peer's certificate.
+    /// Certificate Transparency validation must be enabled for SCTs to be collected.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_get0_peer_scts)]
+    #[cfg(ossl110)]
+    pub fn peer_scts(&self) -> Option<&StackRef<Sct>> {
+        unsafe {
+            let ptr = ffi::SSL_get0_peer_scts(self.as_ptr());
+            StackRef::from_const_ptr_opt(ptr)
+        }
+    }
+
+    /// Enables DANE verification for this connection.
+    ///
+    /// `base_domain` is the TLSA base domain, which is also used for SNI and as a reference
+    /// identifier for peer name checks. DANE must also be enabled on the context with
+    /// [`SslContextBuilder::dane_enable`], and TLSA records added with [`dane_tlsa_add`].
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    ///
+    /// [`SslContextBuilder::dane_enable`]: struct.SslContextBuilder.html#method.dane_enable
+    /// [`dane_tlsa_add`]: #method.dane_tlsa_add
+    #[corresponds(SSL_dane_enable)]
+    #[cfg(ossl110)]
+    pub fn dane_enable(&mut self, base_domain: &str) -> Result<(), ErrorStack> {
+        let base_domain = CString::new(base_domain).unwrap();
+        unsafe { cvt(ffi::SSL_dane_enable(self.as_ptr(), base_domain.as_ptr())).map(|_| ()) }
+    }
+
+    /// Adds a TLSA record to match against the peer's certificate chain.
+    ///
+    /// Returns `false` if the record is well formed but unusable, in which case it is ignored.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_dane_tlsa_add)]
+    #[cfg(ossl110)]
+    pub fn dane_tlsa_add(
+        &mut self,
+        usage: u8,
+        selector: u8,
+        mtype: u8,
+        data: &[u8],
+    ) -> Result<bool, ErrorStack> {
+        unsafe {
+            let r = ffi::SSL_dane_tlsa_add(
+                self.as_ptr(),
+                usage,
+                selector,
+                mtype,
+                data.as_ptr(),
+                data.len(),
+            );
+            if r < 0 {
+                Err(ErrorStack::get())
+            } else {
+                Ok(r > 0)
+            }
+        }
+    }
+
+    /// Sets DANE verification flags, returning the previous flags.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_dane_set_flags)]
+    #[cfg(ossl110)]
+    pub fn set_dane_flags(&mut self, flags: DaneFlags) -> DaneFlags {
+        let bits = unsafe { ffi::SSL_dane_set_flags(self.as_ptr(), flags.bits()) };
+        DaneFlags::from_bits_truncate(bits)
+    }
+
+    /// Clears DANE verification flags, returning the previous flags.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_dane_clear_flags)]
+    #[cfg(ossl110)]
+    pub fn clear_dane_flags(&mut self, flags: DaneFlags) -> DaneFlags {
+        let bits = unsafe { ffi::SSL_dane_clear_flags(self.as_ptr(), flags.bits()) };
+        DaneFlags::from_bits_truncate(bits)
+    }
+
+    /// Returns the TLSA record which matched the peer's certificate chain, if DANE verification
+    /// succeeded.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_get0_dane_tlsa)]
+    #[cfg(ossl110)]
+    pub fn dane_tlsa(&self) -> Option<DaneTlsa<'_>> {
+        unsafe {
+            let mut usage = 0;
+            let mut selector = 0;
+            let mut mtype = 0;
+            let mut data = ptr::null();
+            let mut len = 0;
+            let depth = ffi::SSL_get0_dane_tlsa(
+                self.as_ptr(),
+                &mut usage,
+                &mut selector,
+                &mut mtype,
+                &mut data,
+                &mut len,
+            );
+            if depth < 0 {
+                return None;
+            }
+
+            Some(DaneTlsa {
+                depth: depth as u32,
+                usage,
+                selector,
+                mtype,
+                data: slice::from_raw_parts(data, len),
+            })
+        }
+    }
+
+    /// Returns the certificate in the peer's chain which matched a TLSA record, if DANE
+    /// verification succeeded.
+    ///
+    /// This is `None` if the record matched a bare public key rather than a certificate.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_get0_dane_authority)]
+    #[cfg(ossl110)]
+    pub fn dane_authority(&self) -> Option<&X509Ref> {
+        unsafe {
+            let mut cert = ptr::null_mut();
+            if ffi::SSL_get0_dane_authority(self.as_ptr(), &mut cert, ptr::null_mut()) < 0 {
+                return None;
+            }
+            X509Ref::from_const_ptr_opt(cert)
+        }
+    }
+
+    /// Like [`SslContextBuilder::set_client_cert_types`].
+    ///
+    /// Requires OpenSSL 3.2.0 or newer.
+    ///
+    /// [`SslContextBuilder::set_client_cert_types`]: struct.SslContextBuilder.html#method.set_client_cert_types
+    #[corresponds(SSL_set1_client_cert_type)]
+    #[cfg(ossl320)]
+    pub fn set_client_cert_types(&mut self, types: &[CertificateType]) -> Result<(), ErrorStack> {
+        let types = types.iter().map(|t| t.0).collect::<Vec<_>>();
+        unsafe {
+            cvt(ffi::SSL_set1_client_cert_type(
+                self.as_ptr(),
+                types.as_ptr(),
+                types.len(),
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Like [`SslContextBuilder::set_server_cert_types`].
+    ///
+    /// Requires OpenSSL 3.2.0 or newer.
+    ///
+    /// [`SslContextBuilder::set_server_cert_types`]: struct.SslContextBuilder.html#method.set_server_cert_types
+    #[corresponds(SSL_set1_server_cert_type)]
+    #[cfg(ossl320)]
+    pub fn set_server_cert_types(&mut self, types: &[CertificateType]) -> Result<(), ErrorStack> {
+        let types = types.iter().map(|t| t.0).collect::<Vec<_>>();
+        unsafe {
+            cvt(ffi::SSL_set1_server_cert_type(
+                self.as_ptr(),
+                types.as_ptr(),
+                types.len(),
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Returns the type of credential negotiated for the client.
+    ///
+    /// Requires OpenSSL 3.2.0 or newer.
+    #[corresponds(SSL_get_negotiated_client_cert_type)]
+    #[cfg(ossl320)]
+    pub fn negotiated_client_cert_type(&self) -> CertificateType {
+        unsafe { CertificateType(ffi::SSL_get_negotiated_client_cert_type(self.as_ptr()) as u8) }
+    }
+
+    /// Returns the type of credential negotiated for the server.
+    ///
+    /// Requires OpenSSL 3.2.0 or newer.
+    #[corresponds(SSL_get_negotiated_server_cert_type)]
+    #[cfg(ossl320)]
+    pub fn negotiated_server_cert_type(&self) -> CertificateType {
+        unsafe { CertificateType(ffi::SSL_get_negotiated_server_cert_type(self.as_ptr()) as u8) }
+    }
+
+    /// Adds a raw public key which the peer is expected to authenticate with.
+    ///
+    /// The peer's raw public key is accepted if it matches any of the keys added. DANE must be
+    /// enabled on the connection with [`dane_enable`], and certificate verification enabled
+    /// with [`set_verify`].
+    ///
+    /// Requires OpenSSL 3.2.0 or newer.
+    ///
+    /// [`dane_enable`]: #method.dane_enable
+    /// [`set_verify`]: #method.set_verify
+    #[corresponds(SSL_add_expected_rpk)]
+    #[cfg(ossl320)]
+    pub fn add_expected_rpk<T>(&mut self, key: &PKeyRef<T>) -> Result<(), ErrorStack>
+    where
+        T: HasPublic,
+    {
+        unsafe { cvt(ffi::SSL_add_expected_rpk(self.as_ptr(), key.as_ptr())).map(|_| ()) }
+    }
+
+    /// Returns the raw public key the peer authenticated with, if any.
+    ///
+    /// Requires OpenSSL 3.2.0 or newer.
+    #[corresponds(SSL_get0_peer_rpk)]
+    #[cfg(ossl320)]
+    pub fn peer_rpk(&self) -> Option<&PKeyRef<Public>> {
+        unsafe {
+            let ptr = ffi::SSL_get0_peer_rpk(self.as_ptr());
+            PKeyRef::from_const_ptr_opt(ptr)
+        }
+    }
+
+    /// Configures this connection to run its handshake on behalf of a QUIC implementation.
+    ///
+    /// Handshake data is exchanged through `callbacks` and [`provide_quic_data`] rather than
+    /// TLS records. Only TLS 1.3 can be negotiated, and the QUIC transport parameters must be
+    /// set with [`set_quic_transport_params`] before the handshake starts.
+    ///
+    /// The callbacks can only be set once, and an error is returned by later calls.
+    ///
+    /// Requires OpenSSL 3.5.0 or newer.
+    ///
+    /// [`provide_quic_data`]: #method.provide_quic_data
+    /// [`set_quic_transport_params`]: #method.set_quic_transport_params
+    #[corresponds(SSL_set_quic_tls_cbs)]
+    #[cfg(ossl350)]
+    pub fn set_quic_tls<C>(&mut self, callbacks: C) -> Result<(), ErrorStack>
+    where
+        C: QuicTlsCallbacks,
+    {
+        // OpenSSL keeps pointers into the state, so it must not be replaced.
+        if self
+            .ex_data(Ssl::cached_ex_index::<QuicTlsState>())
+            .is_some()
+        {
+            return Err(ErrorStack::from_reason(
+                ffi::ERR_LIB_SSL,
+                ffi::SSL_R_BAD_VALUE,
+            ));
+        }
+
+        let state = QuicTlsState {
+            callbacks: Box::new(callbacks),
+            read_level: QuicProtectionLevel::NONE,
+            records: Default::default(),
+            transport_params: vec![],
+        };
+        self.set_ex_data(Ssl::cached_ex_index(), state);
+        let arg = self
+            .ex_data_mut(Ssl::cached_ex_index::<QuicTlsState>())
+            .unwrap() as *mut QuicTlsState;
+
+        unsafe {
+            cvt(ffi::SSL_set_quic_tls_cbs(
+                self.as_ptr(),
+                QUIC_TLS_DISPATCH.as_ptr(),
+                arg as *mut c_void,
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Passes handshake data received from the peer in CRYPTO frames at the given encryption level
+    /// to the connection.
+    ///
+    /// The data is processed the next time the handshake is driven, once the read secret for
+    /// `level` has been installed. An error is returned if `level` is not a known encryption
+    /// level.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`set_quic_tls`] has not been called.
+    ///
+    /// Requires OpenSSL 3.5.0 or newer.
+    ///
+    /// [`set_quic_tls`]: #method.set_quic_tls
+    #[cfg(ossl350)]
+    pub fn provide_quic_data(
+        &mut self,
+        level: QuicProtectionLevel,
+        data: &[u8],
+    ) -> Result<(), ErrorStack> {
+        let state = self
+            .ex_data_mut(Ssl::cached_ex_index::<QuicTlsState>())
+            .expect("QUIC TLS callbacks not configured");
+        let records = match state.records.get_mut(level.0 as usize) {
+            Some(records) => records,
+            None => {
+                return Err(ErrorStack::from_reason(
+                    ffi::ERR_LIB_SSL,
+                    ffi::SSL_R_BAD_VALUE,
+                ))
+            }
+        };
+
+        if !data.is_empty() {
+            records.push_back(data.to_vec());
+        }
+        Ok(())
+    }
+
+    /// Sets the QUIC transport parameters sent to the peer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if [`set_quic_tls`] has not been called.
+    ///
+    /// Requires OpenSSL 3.5.0 or newer.
+    ///
+    /// [`set_quic_tls`]: #method.set_quic_tls
+    #[corresponds(SSL_set_quic_tls_transport_params)]
+    #[cfg(ossl350)]
+    pub fn set_quic_transport_params(&mut self, params: &[u8]) -> Result<(), ErrorStack> {
+        let ssl = self.as_ptr();
+        let state = self
+            .ex_data_mut(Ssl::cached_ex_index::<QuicTlsState>())
+            .expect("QUIC TLS callbacks not configured");
+        // OpenSSL does not copy the parameters, so they are kept alive alongside the callbacks.
+        state.transport_params = params.to_vec();
+
+        unsafe {
+            cvt(ffi::SSL_set_quic_tls_transport_params(
+                ssl,
+                state.transport_params.as_ptr(),
+                state.transport_params.len(),
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Enables or disables 0-RTT data for a QUIC handshake.
+    ///
+    /// Requires OpenSSL 3.5.0 or newer.
+    #[corresponds(SSL_set_quic_tls_early_data_enabled)]
+    #[cfg(ossl350)]
+    pub fn set_quic_early_data_enabled(&mut self, enabled: bool) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::SSL_set_quic_tls_early_data_enabled(
+                self.as_ptr(),
+                enabled as c_int,
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Returns a context for applying OpenSSL configuration commands to this connection.
+    ///
+    /// Requires OpenSSL 1.0.2 or newer.
+    #[corresponds(SSL_CONF_CTX_set_ssl)]
+    #[cfg(ossl102)]
+    pub fn conf(&mut self, flags: SslConfFlags) -> Result<SslConf<'_>, ErrorStack> {
+        SslConf::for_ssl(self, flags)
+    }
+
+    /// Suspends the handshake so that certificate verification can be completed asynchronously.
+    ///
+    /// This should be called from a certificate verification callback, which should then return
+    /// `true`. The handshake returns with an `ErrorCode::WANT_RETRY_VERIFY` error, and once the
+    /// application has finished its verification work it should be resumed, at which point the
+    /// verification callback is invoked again.
+    ///
+    /// This is only supported on the client side of a connection.
+    ///
+    /// Requires OpenSSL 3.0.0 or newer.
+    #[corresponds(SSL_set_retry_verify)]
+    #[cfg(ossl300)]
+    pub fn set_retry_verify(&mut self) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::SSL_set_retry_verify(self.as_ptr())).map(|_| ()) }
+    }
+
+    /// Determines if the handshake is suspended waiting for asynchronous certificate verification.
+    ///
+    /// Requires OpenSSL 3.0.0 or newer.
+    #[corresponds(SSL_want_retry_verify)]
+    #[cfg(ossl300)]
+    pub fn want_retry_verify(&self) -> bool {
+        unsafe { ffi::SSL_want_retry_verify(self.as_ptr()) != 0 }
+    }
+
+    /// Like [`SslStream::get_shutdown`].
+    ///
+    /// [`SslStream::get_shutdown`]: struct.SslStream.html#method.get_shutdown
+    #[corresponds(SSL_get_shutdown)]
+    pub fn get_shutdown(&self) -> ShutdownState {
+        unsafe {
+            let bits = ffi::SSL_get_shutdown(self.as_ptr());
+            ShutdownState { bits }
+        }
+    }
+
+    /// Like [`SslStream::set_shutdown`].
+    ///
+    /// [`SslStream::set_shutdown`]: struct.SslStream.html#method.set_shutdown
+    #[corresponds(SSL_set_shutdown)]
+    pub fn set_shutdown(&mut self, state: ShutdownState) {
+        unsafe { ffi::SSL_set_shutdown(self.as_ptr(), state.bits()) }
+    }
+
+    /// Like [`SslContext::certificate`].
+    #[corresponds(SSL_get_certificate)]
+    pub fn certificate(&self) -> Option<&X509Ref> {
+        unsafe {
+            let ptr = ffi::SSL_get_certificate(self.as_ptr());
             X509Ref::from_const_ptr_opt(ptr)
         }
     }
@@ -2518,6 +4577,88 @@ impl SslRef {
         }
     }
 
+    /// Like [`SslContextBuilder::set_certificate`].
+    ///
+    /// Use `add_chain_cert` to add the remainder of the certificate chain.
+    #[corresponds(SSL_use_certificate)]
+    pub fn set_certificate(&mut self, cert: &X509Ref) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::SSL_use_certificate(self.as_ptr(), cert.as_ptr())).map(|_| ()) }
+    }
+
+    /// Appends a certificate to the certificate chain of this `Ssl`.
+    ///
+    /// Unlike [`SslContextBuilder::add_extra_chain_cert`], this only affects this connection.
+    ///
+    /// Requires OpenSSL 1.0.2 or newer.
+    #[corresponds(SSL_add0_chain_cert)]
+    #[cfg(ossl102)]
+    pub fn add_chain_cert(&mut self, cert: X509) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::SSL_add0_chain_cert(self.as_ptr(), cert.as_ptr()) as c_int)?;
+            mem::forget(cert);
+            Ok(())
+        }
+    }
+
+    /// Like [`SslContextBuilder::set_private_key`].
+    #[corresponds(SSL_use_PrivateKey)]
+    pub fn set_private_key<T>(&mut self, key: &PKeyRef<T>) -> Result<(), ErrorStack>
+    where
+        T: HasPrivate,
+    {
+        unsafe { cvt(ffi::SSL_use_PrivateKey(self.as_ptr(), key.as_ptr())).map(|_| ()) }
+    }
+
+    /// Sets a delegated credential to present to clients which support them, along with its
+    /// private key.
+    ///
+    /// `credential` is the serialized `DelegatedCredential` structure defined in [RFC 9345]. It
+    /// must be signed by the key of the certificate configured for the connection, and allows the
+    /// server to authenticate with a short-lived key instead of the certificate's key. Clients
+    /// which do not support delegated credentials are authenticated with the certificate as usual.
+    ///
+    /// This corresponds to [`SSL_set1_delegated_credential`].
+    ///
+    /// Requires BoringSSL. OpenSSL does not support delegated credentials.
+    ///
+    /// [RFC 9345]: https://tools.ietf.org/html/rfc9345
+    /// [`SSL_set1_delegated_credential`]: https://commondatastorage.googleapis.com/chromium-boringssl-docs/ssl.h.html#SSL_set1_delegated_credential
+    #[cfg(boringssl)]
+    pub fn set_delegated_credential<T>(
+        &mut self,
+        credential: &[u8],
+        key: &PKeyRef<T>,
+    ) -> Result<(), ErrorStack>
+    where
+        T: HasPrivate,
+    {
+        unsafe {
+            let buf = cvt_p(ffi::CRYPTO_BUFFER_new(
+                credential.as_ptr(),
+                credential.len(),
+                ptr::null_mut(),
+            ))?;
+            let r =
+                ffi::SSL_set1_delegated_credential(self.as_ptr(), buf, key.as_ptr(), ptr::null());
+            ffi::CRYPTO_BUFFER_free(buf);
+            cvt(r).map(|_| ())
+        }
+    }
+
+    /// Determines if the delegated credential set with [`set_delegated_credential`] was used to
+    /// authenticate the connection.
+    ///
+    /// This corresponds to [`SSL_delegated_credential_used`].
+    ///
+    /// Requires BoringSSL.
+    ///
+    /// [`set_delegated_credential`]: #method.set_delegated_credential
+    /// [`SSL_delegated_credential_used`]: https://commondatastorage.googleapis.com/chromium-boringssl-docs/ssl.h.html#SSL_delegated_credential_used
+    #[cfg(boringssl)]
+    pub fn delegated_credential_used(&self) -> bool {
+        unsafe { ffi::SSL_delegated_credential_used(self.as_ptr()) != 0 }
+    }
+
     #[deprecated(since = "0.10.5", note = "renamed to `version_str`")]
     pub fn version(&self) -> &str {
         self.version_str()
@@ -2619,6 +4760,27 @@ impl SslRef {
         }
     }
 
+    /// Derives the SRTP master keys and salts for the SRTP profile selected by the handshake.
+    ///
+    /// The keying material is exported with the `EXTRACTOR-dtls_srtp` label as described in
+    /// RFC 5764. Returns `None` if no SRTP profile was negotiated, or if the negotiated profile
+    /// does not define master key and salt lengths.
+    #[corresponds(SSL_export_keying_material)]
+    pub fn srtp_keying_material(&self) -> Result<Option<SrtpKeyingMaterial>, ErrorStack> {
+        let id = match self.selected_srtp_profile() {
+            Some(profile) => profile.id(),
+            None => return Ok(None),
+        };
+        let (key_len, salt_len) = match (id.master_key_len(), id.master_salt_len()) {
+            (Some(key_len), Some(salt_len)) => (key_len, salt_len),
+            _ => return Ok(None),
+        };
+
+        let mut material = vec![0; 2 * (key_len + salt_len)];
+        self.export_keying_material(&mut material, "EXTRACTOR-dtls_srtp", None)?;
+        Ok(Some(SrtpKeyingMaterial::new(material, key_len, salt_len)))
+    }
+
     /// Returns the number of bytes remaining in the currently processed TLS record.
     ///
     /// If this is greater than 0, the next call to `read` will not call down to the underlying
@@ -2692,6 +4854,33 @@ impl SslRef {
         unsafe { X509VerifyParamRef::from_ptr_mut(ffi::SSL_get0_param(self.as_ptr())) }
     }
 
+    /// Sets the DNS hostname the peer's certificate is expected to match, replacing any previously
+    /// configured names.
+    ///
+    /// Unlike [`set_hostname`], which only sets the SNI extension, this enables hostname
+    /// verification. Certificate verification must also be enabled with [`set_verify`].
+    ///
+    /// Requires OpenSSL 1.0.2 or LibreSSL 2.6.1 or newer.
+    ///
+    /// [`set_hostname`]: #method.set_hostname
+    /// [`set_verify`]: #method.set_verify
+    #[corresponds(SSL_set1_host)]
+    #[cfg(any(ossl102, libressl261))]
+    pub fn set_host(&mut self, host: &str) -> Result<(), ErrorStack> {
+        self.param_mut().set_host(host)
+    }
+
+    /// Adds a DNS hostname the peer's certificate may match.
+    ///
+    /// This allows a peer reachable under several names to present a certificate for any of them.
+    ///
+    /// Requires OpenSSL 1.0.2 or newer.
+    #[corresponds(SSL_add1_host)]
+    #[cfg(ossl102)]
+    pub fn add_host(&mut self, host: &str) -> Result<(), ErrorStack> {
+        self.param_mut().add_host(host)
+    }
+
     /// Returns the certificate verification result.
     #[corresponds(SSL_get_verify_result)]
     pub fn verify_result(&self) -> X509VerifyResult {
@@ -2790,25 +4979,115 @@ impl SslRef {
         }
     }
 
-    /// Sets the session to be used.
+    /// Sets the session to be used.
+    ///
+    /// This should be called before the handshake to attempt to reuse a previously established
+    /// session. If the server is not willing to reuse the session, a new one will be transparently
+    /// negotiated.
+    ///
+    /// # Safety
+    ///
+    /// The caller of this method is responsible for ensuring that the session is associated
+    /// with the same `SslContext` as this `Ssl`.
+    #[corresponds(SSL_set_session)]
+    pub unsafe fn set_session(&mut self, session: &SslSessionRef) -> Result<(), ErrorStack> {
+        cvt(ffi::SSL_set_session(self.as_ptr(), session.as_ptr())).map(|_| ())
+    }
+
+    /// Like [`SslContextBuilder::set_session_id_context`].
+    ///
+    /// [`SslContextBuilder::set_session_id_context`]: struct.SslContextBuilder.html#method.set_session_id_context
+    #[corresponds(SSL_set_session_id_context)]
+    pub fn set_session_id_context(&mut self, sid_ctx: &[u8]) -> Result<(), ErrorStack> {
+        unsafe {
+            assert!(sid_ctx.len() <= c_uint::max_value() as usize);
+            cvt(ffi::SSL_set_session_id_context(
+                self.as_ptr(),
+                sid_ctx.as_ptr(),
+                sid_ctx.len() as SizeTy,
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Determines if the session provided to `set_session` was successfully reused.
+    #[corresponds(SSL_session_reused)]
+    pub fn session_reused(&self) -> bool {
+        unsafe { ffi::SSL_session_reused(self.as_ptr()) != 0 }
+    }
+
+    /// Returns a summary of the negotiated parameters of the connection.
+    ///
+    /// This collects the protocol version, cipher, ALPN protocol, session reuse, and peer
+    /// certificate, and is intended to be called once the handshake has completed.
+    pub fn connection_info(&self) -> ConnectionInfo<'_> {
+        ConnectionInfo {
+            version: self.version2(),
+            cipher: self.current_cipher().map(|c| c.name()),
+            #[cfg(any(ossl102, libressl261))]
+            alpn_protocol: self.selected_alpn_protocol(),
+            #[cfg(not(any(ossl102, libressl261)))]
+            alpn_protocol: None,
+            session_reused: self.session_reused(),
+            peer_certificate: self.peer_certificate(),
+        }
+    }
+
+    /// Determines if a renegotiation has been requested but not yet completed.
+    #[corresponds(SSL_renegotiate_pending)]
+    #[cfg(not(boringssl))]
+    pub fn renegotiate_pending(&self) -> bool {
+        unsafe { ffi::SSL_renegotiate_pending(self.as_ptr()) != 0 }
+    }
+
+    /// Determines if the peer supports secure renegotiation as defined in RFC 5746.
+    ///
+    /// This is only meaningful for protocol versions before TLSv1.3 once the handshake has
+    /// completed.
+    #[corresponds(SSL_get_secure_renegotiation_support)]
+    #[cfg(not(boringssl))]
+    pub fn secure_renegotiation_support(&self) -> bool {
+        unsafe { ffi::SSL_get_secure_renegotiation_support(self.as_ptr()) != 0 }
+    }
+
+    /// Determines if the peer attempted a renegotiation which was refused.
     ///
-    /// This should be called before the handshake to attempt to reuse a previously established
-    /// session. If the server is not willing to reuse the session, a new one will be transparently
-    /// negotiated.
+    /// Renegotiation requests are refused with a `no_renegotiation` warning alert when
+    /// `SslOptions::NO_RENEGOTIATION` is set or when the peer does not support secure
+    /// renegotiation. The connection otherwise continues, so servers can use this to detect and
+    /// drop clients which repeatedly request renegotiation.
     ///
-    /// # Safety
+    /// Attempts are only recorded for connections created from an `SslContext` on which
+    /// [`SslContextBuilder::track_peer_renegotiation`] was called.
     ///
-    /// The caller of this method is responsible for ensuring that the session is associated
-    /// with the same `SslContext` as this `Ssl`.
-    #[corresponds(SSL_set_session)]
-    pub unsafe fn set_session(&mut self, session: &SslSessionRef) -> Result<(), ErrorStack> {
-        cvt(ffi::SSL_set_session(self.as_ptr(), session.as_ptr())).map(|_| ())
+    /// [`SslContextBuilder::track_peer_renegotiation`]: struct.SslContextBuilder.html#method.track_peer_renegotiation
+    #[cfg(not(boringssl))]
+    pub fn peer_renegotiation_attempted(&self) -> bool {
+        self.ex_data(Ssl::cached_ex_index::<RenegotiationAttempted>())
+            .is_some()
     }
 
-    /// Determines if the session provided to `set_session` was successfully reused.
-    #[corresponds(SSL_session_reused)]
-    pub fn session_reused(&self) -> bool {
-        unsafe { ffi::SSL_session_reused(self.as_ptr()) != 0 }
+    /// Returns the type of the key update which will be sent with the next write, if any.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[corresponds(SSL_get_key_update_type)]
+    #[cfg(ossl111)]
+    pub fn key_update_pending(&self) -> Option<KeyUpdateType> {
+        unsafe {
+            match ffi::SSL_get_key_update_type(self.as_ptr()) {
+                ffi::SSL_KEY_UPDATE_NONE => None,
+                type_ => Some(KeyUpdateType(type_)),
+            }
+        }
+    }
+
+    /// Returns the number of renegotiations which have been started on this connection.
+    ///
+    /// On the client side, this includes renegotiations requested by the server.
+    #[corresponds(SSL_num_renegotiations)]
+    #[cfg(not(boringssl))]
+    pub fn num_renegotiations(&self) -> u64 {
+        unsafe { ffi::SSL_num_renegotiations(self.as_ptr()) as u64 }
     }
 
     /// Sets the status response a client wishes the server to reply with.
@@ -2819,6 +5098,23 @@ impl SslRef {
         }
     }
 
+    /// Returns the status response type requested by the client, if any.
+    ///
+    /// On the server side this reflects the ClientHello, and is intended to be used from within the
+    /// status callback.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_get_tlsext_status_type)]
+    #[cfg(ossl110)]
+    pub fn status_type(&self) -> Option<StatusType> {
+        unsafe {
+            match ffi::SSL_get_tlsext_status_type(self.as_ptr()) {
+                -1 => None,
+                type_ => Some(StatusType::from_raw(type_ as c_int)),
+            }
+        }
+    }
+
     /// Determines if current session used Extended Master Secret
     ///
     /// Returns `None` if the handshake is still in-progress.
@@ -2850,7 +5146,7 @@ impl SslRef {
     }
 
     /// Sets the OCSP response to be returned to the client.
-    #[corresponds(SSL_set_tlsext_status_oscp_resp)]
+    #[corresponds(SSL_set_tlsext_status_ocsp_resp)]
     #[cfg(not(boringssl))]
     pub fn set_ocsp_status(&mut self, response: &[u8]) -> Result<(), ErrorStack> {
         unsafe {
@@ -2875,9 +5171,15 @@ impl SslRef {
     /// Sets the extra data at the specified index.
     ///
     /// This can be used to provide data to callbacks registered with the context. Use the
-    /// `Ssl::new_ex_index` method to create an `Index`.
+    /// `Ssl::new_ex_index` method to create an `Index`. Any previous value at the index is
+    /// dropped.
     #[corresponds(SSL_set_ex_data)]
     pub fn set_ex_data<T>(&mut self, index: Index<Ssl, T>, data: T) {
+        if let Some(old) = self.ex_data_mut(index) {
+            *old = data;
+            return;
+        }
+
         unsafe {
             let data = Box::new(data);
             ffi::SSL_set_ex_data(
@@ -2936,6 +5238,67 @@ impl SslRef {
         unsafe { ffi::SSL_get_max_early_data(self.as_ptr()) }
     }
 
+    /// Sets the maximum amount of early data that will be read on this connection before failing
+    /// the handshake.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[corresponds(SSL_set_recv_max_early_data)]
+    #[cfg(ossl111)]
+    pub fn set_recv_max_early_data(&mut self, bytes: u32) -> Result<(), ErrorStack> {
+        if unsafe { ffi::SSL_set_recv_max_early_data(self.as_ptr(), bytes) } == 1 {
+            Ok(())
+        } else {
+            Err(ErrorStack::get())
+        }
+    }
+
+    /// Gets the maximum amount of early data that will be read on this connection.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[corresponds(SSL_get_recv_max_early_data)]
+    #[cfg(ossl111)]
+    pub fn recv_max_early_data(&self) -> u32 {
+        unsafe { ffi::SSL_get_recv_max_early_data(self.as_ptr()) }
+    }
+
+    /// Sets the number of TLSv1.3 session tickets that will be sent to the client after a full
+    /// handshake on this connection.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[corresponds(SSL_set_num_tickets)]
+    #[cfg(ossl111)]
+    pub fn set_num_tickets(&mut self, num_tickets: usize) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::SSL_set_num_tickets(self.as_ptr(), num_tickets)).map(|_| ()) }
+    }
+
+    /// Gets the number of TLSv1.3 session tickets that will be sent to the client after a full
+    /// handshake on this connection.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[corresponds(SSL_get_num_tickets)]
+    #[cfg(ossl111)]
+    pub fn num_tickets(&self) -> usize {
+        unsafe { ffi::SSL_get_num_tickets(self.as_ptr()) }
+    }
+
+    /// Sets the security level for this connection, overriding that of the context.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_set_security_level)]
+    #[cfg(ossl110)]
+    pub fn set_security_level(&mut self, level: u32) {
+        unsafe { ffi::SSL_set_security_level(self.as_ptr(), level as c_int) }
+    }
+
+    /// Returns the security level of this connection.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_get_security_level)]
+    #[cfg(ossl110)]
+    pub fn security_level(&self) -> u32 {
+        unsafe { ffi::SSL_get_security_level(self.as_ptr()) as u32 }
+    }
+
     /// Copies the contents of the last Finished message sent to the peer into the provided buffer.
     ///
     /// The total size of the message is returned, so this can be used to determine the size of the
@@ -2957,6 +5320,51 @@ impl SslRef {
         }
     }
 
+    /// Returns the `tls-unique` channel binding for the connection, as defined in [RFC 5929].
+    ///
+    /// This is the first Finished message of the most recent handshake: the client's for a full
+    /// handshake, and the server's for a resumed one. `None` is returned if the handshake has not
+    /// completed, or if TLSv1.3 was negotiated, for which `tls-unique` is not defined.
+    ///
+    /// [RFC 5929]: https://tools.ietf.org/html/rfc5929#section-3
+    pub fn tls_unique_channel_binding(&self) -> Option<Vec<u8>> {
+        #[cfg(any(ossl111, libressl340))]
+        {
+            if self.version2() == Some(SslVersion::TLS1_3) {
+                return None;
+            }
+        }
+
+        // The first Finished message is sent by the client unless the session was resumed.
+        let first = if self.is_server() == self.session_reused() {
+            SslRef::finished
+        } else {
+            SslRef::peer_finished
+        };
+
+        let len = first(self, &mut []);
+        if len == 0 {
+            return None;
+        }
+        let mut buf = vec![0; len];
+        first(self, &mut buf);
+        Some(buf)
+    }
+
+    /// Returns the `tls-exporter` channel binding for the connection, as defined in [RFC 9266].
+    ///
+    /// This is 32 bytes of keying material exported with the label `EXPORTER-Channel-Binding`
+    /// and an empty context. For versions before TLSv1.3, it is only secure if the extended master
+    /// secret extension was negotiated.
+    ///
+    /// [RFC 9266]: https://tools.ietf.org/html/rfc9266#section-2
+    #[corresponds(SSL_export_keying_material)]
+    pub fn tls_exporter_channel_binding(&self) -> Result<Vec<u8>, ErrorStack> {
+        let mut buf = vec![0; 32];
+        self.export_keying_material(&mut buf, "EXPORTER-Channel-Binding", Some(&[]))?;
+        Ok(buf)
+    }
+
     /// Determines if the initial handshake has been completed.
     #[corresponds(SSL_is_init_finished)]
     #[cfg(ossl110)]
@@ -2964,6 +5372,29 @@ impl SslRef {
         unsafe { ffi::SSL_is_init_finished(self.as_ptr()) != 0 }
     }
 
+    /// Determines if a handshake is in progress.
+    ///
+    /// Combined with [`state_string_long`], this can be used to report where a stalled
+    /// non-blocking handshake is waiting.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    ///
+    /// [`state_string_long`]: #method.state_string_long
+    #[corresponds(SSL_in_init)]
+    #[cfg(ossl110)]
+    pub fn in_init(&self) -> bool {
+        unsafe { ffi::SSL_in_init(self.as_ptr()) != 0 }
+    }
+
+    /// Determines if no handshake message has been sent or received yet.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(SSL_in_before)]
+    #[cfg(ossl110)]
+    pub fn in_before(&self) -> bool {
+        unsafe { ffi::SSL_in_before(self.as_ptr()) != 0 }
+    }
+
     /// Determines if the client's hello message is in the SSLv2 format.
     ///
     /// This can only be used inside of the client hello callback. Otherwise, `false` is returned.
@@ -3069,12 +5500,119 @@ impl SslRef {
         }
     }
 
+    /// Returns the types of the extensions present in the client's hello message, in the order
+    /// in which they were sent.
+    ///
+    /// This can only be used inside of the client hello callback. Otherwise, `None` is returned.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[corresponds(SSL_client_hello_get1_extensions_present)]
+    #[cfg(ossl111)]
+    pub fn client_hello_extensions_present(&self) -> Option<Vec<TlsExtType>> {
+        unsafe {
+            let mut ptr = ptr::null_mut();
+            let mut len = 0;
+            if ffi::SSL_client_hello_get1_extensions_present(self.as_ptr(), &mut ptr, &mut len) != 1
+            {
+                return None;
+            }
+            if ptr.is_null() {
+                return Some(vec![]);
+            }
+
+            let types = slice::from_raw_parts(ptr, len)
+                .iter()
+                .map(|&t| TlsExtType(t as u16))
+                .collect();
+            ffi::OPENSSL_free(ptr as *mut c_void);
+            Some(types)
+        }
+    }
+
+    /// Returns the contents of an extension of the client's hello message.
+    ///
+    /// This can only be used inside of the client hello callback. Otherwise, or if the extension
+    /// is not present, `None` is returned.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[corresponds(SSL_client_hello_get0_ext)]
+    #[cfg(ossl111)]
+    pub fn client_hello_ext(&self, ext_type: TlsExtType) -> Option<&[u8]> {
+        unsafe {
+            let mut ptr = ptr::null();
+            let mut len = 0;
+            if ffi::SSL_client_hello_get0_ext(
+                self.as_ptr(),
+                ext_type.as_raw() as c_uint,
+                &mut ptr,
+                &mut len,
+            ) == 1
+            {
+                Some(slice::from_raw_parts(ptr, len))
+            } else {
+                None
+            }
+        }
+    }
+
+    /// Returns the host name requested by the server name indication extension of the client's
+    /// hello message.
+    ///
+    /// Unlike `servername`, this is available from within the client hello callback, before the
+    /// extension has been processed.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[cfg(ossl111)]
+    pub fn client_hello_servername(&self) -> Option<&str> {
+        let mut ext = self.client_hello_ext(TlsExtType::SERVER_NAME)?;
+
+        let mut list = read_u16_prefixed(&mut ext)?;
+        while !list.is_empty() {
+            let (&name_type, rest) = list.split_first()?;
+            list = rest;
+            let name = read_u16_prefixed(&mut list)?;
+            if c_int::from(name_type) == ffi::TLSEXT_NAMETYPE_host_name {
+                return str::from_utf8(name).ok();
+            }
+        }
+
+        None
+    }
+
+    /// Returns the protocols offered by the ALPN extension of the client's hello message.
+    ///
+    /// The protocols are returned in ALPN wire format, which is a sequence of length-prefixed
+    /// strings, as accepted by [`select_next_proto`].
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[cfg(ossl111)]
+    pub fn client_hello_alpn_protocols(&self) -> Option<&[u8]> {
+        let mut ext = self.client_hello_ext(TlsExtType::ALPN)?;
+        read_u16_prefixed(&mut ext)
+    }
+
     /// Sets the MTU used for DTLS connections.
     #[corresponds(SSL_set_mtu)]
     pub fn set_mtu(&mut self, mtu: u32) -> Result<(), ErrorStack> {
         unsafe { cvt(ffi::SSL_set_mtu(self.as_ptr(), mtu as MtuTy) as c_int).map(|_| ()) }
     }
 
+    /// Returns the time remaining until the DTLS retransmission timer expires.
+    ///
+    /// Returns `None` if no timer is running. A zero duration indicates that the timer has
+    /// already expired, and `SslStream::handle_dtls_timeout` should be called.
+    #[corresponds(DTLSv1_get_timeout)]
+    pub fn dtls_timeout(&self) -> Option<Duration> {
+        unsafe {
+            let mut tv = mem::zeroed::<libc::timeval>();
+            if ffi::DTLSv1_get_timeout(self.as_ptr(), &mut tv) > 0 {
+                Some(Duration::new(tv.tv_sec as u64, tv.tv_usec as u32 * 1000))
+            } else {
+                None
+            }
+        }
+    }
+
     /// Returns the PSK identity hint used during connection setup.
     ///
     /// May return `None` if no PSK identity hint was used during the connection setup.
@@ -3129,15 +5667,38 @@ impl<S> MidHandshakeSslStream<S> {
         self.stream.ssl()
     }
 
+    /// Returns a mutable reference to the `Ssl` of the stream.
+    pub fn ssl_mut(&mut self) -> &mut SslRef {
+        &mut self.stream.ssl
+    }
+
     /// Returns the underlying error which interrupted this handshake.
     pub fn error(&self) -> &Error {
         &self.error
     }
 
+    /// Returns the code of the error which interrupted this handshake.
+    ///
+    /// For a `HandshakeError::WouldBlock`, this is either `ErrorCode::WANT_READ` or
+    /// `ErrorCode::WANT_WRITE`, indicating which readiness event to wait for before retrying.
+    pub fn code(&self) -> ErrorCode {
+        self.error.code()
+    }
+
     /// Consumes `self`, returning its error.
     pub fn into_error(self) -> Error {
         self.error
     }
+
+    /// Consumes `self`, returning the stream with its handshake still in progress.
+    ///
+    /// The handshake can be continued with [`SslStream::do_handshake`], which takes the stream by
+    /// reference and can therefore be retried any number of times on transient failures.
+    ///
+    /// [`SslStream::do_handshake`]: struct.SslStream.html#method.do_handshake
+    pub fn into_stream(self) -> SslStream<S> {
+        self.stream
+    }
 }
 
 impl<S> MidHandshakeSslStream<S>
@@ -3330,6 +5891,42 @@ impl<S: Read + Write> SslStream<S> {
         }
     }
 
+    /// Schedules a renegotiation of the connection.
+    ///
+    /// The renegotiation is performed by the next call to `do_handshake`, or as part of subsequent
+    /// reads and writes. Renegotiation is not supported in TLSv1.3.
+    #[corresponds(SSL_renegotiate)]
+    #[cfg(not(boringssl))]
+    pub fn renegotiate(&mut self) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::SSL_renegotiate(self.ssl.as_ptr())).map(|_| ()) }
+    }
+
+    /// Schedules a TLSv1.3 key update.
+    ///
+    /// The KeyUpdate message is sent by the next call to `do_handshake`, or along with the next
+    /// write. This fails if the connection does not use TLSv1.3.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[corresponds(SSL_key_update)]
+    #[cfg(ossl111)]
+    pub fn key_update(&mut self, update_type: KeyUpdateType) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::SSL_key_update(self.ssl.as_ptr(), update_type.as_raw())).map(|_| ()) }
+    }
+
+    /// Requests a certificate from the client after a TLSv1.3 handshake has completed.
+    ///
+    /// The CertificateRequest message is sent by the next call to `do_handshake`, or along with
+    /// the next write. The client's certificate is processed as part of a subsequent read, and is
+    /// verified according to the verification mode of the connection. This fails if the client did
+    /// not advertise support for post-handshake authentication.
+    ///
+    /// Requires OpenSSL 1.1.1 or newer.
+    #[corresponds(SSL_verify_client_post_handshake)]
+    #[cfg(ossl111)]
+    pub fn verify_client_post_handshake(&mut self) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::SSL_verify_client_post_handshake(self.ssl.as_ptr())).map(|_| ()) }
+    }
+
     /// Perform a stateless server-side handshake.
     ///
     /// Requires that cookie generation and verification callbacks were
@@ -3351,6 +5948,48 @@ impl<S: Read + Write> SslStream<S> {
         }
     }
 
+    /// Handles an expired DTLS retransmission timer.
+    ///
+    /// This should be called once the duration returned by `SslRef::dtls_timeout` has elapsed.
+    /// Returns `Ok(true)` if the last flight was retransmitted, and `Ok(false)` if the timer had
+    /// not yet expired.
+    #[corresponds(DTLSv1_handle_timeout)]
+    pub fn handle_dtls_timeout(&mut self) -> Result<bool, Error> {
+        let ret = unsafe { ffi::DTLSv1_handle_timeout(self.ssl.as_ptr()) as c_int };
+        if ret >= 0 {
+            Ok(ret > 0)
+        } else {
+            Err(self.make_error(ret))
+        }
+    }
+
+    /// Listens for a DTLS ClientHello containing a valid cookie.
+    ///
+    /// Requires that cookie generation and verification callbacks were set on the SSL context.
+    /// ClientHellos without a valid cookie are answered with a HelloVerifyRequest. Once a valid
+    /// cookie has been received, the handshake should be continued via `accept`.
+    ///
+    /// On success, the address of the peer is returned if the underlying BIO tracks datagram
+    /// peers. The BIO wrapping the stream of an `SslStream` does not, so `None` is returned in
+    /// that case and the caller is responsible for remembering the peer.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(DTLSv1_listen)]
+    #[cfg(ossl110)]
+    pub fn dtls_listen(&mut self) -> Result<Option<SocketAddr>, Error> {
+        unsafe {
+            let client = cvt_p(ffi::BIO_ADDR_new())?;
+            let ret = ffi::DTLSv1_listen(self.ssl.as_ptr(), client);
+            let result = if ret > 0 {
+                Ok(bio_addr_to_socket_addr(client))
+            } else {
+                Err(self.make_error(ret))
+            };
+            ffi::BIO_ADDR_free(client);
+            result
+        }
+    }
+
     /// Like `read`, but returns an `ssl::Error` rather than an `io::Error`.
     ///
     /// It is particularly useful with a non-blocking socket, where the error value will identify if
@@ -3366,11 +6005,9 @@ impl<S: Read + Write> SslStream<S> {
             return Ok(0);
         }
 
-        let ret = self.ssl.read(buf);
-        if ret > 0 {
-            Ok(ret as usize)
-        } else {
-            Err(self.make_error(ret))
+        match self.ssl.read(buf) {
+            Ok(n) => Ok(n),
+            Err(ret) => Err(self.make_error(ret)),
         }
     }
 
@@ -3385,11 +6022,9 @@ impl<S: Read + Write> SslStream<S> {
             return Ok(0);
         }
 
-        let ret = self.ssl.write(buf);
-        if ret > 0 {
-            Ok(ret as usize)
-        } else {
-            Err(self.make_error(ret))
+        match self.ssl.write(buf) {
+            Ok(n) => Ok(n),
+            Err(ret) => Err(self.make_error(ret)),
         }
     }
 
@@ -3401,11 +6036,33 @@ impl<S: Read + Write> SslStream<S> {
             return Ok(0);
         }
 
-        let ret = self.ssl.peek(buf);
-        if ret > 0 {
-            Ok(ret as usize)
-        } else {
-            Err(self.make_error(ret))
+        match self.ssl.peek(buf) {
+            Ok(n) => Ok(n),
+            Err(ret) => Err(self.make_error(ret)),
+        }
+    }
+
+    /// Reads data from the stream like `read`, but without removing it from the queue.
+    ///
+    /// Subsequent calls to `peek` or `read` return the same data. This can be used to inspect
+    /// the first bytes sent by the peer, for example to determine which protocol it speaks,
+    /// before handing the stream off.
+    #[corresponds(SSL_peek)]
+    pub fn peek(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            match self.ssl_peek(buf) {
+                Ok(n) => return Ok(n),
+                Err(ref e) if e.code() == ErrorCode::ZERO_RETURN => return Ok(0),
+                Err(ref e) if e.code() == ErrorCode::SYSCALL && e.io_error().is_none() => {
+                    return Ok(0);
+                }
+                Err(ref e) if e.code() == ErrorCode::WANT_READ && e.io_error().is_none() => {}
+                Err(e) => {
+                    return Err(e
+                        .into_io_error()
+                        .unwrap_or_else(|e| io::Error::new(io::ErrorKind::Other, e)));
+                }
+            }
         }
     }
 
@@ -3418,6 +6075,13 @@ impl<S: Read + Write> SslStream<S> {
     /// While the connection may be closed after the first step, it is recommended to fully shut the
     /// session down. In particular, it must be fully shut down if the connection is to be used for
     /// further communication in the future.
+    ///
+    /// To wait for the peer's close notify message, call this method again after
+    /// `ShutdownResult::Sent` is returned. On a nonblocking stream this may fail with
+    /// `ErrorCode::WANT_READ`, in which case it should be retried once the underlying stream is
+    /// readable. If the peer has already closed its side of the session, for example because a
+    /// read returned 0 bytes, the first call sends our close notify message and returns
+    /// `ShutdownResult::Received` directly.
     #[corresponds(SSL_shutdown)]
     pub fn shutdown(&mut self) -> Result<ShutdownResult, Error> {
         match unsafe { ffi::SSL_shutdown(self.ssl.as_ptr()) } {
@@ -3429,11 +6093,8 @@ impl<S: Read + Write> SslStream<S> {
 
     /// Returns the session's shutdown state.
     #[corresponds(SSL_get_shutdown)]
-    pub fn get_shutdown(&mut self) -> ShutdownState {
-        unsafe {
-            let bits = ffi::SSL_get_shutdown(self.ssl.as_ptr());
-            ShutdownState { bits }
-        }
+    pub fn get_shutdown(&self) -> ShutdownState {
+        self.ssl.get_shutdown()
     }
 
     /// Sets the session's shutdown state.
@@ -3442,7 +6103,7 @@ impl<S: Read + Write> SslStream<S> {
     /// shutdown was not completed.
     #[corresponds(SSL_set_shutdown)]
     pub fn set_shutdown(&mut self, state: ShutdownState) {
-        unsafe { ffi::SSL_set_shutdown(self.ssl.as_ptr(), state.bits()) }
+        self.ssl.set_shutdown(state)
     }
 }
 
@@ -3451,25 +6112,7 @@ impl<S> SslStream<S> {
         self.check_panic();
 
         let code = self.ssl.get_error(ret);
-
-        let cause = match code {
-            ErrorCode::SSL => Some(InnerError::Ssl(ErrorStack::get())),
-            ErrorCode::SYSCALL => {
-                let errs = ErrorStack::get();
-                if errs.errors().is_empty() {
-                    self.get_bio_error().map(InnerError::Io)
-                } else {
-                    Some(InnerError::Ssl(errs))
-                }
-            }
-            ErrorCode::ZERO_RETURN => None,
-            ErrorCode::WANT_READ | ErrorCode::WANT_WRITE => {
-                self.get_bio_error().map(InnerError::Io)
-            }
-            _ => None,
-        };
-
-        Error { code, cause }
+        Error::from_code(code, || self.get_bio_error())
     }
 
     fn check_panic(&mut self) {
@@ -3544,11 +6187,38 @@ impl<S: Read + Write> Write for SslStream<S> {
         }
     }
 
+    fn write_vectored(&mut self, bufs: &[io::IoSlice<'_>]) -> io::Result<usize> {
+        // TLS has no notion of scatter/gather, so small buffers are coalesced to avoid emitting a
+        // separate record for each of them.
+        let mut bufs = bufs.iter().filter(|b| !b.is_empty());
+        let first = match bufs.next() {
+            Some(first) => first,
+            None => return Ok(0),
+        };
+        if first.len() >= MAX_VECTORED_WRITE {
+            return self.write(first);
+        }
+
+        let mut buf = Vec::with_capacity(MAX_VECTORED_WRITE);
+        buf.extend_from_slice(first);
+        for b in bufs {
+            let len = cmp::min(b.len(), MAX_VECTORED_WRITE - buf.len());
+            buf.extend_from_slice(&b[..len]);
+            if buf.len() == MAX_VECTORED_WRITE {
+                break;
+            }
+        }
+        self.write(&buf)
+    }
+
     fn flush(&mut self) -> io::Result<()> {
         self.get_mut().flush()
     }
 }
 
+// The maximum plaintext size of a TLS record.
+const MAX_VECTORED_WRITE: usize = 16384;
+
 /// A partially constructed `SslStream`, useful for unusual handshakes.
 #[deprecated(
     since = "0.10.32",
@@ -3841,6 +6511,51 @@ cfg_if! {
         };
     }
 }
+#[cfg(ossl111)]
+fn read_u16_prefixed<'a>(buf: &mut &'a [u8]) -> Option<&'a [u8]> {
+    if buf.len() < 2 {
+        return None;
+    }
+    let len = usize::from(u16::from_be_bytes([buf[0], buf[1]]));
+    let rest = &buf[2..];
+    if rest.len() < len {
+        return None;
+    }
+    let (value, rest) = rest.split_at(len);
+    *buf = rest;
+    Some(value)
+}
+
+#[cfg(ossl110)]
+unsafe fn bio_addr_to_socket_addr(addr: *const ffi::BIO_ADDR) -> Option<SocketAddr> {
+    #[cfg(unix)]
+    {
+        if ffi::BIO_ADDR_family(addr) == libc::AF_UNIX {
+            return None;
+        }
+    }
+
+    let mut len = 0;
+    if ffi::BIO_ADDR_rawaddress(addr, ptr::null_mut(), &mut len) == 0 {
+        return None;
+    }
+    let port = u16::from_be(ffi::BIO_ADDR_rawport(addr));
+
+    match len {
+        4 => {
+            let mut buf = [0; 4];
+            ffi::BIO_ADDR_rawaddress(addr, buf.as_mut_ptr() as *mut c_void, &mut len);
+            Some(SocketAddr::from((Ipv4Addr::from(buf), port)))
+        }
+        16 => {
+            let mut buf = [0; 16];
+            ffi::BIO_ADDR_rawaddress(addr, buf.as_mut_ptr() as *mut c_void, &mut len);
+            Some(SocketAddr::from((Ipv6Addr::from(buf), port)))
+        }
+        _ => None,
+    }
+}
+
 cfg_if! {
     if #[cfg(ossl110)] {
         unsafe fn get_new_idx(f: ffi::CRYPTO_EX_free) -> c_int {
@@ -0,0 +1,203 @@
+use foreign_types::ForeignTypeRef;
+use libc::c_int;
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::os::unix::io::AsRawFd;
+
+use crate::error::ErrorStack;
+use crate::ssl::{Error, ErrorCode, ShutdownResult, Ssl, SslRef};
+use crate::{cvt_p, init};
+use openssl_macros::corresponds;
+
+/// A DTLS session running over an SCTP association.
+///
+/// SCTP carries DTLS records as SCTP messages, relying on SCTP-AUTH (RFC 4895) to authenticate
+/// the association and on its ordered, reliable streams for the handshake, as described in
+/// [RFC 6083]. OpenSSL handles this with a dedicated datagram BIO attached directly to the SCTP
+/// socket, so unlike [`SslStream`] the underlying socket is only used for its file descriptor.
+///
+/// The `Ssl` should be created from a context built with [`SslMethod::dtls`]. The socket must be
+/// a connected one-to-one style SCTP socket (`SOCK_STREAM` or `SOCK_SEQPACKET` with
+/// `IPPROTO_SCTP`), and SCTP-AUTH must be enabled on the host.
+///
+/// Requires OpenSSL 1.0.1 or newer built with SCTP support (`enable-sctp`).
+///
+/// [RFC 6083]: https://tools.ietf.org/html/rfc6083
+/// [`SslStream`]: struct.SslStream.html
+/// [`SslMethod::dtls`]: struct.SslMethod.html#method.dtls
+pub struct DtlsSctpStream<S> {
+    // Declared before the socket so the BIO is freed before the descriptor is closed.
+    ssl: Ssl,
+    stream: S,
+}
+
+impl<S> fmt::Debug for DtlsSctpStream<S>
+where
+    S: fmt::Debug,
+{
+    fn fmt(&self, fmt: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt.debug_struct("DtlsSctpStream")
+            .field("stream", &self.stream)
+            .field("ssl", &self.ssl)
+            .finish()
+    }
+}
+
+impl<S: AsRawFd> DtlsSctpStream<S> {
+    /// Creates a new `DtlsSctpStream`, attaching an SCTP datagram BIO for `stream` to the `Ssl`.
+    ///
+    /// The descriptor is not closed by OpenSSL; it is closed when `stream` is dropped.
+    #[corresponds(BIO_new_dgram_sctp)]
+    pub fn new(ssl: Ssl, stream: S) -> Result<DtlsSctpStream<S>, ErrorStack> {
+        init();
+
+        unsafe {
+            let bio = cvt_p(ffi::BIO_new_dgram_sctp(
+                stream.as_raw_fd(),
+                ffi::BIO_NOCLOSE,
+            ))?;
+            ffi::SSL_set_bio(ssl.as_ptr(), bio, bio);
+        }
+
+        Ok(DtlsSctpStream { ssl, stream })
+    }
+
+    /// Returns a shared reference to the underlying socket.
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    /// Returns a shared reference to the `Ssl` object associated with this stream.
+    pub fn ssl(&self) -> &SslRef {
+        &self.ssl
+    }
+
+    /// Returns a mutable reference to the `Ssl` object associated with this stream.
+    pub fn ssl_mut(&mut self) -> &mut SslRef {
+        &mut self.ssl
+    }
+
+    /// Initiates a client-side handshake.
+    #[corresponds(SSL_connect)]
+    pub fn connect(&mut self) -> Result<(), Error> {
+        let ret = unsafe { ffi::SSL_connect(self.ssl.as_ptr()) };
+        if ret > 0 {
+            Ok(())
+        } else {
+            Err(self.make_error(ret))
+        }
+    }
+
+    /// Initiates a server-side handshake.
+    #[corresponds(SSL_accept)]
+    pub fn accept(&mut self) -> Result<(), Error> {
+        let ret = unsafe { ffi::SSL_accept(self.ssl.as_ptr()) };
+        if ret > 0 {
+            Ok(())
+        } else {
+            Err(self.make_error(ret))
+        }
+    }
+
+    /// Initiates or continues the handshake.
+    ///
+    /// `set_connect_state` or `set_accept_state` must be called on the `Ssl` first.
+    #[corresponds(SSL_do_handshake)]
+    pub fn do_handshake(&mut self) -> Result<(), Error> {
+        let ret = unsafe { ffi::SSL_do_handshake(self.ssl.as_ptr()) };
+        if ret > 0 {
+            Ok(())
+        } else {
+            Err(self.make_error(ret))
+        }
+    }
+
+    /// Reads decrypted application data.
+    #[corresponds(SSL_read)]
+    pub fn ssl_read(&mut self, buf: &mut [u8]) -> Result<usize, Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        match self.ssl.read(buf) {
+            Ok(n) => Ok(n),
+            Err(ret) => Err(self.make_error(ret)),
+        }
+    }
+
+    /// Encrypts and sends application data.
+    #[corresponds(SSL_write)]
+    pub fn ssl_write(&mut self, buf: &[u8]) -> Result<usize, Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        match self.ssl.write(buf) {
+            Ok(n) => Ok(n),
+            Err(ret) => Err(self.make_error(ret)),
+        }
+    }
+
+    /// Shuts down the session.
+    ///
+    /// OpenSSL waits for all outstanding SCTP messages to be acknowledged before sending the
+    /// close notify alert, so that no application data is lost when the association's keys are
+    /// discarded. See [`SslStream::shutdown`] for details.
+    ///
+    /// [`SslStream::shutdown`]: struct.SslStream.html#method.shutdown
+    #[corresponds(SSL_shutdown)]
+    pub fn shutdown(&mut self) -> Result<ShutdownResult, Error> {
+        match unsafe { ffi::SSL_shutdown(self.ssl.as_ptr()) } {
+            0 => Ok(ShutdownResult::Sent),
+            1 => Ok(ShutdownResult::Received),
+            n => Err(self.make_error(n)),
+        }
+    }
+
+    fn make_error(&mut self, ret: c_int) -> Error {
+        // Capture errno before anything else can overwrite it.
+        let os_error = io::Error::last_os_error();
+        let code = self.ssl.get_error(ret);
+        Error::from_code(code, || if ret < 0 { Some(os_error) } else { None })
+    }
+}
+
+impl<S: AsRawFd> Read for DtlsSctpStream<S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        loop {
+            match self.ssl_read(buf) {
+                Ok(n) => return Ok(n),
+                Err(ref e) if e.code() == ErrorCode::ZERO_RETURN => return Ok(0),
+                Err(ref e) if e.code() == ErrorCode::SYSCALL && e.io_error().is_none() => {
+                    return Ok(0);
+                }
+                Err(ref e) if e.code() == ErrorCode::WANT_READ && e.io_error().is_none() => {}
+                Err(e) => {
+                    return Err(e
+                        .into_io_error()
+                        .unwrap_or_else(|e| io::Error::new(io::ErrorKind::Other, e)));
+                }
+            }
+        }
+    }
+}
+
+impl<S: AsRawFd> Write for DtlsSctpStream<S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        loop {
+            match self.ssl_write(buf) {
+                Ok(n) => return Ok(n),
+                Err(ref e) if e.code() == ErrorCode::WANT_READ && e.io_error().is_none() => {}
+                Err(e) => {
+                    return Err(e
+                        .into_io_error()
+                        .unwrap_or_else(|e| io::Error::new(io::ErrorKind::Other, e)));
+                }
+            }
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
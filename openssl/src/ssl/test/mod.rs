@@ -16,27 +16,32 @@ use std::time::Duration;
 
 use crate::dh::Dh;
 use crate::error::ErrorStack;
-use crate::hash::MessageDigest;
+use crate::hash::{hash, MessageDigest};
 #[cfg(not(boringssl))]
 use crate::ocsp::{OcspResponse, OcspResponseStatus};
+#[cfg(ossl111)]
+use crate::pkey::Id;
 use crate::pkey::PKey;
 use crate::srtp::SrtpProfileId;
 use crate::ssl;
 use crate::ssl::test::server::Server;
-#[cfg(any(ossl110, ossl111, libressl261))]
+#[cfg(ossl102)]
+use crate::ssl::CertCallbackResponse;
+#[cfg(not(boringssl))]
 use crate::ssl::SslVersion;
 #[cfg(ossl111)]
-use crate::ssl::{ClientHelloResponse, ExtensionContext};
+use crate::ssl::{ClientHelloResponse, ExtensionContext, KeyUpdateType, TlsExtType};
 use crate::ssl::{
-    Error, HandshakeError, MidHandshakeSslStream, ShutdownResult, ShutdownState, Ssl, SslAcceptor,
-    SslAcceptorBuilder, SslConnector, SslContext, SslContextBuilder, SslFiletype, SslMethod,
-    SslOptions, SslSessionCacheMode, SslStream, SslVerifyMode, StatusType,
+    Error, HandshakeError, MidHandshakeSslStream, ReloadableSslAcceptor, ServerNameContexts,
+    ShutdownResult, ShutdownState, Ssl, SslAcceptor, SslAcceptorBuilder, SslConnector,
+    SslContentType, SslContext, SslContextBuilder, SslFiletype, SslMethod, SslOptions,
+    SslSessionCacheMode, SslStream, SslVerifyMode, StatusType, WildcardPolicy,
 };
 #[cfg(ossl102)]
 use crate::x509::store::X509StoreBuilder;
 #[cfg(ossl102)]
 use crate::x509::verify::X509CheckFlags;
-use crate::x509::{X509Name, X509StoreContext, X509VerifyResult, X509};
+use crate::x509::{X509Extension, X509Name, X509StoreContext, X509VerifyResult, X509};
 
 mod server;
 
@@ -306,6 +311,35 @@ fn state() {
     );
 }
 
+#[test]
+#[cfg(ossl110)]
+fn handshake_state() {
+    use crate::ssl::SslConnection;
+
+    let ctx = SslContext::builder(SslMethod::tls()).unwrap().build();
+    let mut ssl = Ssl::new(&ctx).unwrap();
+    ssl.set_connect_state();
+    assert!(!ssl.is_server());
+    assert!(ssl.in_before());
+    assert!(ssl.in_init());
+    assert!(!ssl.is_init_finished());
+
+    let mut conn = SslConnection::new(ssl).unwrap();
+    let e = conn.do_handshake().unwrap_err();
+    assert_eq!(e.code(), ssl::ErrorCode::WANT_READ);
+    assert!(!conn.ssl().in_before());
+    assert!(conn.ssl().in_init());
+    assert_eq!(
+        conn.ssl().state_string_long(),
+        "SSLv3/TLS write client hello"
+    );
+
+    let server = Server::builder().build();
+    let s = server.client().connect();
+    assert!(!s.ssl().in_init());
+    assert!(s.ssl().is_init_finished());
+}
+
 /// Tests that when both the client as well as the server use SRTP and their
 /// lists of supported protocols have an overlap -- with only ONE protocol
 /// being valid for both.
@@ -357,6 +391,12 @@ fn test_connect_with_srtp_ctx() {
         .export_keying_material(&mut buf, "EXTRACTOR-dtls_srtp", None)
         .expect("extract");
 
+    let material = stream.ssl().srtp_keying_material().unwrap().unwrap();
+    assert_eq!(material.client_write_key(), &buf[..16]);
+    assert_eq!(material.server_write_key(), &buf[16..32]);
+    assert_eq!(material.client_write_salt(), &buf[32..46]);
+    assert_eq!(material.server_write_salt(), &buf[46..]);
+
     stream.read_exact(&mut [0]).unwrap();
 
     let buf2 = guard.join().unwrap();
@@ -450,6 +490,24 @@ fn test_alpn_server_advertise_multiple() {
     assert_eq!(s.ssl().selected_alpn_protocol(), Some(&b"spdy/3.1"[..]));
 }
 
+#[test]
+fn select_next_proto() {
+    let server = b"\x02h2\x08http/1.1";
+    assert_eq!(
+        ssl::select_next_proto(server, b"\x08http/1.1\x02h2"),
+        Some(&b"h2"[..])
+    );
+    assert_eq!(
+        ssl::select_next_proto(server, b"\x08http/1.1"),
+        Some(&b"http/1.1"[..])
+    );
+    assert_eq!(ssl::select_next_proto(server, b"\x06spdy/1"), None);
+    assert_eq!(ssl::select_next_proto(server, b""), None);
+    assert_eq!(ssl::select_next_proto(b"", b"\x02h2"), None);
+    assert_eq!(ssl::select_next_proto(server, b"\x03h2"), None);
+    assert_eq!(ssl::select_next_proto(server, b"\x00\x02h2"), None);
+}
+
 #[test]
 #[cfg(any(ossl110))]
 fn test_alpn_server_select_none_fatal() {
@@ -625,6 +683,13 @@ fn default_verify_paths() {
     assert!(result.ends_with(b"</HTML>\r\n") || result.ends_with(b"</html>"));
 }
 
+#[test]
+fn default_verify_paths_or_platform() {
+    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    let platform = ctx.set_default_verify_paths_or_platform().unwrap();
+    assert_eq!(platform, cfg!(all(windows, ossl320)));
+}
+
 #[test]
 fn add_extra_chain_cert() {
     let cert = X509::from_pem(CERT).unwrap();
@@ -801,265 +866,2463 @@ fn connector_client_server_mozilla_modern_v5() {
 }
 
 #[test]
-fn shutdown() {
-    let mut server = Server::builder();
-    server.io_cb(|mut s| {
-        assert_eq!(s.read(&mut [0]).unwrap(), 0);
-        assert_eq!(s.shutdown().unwrap(), ShutdownResult::Received);
+#[cfg(ossl110)]
+fn connector_client_server_mozilla_old() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let t = thread::spawn(move || {
+        let key = PKey::private_key_from_pem(KEY).unwrap();
+        let cert = X509::from_pem(CERT).unwrap();
+        let mut acceptor = SslAcceptor::mozilla_old(SslMethod::tls()).unwrap();
+        assert!(acceptor
+            .options()
+            .contains(SslOptions::CIPHER_SERVER_PREFERENCE));
+        acceptor.set_private_key(&key).unwrap();
+        acceptor.set_certificate(&cert).unwrap();
+        let acceptor = acceptor.build();
+        let stream = listener.accept().unwrap().0;
+        let mut stream = acceptor.accept(stream).unwrap();
+
+        stream.write_all(b"hello").unwrap();
     });
-    let server = server.build();
 
-    let mut s = server.client().connect();
+    // a legacy client which only speaks TLS 1.0
+    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    ctx.set_max_proto_version(Some(SslVersion::TLS1)).unwrap();
+    #[cfg(ossl300)]
+    ctx.set_cipher_list("DEFAULT:@SECLEVEL=0").unwrap();
+    let ssl = Ssl::new(&ctx.build()).unwrap();
 
-    assert_eq!(s.get_shutdown(), ShutdownState::empty());
-    assert_eq!(s.shutdown().unwrap(), ShutdownResult::Sent);
-    assert_eq!(s.get_shutdown(), ShutdownState::SENT);
-    assert_eq!(s.shutdown().unwrap(), ShutdownResult::Received);
-    assert_eq!(
-        s.get_shutdown(),
-        ShutdownState::SENT | ShutdownState::RECEIVED
-    );
-}
+    let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    let mut stream = ssl.connect(stream).unwrap();
+    assert_eq!(stream.ssl().version2(), Some(SslVersion::TLS1));
 
-#[test]
-fn client_ca_list() {
-    let names = X509Name::load_client_ca_file("test/root-ca.pem").unwrap();
-    assert_eq!(names.len(), 1);
+    let mut buf = [0; 5];
+    stream.read_exact(&mut buf).unwrap();
+    assert_eq!(b"hello", &buf);
 
-    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
-    ctx.set_client_ca_list(names);
+    t.join().unwrap();
 }
 
 #[test]
-fn cert_store() {
-    let server = Server::builder().build();
+fn reloadable_acceptor() {
+    fn acceptor(cert: &[u8], key: &[u8]) -> SslAcceptor {
+        let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+        acceptor
+            .set_certificate(&X509::from_pem(cert).unwrap())
+            .unwrap();
+        acceptor
+            .set_private_key(&PKey::private_key_from_pem(key).unwrap())
+            .unwrap();
+        acceptor.build()
+    }
 
-    let mut client = server.client();
-    let cert = X509::from_pem(ROOT_CERT).unwrap();
-    client.ctx().cert_store_mut().add_cert(cert).unwrap();
-    client.ctx().set_verify(SslVerifyMode::PEER);
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
 
-    client.connect();
-}
+    let reloadable = ReloadableSslAcceptor::new(acceptor(CERT, KEY));
+    let server_acceptor = reloadable.clone();
 
-#[test]
-#[cfg_attr(any(all(libressl321, not(libressl340)), boringssl), ignore)]
-fn tmp_dh_callback() {
-    static CALLED_BACK: AtomicBool = AtomicBool::new(false);
+    let t = thread::spawn(move || {
+        let stream = listener.accept().unwrap().0;
+        let mut first = server_acceptor.accept(stream).unwrap();
+        first.write_all(&[0]).unwrap();
 
-    let mut server = Server::builder();
-    server.ctx().set_tmp_dh_callback(|_, _, _| {
-        CALLED_BACK.store(true, Ordering::SeqCst);
-        let dh = include_bytes!("../../../test/dhparams.pem");
-        Dh::params_from_pem(dh)
+        let stream = listener.accept().unwrap().0;
+        let mut second = server_acceptor.accept(stream).unwrap();
+        second.write_all(&[0]).unwrap();
+
+        // the connection accepted before the reload is unaffected
+        first.write_all(&[1]).unwrap();
     });
 
-    let server = server.build();
+    let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+    connector.set_verify(SslVerifyMode::NONE);
+    let connector = connector.build();
 
-    let mut client = server.client();
-    // TLS 1.3 has no DH suites, so make sure we don't pick that version
-    #[cfg(any(ossl111, libressl340))]
-    client.ctx().set_options(super::SslOptions::NO_TLSV1_3);
-    client.ctx().set_cipher_list("EDH").unwrap();
-    client.connect();
+    let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    let mut first = connector.connect("foobar.com", stream).unwrap();
+    first.read_exact(&mut [0]).unwrap();
+    let cert = first.ssl().peer_certificate().unwrap();
+    assert_eq!(
+        cert.to_der().unwrap(),
+        X509::from_pem(CERT).unwrap().to_der().unwrap()
+    );
 
-    assert!(CALLED_BACK.load(Ordering::SeqCst));
+    let root_key = include_bytes!("../../../test/root-ca.key");
+    reloadable.replace(acceptor(ROOT_CERT, root_key));
+
+    let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    let mut second = connector.connect("foobar.com", stream).unwrap();
+    second.read_exact(&mut [0]).unwrap();
+    let cert = second.ssl().peer_certificate().unwrap();
+    assert_eq!(
+        cert.to_der().unwrap(),
+        X509::from_pem(ROOT_CERT).unwrap().to_der().unwrap()
+    );
+
+    let mut buf = [0];
+    first.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, [1]);
+
+    t.join().unwrap();
 }
 
 #[test]
-#[cfg(all(ossl101, not(ossl110)))]
-#[allow(deprecated)]
-fn tmp_ecdh_callback() {
-    use crate::ec::EcKey;
-    use crate::nid::Nid;
+fn server_name_contexts() {
+    fn context(cert: &[u8], key: &[u8]) -> SslContext {
+        let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+        ctx.set_certificate(&X509::from_pem(cert).unwrap()).unwrap();
+        ctx.set_private_key(&PKey::private_key_from_pem(key).unwrap())
+            .unwrap();
+        ctx.build()
+    }
 
-    static CALLED_BACK: AtomicBool = AtomicBool::new(false);
+    let root_key = include_bytes!("../../../test/root-ca.key");
+    let mut contexts = ServerNameContexts::new();
+    contexts.insert("*.example.com", context(ROOT_CERT, root_key));
+    contexts.insert("foobar.com", context(CERT, KEY));
 
-    let mut server = Server::builder();
-    server.ctx().set_tmp_ecdh_callback(|_, _, _| {
-        CALLED_BACK.store(true, Ordering::SeqCst);
-        EcKey::from_curve_name(Nid::X9_62_PRIME256V1)
+    assert!(contexts.get("foobar.com").is_some());
+    assert!(contexts.get("FooBar.com.").is_some());
+    assert!(contexts.get("www.example.com").is_some());
+    assert!(contexts.get("example.com").is_none());
+    assert!(contexts.get("a.b.example.com").is_none());
+    assert!(contexts.get(".example.com").is_none());
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let t = thread::spawn(move || {
+        let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+        acceptor
+            .set_certificate(&X509::from_pem(CERT).unwrap())
+            .unwrap();
+        acceptor
+            .set_private_key(&PKey::private_key_from_pem(KEY).unwrap())
+            .unwrap();
+        acceptor.set_server_name_contexts(contexts);
+        let acceptor = acceptor.build();
+
+        for _ in 0..2 {
+            let stream = listener.accept().unwrap().0;
+            let mut stream = acceptor.accept(stream).unwrap();
+            stream.write_all(&[0]).unwrap();
+        }
     });
 
-    let server = server.build();
+    let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+    connector.set_verify(SslVerifyMode::NONE);
+    let connector = connector.build();
 
-    let mut client = server.client();
-    client.ctx().set_cipher_list("ECDH").unwrap();
-    client.connect();
+    let peer_cert = |domain: &str| {
+        let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        let mut stream = connector.connect(domain, stream).unwrap();
+        stream.read_exact(&mut [0]).unwrap();
+        let cert = stream.ssl().peer_certificate().unwrap();
+        cert.to_der().unwrap()
+    };
 
-    assert!(CALLED_BACK.load(Ordering::SeqCst));
+    let root = X509::from_pem(ROOT_CERT).unwrap().to_der().unwrap();
+    let cert = X509::from_pem(CERT).unwrap().to_der().unwrap();
+    assert_eq!(peer_cert("WWW.example.com"), root);
+    assert_eq!(peer_cert("a.b.example.com"), cert);
+
+    t.join().unwrap();
 }
 
 #[test]
-#[cfg_attr(any(all(libressl321, not(libressl340)), boringssl), ignore)]
-fn tmp_dh_callback_ssl() {
-    static CALLED_BACK: AtomicBool = AtomicBool::new(false);
+fn connector_client_session_cache() {
+    use crate::x509::verify::X509VerifyFlags;
 
-    let mut server = Server::builder();
-    server.ssl_cb(|ssl| {
-        ssl.set_tmp_dh_callback(|_, _, _| {
-            CALLED_BACK.store(true, Ordering::SeqCst);
-            let dh = include_bytes!("../../../test/dhparams.pem");
-            Dh::params_from_pem(dh)
-        });
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let t = thread::spawn(move || {
+        let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+        acceptor
+            .set_certificate(&X509::from_pem(CERT).unwrap())
+            .unwrap();
+        acceptor
+            .set_private_key(&PKey::private_key_from_pem(KEY).unwrap())
+            .unwrap();
+        let acceptor = acceptor.build();
+
+        for _ in 0..3 {
+            let stream = listener.accept().unwrap().0;
+            let mut stream = acceptor.accept(stream).unwrap();
+            stream.write_all(&[0]).unwrap();
+            // sessions are removed from the server's cache if not shut down cleanly
+            stream.shutdown().unwrap();
+        }
     });
 
-    let server = server.build();
+    let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+    connector.set_ca_file("test/root-ca.pem").unwrap();
+    connector
+        .verify_param_mut()
+        .set_flags(X509VerifyFlags::NO_CHECK_TIME)
+        .unwrap();
+    connector
+        .set_client_session_cache(8, Duration::from_secs(60))
+        .unwrap();
+    let connector = connector.build();
 
-    let mut client = server.client();
-    // TLS 1.3 has no DH suites, so make sure we don't pick that version
-    #[cfg(any(ossl111, libressl340))]
-    client.ctx().set_options(super::SslOptions::NO_TLSV1_3);
-    client.ctx().set_cipher_list("EDH").unwrap();
-    client.connect();
+    let connect = |port_key: u16| {
+        let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        let mut stream = connector
+            .configure()
+            .unwrap()
+            .port(port_key)
+            .connect("foobar.com", stream)
+            .unwrap();
+        stream.read_exact(&mut [0]).unwrap();
+        stream.shutdown().unwrap();
+        stream.ssl().session_reused()
+    };
 
-    assert!(CALLED_BACK.load(Ordering::SeqCst));
+    assert!(!connect(port));
+    assert!(connect(port));
+    // a different port does not share the session
+    assert!(!connect(port.wrapping_add(1)));
+
+    t.join().unwrap();
 }
 
 #[test]
-#[cfg(all(ossl101, not(ossl110)))]
-#[allow(deprecated)]
-fn tmp_ecdh_callback_ssl() {
-    use crate::ec::EcKey;
-    use crate::nid::Nid;
+fn connector_client_session_cache_verification() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
 
-    static CALLED_BACK: AtomicBool = AtomicBool::new(false);
+    let t = thread::spawn(move || {
+        let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+        acceptor
+            .set_certificate(&X509::from_pem(CERT).unwrap())
+            .unwrap();
+        acceptor
+            .set_private_key(&PKey::private_key_from_pem(KEY).unwrap())
+            .unwrap();
+        let acceptor = acceptor.build();
 
-    let mut server = Server::builder();
-    server.ssl_cb(|ssl| {
-        ssl.set_tmp_ecdh_callback(|_, _, _| {
-            CALLED_BACK.store(true, Ordering::SeqCst);
-            EcKey::from_curve_name(Nid::X9_62_PRIME256V1)
-        });
+        let stream = listener.accept().unwrap().0;
+        let mut stream = acceptor.accept(stream).unwrap();
+        stream.write_all(&[0]).unwrap();
+        stream.shutdown().unwrap();
+
+        let stream = listener.accept().unwrap().0;
+        acceptor.accept(stream).unwrap_err();
     });
 
-    let server = server.build();
+    // the server's certificate isn't trusted
+    let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+    connector
+        .set_client_session_cache(8, Duration::from_secs(60))
+        .unwrap();
+    let connector = connector.build();
 
-    let mut client = server.client();
-    client.ctx().set_cipher_list("ECDH").unwrap();
-    client.connect();
+    let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    let mut stream = connector
+        .configure()
+        .unwrap()
+        .danger_accept_invalid_certs(true)
+        .connect("foobar.com", stream)
+        .unwrap();
+    stream.read_exact(&mut [0]).unwrap();
+    stream.shutdown().unwrap();
 
-    assert!(CALLED_BACK.load(Ordering::SeqCst));
-}
+    // the session from the insecure connection must not be resumed
+    let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    connector
+        .configure()
+        .unwrap()
+        .connect("foobar.com", stream)
+        .unwrap_err();
 
-#[test]
-fn idle_session() {
-    let ctx = SslContext::builder(SslMethod::tls()).unwrap().build();
-    let ssl = Ssl::new(&ctx).unwrap();
-    assert!(ssl.session().is_none());
+    t.join().unwrap();
 }
 
-/// possible LibreSSL bug since 3.2.1
 #[test]
-#[cfg_attr(libressl321, ignore)]
-fn active_session() {
+fn connector_danger_accept_invalid_certs() {
     let server = Server::builder().build();
 
-    let s = server.client().connect();
+    let connector = SslConnector::builder(SslMethod::tls()).unwrap().build();
+    let mut s = connector
+        .configure()
+        .unwrap()
+        .danger_accept_invalid_certs(true)
+        .connect("foobar.com", server.connect_tcp())
+        .unwrap();
+    s.read_exact(&mut [0]).unwrap();
 
-    let session = s.ssl().session().unwrap();
-    let len = session.master_key_len();
-    let mut buf = vec![0; len - 1];
-    let copied = session.master_key(&mut buf);
-    assert_eq!(copied, buf.len());
-    let mut buf = vec![0; len + 1];
-    let copied = session.master_key(&mut buf);
-    assert_eq!(copied, len);
+    assert_ne!(s.ssl().verify_result(), X509VerifyResult::OK);
+    assert!(s.ssl().peer_certificate().is_some());
+    assert!(s.ssl().peer_cert_chain().is_some());
 }
 
 #[test]
-#[cfg(not(boringssl))]
-fn status_callbacks() {
-    static CALLED_BACK_SERVER: AtomicBool = AtomicBool::new(false);
-    static CALLED_BACK_CLIENT: AtomicBool = AtomicBool::new(false);
+fn connector_danger_accept_invalid_certs_verify_callback() {
+    static CALLED_BACK: AtomicBool = AtomicBool::new(false);
 
-    let mut server = Server::builder();
+    let server = Server::builder().build();
+
+    let connector = SslConnector::builder(SslMethod::tls()).unwrap().build();
+    let mut s = connector
+        .configure()
+        .unwrap()
+        .danger_accept_invalid_certs(true)
+        .verify_callback(|_, _| {
+            CALLED_BACK.store(true, Ordering::SeqCst);
+            false
+        })
+        .connect("foobar.com", server.connect_tcp())
+        .unwrap();
+    s.read_exact(&mut [0]).unwrap();
+
+    assert!(CALLED_BACK.load(Ordering::SeqCst));
+    assert_ne!(s.ssl().verify_result(), X509VerifyResult::OK);
+}
+
+#[test]
+#[cfg(ossl110h)]
+fn peer_renegotiation_attempted() {
+    let mut server = Server::builder();
+    server.ctx().set_options(SslOptions::NO_RENEGOTIATION);
+    server.ctx().track_peer_renegotiation();
+    server.io_cb(|mut s| {
+        assert!(!s.ssl().peer_renegotiation_attempted());
+        s.read(&mut [0]).unwrap_err();
+        assert!(s.ssl().peer_renegotiation_attempted());
+    });
+    let server = server.build();
+
+    let mut client = server.client();
+    client
+        .ctx()
+        .set_max_proto_version(Some(SslVersion::TLS1_2))
+        .unwrap();
+    let mut s = client.connect();
+    assert!(!s.ssl().peer_renegotiation_attempted());
+    s.renegotiate().unwrap();
+    s.do_handshake().unwrap_err();
+}
+
+#[test]
+#[cfg(ossl110h)]
+fn peer_renegotiation_not_tracked() {
+    let mut server = Server::builder();
+    server.ctx().set_options(SslOptions::NO_RENEGOTIATION);
+    server.io_cb(|mut s| {
+        s.read(&mut [0]).unwrap_err();
+        assert!(!s.ssl().peer_renegotiation_attempted());
+    });
+    let server = server.build();
+
+    let mut client = server.client();
+    client
+        .ctx()
+        .set_max_proto_version(Some(SslVersion::TLS1_2))
+        .unwrap();
+    let mut s = client.connect();
+    s.renegotiate().unwrap();
+    s.do_handshake().unwrap_err();
+}
+
+#[test]
+fn peek() {
+    let mut server = Server::builder();
+    server.io_cb(|mut s| s.write_all(b"hello").unwrap());
+    let server = server.build();
+
+    let mut s = server.client().connect();
+    let mut buf = [0; 5];
+    let n = s.peek(&mut buf).unwrap();
+    assert!(n > 0);
+    assert_eq!(&buf[..n], &b"hello"[..n]);
+    s.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+}
+
+#[test]
+fn connector_verify_callback() {
+    use crate::x509::verify::X509VerifyFlags;
+
+    static CALLED_BACK: AtomicBool = AtomicBool::new(false);
+
+    let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+    connector.set_ca_file("test/root-ca.pem").unwrap();
+    connector
+        .verify_param_mut()
+        .set_flags(X509VerifyFlags::NO_CHECK_TIME)
+        .unwrap();
+    let connector = connector.build();
+
+    let server = Server::builder().build();
+    let mut s = connector
+        .configure()
+        .unwrap()
+        .verify_callback(|preverify_ok, _| {
+            CALLED_BACK.store(true, Ordering::SeqCst);
+            preverify_ok
+        })
+        .connect("foobar.com", server.connect_tcp())
+        .unwrap();
+    s.read_exact(&mut [0]).unwrap();
+    assert!(CALLED_BACK.load(Ordering::SeqCst));
+
+    let mut server = Server::builder();
+    server.should_error();
+    let server = server.build();
+    connector
+        .configure()
+        .unwrap()
+        .verify_callback(|_, _| false)
+        .connect("foobar.com", server.connect_tcp())
+        .unwrap_err();
+}
+
+#[test]
+#[cfg(not(boringssl))]
+fn connector_add_root_certificate() {
+    use crate::x509::verify::X509VerifyFlags;
+
+    let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+    connector
+        .add_root_certificate(&X509::from_pem(ROOT_CERT).unwrap())
+        .unwrap();
+    connector
+        .set_min_proto_version(Some(SslVersion::TLS1_2))
+        .unwrap();
+    connector
+        .verify_param_mut()
+        .set_flags(X509VerifyFlags::NO_CHECK_TIME)
+        .unwrap();
+    let connector = connector.build();
+
+    let server = Server::builder().build();
+    let mut s = connector
+        .connect("foobar.com", server.connect_tcp())
+        .unwrap();
+    s.read_exact(&mut [0]).unwrap();
+}
+
+#[test]
+fn connector_pinned_public_keys() {
+    use crate::x509::verify::X509VerifyFlags;
+
+    let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+    connector.set_ca_file("test/root-ca.pem").unwrap();
+    connector
+        .verify_param_mut()
+        .set_flags(X509VerifyFlags::NO_CHECK_TIME)
+        .unwrap();
+    let connector = connector.build();
+
+    let root = X509::from_pem(ROOT_CERT).unwrap();
+    let spki = root.public_key().unwrap().public_key_to_der().unwrap();
+    let mut pin = [0; 32];
+    pin.copy_from_slice(&hash(MessageDigest::sha256(), &spki).unwrap());
+
+    let server = Server::builder().build();
+    connector
+        .configure()
+        .unwrap()
+        .pinned_public_keys(vec![[0; 32], pin])
+        .connect("foobar.com", server.connect_tcp())
+        .unwrap()
+        .read_exact(&mut [0])
+        .unwrap();
+
+    let mut server = Server::builder();
+    server.should_error();
+    let server = server.build();
+    let err = connector
+        .configure()
+        .unwrap()
+        .pinned_public_keys(vec![[0; 32]])
+        .connect("foobar.com", server.connect_tcp())
+        .unwrap_err();
+    match err {
+        HandshakeError::Failure(s) => assert_eq!(
+            s.ssl().verify_result(),
+            X509VerifyResult::APPLICATION_VERIFICATION
+        ),
+        _ => panic!("unexpected error"),
+    }
+}
+
+#[test]
+fn connector_pinned_public_keys_danger_accept_invalid_certs() {
+    let cert = X509::from_pem(CERT).unwrap();
+    let spki = cert.public_key().unwrap().public_key_to_der().unwrap();
+    let mut pin = [0; 32];
+    pin.copy_from_slice(&hash(MessageDigest::sha256(), &spki).unwrap());
+
+    // the server's certificate isn't trusted, so the pin can't be checked
+    let connector = SslConnector::builder(SslMethod::tls()).unwrap().build();
+    let mut server = Server::builder();
+    server.should_error();
+    let server = server.build();
+    connector
+        .configure()
+        .unwrap()
+        .danger_accept_invalid_certs(true)
+        .pinned_public_keys(vec![pin])
+        .connect("foobar.com", server.connect_tcp())
+        .unwrap_err();
+}
+
+#[test]
+fn connector_pinned_public_keys_session_cache() {
+    use crate::x509::verify::X509VerifyFlags;
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    let t = thread::spawn(move || {
+        let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+        acceptor
+            .set_certificate(&X509::from_pem(CERT).unwrap())
+            .unwrap();
+        acceptor
+            .set_private_key(&PKey::private_key_from_pem(KEY).unwrap())
+            .unwrap();
+        let acceptor = acceptor.build();
+
+        let stream = listener.accept().unwrap().0;
+        let mut stream = acceptor.accept(stream).unwrap();
+        stream.write_all(&[0]).unwrap();
+        stream.shutdown().unwrap();
+
+        let stream = listener.accept().unwrap().0;
+        acceptor.accept(stream).unwrap_err();
+    });
+
+    let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+    connector.set_ca_file("test/root-ca.pem").unwrap();
+    connector
+        .verify_param_mut()
+        .set_flags(X509VerifyFlags::NO_CHECK_TIME)
+        .unwrap();
+    connector
+        .set_client_session_cache(8, Duration::from_secs(60))
+        .unwrap();
+    let connector = connector.build();
+
+    let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    let mut stream = connector
+        .configure()
+        .unwrap()
+        .connect("foobar.com", stream)
+        .unwrap();
+    stream.read_exact(&mut [0]).unwrap();
+    stream.shutdown().unwrap();
+
+    // the session was established without the pin, so must not be resumed
+    let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+    let err = connector
+        .configure()
+        .unwrap()
+        .pinned_public_keys(vec![[0; 32]])
+        .connect("foobar.com", stream)
+        .unwrap_err();
+    match err {
+        HandshakeError::Failure(s) => assert_eq!(
+            s.ssl().verify_result(),
+            X509VerifyResult::APPLICATION_VERIFICATION
+        ),
+        _ => panic!("unexpected error"),
+    }
+
+    t.join().unwrap();
+}
+
+#[cfg(ossl102)]
+fn self_signed_cert(name: &str, extensions: Vec<X509Extension>) -> X509 {
+    use crate::asn1::Asn1Time;
+    use crate::bn::BigNum;
+    use crate::nid::Nid;
+    use crate::x509::extension::SubjectAlternativeName;
+
+    let key = PKey::private_key_from_pem(KEY).unwrap();
+    let mut subject = X509Name::builder().unwrap();
+    subject.append_entry_by_nid(Nid::COMMONNAME, name).unwrap();
+    let subject = subject.build();
+    let serial = BigNum::from_u32(1).unwrap().to_asn1_integer().unwrap();
+    let mut builder = X509::builder().unwrap();
+    builder.set_version(2).unwrap();
+    builder.set_serial_number(&serial).unwrap();
+    builder.set_subject_name(&subject).unwrap();
+    builder.set_issuer_name(&subject).unwrap();
+    builder
+        .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+        .unwrap();
+    builder
+        .set_not_after(&Asn1Time::days_from_now(1).unwrap())
+        .unwrap();
+    builder.set_pubkey(&key).unwrap();
+    let san = SubjectAlternativeName::new()
+        .dns(name)
+        .build(&builder.x509v3_context(None, None))
+        .unwrap();
+    builder.append_extension(san).unwrap();
+    for extension in extensions {
+        builder.append_extension(extension).unwrap();
+    }
+    builder.sign(&key, MessageDigest::sha256()).unwrap();
+    builder.build()
+}
+
+#[test]
+#[cfg(ossl102)]
+fn connector_wildcard_policy() {
+    let cert = self_signed_cert("*.example.com", vec![]);
+
+    let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+    connector.add_root_certificate(&cert).unwrap();
+    let connector = connector.build();
+
+    let server = |cert: X509, should_error: bool| {
+        let mut server = Server::builder();
+        server.ctx().set_certificate(&cert).unwrap();
+        if should_error {
+            server.should_error();
+        }
+        server.build()
+    };
+
+    let s = server(cert.clone(), false);
+    connector
+        .connect("www.example.com", s.connect_tcp())
+        .unwrap()
+        .read_exact(&mut [0])
+        .unwrap();
+
+    let mut policy = WildcardPolicy::new();
+    policy.set_allow_wildcards(false);
+    let s = server(cert.clone(), true);
+    connector
+        .configure()
+        .unwrap()
+        .wildcard_policy(policy)
+        .connect("www.example.com", s.connect_tcp())
+        .unwrap_err();
+
+    let mut policy = WildcardPolicy::new();
+    policy.add_public_suffix("example.com");
+    let s = server(cert, true);
+    let err = connector
+        .configure()
+        .unwrap()
+        .wildcard_policy(policy)
+        .connect("www.example.com", s.connect_tcp())
+        .unwrap_err();
+    match err {
+        HandshakeError::Failure(s) => assert_eq!(
+            s.ssl().verify_result(),
+            X509VerifyResult::APPLICATION_VERIFICATION
+        ),
+        _ => panic!("unexpected error"),
+    }
+}
+
+#[test]
+#[cfg(ossl110)]
+fn connector_ocsp_must_staple() {
+    use crate::asn1::{Asn1Object, Asn1Time};
+    use crate::nid::Nid;
+    use crate::ocsp::{OcspBasicResponse, OcspCertId, OcspCertStatus, OcspFlag, OcspRevokedStatus};
+
+    let must_staple =
+        X509Extension::new_nid(None, None, Nid::TLSFEATURE, "status_request").unwrap();
+    let cert = self_signed_cert("foobar.com", vec![must_staple]);
+
+    let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+    connector.add_root_certificate(&cert).unwrap();
+    let connector = connector.build();
+
+    let server = |cert: &X509, staple: Option<Vec<u8>>, should_error: bool| {
+        let mut server = Server::builder();
+        server.ctx().set_certificate(cert).unwrap();
+        if let Some(staple) = staple {
+            server
+                .ctx()
+                .set_status_callback(move |ssl| {
+                    ssl.set_ocsp_status(&staple).unwrap();
+                    Ok(true)
+                })
+                .unwrap();
+        }
+        if should_error {
+            server.should_error();
+        }
+        server.build()
+    };
+
+    let connect = |s: &server::Server| {
+        connector
+            .configure()
+            .unwrap()
+            .require_ocsp_must_staple(true)
+            .connect("foobar.com", s.connect_tcp())
+    };
+
+    // the certificate is self-signed, so is its own OCSP responder
+    let staple = |status: OcspCertStatus| {
+        let key = PKey::private_key_from_pem(KEY).unwrap();
+        let id = OcspCertId::from_cert(MessageDigest::sha1(), &cert, &cert).unwrap();
+        let now = Asn1Time::days_from_now(0).unwrap();
+        let next = Asn1Time::days_from_now(1).unwrap();
+        let mut basic = OcspBasicResponse::new().unwrap();
+        basic
+            .add_status(
+                &id,
+                status,
+                OcspRevokedStatus::KEY_COMPROMISE,
+                Some(&now),
+                &now,
+                Some(&next),
+            )
+            .unwrap();
+        basic
+            .sign(
+                &cert,
+                &key,
+                MessageDigest::sha256(),
+                None,
+                OcspFlag::NO_CERTS,
+            )
+            .unwrap();
+        OcspResponse::create(OcspResponseStatus::SUCCESSFUL, Some(&basic))
+            .unwrap()
+            .to_der()
+            .unwrap()
+    };
+
+    // the server does not staple a response
+    let s = server(&cert, None, true);
+    connect(&s).unwrap_err();
+
+    let s = server(&cert, None, false);
+    connector
+        .connect("foobar.com", s.connect_tcp())
+        .unwrap()
+        .read_exact(&mut [0])
+        .unwrap();
+
+    let s = server(&cert, Some(staple(OcspCertStatus::GOOD)), false);
+    connect(&s).unwrap().read_exact(&mut [0]).unwrap();
+
+    // the response is verified against the store the connection is verified with
+    #[cfg(ossl300)]
+    {
+        let mut store = X509StoreBuilder::new().unwrap();
+        store.add_cert(cert.clone()).unwrap();
+        let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+        connector.set_verify_cert_store(store.build()).unwrap();
+        let connector = connector.build();
+
+        let s = server(&cert, Some(staple(OcspCertStatus::GOOD)), false);
+        connector
+            .configure()
+            .unwrap()
+            .require_ocsp_must_staple(true)
+            .connect("foobar.com", s.connect_tcp())
+            .unwrap()
+            .read_exact(&mut [0])
+            .unwrap();
+    }
+
+    let s = server(&cert, Some(staple(OcspCertStatus::REVOKED)), true);
+    connect(&s).unwrap_err();
+
+    // the signature is at the end of the response as no certificates are included
+    let mut bad_signature = staple(OcspCertStatus::GOOD);
+    *bad_signature.last_mut().unwrap() ^= 1;
+    let s = server(&cert, Some(bad_signature), true);
+    connect(&s).unwrap_err();
+
+    // certificates without the extension are unaffected
+    let plain = self_signed_cert("foobar.com", vec![]);
+    let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+    connector.add_root_certificate(&plain).unwrap();
+    let connector = connector.build();
+
+    let s = server(&plain, None, false);
+    connector
+        .configure()
+        .unwrap()
+        .require_ocsp_must_staple(true)
+        .connect("foobar.com", s.connect_tcp())
+        .unwrap()
+        .read_exact(&mut [0])
+        .unwrap();
+
+    // a malformed extension is not ignored
+    let oid = Asn1Object::from_str("1.3.6.1.5.5.7.1.24").unwrap();
+    let malformed = X509Extension::new_from_der(&oid, false, &[0x30, 0x03, 0x02, 0x01]).unwrap();
+    let malformed = self_signed_cert("foobar.com", vec![malformed]);
+    let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+    connector.add_root_certificate(&malformed).unwrap();
+    let connector = connector.build();
+
+    let s = server(&malformed, None, true);
+    connector
+        .configure()
+        .unwrap()
+        .require_ocsp_must_staple(true)
+        .connect("foobar.com", s.connect_tcp())
+        .unwrap_err();
+
+    // Must-Staple can't be enforced once the status callback has been replaced
+    let mut connector = SslConnector::builder(SslMethod::tls()).unwrap();
+    connector.set_status_callback(|_| Ok(true)).unwrap();
+    let connector = connector.build();
+    assert!(connector
+        .configure()
+        .unwrap()
+        .require_ocsp_must_staple(true)
+        .into_ssl("foobar.com")
+        .is_err());
+    connector
+        .configure()
+        .unwrap()
+        .into_ssl("foobar.com")
+        .unwrap();
+}
+
+#[test]
+fn context_ex_data() {
+    use std::sync::atomic::AtomicUsize;
+
+    static DROPPED: AtomicUsize = AtomicUsize::new(0);
+
+    struct Data(&'static str);
+
+    impl Drop for Data {
+        fn drop(&mut self) {
+            DROPPED.fetch_add(1, Ordering::SeqCst);
+        }
+    }
+
+    let idx = SslContext::new_ex_index::<Data>().unwrap();
+
+    let mut server = Server::builder();
+    server.ctx().set_ex_data(idx, Data("first"));
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 0);
+    server.ctx().set_ex_data(idx, Data("second"));
+    assert_eq!(DROPPED.load(Ordering::SeqCst), 1);
+    assert_eq!(server.ctx().ex_data(idx).unwrap().0, "second");
+    server.ctx().ex_data_mut(idx).unwrap().0 = "third";
+    server.ctx().set_servername_callback(move |ssl, _| {
+        assert_eq!(ssl.ssl_context().ex_data(idx).unwrap().0, "third");
+        Ok(())
+    });
+    let server = server.build();
+
+    server.client().connect();
+}
+
+#[test]
+fn ssl_ex_data() {
+    let idx = Ssl::new_ex_index::<String>().unwrap();
+
+    let ctx = SslContext::builder(SslMethod::tls()).unwrap().build();
+    let mut ssl = Ssl::new(&ctx).unwrap();
+    ssl.set_ex_data(idx, "first".to_string());
+    ssl.set_ex_data(idx, "second".to_string());
+    assert_eq!(ssl.ex_data(idx).unwrap(), "second");
+}
+
+#[test]
+fn shutdown() {
+    let mut server = Server::builder();
+    server.io_cb(|mut s| {
+        assert_eq!(s.read(&mut [0]).unwrap(), 0);
+        assert_eq!(s.shutdown().unwrap(), ShutdownResult::Received);
+    });
+    let server = server.build();
+
+    let mut s = server.client().connect();
+
+    assert_eq!(s.get_shutdown(), ShutdownState::empty());
+    assert_eq!(s.shutdown().unwrap(), ShutdownResult::Sent);
+    assert_eq!(s.get_shutdown(), ShutdownState::SENT);
+    assert_eq!(s.shutdown().unwrap(), ShutdownResult::Received);
+    assert_eq!(
+        s.get_shutdown(),
+        ShutdownState::SENT | ShutdownState::RECEIVED
+    );
+}
+
+#[test]
+fn shutdown_peer_initiated() {
+    let mut server = Server::builder();
+    server.io_cb(|mut s| {
+        assert_eq!(s.shutdown().unwrap(), ShutdownResult::Sent);
+        assert_eq!(s.shutdown().unwrap(), ShutdownResult::Received);
+        assert_eq!(
+            s.ssl().get_shutdown(),
+            ShutdownState::SENT | ShutdownState::RECEIVED
+        );
+    });
+    let server = server.build();
+
+    let mut s = server.client().connect();
+
+    assert_eq!(s.read(&mut [0]).unwrap(), 0);
+    assert_eq!(s.get_shutdown(), ShutdownState::RECEIVED);
+    assert_eq!(s.shutdown().unwrap(), ShutdownResult::Received);
+    assert_eq!(
+        s.get_shutdown(),
+        ShutdownState::SENT | ShutdownState::RECEIVED
+    );
+}
+
+#[test]
+fn set_shutdown() {
+    let server = Server::builder().build();
+
+    let mut s = server.client().connect();
+
+    assert_eq!(s.ssl().get_shutdown(), ShutdownState::empty());
+    s.set_shutdown(ShutdownState::SENT | ShutdownState::RECEIVED);
+    assert_eq!(
+        s.ssl().get_shutdown(),
+        ShutdownState::SENT | ShutdownState::RECEIVED
+    );
+}
+
+#[test]
+fn client_cert_callback() {
+    static CALLED_BACK: AtomicBool = AtomicBool::new(false);
+
+    let mut server = Server::builder();
+    let names = X509Name::load_client_ca_file("test/root-ca.pem").unwrap();
+    server.ctx().set_client_ca_list(names);
+    server.ctx().set_verify_callback(
+        SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT,
+        |_, _| true,
+    );
+    server.io_cb(|s| assert!(s.ssl().peer_certificate().is_some()));
+    let server = server.build();
+
+    let mut client = server.client();
+    client.ctx().set_client_cert_callback(|ssl| {
+        CALLED_BACK.store(true, Ordering::SeqCst);
+        let names = ssl.client_ca_list().unwrap();
+        assert_eq!(names.len(), 1);
+
+        let cert = X509::from_pem(CERT).unwrap();
+        let key = PKey::private_key_from_pem(KEY).unwrap();
+        Some((cert, key))
+    });
+    client.connect();
+
+    assert!(CALLED_BACK.load(Ordering::SeqCst));
+}
+
+#[test]
+fn client_ca_list() {
+    let names = X509Name::load_client_ca_file("test/root-ca.pem").unwrap();
+    assert_eq!(names.len(), 1);
+
+    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    ctx.set_client_ca_list(names);
+    ctx.add_client_ca(&X509::from_pem(CERT).unwrap()).unwrap();
+    let ctx = ctx.build();
+    assert_eq!(ctx.client_ca_list().unwrap().len(), 2);
+}
+
+#[test]
+fn ssl_client_ca_list() {
+    static CALLED_BACK: AtomicBool = AtomicBool::new(false);
+
+    let mut server = Server::builder();
+    server
+        .ctx()
+        .set_verify_callback(SslVerifyMode::PEER, |_, _| true);
+    server.ssl_cb(|ssl| {
+        let names = X509Name::load_client_ca_file("test/root-ca.pem").unwrap();
+        ssl.set_client_ca_list(names);
+        ssl.add_client_ca(&X509::from_pem(CERT).unwrap()).unwrap();
+        assert_eq!(ssl.client_ca_list().unwrap().len(), 2);
+    });
+    let server = server.build();
+
+    let mut client = server.client();
+    client.ctx().set_client_cert_callback(|ssl| {
+        CALLED_BACK.store(true, Ordering::SeqCst);
+        assert_eq!(ssl.client_ca_list().unwrap().len(), 2);
+        None
+    });
+    client.connect();
+
+    assert!(CALLED_BACK.load(Ordering::SeqCst));
+}
+
+#[test]
+#[cfg(ossl102)]
+fn build_cert_chain() {
+    use crate::ssl::SslBuildChainFlags;
+    use crate::x509::verify::X509VerifyFlags;
+
+    let mut server = Server::builder();
+    let store = server.ctx().cert_store_mut();
+    store.add_cert(X509::from_pem(ROOT_CERT).unwrap()).unwrap();
+    store.set_flags(X509VerifyFlags::NO_CHECK_TIME).unwrap();
+    server
+        .ctx()
+        .build_cert_chain(SslBuildChainFlags::empty())
+        .unwrap();
+    let server = server.build();
+
+    let s = server.client().connect();
+    assert_eq!(s.ssl().peer_cert_chain().unwrap().len(), 2);
+}
+
+#[test]
+#[cfg(ossl102)]
+fn set_chain() {
+    use crate::stack::Stack;
+
+    let mut server = Server::builder();
+    let mut chain = Stack::new().unwrap();
+    chain.push(X509::from_pem(ROOT_CERT).unwrap()).unwrap();
+    server.ctx().set_chain(chain).unwrap();
+    let server = server.build();
+
+    let s = server.client().connect();
+    assert_eq!(s.ssl().peer_cert_chain().unwrap().len(), 2);
+
+    let mut server = Server::builder();
+    server
+        .ctx()
+        .add_chain_cert(X509::from_pem(ROOT_CERT).unwrap())
+        .unwrap();
+    let server = server.build();
+
+    let s = server.client().connect();
+    assert_eq!(s.ssl().peer_cert_chain().unwrap().len(), 2);
+}
+
+#[test]
+fn cert_store() {
+    let server = Server::builder().build();
+
+    let mut client = server.client();
+    let cert = X509::from_pem(ROOT_CERT).unwrap();
+    client.ctx().cert_store_mut().add_cert(cert).unwrap();
+    client.ctx().set_verify(SslVerifyMode::PEER);
+
+    client.connect();
+}
+
+#[test]
+#[cfg(ossl102)]
+fn set_host() {
+    use crate::x509::verify::X509VerifyFlags;
+
+    let server = Server::builder().build();
+
+    let mut client = server.client();
+    client.ctx().set_ca_file("test/root-ca.pem").unwrap();
+    client.ctx().set_verify(SslVerifyMode::PEER);
+    let mut client = client.build().builder();
+    client
+        .ssl()
+        .param_mut()
+        .set_flags(X509VerifyFlags::NO_CHECK_TIME)
+        .unwrap();
+    client.ssl().set_host("bogus.com").unwrap();
+    client.ssl().add_host("foobar.com").unwrap();
+    client.connect();
+
+    let mut server = Server::builder();
+    server.should_error();
+    let server = server.build();
+
+    let mut client = server.client();
+    client.ctx().set_ca_file("test/root-ca.pem").unwrap();
+    client.ctx().set_verify(SslVerifyMode::PEER);
+    let mut client = client.build().builder();
+    client
+        .ssl()
+        .param_mut()
+        .set_flags(X509VerifyFlags::NO_CHECK_TIME)
+        .unwrap();
+    client.ssl().set_host("bogus.com").unwrap();
+    client.connect_err();
+}
+
+#[test]
+#[cfg(ossl102)]
+fn set_empty_host() {
+    use crate::x509::verify::X509VerifyFlags;
+
+    let server = Server::builder().build();
+
+    let mut client = server.client();
+    client.ctx().set_ca_file("test/root-ca.pem").unwrap();
+    client.ctx().set_verify(SslVerifyMode::PEER);
+    let mut client = client.build().builder();
+    client
+        .ssl()
+        .param_mut()
+        .set_flags(X509VerifyFlags::NO_CHECK_TIME)
+        .unwrap();
+    // Empty names are ignored by add_host and clear the names set so far with set_host.
+    client.ssl().set_host("bogus.com").unwrap();
+    client.ssl().add_host("").unwrap();
+    client.ssl().set_host("").unwrap();
+    client.connect();
+}
+
+#[test]
+#[cfg(ossl102)]
+fn set_cert_store() {
+    use crate::x509::verify::X509VerifyFlags;
+
+    let server = Server::builder().build();
+
+    let mut store = X509StoreBuilder::new().unwrap();
+    store.add_cert(X509::from_pem(ROOT_CERT).unwrap()).unwrap();
+    store.set_flags(X509VerifyFlags::NO_CHECK_TIME).unwrap();
+
+    let mut client = server.client();
+    client.ctx().set_cert_store(store.build());
+    client.ctx().set_verify(SslVerifyMode::PEER);
+
+    client.connect();
+}
+
+#[test]
+#[cfg_attr(any(all(libressl321, not(libressl340)), boringssl), ignore)]
+fn tmp_dh_callback() {
+    static CALLED_BACK: AtomicBool = AtomicBool::new(false);
+
+    let mut server = Server::builder();
+    server.ctx().set_tmp_dh_callback(|_, _, _| {
+        CALLED_BACK.store(true, Ordering::SeqCst);
+        let dh = include_bytes!("../../../test/dhparams.pem");
+        Dh::params_from_pem(dh)
+    });
+
+    let server = server.build();
+
+    let mut client = server.client();
+    // TLS 1.3 has no DH suites, so make sure we don't pick that version
+    #[cfg(any(ossl111, libressl340))]
+    client.ctx().set_options(super::SslOptions::NO_TLSV1_3);
+    client.ctx().set_cipher_list("EDH").unwrap();
+    client.connect();
+
+    assert!(CALLED_BACK.load(Ordering::SeqCst));
+}
+
+#[test]
+#[cfg(all(ossl101, not(ossl110)))]
+#[allow(deprecated)]
+fn tmp_ecdh_callback() {
+    use crate::ec::EcKey;
+    use crate::nid::Nid;
+
+    static CALLED_BACK: AtomicBool = AtomicBool::new(false);
+
+    let mut server = Server::builder();
+    server.ctx().set_tmp_ecdh_callback(|_, _, _| {
+        CALLED_BACK.store(true, Ordering::SeqCst);
+        EcKey::from_curve_name(Nid::X9_62_PRIME256V1)
+    });
+
+    let server = server.build();
+
+    let mut client = server.client();
+    client.ctx().set_cipher_list("ECDH").unwrap();
+    client.connect();
+
+    assert!(CALLED_BACK.load(Ordering::SeqCst));
+}
+
+#[test]
+#[cfg_attr(any(all(libressl321, not(libressl340)), boringssl), ignore)]
+fn tmp_dh_callback_ssl() {
+    static CALLED_BACK: AtomicBool = AtomicBool::new(false);
+
+    let mut server = Server::builder();
+    server.ssl_cb(|ssl| {
+        ssl.set_tmp_dh_callback(|_, _, _| {
+            CALLED_BACK.store(true, Ordering::SeqCst);
+            let dh = include_bytes!("../../../test/dhparams.pem");
+            Dh::params_from_pem(dh)
+        });
+    });
+
+    let server = server.build();
+
+    let mut client = server.client();
+    // TLS 1.3 has no DH suites, so make sure we don't pick that version
+    #[cfg(any(ossl111, libressl340))]
+    client.ctx().set_options(super::SslOptions::NO_TLSV1_3);
+    client.ctx().set_cipher_list("EDH").unwrap();
+    client.connect();
+
+    assert!(CALLED_BACK.load(Ordering::SeqCst));
+}
+
+#[test]
+#[cfg(all(ossl101, not(ossl110)))]
+#[allow(deprecated)]
+fn tmp_ecdh_callback_ssl() {
+    use crate::ec::EcKey;
+    use crate::nid::Nid;
+
+    static CALLED_BACK: AtomicBool = AtomicBool::new(false);
+
+    let mut server = Server::builder();
+    server.ssl_cb(|ssl| {
+        ssl.set_tmp_ecdh_callback(|_, _, _| {
+            CALLED_BACK.store(true, Ordering::SeqCst);
+            EcKey::from_curve_name(Nid::X9_62_PRIME256V1)
+        });
+    });
+
+    let server = server.build();
+
+    let mut client = server.client();
+    client.ctx().set_cipher_list("ECDH").unwrap();
+    client.connect();
+
+    assert!(CALLED_BACK.load(Ordering::SeqCst));
+}
+
+#[test]
+fn idle_session() {
+    let ctx = SslContext::builder(SslMethod::tls()).unwrap().build();
+    let ssl = Ssl::new(&ctx).unwrap();
+    assert!(ssl.session().is_none());
+}
+
+#[test]
+#[cfg(ossl110)]
+fn context_status_type() {
+    static CALLED_BACK_SERVER: AtomicBool = AtomicBool::new(false);
+    static CALLED_BACK_CLIENT: AtomicBool = AtomicBool::new(false);
+
+    let mut server = Server::builder();
+    server
+        .ctx()
+        .set_status_callback(|ssl| {
+            CALLED_BACK_SERVER.store(true, Ordering::SeqCst);
+            assert!(ssl.status_type() == Some(StatusType::OCSP));
+            let response = OcspResponse::create(OcspResponseStatus::TRY_LATER, None).unwrap();
+            let response = response.to_der().unwrap();
+            ssl.set_ocsp_status(&response).unwrap();
+            Ok(true)
+        })
+        .unwrap();
+
+    let server = server.build();
+
+    let mut client = server.client();
+    client.ctx().set_status_type(StatusType::OCSP).unwrap();
+    client
+        .ctx()
+        .set_status_callback(|ssl| {
+            CALLED_BACK_CLIENT.store(true, Ordering::SeqCst);
+            let response = OcspResponse::from_der(ssl.ocsp_status().unwrap()).unwrap();
+            assert_eq!(response.status(), OcspResponseStatus::TRY_LATER);
+            Ok(true)
+        })
+        .unwrap();
+
+    client.connect();
+
+    assert!(CALLED_BACK_SERVER.load(Ordering::SeqCst));
+    assert!(CALLED_BACK_CLIENT.load(Ordering::SeqCst));
+}
+
+/// possible LibreSSL bug since 3.2.1
+#[test]
+#[cfg_attr(libressl321, ignore)]
+fn active_session() {
+    let server = Server::builder().build();
+
+    let s = server.client().connect();
+
+    let session = s.ssl().session().unwrap();
+    let len = session.master_key_len();
+    let mut buf = vec![0; len - 1];
+    let copied = session.master_key(&mut buf);
+    assert_eq!(copied, buf.len());
+    let mut buf = vec![0; len + 1];
+    let copied = session.master_key(&mut buf);
+    assert_eq!(copied, len);
+}
+
+#[test]
+#[cfg(ossl111)]
+fn session_introspection() {
+    let mut server = Server::builder();
+    server.io_cb(|mut s| s.write_all(&[0]).unwrap());
+    let server = server.build();
+
+    let mut s = server.client().connect();
+    // TLSv1.3 session tickets are sent after the handshake.
+    s.read_exact(&mut [0]).unwrap();
+
+    let session = s.ssl().session().unwrap().to_owned();
+    assert_eq!(session.protocol_version(), s.ssl().version2().unwrap());
+    assert_eq!(
+        session.cipher().unwrap().name(),
+        s.ssl().current_cipher().unwrap().name()
+    );
+    assert!(session.has_ticket());
+    assert!(session.ticket_lifetime_hint() > 0);
+    assert!(session.time() > 0);
+
+    let mut session = session;
+    session.set_timeout(1234);
+    assert_eq!(session.timeout(), 1234);
+}
+
+#[test]
+#[cfg(ossl111)]
+fn client_random_key_log() {
+    use std::sync::{Arc, Mutex};
+
+    let mut server = Server::builder();
+    server
+        .ctx()
+        .set_max_proto_version(Some(SslVersion::TLS1_2))
+        .unwrap();
+    server.io_cb(|mut s| {
+        let mut client_random = [0; 32];
+        assert_eq!(s.ssl().client_random(&mut client_random), 32);
+        let mut server_random = [0; 32];
+        assert_eq!(s.ssl().server_random(&mut server_random), 32);
+        s.write_all(&client_random).unwrap();
+        s.write_all(&server_random).unwrap();
+    });
+    let server = server.build();
+
+    let lines = Arc::new(Mutex::new(vec![]));
+    let mut client = server.client();
+    let keylog = lines.clone();
+    client
+        .ctx()
+        .set_keylog_callback(move |_, line| keylog.lock().unwrap().push(line.to_string()));
+    let mut s = client.connect();
+
+    let mut client_random = [0; 32];
+    s.read_exact(&mut client_random).unwrap();
+    let mut server_random = [0; 32];
+    s.read_exact(&mut server_random).unwrap();
+
+    assert_eq!(s.ssl().client_random(&mut []), 32);
+    let mut buf = [0; 32];
+    s.ssl().client_random(&mut buf);
+    assert_eq!(buf, client_random);
+    s.ssl().server_random(&mut buf);
+    assert_eq!(buf, server_random);
+
+    let session = s.ssl().session().unwrap();
+    let mut master_key = vec![0; session.master_key_len()];
+    session.master_key(&mut master_key);
+
+    let expected = format!(
+        "CLIENT_RANDOM {} {}",
+        hex::encode(client_random),
+        hex::encode(master_key)
+    );
+    assert_eq!(*lines.lock().unwrap(), [expected]);
+}
+
+#[test]
+#[cfg(ossl110)]
+fn renegotiate() {
+    let mut server = Server::builder();
+    server
+        .ctx()
+        .set_max_proto_version(Some(SslVersion::TLS1_2))
+        .unwrap();
+    server.io_cb(|mut s| {
+        assert!(s.ssl().secure_renegotiation_support());
+        assert!(!s.ssl().renegotiate_pending());
+
+        s.renegotiate().unwrap();
+        assert!(s.ssl().renegotiate_pending());
+        s.do_handshake().unwrap();
+
+        s.write_all(&[1]).unwrap();
+        s.read_exact(&mut [0]).unwrap();
+        assert!(!s.ssl().renegotiate_pending());
+        assert_eq!(s.ssl().num_renegotiations(), 1);
+    });
+    let server = server.build();
+
+    let mut s = server.client().connect();
+    assert_eq!(s.ssl().num_renegotiations(), 0);
+
+    let mut buf = [0];
+    s.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, [1]);
+    assert_eq!(s.ssl().num_renegotiations(), 1);
+    s.write_all(&[2]).unwrap();
+}
+
+#[test]
+#[cfg(ossl111)]
+fn key_update() {
+    let mut server = Server::builder();
+    server.io_cb(|mut s| {
+        let mut buf = [0];
+        s.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, [1]);
+        assert_eq!(
+            s.ssl().key_update_pending(),
+            Some(KeyUpdateType::NOT_REQUESTED)
+        );
+        s.write_all(&[2]).unwrap();
+        assert_eq!(s.ssl().key_update_pending(), None);
+    });
+    let server = server.build();
+
+    let mut s = server.client().connect();
+    assert_eq!(s.ssl().key_update_pending(), None);
+    s.key_update(KeyUpdateType::REQUESTED).unwrap();
+    assert_eq!(s.ssl().key_update_pending(), Some(KeyUpdateType::REQUESTED));
+    s.write_all(&[1]).unwrap();
+    assert_eq!(s.ssl().key_update_pending(), None);
+
+    let mut buf = [0];
+    s.read_exact(&mut buf).unwrap();
+    assert_eq!(buf, [2]);
+}
+
+#[test]
+#[cfg(ossl111)]
+fn post_handshake_auth() {
+    static VERIFIED: AtomicBool = AtomicBool::new(false);
+
+    let mut server = Server::builder();
+    server.ctx().set_verify_callback(
+        SslVerifyMode::PEER | SslVerifyMode::POST_HANDSHAKE,
+        |_, _| {
+            VERIFIED.store(true, Ordering::SeqCst);
+            true
+        },
+    );
+    server.io_cb(|mut s| {
+        assert!(s.ssl().peer_certificate().is_none());
+        s.verify_client_post_handshake().unwrap();
+        s.write_all(&[1]).unwrap();
+        s.read_exact(&mut [0]).unwrap();
+        assert!(s.ssl().peer_certificate().is_some());
+    });
+    let server = server.build();
+
+    let mut client = server.client();
+    client.ctx().set_post_handshake_auth(true);
+    client
+        .ctx()
+        .set_certificate_file("test/cert.pem", SslFiletype::PEM)
+        .unwrap();
+    client
+        .ctx()
+        .set_private_key_file("test/key.pem", SslFiletype::PEM)
+        .unwrap();
+    let mut s = client.connect();
+    assert!(!VERIFIED.load(Ordering::SeqCst));
+
+    s.read_exact(&mut [0]).unwrap();
+    s.write_all(&[2]).unwrap();
+    drop(server);
+
+    assert!(VERIFIED.load(Ordering::SeqCst));
+}
+
+#[test]
+#[cfg(not(boringssl))]
+fn status_callbacks() {
+    static CALLED_BACK_SERVER: AtomicBool = AtomicBool::new(false);
+    static CALLED_BACK_CLIENT: AtomicBool = AtomicBool::new(false);
+
+    let mut server = Server::builder();
+    server
+        .ctx()
+        .set_status_callback(|ssl| {
+            CALLED_BACK_SERVER.store(true, Ordering::SeqCst);
+            let response = OcspResponse::create(OcspResponseStatus::UNAUTHORIZED, None).unwrap();
+            let response = response.to_der().unwrap();
+            ssl.set_ocsp_status(&response).unwrap();
+            Ok(true)
+        })
+        .unwrap();
+
+    let server = server.build();
+
+    let mut client = server.client();
+    client
+        .ctx()
+        .set_status_callback(|ssl| {
+            CALLED_BACK_CLIENT.store(true, Ordering::SeqCst);
+            let response = OcspResponse::from_der(ssl.ocsp_status().unwrap()).unwrap();
+            assert_eq!(response.status(), OcspResponseStatus::UNAUTHORIZED);
+            Ok(true)
+        })
+        .unwrap();
+
+    let mut client = client.build().builder();
+    client.ssl().set_status_type(StatusType::OCSP).unwrap();
+
+    client.connect();
+
+    assert!(CALLED_BACK_SERVER.load(Ordering::SeqCst));
+    assert!(CALLED_BACK_CLIENT.load(Ordering::SeqCst));
+}
+
+/// possible LibreSSL bug since 3.2.1
+#[test]
+#[cfg_attr(libressl321, ignore)]
+fn new_session_callback() {
+    static CALLED_BACK: AtomicBool = AtomicBool::new(false);
+
+    let mut server = Server::builder();
+    server.ctx().set_session_id_context(b"foo").unwrap();
+
+    let server = server.build();
+
+    let mut client = server.client();
+
+    client
+        .ctx()
+        .set_session_cache_mode(SslSessionCacheMode::CLIENT | SslSessionCacheMode::NO_INTERNAL);
+    client
+        .ctx()
+        .set_new_session_callback(|_, _| CALLED_BACK.store(true, Ordering::SeqCst));
+
+    client.connect();
+
+    assert!(CALLED_BACK.load(Ordering::SeqCst));
+}
+
+/// possible LibreSSL bug since 3.2.1
+#[test]
+#[cfg_attr(libressl321, ignore)]
+fn new_session_callback_swapped_ctx() {
+    static CALLED_BACK: AtomicBool = AtomicBool::new(false);
+
+    let mut server = Server::builder();
+    server.ctx().set_session_id_context(b"foo").unwrap();
+
+    let server = server.build();
+
+    let mut client = server.client();
+
+    client
+        .ctx()
+        .set_session_cache_mode(SslSessionCacheMode::CLIENT | SslSessionCacheMode::NO_INTERNAL);
+    client
+        .ctx()
+        .set_new_session_callback(|_, _| CALLED_BACK.store(true, Ordering::SeqCst));
+
+    let mut client = client.build().builder();
+
+    let ctx = SslContextBuilder::new(SslMethod::tls()).unwrap().build();
+    client.ssl().set_ssl_context(&ctx).unwrap();
+
+    client.connect();
+
+    assert!(CALLED_BACK.load(Ordering::SeqCst));
+}
+
+#[test]
+#[cfg(not(boringssl))]
+fn session_id_context_client_auth_resumption() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let guard = thread::spawn(move || {
+        let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+        ctx.set_certificate_file(Path::new("test/cert.pem"), SslFiletype::PEM)
+            .unwrap();
+        ctx.set_private_key_file(Path::new("test/key.pem"), SslFiletype::PEM)
+            .unwrap();
+        ctx.set_max_proto_version(Some(SslVersion::TLS1_2)).unwrap();
+        ctx.set_verify_callback(
+            SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT,
+            |_, _| true,
+        );
+        let ctx = ctx.build();
+
+        for _ in 0..2 {
+            let stream = listener.accept().unwrap().0;
+            let mut ssl = Ssl::new(&ctx).unwrap();
+            ssl.set_session_id_context(b"foo").unwrap();
+            let mut stream = ssl.accept(stream).unwrap();
+            #[cfg(ossl110)]
+            assert_eq!(stream.ssl().session().unwrap().id_context(), b"foo");
+            stream.write_all(&[0]).unwrap();
+        }
+    });
+
+    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    ctx.set_certificate_file(Path::new("test/cert.pem"), SslFiletype::PEM)
+        .unwrap();
+    ctx.set_private_key_file(Path::new("test/key.pem"), SslFiletype::PEM)
+        .unwrap();
+    let ctx = ctx.build();
+
+    let ssl = Ssl::new(&ctx).unwrap();
+    let mut stream = ssl.connect(TcpStream::connect(addr).unwrap()).unwrap();
+    stream.read_exact(&mut [0]).unwrap();
+    assert!(!stream.ssl().session_reused());
+    let session = stream.ssl().session().unwrap().to_owned();
+
+    let mut ssl = Ssl::new(&ctx).unwrap();
+    unsafe { ssl.set_session(&session).unwrap() };
+    let mut stream = ssl.connect(TcpStream::connect(addr).unwrap()).unwrap();
+    stream.read_exact(&mut [0]).unwrap();
+    assert!(stream.ssl().session_reused());
+
+    guard.join().unwrap();
+}
+
+#[test]
+#[cfg(ossl111)]
+fn recv_max_early_data() {
+    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    ctx.set_recv_max_early_data(1024).unwrap();
+    let ctx = ctx.build();
+    assert_eq!(ctx.recv_max_early_data(), 1024);
+
+    let mut ssl = Ssl::new(&ctx).unwrap();
+    assert_eq!(ssl.recv_max_early_data(), 1024);
+    ssl.set_recv_max_early_data(2048).unwrap();
+    assert_eq!(ssl.recv_max_early_data(), 2048);
+}
+
+#[test]
+#[cfg(ossl111)]
+fn early_data_anti_replay() {
+    // Resumes a session twice, sending early data each time, and returns whether the server
+    // accepted the early data on each resumption.
+    fn replay(anti_replay: bool) -> Vec<bool> {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        let guard = thread::spawn(move || {
+            let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+            ctx.set_certificate_file(Path::new("test/cert.pem"), SslFiletype::PEM)
+                .unwrap();
+            ctx.set_private_key_file(Path::new("test/key.pem"), SslFiletype::PEM)
+                .unwrap();
+            ctx.set_max_early_data(1024).unwrap();
+            ctx.set_anti_replay_enabled(anti_replay);
+            let ctx = ctx.build();
+
+            for _ in 0..3 {
+                let stream = listener.accept().unwrap().0;
+                let mut ssl = Ssl::new(&ctx).unwrap();
+                ssl.set_accept_state();
+                let mut stream = SslStream::new(ssl, stream).unwrap();
+                let mut buf = [0; 16];
+                let n = stream.read_early_data(&mut buf).unwrap();
+                stream.accept().unwrap();
+                stream.write_all(&[n as u8]).unwrap();
+                // Sessions of connections which are not shut down are removed from the cache.
+                stream.shutdown().unwrap();
+            }
+        });
+
+        let ctx = SslContext::builder(SslMethod::tls()).unwrap().build();
+
+        let ssl = Ssl::new(&ctx).unwrap();
+        let mut stream = ssl.connect(TcpStream::connect(addr).unwrap()).unwrap();
+        stream.read_exact(&mut [0]).unwrap();
+        let session = stream.ssl().session().unwrap().to_owned();
+        assert_eq!(session.max_early_data(), 1024);
+
+        let mut accepted = vec![];
+        for _ in 0..2 {
+            let mut ssl = Ssl::new(&ctx).unwrap();
+            unsafe { ssl.set_session(&session).unwrap() };
+            ssl.set_connect_state();
+            let mut stream = SslStream::new(ssl, TcpStream::connect(addr).unwrap()).unwrap();
+            stream.write_early_data(b"hello").unwrap();
+            stream.connect().unwrap();
+            let mut n = [0];
+            stream.read_exact(&mut n).unwrap();
+            accepted.push(n[0] == 5);
+        }
+
+        guard.join().unwrap();
+        accepted
+    }
+
+    assert_eq!(replay(true), [true, false]);
+    assert_eq!(replay(false), [true, true]);
+}
+
+#[test]
+#[cfg(ossl111)]
+fn num_tickets() {
+    static CALLED_BACK: AtomicBool = AtomicBool::new(false);
+
+    let mut server = Server::builder();
+    server.ctx().set_session_id_context(b"foo").unwrap();
+    server.ctx().set_num_tickets(0).unwrap();
+    server.ssl_cb(|s| {
+        assert_eq!(s.num_tickets(), 0);
+        s.set_num_tickets(0).unwrap();
+    });
+
+    let server = server.build();
+
+    let mut client = server.client();
+
+    client
+        .ctx()
+        .set_session_cache_mode(SslSessionCacheMode::CLIENT | SslSessionCacheMode::NO_INTERNAL);
+    client
+        .ctx()
+        .set_new_session_callback(|_, _| CALLED_BACK.store(true, Ordering::SeqCst));
+
+    let s = client.connect();
+    assert_eq!(s.ssl().version2(), Some(SslVersion::TLS1_3));
+
+    assert!(!CALLED_BACK.load(Ordering::SeqCst));
+
+    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    ctx.set_num_tickets(5).unwrap();
+    assert_eq!(ctx.build().num_tickets(), 5);
+}
+
+#[test]
+#[cfg(ossl110)]
+fn security_level() {
+    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    ctx.set_security_level(0);
+    assert_eq!(ctx.security_level(), 0);
+    let ctx = ctx.build();
+    assert_eq!(ctx.security_level(), 0);
+
+    let mut ssl = Ssl::new(&ctx).unwrap();
+    assert_eq!(ssl.security_level(), 0);
+    ssl.set_security_level(3);
+    assert_eq!(ssl.security_level(), 3);
+}
+
+#[test]
+fn cipher_info() {
+    let server = Server::builder().build();
+
+    let s = server.client().connect();
+    let cipher = s.ssl().current_cipher().unwrap();
+    assert!(!cipher.name().is_empty());
+    assert!(cipher.bits().secret > 0);
+    assert!(cipher.description().contains(cipher.name()));
+    #[cfg(ossl111)]
+    assert!(cipher.standard_name().unwrap().starts_with("TLS_"));
+
+    let ciphers = s.ssl().ciphers().unwrap();
+    assert!(ciphers.iter().any(|c| c.name() == cipher.name()));
+}
+
+#[test]
+#[cfg(ossl110)]
+fn context_ciphers() {
+    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    ctx.set_cipher_list("ECDHE-RSA-AES128-GCM-SHA256").unwrap();
+    let ctx = ctx.build();
+
+    let names = ctx
+        .ciphers()
+        .unwrap()
+        .iter()
+        .map(|c| c.name())
+        .collect::<Vec<_>>();
+    assert!(names.contains(&"ECDHE-RSA-AES128-GCM-SHA256"));
+    assert!(!names.contains(&"ECDHE-RSA-AES256-GCM-SHA384"));
+}
+
+#[test]
+fn peer_cert_chain() {
+    let server = Server::builder().build();
+
+    let mut client = server.client();
+    client.ctx().set_ca_file("test/root-ca.pem").unwrap();
+    client
+        .ctx()
+        .set_verify_callback(SslVerifyMode::PEER, |_, _| true);
+
+    let s = client.connect();
+    let leaf = X509::from_pem(CERT).unwrap();
+
+    let chain = s.ssl().peer_cert_chain().unwrap();
+    assert_eq!(chain.len(), 1);
+    assert_eq!(chain[0].to_der().unwrap(), leaf.to_der().unwrap());
+
+    #[cfg(ossl110)]
+    {
+        let root = X509::from_pem(ROOT_CERT).unwrap();
+        let chain = s.ssl().verified_chain().unwrap();
+        assert_eq!(chain.len(), 2);
+        assert_eq!(chain[0].to_der().unwrap(), leaf.to_der().unwrap());
+        assert_eq!(chain[1].to_der().unwrap(), root.to_der().unwrap());
+    }
+}
+
+#[test]
+#[cfg(ossl111)]
+fn sigalgs() {
+    use crate::nid::Nid;
+
+    let mut server = Server::builder();
+    server.ctx().set_sigalgs_list("RSA-PSS+SHA256").unwrap();
+    server.io_cb(|s| {
+        let sigalgs = s.ssl().shared_sigalgs();
+        assert_eq!(sigalgs.len(), 1);
+        assert_eq!(sigalgs[0].sign, Nid::RSASSAPSS);
+        assert_eq!(sigalgs[0].hash, Some(Nid::SHA256));
+        assert_eq!(sigalgs[0].sign_hash, None);
+        assert_eq!(sigalgs[0].code, 0x0804);
+    });
+    let server = server.build();
+
+    let mut client = server.client();
+    client
+        .ctx()
+        .set_client_sigalgs_list("RSA-PSS+SHA256:ECDSA+SHA256")
+        .unwrap();
+    let s = client.connect();
+    assert_eq!(s.ssl().peer_signature_type_nid(), Some(Nid::RSASSAPSS));
+    assert_eq!(s.ssl().peer_signature_nid(), Some(Nid::SHA256));
+}
+
+#[test]
+#[cfg(ossl111)]
+fn groups() {
+    use crate::nid::Nid;
+
+    let x25519 = Nid::from_raw(ffi::NID_X25519);
+
+    let mut server = Server::builder();
+    server.ctx().set_groups_list("P-256:X25519").unwrap();
+    server.io_cb(move |s| {
+        assert_eq!(s.ssl().peer_groups(), [x25519, Nid::X9_62_PRIME256V1]);
+        assert_eq!(s.ssl().shared_groups(), [x25519, Nid::X9_62_PRIME256V1]);
+    });
+    let server = server.build();
+
+    let mut client = server.client();
+    client.ctx().set_groups_list("X25519:P-256").unwrap();
+    let _s = client.connect();
+    #[cfg(ossl300)]
+    assert_eq!(_s.ssl().negotiated_group(), Some(x25519));
+}
+
+#[test]
+fn msg_callback() {
+    static SERVER_SAW_CLIENT_HELLO: AtomicBool = AtomicBool::new(false);
+    static CLIENT_SENT_CLIENT_HELLO: AtomicBool = AtomicBool::new(false);
+
+    let mut server = Server::builder();
+    server
+        .ctx()
+        .set_msg_callback(|_, write, _, content_type, buf| {
+            if !write && content_type == SslContentType::HANDSHAKE && buf.first() == Some(&1) {
+                SERVER_SAW_CLIENT_HELLO.store(true, Ordering::SeqCst);
+            }
+        });
+    let server = server.build();
+
+    let mut client = server.client().build().builder();
+    client
+        .ssl()
+        .set_msg_callback(|_, write, _, content_type, buf| {
+            if write && content_type == SslContentType::HANDSHAKE && buf.first() == Some(&1) {
+                CLIENT_SENT_CLIENT_HELLO.store(true, Ordering::SeqCst);
+            }
+        });
+    client.connect();
+
+    assert!(SERVER_SAW_CLIENT_HELLO.load(Ordering::SeqCst));
+    assert!(CLIENT_SENT_CLIENT_HELLO.load(Ordering::SeqCst));
+}
+
+#[test]
+fn msg_callback_context_switch() {
+    static SERVER_SAW_CLIENT_HELLO: AtomicBool = AtomicBool::new(false);
+
+    let mut server = Server::builder();
+    server
+        .ctx()
+        .set_msg_callback(|_, write, _, content_type, buf| {
+            if !write && content_type == SslContentType::HANDSHAKE && buf.first() == Some(&1) {
+                SERVER_SAW_CLIENT_HELLO.store(true, Ordering::SeqCst);
+            }
+        });
+    // Switch to a context without a message callback, as an SNI callback would.
+    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    ctx.set_certificate_chain_file("test/cert.pem").unwrap();
+    ctx.set_private_key_file("test/key.pem", SslFiletype::PEM)
+        .unwrap();
+    let ctx = ctx.build();
+    server.ssl_cb(move |ssl| {
+        ssl.set_ssl_context(&ctx).unwrap();
+    });
+    let server = server.build();
+
+    server.client().connect();
+
+    assert!(SERVER_SAW_CLIENT_HELLO.load(Ordering::SeqCst));
+}
+
+#[test]
+#[cfg(ossl320)]
+fn raw_public_key() {
+    use crate::ssl::CertificateType;
+
+    let cert = X509::from_pem(CERT).unwrap();
+    let key = cert.public_key().unwrap();
+
+    let mut server = Server::builder();
     server
         .ctx()
-        .set_status_callback(|ssl| {
-            CALLED_BACK_SERVER.store(true, Ordering::SeqCst);
-            let response = OcspResponse::create(OcspResponseStatus::UNAUTHORIZED, None).unwrap();
-            let response = response.to_der().unwrap();
-            ssl.set_ocsp_status(&response).unwrap();
-            Ok(true)
-        })
+        .set_server_cert_types(&[CertificateType::RPK, CertificateType::X509])
+        .unwrap();
+    server.ssl_cb(|ssl| {
+        assert_eq!(ssl.negotiated_server_cert_type(), CertificateType::X509);
+    });
+    let server = server.build();
+
+    let mut client = server.client();
+    client.ctx().dane_enable().unwrap();
+    client.ctx().set_verify(SslVerifyMode::PEER);
+    client
+        .ctx()
+        .set_server_cert_types(&[CertificateType::RPK])
+        .unwrap();
+    let mut client = client.build().builder();
+    client.ssl().dane_enable("example.com").unwrap();
+    client.ssl().add_expected_rpk(&key).unwrap();
+
+    let s = client.connect();
+    assert_eq!(s.ssl().negotiated_server_cert_type(), CertificateType::RPK);
+    assert_eq!(s.ssl().negotiated_client_cert_type(), CertificateType::X509);
+    assert!(s.ssl().peer_certificate().is_none());
+    assert!(s.ssl().peer_rpk().unwrap().public_eq(&key));
+    assert!(s
+        .ssl()
+        .session()
+        .unwrap()
+        .peer_rpk()
+        .unwrap()
+        .public_eq(&key));
+}
+
+#[test]
+#[cfg(ossl110)]
+fn dane() {
+    use crate::hash;
+    use crate::ssl::DaneFlags;
+
+    let cert = X509::from_pem(CERT).unwrap();
+    let digest = hash::hash(MessageDigest::sha256(), &cert.to_der().unwrap()).unwrap();
+
+    let server = Server::builder().build();
+
+    let mut client = server.client();
+    client.ctx().dane_enable().unwrap();
+    client.ctx().set_verify(SslVerifyMode::PEER);
+    let mut client = client.build().builder();
+    client.ssl().dane_enable("example.com").unwrap();
+    client
+        .ssl()
+        .set_dane_flags(DaneFlags::NO_DANE_EE_NAMECHECKS);
+    assert!(client.ssl().dane_tlsa_add(3, 0, 1, &digest).unwrap());
+
+    let s = client.connect();
+    let tlsa = s.ssl().dane_tlsa().unwrap();
+    assert_eq!(tlsa.depth, 0);
+    assert_eq!(tlsa.usage, 3);
+    assert_eq!(tlsa.data, &*digest);
+    assert_eq!(
+        s.ssl().dane_authority().unwrap().to_der().unwrap(),
+        cert.to_der().unwrap()
+    );
+}
+
+#[test]
+#[cfg(ossl110)]
+fn dane_mismatch() {
+    let mut server = Server::builder();
+    server.should_error();
+    let server = server.build();
+
+    let mut client = server.client();
+    client.ctx().dane_enable().unwrap();
+    client.ctx().set_verify(SslVerifyMode::PEER);
+    let mut client = client.build().builder();
+    client.ssl().dane_enable("foobar.com").unwrap();
+    client.ssl().dane_tlsa_add(3, 0, 1, &[0; 32]).unwrap();
+
+    client.connect_err();
+}
+
+#[test]
+#[cfg(ossl110)]
+fn ct_permissive() {
+    use crate::ssl::CtValidationMode;
+    use crate::x509::verify::X509VerifyFlags;
+
+    let server = Server::builder().build();
+
+    let mut client = server.client();
+    client.ctx().set_ca_file("test/root-ca.pem").unwrap();
+    client
+        .ctx()
+        .verify_param_mut()
+        .set_flags(X509VerifyFlags::NO_CHECK_TIME)
+        .unwrap();
+    client.ctx().set_verify(SslVerifyMode::PEER);
+    client
+        .ctx()
+        .enable_ct(CtValidationMode::PERMISSIVE)
         .unwrap();
 
+    let s = client.connect();
+    assert!(s.ssl().ct_is_enabled());
+    assert_eq!(s.ssl().peer_scts().map_or(0, |scts| scts.len()), 0);
+}
+
+#[test]
+#[cfg(ossl110)]
+fn ct_strict() {
+    use crate::ssl::CtValidationMode;
+    use crate::x509::verify::X509VerifyFlags;
+
+    let mut server = Server::builder();
+    server.should_error();
     let server = server.build();
 
     let mut client = server.client();
+    client.ctx().set_ca_file("test/root-ca.pem").unwrap();
     client
         .ctx()
-        .set_status_callback(|ssl| {
-            CALLED_BACK_CLIENT.store(true, Ordering::SeqCst);
-            let response = OcspResponse::from_der(ssl.ocsp_status().unwrap()).unwrap();
-            assert_eq!(response.status(), OcspResponseStatus::UNAUTHORIZED);
-            Ok(true)
-        })
+        .verify_param_mut()
+        .set_flags(X509VerifyFlags::NO_CHECK_TIME)
         .unwrap();
+    client.ctx().set_verify(SslVerifyMode::PEER);
+    client.ctx().enable_ct(CtValidationMode::STRICT).unwrap();
 
-    let mut client = client.build().builder();
-    client.ssl().set_status_type(StatusType::OCSP).unwrap();
+    client.connect_err();
+}
 
-    client.connect();
+#[test]
+fn write_vectored() {
+    let mut server = Server::builder();
+    server.io_cb(|mut s| {
+        let mut buf = [0; 11];
+        s.read_exact(&mut buf).unwrap();
+        assert_eq!(&buf, b"hello world");
+    });
+    let server = server.build();
 
-    assert!(CALLED_BACK_SERVER.load(Ordering::SeqCst));
-    assert!(CALLED_BACK_CLIENT.load(Ordering::SeqCst));
+    let mut s = server.client().connect();
+    let bufs = [
+        io::IoSlice::new(b"hello"),
+        io::IoSlice::new(b""),
+        io::IoSlice::new(b" "),
+        io::IoSlice::new(b"world"),
+    ];
+    assert_eq!(s.write_vectored(&bufs).unwrap(), 11);
+    assert_eq!(s.write_vectored(&[]).unwrap(), 0);
 }
 
-/// possible LibreSSL bug since 3.2.1
 #[test]
-#[cfg_attr(libressl321, ignore)]
-fn new_session_callback() {
-    static CALLED_BACK: AtomicBool = AtomicBool::new(false);
+fn ssl_connection() {
+    use crate::ssl::SslConnection;
 
-    let mut server = Server::builder();
-    server.ctx().set_session_id_context(b"foo").unwrap();
+    fn transfer(from: &mut SslConnection, to: &mut SslConnection) {
+        let mut buf = vec![];
+        while from.pending_tls() > 0 {
+            from.write_tls(&mut buf).unwrap();
+        }
+        let mut rd = &buf[..];
+        while !rd.is_empty() {
+            to.read_tls(&mut rd).unwrap();
+        }
+    }
 
-    let server = server.build();
+    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    ctx.set_certificate_file(Path::new("test/cert.pem"), SslFiletype::PEM)
+        .unwrap();
+    ctx.set_private_key_file(Path::new("test/key.pem"), SslFiletype::PEM)
+        .unwrap();
+    let mut server = Ssl::new(&ctx.build()).unwrap();
+    server.set_accept_state();
+    let mut server = SslConnection::new(server).unwrap();
 
-    let mut client = server.client();
+    let ctx = SslContext::builder(SslMethod::tls()).unwrap().build();
+    let mut client = Ssl::new(&ctx).unwrap();
+    client.set_connect_state();
+    let mut client = SslConnection::new(client).unwrap();
+
+    let mut client_done = false;
+    let mut server_done = false;
+    while !client_done || !server_done {
+        match client.do_handshake() {
+            Ok(()) => client_done = true,
+            Err(e) => assert_eq!(e.code(), ssl::ErrorCode::WANT_READ),
+        }
+        transfer(&mut client, &mut server);
+        match server.do_handshake() {
+            Ok(()) => server_done = true,
+            Err(e) => assert_eq!(e.code(), ssl::ErrorCode::WANT_READ),
+        }
+        transfer(&mut server, &mut client);
+    }
 
+    let mut buf = [0; 5];
+    assert_eq!(
+        server.ssl_read(&mut buf).unwrap_err().code(),
+        ssl::ErrorCode::WANT_READ
+    );
+
+    assert_eq!(client.ssl_write(b"hello").unwrap(), 5);
+    transfer(&mut client, &mut server);
+    assert_eq!(server.ssl_read(&mut buf).unwrap(), 5);
+    assert_eq!(&buf, b"hello");
+}
+
+#[test]
+fn bio_pair() {
+    use crate::ssl::BioPair;
+
+    let (mut a, mut b) = BioPair::new(0).unwrap();
+    let mut buf = [0; 5];
+    assert_eq!(
+        b.read(&mut buf).unwrap_err().kind(),
+        io::ErrorKind::WouldBlock
+    );
+
+    a.write_all(b"hello").unwrap();
+    assert_eq!(b.pending(), 5);
+    b.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+
+    a.shutdown_write();
+    assert_eq!(b.read(&mut buf).unwrap(), 0);
+}
+
+#[test]
+fn bio_pair_stream() {
+    use crate::ssl::BioPair;
+
+    let (client_bio, server_bio) = BioPair::new(0).unwrap();
+
+    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    ctx.set_certificate_file(Path::new("test/cert.pem"), SslFiletype::PEM)
+        .unwrap();
+    ctx.set_private_key_file(Path::new("test/key.pem"), SslFiletype::PEM)
+        .unwrap();
+    let server = Ssl::new(&ctx.build()).unwrap();
+    let mut server = SslStream::new(server, server_bio).unwrap();
+
+    let ctx = SslContext::builder(SslMethod::tls()).unwrap().build();
+    let client = Ssl::new(&ctx).unwrap();
+    let mut client = SslStream::new(client, client_bio).unwrap();
+
+    let mut client_done = false;
+    let mut server_done = false;
+    while !client_done || !server_done {
+        if !client_done {
+            match client.connect() {
+                Ok(()) => client_done = true,
+                Err(e) => assert_eq!(e.code(), ssl::ErrorCode::WANT_READ),
+            }
+        }
+        if !server_done {
+            match server.accept() {
+                Ok(()) => server_done = true,
+                Err(e) => assert_eq!(e.code(), ssl::ErrorCode::WANT_READ),
+            }
+        }
+    }
+
+    client.write_all(b"hello").unwrap();
+    let mut buf = [0; 5];
+    server.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"hello");
+}
+
+#[test]
+#[cfg(ossl350)]
+fn quic_tls() {
+    use crate::ssl::{QuicProtectionLevel, QuicTlsCallbacks, SslConnection};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Default)]
+    struct Endpoint {
+        write_level: Option<QuicProtectionLevel>,
+        outgoing: Vec<(QuicProtectionLevel, Vec<u8>)>,
+        secrets: Vec<(QuicProtectionLevel, bool)>,
+        peer_params: Vec<u8>,
+    }
+
+    struct Callbacks(Arc<Mutex<Endpoint>>);
+
+    impl QuicTlsCallbacks for Callbacks {
+        fn send_crypto_data(&mut self, data: &[u8]) -> Result<(), ErrorStack> {
+            let mut endpoint = self.0.lock().unwrap();
+            let level = endpoint.write_level.unwrap_or(QuicProtectionLevel::NONE);
+            endpoint.outgoing.push((level, data.to_vec()));
+            Ok(())
+        }
+
+        fn set_secret(
+            &mut self,
+            level: QuicProtectionLevel,
+            write: bool,
+            secret: &[u8],
+        ) -> Result<(), ErrorStack> {
+            assert!(!secret.is_empty());
+            let mut endpoint = self.0.lock().unwrap();
+            if write {
+                endpoint.write_level = Some(level);
+            }
+            endpoint.secrets.push((level, write));
+            Ok(())
+        }
+
+        fn transport_params(&mut self, params: &[u8]) -> Result<(), ErrorStack> {
+            self.0.lock().unwrap().peer_params = params.to_vec();
+            Ok(())
+        }
+
+        fn alert(&mut self, alert: u8) -> Result<(), ErrorStack> {
+            panic!("unexpected alert {}", alert);
+        }
+    }
+
+    fn transfer(from: &Mutex<Endpoint>, to: &mut SslConnection) {
+        let outgoing = mem::take(&mut from.lock().unwrap().outgoing);
+        for (level, data) in outgoing {
+            to.ssl_mut().provide_quic_data(level, &data).unwrap();
+        }
+    }
+
+    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    ctx.set_certificate_file(Path::new("test/cert.pem"), SslFiletype::PEM)
+        .unwrap();
+    ctx.set_private_key_file(Path::new("test/key.pem"), SslFiletype::PEM)
+        .unwrap();
+    ctx.set_alpn_select_callback(|_, client| {
+        ssl::select_next_proto(b"\x02hq", client).ok_or(ssl::AlpnError::NOACK)
+    });
+    let mut server = Ssl::new(&ctx.build()).unwrap();
+    server.set_accept_state();
+    let server_state = Arc::new(Mutex::new(Endpoint::default()));
+    server
+        .set_quic_tls(Callbacks(server_state.clone()))
+        .unwrap();
+    server.set_quic_transport_params(b"server params").unwrap();
+    let mut server = SslConnection::new(server).unwrap();
+
+    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    ctx.set_alpn_protos(b"\x02hq").unwrap();
+    let mut client = Ssl::new(&ctx.build()).unwrap();
+    client.set_connect_state();
+    let client_state = Arc::new(Mutex::new(Endpoint::default()));
     client
-        .ctx()
-        .set_session_cache_mode(SslSessionCacheMode::CLIENT | SslSessionCacheMode::NO_INTERNAL);
-    client
-        .ctx()
-        .set_new_session_callback(|_, _| CALLED_BACK.store(true, Ordering::SeqCst));
+        .set_quic_tls(Callbacks(client_state.clone()))
+        .unwrap();
+    client.set_quic_transport_params(b"client params").unwrap();
+    let mut client = SslConnection::new(client).unwrap();
+
+    let mut client_done = false;
+    let mut server_done = false;
+    while !client_done || !server_done {
+        match client.do_handshake() {
+            Ok(()) => client_done = true,
+            Err(e) => assert_eq!(e.code(), ssl::ErrorCode::WANT_READ),
+        }
+        transfer(&client_state, &mut server);
+        match server.do_handshake() {
+            Ok(()) => server_done = true,
+            Err(e) => assert_eq!(e.code(), ssl::ErrorCode::WANT_READ),
+        }
+        transfer(&server_state, &mut client);
+    }
 
-    client.connect();
+    assert_eq!(client.ssl().version2(), Some(SslVersion::TLS1_3));
+    assert_eq!(client_state.lock().unwrap().peer_params, b"server params");
+    assert_eq!(server_state.lock().unwrap().peer_params, b"client params");
+    for state in &[client_state, server_state] {
+        let secrets = &state.lock().unwrap().secrets;
+        assert!(secrets.contains(&(QuicProtectionLevel::HANDSHAKE, true)));
+        assert!(secrets.contains(&(QuicProtectionLevel::HANDSHAKE, false)));
+        assert!(secrets.contains(&(QuicProtectionLevel::APPLICATION, true)));
+        assert!(secrets.contains(&(QuicProtectionLevel::APPLICATION, false)));
+    }
+}
 
-    assert!(CALLED_BACK.load(Ordering::SeqCst));
+#[test]
+#[cfg(ossl350)]
+fn quic_tls_invalid_level() {
+    use crate::ssl::{QuicProtectionLevel, QuicTlsCallbacks};
+
+    struct Callbacks;
+
+    impl QuicTlsCallbacks for Callbacks {
+        fn send_crypto_data(&mut self, _: &[u8]) -> Result<(), ErrorStack> {
+            Ok(())
+        }
+
+        fn set_secret(
+            &mut self,
+            _: QuicProtectionLevel,
+            _: bool,
+            _: &[u8],
+        ) -> Result<(), ErrorStack> {
+            Ok(())
+        }
+
+        fn transport_params(&mut self, _: &[u8]) -> Result<(), ErrorStack> {
+            Ok(())
+        }
+
+        fn alert(&mut self, _: u8) -> Result<(), ErrorStack> {
+            Ok(())
+        }
+    }
+
+    let ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    let mut ssl = Ssl::new(&ctx.build()).unwrap();
+    ssl.set_quic_tls(Callbacks).unwrap();
+
+    ssl.provide_quic_data(QuicProtectionLevel::HANDSHAKE, b"data")
+        .unwrap();
+    let error = ssl
+        .provide_quic_data(QuicProtectionLevel::from_raw(4), b"data")
+        .unwrap_err();
+    assert_eq!(error.errors()[0].reason(), Some("bad value"));
 }
 
-/// possible LibreSSL bug since 3.2.1
 #[test]
-#[cfg_attr(libressl321, ignore)]
-fn new_session_callback_swapped_ctx() {
-    static CALLED_BACK: AtomicBool = AtomicBool::new(false);
+#[cfg(ossl350)]
+fn quic_tls_set_twice() {
+    use crate::ssl::{QuicProtectionLevel, QuicTlsCallbacks};
 
-    let mut server = Server::builder();
-    server.ctx().set_session_id_context(b"foo").unwrap();
+    struct Callbacks;
+
+    impl QuicTlsCallbacks for Callbacks {
+        fn send_crypto_data(&mut self, _: &[u8]) -> Result<(), ErrorStack> {
+            Ok(())
+        }
+
+        fn set_secret(
+            &mut self,
+            _: QuicProtectionLevel,
+            _: bool,
+            _: &[u8],
+        ) -> Result<(), ErrorStack> {
+            Ok(())
+        }
+
+        fn transport_params(&mut self, _: &[u8]) -> Result<(), ErrorStack> {
+            Ok(())
+        }
+
+        fn alert(&mut self, _: u8) -> Result<(), ErrorStack> {
+            Ok(())
+        }
+    }
 
+    let ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    let mut ssl = Ssl::new(&ctx.build()).unwrap();
+    ssl.set_quic_tls(Callbacks).unwrap();
+    ssl.set_quic_transport_params(b"params").unwrap();
+
+    let error = ssl.set_quic_tls(Callbacks).unwrap_err();
+    assert_eq!(error.errors()[0].reason(), Some("bad value"));
+    ssl.provide_quic_data(QuicProtectionLevel::HANDSHAKE, b"data")
+        .unwrap();
+}
+
+#[test]
+fn ssl_verify_mode() {
+    let mut server = Server::builder();
+    server.ssl_cb(|ssl| {
+        ssl.set_verify(SslVerifyMode::PEER | SslVerifyMode::FAIL_IF_NO_PEER_CERT);
+        assert_eq!(ssl.ssl_context().verify_mode(), SslVerifyMode::NONE);
+    });
+    server.should_error();
     let server = server.build();
 
     let mut client = server.client();
+    // In TLS 1.3 the client completes its side of the handshake before the server rejects it
+    #[cfg(any(ossl111, libressl340))]
+    client.ctx().set_options(super::SslOptions::NO_TLSV1_3);
+    client.connect_err();
+}
 
-    client
-        .ctx()
-        .set_session_cache_mode(SslSessionCacheMode::CLIENT | SslSessionCacheMode::NO_INTERNAL);
-    client
-        .ctx()
-        .set_new_session_callback(|_, _| CALLED_BACK.store(true, Ordering::SeqCst));
+#[test]
+fn ssl_verify_depth() {
+    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    ctx.set_verify_depth(5);
+    let ctx = ctx.build();
+    assert_eq!(ctx.verify_depth(), 5);
 
-    let mut client = client.build().builder();
+    let mut ssl = Ssl::new(&ctx).unwrap();
+    assert_eq!(ssl.verify_depth(), 5);
+    ssl.set_verify_depth(0);
+    assert_eq!(ssl.verify_depth(), 0);
+    assert_eq!(ssl.ssl_context().verify_depth(), 5);
+}
 
-    let ctx = SslContextBuilder::new(SslMethod::tls()).unwrap().build();
-    client.ssl().set_ssl_context(&ctx).unwrap();
+#[test]
+#[cfg(ossl110)]
+fn ssl_conf() {
+    use crate::ssl::SslConfFlags;
 
-    client.connect();
+    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    let mut conf = ctx.conf(SslConfFlags::FILE | SslConfFlags::CLIENT).unwrap();
+    conf.cmd("MinProtocol", "TLSv1.2").unwrap();
+    conf.cmd("CipherString", "ECDHE+AESGCM").unwrap();
+    assert!(conf.cmd("NotACommand", "yes").is_err());
+    assert!(conf.cmd("MinProtocol", "SSLv1").is_err());
+    conf.finish().unwrap();
+    assert_eq!(ctx.min_proto_version(), Some(SslVersion::TLS1_2));
+
+    let mut conf = ctx.conf(SslConfFlags::CMDLINE).unwrap();
+    conf.cmd("-no_ticket", "").unwrap();
+    conf.finish().unwrap();
+    assert!(ctx.options().contains(SslOptions::NO_TICKET));
+}
 
-    assert!(CALLED_BACK.load(Ordering::SeqCst));
+#[test]
+#[cfg(ossl110)]
+fn ssl_conf_connection() {
+    use crate::ssl::SslConfFlags;
+
+    let server = Server::builder().build();
+
+    let mut client = server.client().build().builder();
+    let mut conf = client
+        .ssl()
+        .conf(SslConfFlags::FILE | SslConfFlags::CLIENT)
+        .unwrap();
+    conf.set_prefix("TLS").unwrap();
+    assert!(conf.cmd("MaxProtocol", "TLSv1.2").is_err());
+    conf.cmd("TLSMaxProtocol", "TLSv1.2").unwrap();
+    conf.finish().unwrap();
+
+    let s = client.connect();
+    assert_eq!(s.ssl().version2(), Some(SslVersion::TLS1_2));
 }
 
 #[test]
@@ -1104,9 +3367,116 @@ fn keying_export() {
 
     stream.read_exact(&mut [0]).unwrap();
 
-    let buf2 = guard.join().unwrap();
+    let buf2 = guard.join().unwrap();
+
+    assert_eq!(buf, buf2);
+}
+
+#[test]
+#[cfg(ossl111)]
+fn tmp_key() {
+    let mut server = Server::builder();
+    server.ctx().set_groups_list("X25519").unwrap();
+    server.io_cb(|mut s| {
+        let peer = s.ssl().peer_tmp_key().unwrap();
+        let local = s.ssl().tmp_key().unwrap();
+        assert_eq!(peer.id(), Id::X25519);
+        assert_eq!(local.id(), Id::X25519);
+        s.write_all(&peer.raw_public_key().unwrap()).unwrap();
+        s.write_all(&local.raw_public_key().unwrap()).unwrap();
+    });
+    let server = server.build();
+
+    let mut s = server.client().connect();
+    let mut client_key = [0; 32];
+    s.read_exact(&mut client_key).unwrap();
+    let mut server_key = [0; 32];
+    s.read_exact(&mut server_key).unwrap();
+
+    let peer = s.ssl().peer_tmp_key().unwrap();
+    assert_eq!(peer.id(), Id::X25519);
+    assert_eq!(peer.bits(), 253);
+    assert_eq!(peer.raw_public_key().unwrap(), server_key);
+    assert_eq!(
+        s.ssl().tmp_key().unwrap().raw_public_key().unwrap(),
+        client_key
+    );
+}
+
+#[test]
+#[cfg(any(ossl102, libressl261))]
+fn connection_info() {
+    let mut server = Server::builder();
+    server.ctx().set_alpn_select_callback(|_, client| {
+        ssl::select_next_proto(b"\x08http/1.1", client).ok_or(ssl::AlpnError::NOACK)
+    });
+    let server = server.build();
+
+    let mut client = server.client();
+    client.ctx().set_alpn_protos(b"\x08http/1.1").unwrap();
+    let s = client.connect();
+
+    let info = s.ssl().connection_info();
+    assert_eq!(info.version, s.ssl().version2());
+    assert_eq!(info.cipher, Some(s.ssl().current_cipher().unwrap().name()));
+    assert_eq!(info.alpn_protocol, Some(&b"http/1.1"[..]));
+    assert!(!info.session_reused);
+    assert_eq!(
+        info.peer_certificate.unwrap().to_der().unwrap(),
+        s.ssl().peer_certificate().unwrap().to_der().unwrap()
+    );
+}
+
+#[test]
+#[cfg(not(boringssl))]
+fn channel_binding() {
+    let mut server = Server::builder();
+    server
+        .ctx()
+        .set_max_proto_version(Some(SslVersion::TLS1_2))
+        .unwrap();
+    server.io_cb(|mut s| {
+        let unique = s.ssl().tls_unique_channel_binding().unwrap();
+        let exporter = s.ssl().tls_exporter_channel_binding().unwrap();
+        s.write_all(&[unique.len() as u8]).unwrap();
+        s.write_all(&unique).unwrap();
+        s.write_all(&exporter).unwrap();
+    });
+    let server = server.build();
+
+    let mut s = server.client().connect();
+    let mut len = [0];
+    s.read_exact(&mut len).unwrap();
+    let mut unique = vec![0; len[0] as usize];
+    s.read_exact(&mut unique).unwrap();
+    let mut exporter = [0; 32];
+    s.read_exact(&mut exporter).unwrap();
+
+    assert_eq!(s.ssl().tls_unique_channel_binding().unwrap(), unique);
+    let mut finished = vec![0; s.ssl().finished(&mut [])];
+    s.ssl().finished(&mut finished);
+    assert_eq!(finished, unique);
+    assert_eq!(s.ssl().tls_exporter_channel_binding().unwrap(), exporter);
+}
+
+#[test]
+#[cfg(ossl111)]
+fn tls13_channel_binding() {
+    let mut server = Server::builder();
+    server.io_cb(|mut s| {
+        assert_eq!(s.ssl().tls_unique_channel_binding(), None);
+        let exporter = s.ssl().tls_exporter_channel_binding().unwrap();
+        s.write_all(&exporter).unwrap();
+    });
+    let server = server.build();
+
+    let mut s = server.client().connect();
+    let mut exporter = [0; 32];
+    s.read_exact(&mut exporter).unwrap();
 
-    assert_eq!(buf, buf2);
+    assert_eq!(s.ssl().version2(), Some(SslVersion::TLS1_3));
+    assert_eq!(s.ssl().tls_unique_channel_binding(), None);
+    assert_eq!(s.ssl().tls_exporter_channel_binding().unwrap(), exporter);
 }
 
 #[test]
@@ -1135,6 +3505,119 @@ fn no_version_overlap() {
     client.connect_err();
 }
 
+#[test]
+#[cfg(not(boringssl))]
+fn ssl_no_version_overlap() {
+    let mut server = Server::builder();
+    server.ssl_cb(|ssl| {
+        ssl.set_min_proto_version(None).unwrap();
+        ssl.set_max_proto_version(Some(SslVersion::TLS1_1)).unwrap();
+        #[cfg(any(ossl110g, libressl270, not(any(ossl110, libressl261))))]
+        assert_eq!(ssl.max_proto_version(), Some(SslVersion::TLS1_1));
+    });
+    server.should_error();
+    let server = server.build();
+
+    let client = server.client();
+    let mut client = client.build().builder();
+    let ssl = client.ssl();
+    ssl.set_min_proto_version(Some(SslVersion::TLS1_2)).unwrap();
+    ssl.set_max_proto_version(None).unwrap();
+    #[cfg(any(ossl110g, libressl270, not(any(ossl110, libressl261))))]
+    {
+        assert_eq!(ssl.min_proto_version(), Some(SslVersion::TLS1_2));
+        assert_eq!(ssl.max_proto_version(), None);
+    }
+
+    client.connect_err();
+}
+
+#[test]
+#[cfg(ossl102)]
+fn cert_callback() {
+    static CALLED_BACK: AtomicBool = AtomicBool::new(false);
+
+    let mut server = Server::builder();
+    server.ctx().set_cert_callback(|ssl| {
+        CALLED_BACK.store(true, Ordering::SeqCst);
+        assert_eq!(ssl.servername(ssl::NameType::HOST_NAME), Some("foobar.com"));
+
+        // Replace the context's certificate with a different one.
+        let cert = include_bytes!("../../../test/root-ca.pem");
+        let cert = X509::from_pem(cert).unwrap();
+        let key = include_bytes!("../../../test/root-ca.key");
+        let key = PKey::private_key_from_pem(key).unwrap();
+        ssl.set_certificate(&cert)?;
+        ssl.set_private_key(&key)?;
+        Ok(CertCallbackResponse::SUCCESS)
+    });
+
+    let server = server.build();
+
+    let mut client = server.client().build().builder();
+    client.ssl().set_hostname("foobar.com").unwrap();
+    let s = client.connect();
+
+    assert!(CALLED_BACK.load(Ordering::SeqCst));
+    let peer = s.ssl().peer_certificate().unwrap();
+    let cert = X509::from_pem(include_bytes!("../../../test/root-ca.pem")).unwrap();
+    assert_eq!(peer.to_der().unwrap(), cert.to_der().unwrap());
+}
+
+#[test]
+#[cfg(ossl102)]
+fn cert_callback_error() {
+    let mut server = Server::builder();
+    server.ctx().set_cert_callback(|_| Err(ErrorStack::get()));
+    server.should_error();
+    let server = server.build();
+
+    server.client().connect_err();
+}
+
+#[test]
+#[cfg(ossl102)]
+fn cert_callback_retry() {
+    use std::sync::atomic::AtomicUsize;
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let guard = thread::spawn(move || {
+        let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+        ctx.set_cert_callback(|ssl| {
+            // The certificate isn't available on the first attempt.
+            if CALLS.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Ok(CertCallbackResponse::RETRY);
+            }
+
+            let cert = X509::from_pem(include_bytes!("../../../test/cert.pem")).unwrap();
+            let key = PKey::private_key_from_pem(include_bytes!("../../../test/key.pem")).unwrap();
+            ssl.set_certificate(&cert)?;
+            ssl.set_private_key(&key)?;
+            Ok(CertCallbackResponse::SUCCESS)
+        });
+        let ssl = Ssl::new(&ctx.build()).unwrap();
+
+        let stream = listener.accept().unwrap().0;
+        let mut stream = SslStream::new(ssl, stream).unwrap();
+        let error = stream.accept().unwrap_err();
+        assert_eq!(error.code(), ssl::ErrorCode::WANT_X509_LOOKUP);
+        stream.do_handshake().unwrap();
+        stream.write_all(&[0]).unwrap();
+    });
+
+    let ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    let ssl = Ssl::new(&ctx.build()).unwrap();
+    let mut stream = ssl.connect(TcpStream::connect(addr).unwrap()).unwrap();
+    stream.read_exact(&mut [0]).unwrap();
+
+    guard.join().unwrap();
+    assert_eq!(CALLS.load(Ordering::SeqCst), 2);
+}
+
 #[test]
 #[cfg(ossl111)]
 fn custom_extensions() {
@@ -1172,6 +3655,69 @@ fn custom_extensions() {
     assert!(FOUND_EXTENSION.load(Ordering::SeqCst));
 }
 
+#[test]
+#[cfg(ossl111)]
+fn custom_extensions_response() {
+    static SERVER_FOUND_EXTENSION: AtomicBool = AtomicBool::new(false);
+    static CLIENT_FOUND_EXTENSION: AtomicBool = AtomicBool::new(false);
+    static CLIENT_FOUND_CERT_EXTENSION: AtomicBool = AtomicBool::new(false);
+
+    let context = ExtensionContext::CLIENT_HELLO
+        | ExtensionContext::TLS1_3_ENCRYPTED_EXTENSIONS
+        | ExtensionContext::TLS1_3_CERTIFICATE;
+
+    let mut server = Server::builder();
+    server
+        .ctx()
+        .add_custom_ext(
+            12345,
+            context,
+            |_, ctx, cert| -> Result<Option<&'static [u8]>, _> {
+                if ctx.contains(ExtensionContext::TLS1_3_CERTIFICATE) {
+                    assert_eq!(cert.unwrap().0, 0);
+                    Ok(Some(b"cert"))
+                } else {
+                    assert!(cert.is_none());
+                    Ok(Some(b"world"))
+                }
+            },
+            |_, ctx, data, _| {
+                assert!(ctx.contains(ExtensionContext::CLIENT_HELLO));
+                SERVER_FOUND_EXTENSION.store(data == b"hello", Ordering::SeqCst);
+                Ok(())
+            },
+        )
+        .unwrap();
+
+    let server = server.build();
+
+    let mut client = server.client();
+    client
+        .ctx()
+        .add_custom_ext(
+            12345,
+            context,
+            |_, _, _| Ok(Some(b"hello")),
+            |_, ctx, data, cert| {
+                if ctx.contains(ExtensionContext::TLS1_3_CERTIFICATE) {
+                    assert!(cert.is_some());
+                    CLIENT_FOUND_CERT_EXTENSION.store(data == b"cert", Ordering::SeqCst);
+                } else {
+                    assert!(ctx.contains(ExtensionContext::TLS1_3_ENCRYPTED_EXTENSIONS));
+                    CLIENT_FOUND_EXTENSION.store(data == b"world", Ordering::SeqCst);
+                }
+                Ok(())
+            },
+        )
+        .unwrap();
+
+    client.connect();
+
+    assert!(SERVER_FOUND_EXTENSION.load(Ordering::SeqCst));
+    assert!(CLIENT_FOUND_EXTENSION.load(Ordering::SeqCst));
+    assert!(CLIENT_FOUND_CERT_EXTENSION.load(Ordering::SeqCst));
+}
+
 fn _check_kinds() {
     fn is_send<T: Send>() {}
     fn is_sync<T: Sync>() {}
@@ -1308,6 +3854,216 @@ fn stateless() {
     server_stream.do_handshake().unwrap();
 }
 
+#[test]
+fn mid_handshake_retry() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    stream.set_nonblocking(true).unwrap();
+
+    // the server isn't running yet, so the handshake is guaranteed to block
+    let ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    let ssl = Ssl::new(&ctx.build()).unwrap();
+    let mut mid = match ssl.connect(stream) {
+        Err(HandshakeError::WouldBlock(mid)) => mid,
+        Ok(_) => panic!("handshake completed without blocking"),
+        Err(e) => panic!("unexpected handshake error: {}", e),
+    };
+    assert_eq!(mid.code(), ssl::ErrorCode::WANT_READ);
+    mid.ssl_mut().set_verify(SslVerifyMode::NONE);
+
+    let guard = thread::spawn(move || {
+        let stream = listener.accept().unwrap().0;
+        let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+        ctx.set_certificate_file(Path::new("test/cert.pem"), SslFiletype::PEM)
+            .unwrap();
+        ctx.set_private_key_file(Path::new("test/key.pem"), SslFiletype::PEM)
+            .unwrap();
+        let ssl = Ssl::new(&ctx.build()).unwrap();
+        let mut stream = ssl.accept(stream).unwrap();
+        stream.write_all(&[0]).unwrap();
+    });
+
+    let mut stream = mid.into_stream();
+    loop {
+        match stream.do_handshake() {
+            Ok(()) => break,
+            Err(e) if e.code() == ssl::ErrorCode::WANT_READ => {
+                thread::sleep(Duration::from_millis(1))
+            }
+            Err(e) => panic!("unexpected handshake error: {}", e),
+        }
+    }
+    stream.get_mut().set_nonblocking(false).unwrap();
+    stream.read_exact(&mut [0]).unwrap();
+
+    guard.join().unwrap();
+}
+
+#[test]
+#[cfg(ossl300)]
+fn retry_verify() {
+    static RETRIED: AtomicBool = AtomicBool::new(false);
+
+    let server = Server::builder().build();
+
+    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    ctx.set_verify_callback(SslVerifyMode::PEER, |_, x509_ctx| {
+        if !RETRIED.swap(true, Ordering::SeqCst) {
+            let ssl_idx = X509StoreContext::ssl_idx().unwrap();
+            x509_ctx
+                .ex_data_mut(ssl_idx)
+                .unwrap()
+                .set_retry_verify()
+                .unwrap();
+        }
+        true
+    });
+    let ssl = Ssl::new(&ctx.build()).unwrap();
+    let mut stream = SslStream::new(ssl, server.connect_tcp()).unwrap();
+
+    let e = stream.connect().unwrap_err();
+    assert_eq!(e.code(), ssl::ErrorCode::WANT_RETRY_VERIFY);
+    assert!(stream.ssl().want_retry_verify());
+
+    stream.do_handshake().unwrap();
+    assert!(!stream.ssl().want_retry_verify());
+    stream.read_exact(&mut [0]).unwrap();
+}
+
+#[test]
+fn dtls_timeout() {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let stream = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+    stream.set_nonblocking(true).unwrap();
+
+    let ctx = SslContext::builder(SslMethod::dtls()).unwrap();
+    let mut ssl = Ssl::new(&ctx.build()).unwrap();
+    ssl.set_mtu(1500).unwrap();
+    assert_eq!(ssl.dtls_timeout(), None);
+
+    let mut stream = SslStream::new(ssl, stream).unwrap();
+    let e = stream.connect().unwrap_err();
+    assert_eq!(e.code(), ssl::ErrorCode::WANT_READ);
+
+    let timeout = stream.ssl().dtls_timeout().unwrap();
+    assert!(timeout <= Duration::from_secs(1));
+    assert!(!stream.handle_dtls_timeout().unwrap());
+}
+
+#[test]
+#[cfg(ossl110)]
+fn dtls_listen() {
+    const COOKIE: &[u8] = b"oatmeal raisin";
+    static GENERATED: AtomicBool = AtomicBool::new(false);
+    static VERIFIED: AtomicBool = AtomicBool::new(false);
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+
+    let guard = thread::spawn(move || {
+        let stream = listener.accept().unwrap().0;
+        let mut ctx = SslContext::builder(SslMethod::dtls()).unwrap();
+        ctx.set_certificate_file(Path::new("test/cert.pem"), SslFiletype::PEM)
+            .unwrap();
+        ctx.set_private_key_file(Path::new("test/key.pem"), SslFiletype::PEM)
+            .unwrap();
+        ctx.set_cookie_generate_cb(|_, buf| {
+            GENERATED.store(true, Ordering::SeqCst);
+            buf[..COOKIE.len()].copy_from_slice(COOKIE);
+            Ok(COOKIE.len())
+        });
+        ctx.set_cookie_verify_cb(|_, cookie| {
+            VERIFIED.store(true, Ordering::SeqCst);
+            cookie == COOKIE
+        });
+        let mut ssl = Ssl::new(&ctx.build()).unwrap();
+        ssl.set_mtu(1500).unwrap();
+        let mut stream = SslStream::new(ssl, stream).unwrap();
+
+        assert_eq!(stream.dtls_listen().unwrap(), None);
+        stream.accept().unwrap();
+        stream.write_all(&[0]).unwrap();
+    });
+
+    let stream = TcpStream::connect(addr).unwrap();
+    let ctx = SslContext::builder(SslMethod::dtls()).unwrap();
+    let mut ssl = Ssl::new(&ctx.build()).unwrap();
+    ssl.set_mtu(1500).unwrap();
+    let mut stream = ssl.connect(stream).unwrap();
+    stream.read_exact(&mut [0]).unwrap();
+
+    guard.join().unwrap();
+
+    assert!(GENERATED.load(Ordering::SeqCst));
+    assert!(VERIFIED.load(Ordering::SeqCst));
+}
+
+#[test]
+#[cfg(all(unix, ossl101, not(osslconf = "OPENSSL_NO_SCTP")))]
+fn dtls_sctp() {
+    use crate::ssl::DtlsSctpStream;
+    use std::net::Ipv4Addr;
+    use std::os::unix::io::{AsRawFd, FromRawFd};
+    use std::ptr;
+
+    // the socket owns the descriptor and closes it when dropped
+    fn sctp_socket() -> Option<TcpStream> {
+        let fd = unsafe { libc::socket(libc::AF_INET, libc::SOCK_STREAM, libc::IPPROTO_SCTP) };
+        if fd < 0 {
+            None
+        } else {
+            Some(unsafe { TcpStream::from_raw_fd(fd) })
+        }
+    }
+
+    // the kernel may not support SCTP
+    let listener = match sctp_socket() {
+        Some(listener) => listener,
+        None => return,
+    };
+    let mut addr: libc::sockaddr_in = unsafe { mem::zeroed() };
+    addr.sin_family = libc::AF_INET as libc::sa_family_t;
+    addr.sin_addr.s_addr = u32::from(Ipv4Addr::LOCALHOST).to_be();
+    let mut len = mem::size_of::<libc::sockaddr_in>() as libc::socklen_t;
+    unsafe {
+        let fd = listener.as_raw_fd();
+        let sockaddr = &mut addr as *mut _ as *mut libc::sockaddr;
+        assert_eq!(libc::bind(fd, sockaddr, len), 0);
+        assert_eq!(libc::listen(fd, 1), 0);
+        assert_eq!(libc::getsockname(fd, sockaddr, &mut len), 0);
+    }
+
+    let guard = thread::spawn(move || {
+        let fd = unsafe { libc::accept(listener.as_raw_fd(), ptr::null_mut(), ptr::null_mut()) };
+        assert!(fd >= 0);
+        let stream = unsafe { TcpStream::from_raw_fd(fd) };
+        let mut ctx = SslContext::builder(SslMethod::dtls()).unwrap();
+        ctx.set_certificate_file(Path::new("test/cert.pem"), SslFiletype::PEM)
+            .unwrap();
+        ctx.set_private_key_file(Path::new("test/key.pem"), SslFiletype::PEM)
+            .unwrap();
+        let ssl = Ssl::new(&ctx.build()).unwrap();
+        let mut stream = DtlsSctpStream::new(ssl, stream).unwrap();
+        stream.accept().unwrap();
+        stream.write_all(&[0]).unwrap();
+        stream.read_exact(&mut [0]).unwrap();
+    });
+
+    let stream = sctp_socket().unwrap();
+    unsafe {
+        let sockaddr = &addr as *const _ as *const libc::sockaddr;
+        assert_eq!(libc::connect(stream.as_raw_fd(), sockaddr, len), 0);
+    }
+    let ctx = SslContext::builder(SslMethod::dtls()).unwrap();
+    let ssl = Ssl::new(&ctx.build()).unwrap();
+    let mut stream = DtlsSctpStream::new(ssl, stream).unwrap();
+    stream.connect().unwrap();
+    stream.read_exact(&mut [0]).unwrap();
+    stream.write_all(&[0]).unwrap();
+
+    guard.join().unwrap();
+}
+
 #[cfg(not(osslconf = "OPENSSL_NO_PSK"))]
 #[test]
 fn psk_ciphers() {
@@ -1395,6 +4151,44 @@ fn client_hello() {
     assert!(CALLED_BACK.load(Ordering::SeqCst));
 }
 
+#[test]
+#[cfg(ossl111)]
+fn client_hello_extensions() {
+    static CALLED_BACK: AtomicBool = AtomicBool::new(false);
+
+    let mut server = Server::builder();
+    server.ctx().set_client_hello_callback(|ssl, _| {
+        let present = ssl.client_hello_extensions_present().unwrap();
+        assert!(present.contains(&TlsExtType::SERVER_NAME));
+        assert!(present.contains(&TlsExtType::ALPN));
+        assert!(present.contains(&TlsExtType::SUPPORTED_VERSIONS));
+
+        assert!(ssl.client_hello_ext(TlsExtType::KEY_SHARE).is_some());
+        assert!(ssl.client_hello_ext(TlsExtType::from_raw(12345)).is_none());
+        assert_eq!(ssl.client_hello_servername(), Some("foobar.com"));
+        assert_eq!(
+            ssl.client_hello_alpn_protocols(),
+            Some(&b"\x06http/2\x08http/1.1"[..])
+        );
+
+        CALLED_BACK.store(true, Ordering::SeqCst);
+        Ok(ClientHelloResponse::SUCCESS)
+    });
+
+    let server = server.build();
+
+    let mut client = server.client();
+    client
+        .ctx()
+        .set_alpn_protos(b"\x06http/2\x08http/1.1")
+        .unwrap();
+    let mut client = client.build().builder();
+    client.ssl().set_hostname("foobar.com").unwrap();
+    client.connect();
+
+    assert!(CALLED_BACK.load(Ordering::SeqCst));
+}
+
 #[test]
 #[cfg(ossl111)]
 fn openssl_cipher_name() {
@@ -1413,3 +4207,44 @@ fn session_cache_size() {
     let ctx = ctx.build();
     assert_eq!(ctx.session_cache_size(), 1234);
 }
+
+#[test]
+fn session_timeout() {
+    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    ctx.set_session_timeout(60);
+    let ctx = ctx.build();
+    assert_eq!(ctx.session_timeout(), 60);
+}
+
+#[test]
+fn session_cache_len() {
+    let server = Server::builder().build();
+
+    let mut client = server.client();
+    client
+        .ctx()
+        .set_session_cache_mode(SslSessionCacheMode::CLIENT);
+    // Bound the cache below the number of tickets sent by default with TLSv1.3.
+    client.ctx().set_session_cache_size(1);
+    let s = client.connect();
+
+    assert_eq!(s.ssl().ssl_context().session_cache_len(), 1);
+}
+
+#[test]
+fn session_cache_mode() {
+    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    let old = ctx.set_session_cache_mode(SslSessionCacheMode::OFF);
+    assert_eq!(old, SslSessionCacheMode::SERVER);
+    let ctx = ctx.build();
+    assert_eq!(ctx.session_cache_mode(), SslSessionCacheMode::OFF);
+}
+
+#[test]
+fn session_tickets_enabled() {
+    let mut ctx = SslContext::builder(SslMethod::tls()).unwrap();
+    ctx.set_session_tickets_enabled(false);
+    assert!(ctx.options().contains(SslOptions::NO_TICKET));
+    ctx.set_session_tickets_enabled(true);
+    assert!(!ctx.options().contains(SslOptions::NO_TICKET));
+}
@@ -17,10 +17,16 @@
 //! let extension: X509Extension = bc.build().unwrap();
 //! ```
 use std::fmt::Write;
+use std::net::IpAddr;
 
+use foreign_types::ForeignType;
+
+use crate::asn1::Asn1Object;
+use crate::cvt_p;
 use crate::error::ErrorStack;
 use crate::nid::Nid;
-use crate::x509::{X509Extension, X509v3Context};
+use crate::stack::Stack;
+use crate::x509::{GeneralName, X509Extension, X509v3Context};
 
 /// An extension which indicates whether a certificate is a CA certificate.
 pub struct BasicConstraints {
@@ -465,9 +471,24 @@ impl AuthorityKeyIdentifier {
 
 /// An extension that allows additional identities to be bound to the subject
 /// of the certificate.
+///
+/// DNS names, email addresses, URIs, IP addresses and registered IDs are encoded directly rather
+/// than through OpenSSL's configuration syntax, so their values may contain any characters.
 pub struct SubjectAlternativeName {
     critical: bool,
-    names: Vec<String>,
+    items: Vec<RustGeneralName>,
+}
+
+enum RustGeneralName {
+    Dns(String),
+    Email(String),
+    Uri(String),
+    Ip(String),
+    IpAddr(IpAddr),
+    Rid(String),
+    OtherName(String, Vec<u8>),
+    // Names which are still parsed from OpenSSL's configuration syntax.
+    Conf(String),
 }
 
 impl Default for SubjectAlternativeName {
@@ -481,7 +502,7 @@ impl SubjectAlternativeName {
     pub fn new() -> SubjectAlternativeName {
         SubjectAlternativeName {
             critical: false,
-            names: vec![],
+            items: vec![],
         }
     }
 
@@ -492,56 +513,122 @@ impl SubjectAlternativeName {
     }
 
     /// Sets the `email` flag.
+    ///
+    /// The special values `copy` and `move` take the address from the subject of the
+    /// certificate, as in OpenSSL's configuration syntax.
     pub fn email(&mut self, email: &str) -> &mut SubjectAlternativeName {
-        self.names.push(format!("email:{}", email));
+        let item = match email {
+            "copy" | "move" => RustGeneralName::Conf(format!("email:{}", email)),
+            _ => RustGeneralName::Email(email.to_string()),
+        };
+        self.items.push(item);
         self
     }
 
     /// Sets the `uri` flag.
     pub fn uri(&mut self, uri: &str) -> &mut SubjectAlternativeName {
-        self.names.push(format!("URI:{}", uri));
+        self.items.push(RustGeneralName::Uri(uri.to_string()));
         self
     }
 
     /// Sets the `dns` flag.
     pub fn dns(&mut self, dns: &str) -> &mut SubjectAlternativeName {
-        self.names.push(format!("DNS:{}", dns));
+        self.items.push(RustGeneralName::Dns(dns.to_string()));
         self
     }
 
     /// Sets the `rid` flag.
     pub fn rid(&mut self, rid: &str) -> &mut SubjectAlternativeName {
-        self.names.push(format!("RID:{}", rid));
+        self.items.push(RustGeneralName::Rid(rid.to_string()));
         self
     }
 
     /// Sets the `ip` flag.
+    ///
+    /// The address is parsed when the extension is built, which fails if it is not a valid IPv4
+    /// or IPv6 address.
     pub fn ip(&mut self, ip: &str) -> &mut SubjectAlternativeName {
-        self.names.push(format!("IP:{}", ip));
+        self.items.push(RustGeneralName::Ip(ip.to_string()));
+        self
+    }
+
+    /// Adds an IP address.
+    pub fn ip_addr(&mut self, ip: IpAddr) -> &mut SubjectAlternativeName {
+        self.items.push(RustGeneralName::IpAddr(ip));
         self
     }
 
     /// Sets the `dirName` flag.
+    ///
+    /// The value is the name of a section of the configuration set on the `X509v3Context`.
     pub fn dir_name(&mut self, dir_name: &str) -> &mut SubjectAlternativeName {
-        self.names.push(format!("dirName:{}", dir_name));
+        self.items
+            .push(RustGeneralName::Conf(format!("dirName:{}", dir_name)));
         self
     }
 
     /// Sets the `otherName` flag.
+    ///
+    /// The value uses OpenSSL's configuration syntax, `OID;TYPE:value`.
     pub fn other_name(&mut self, other_name: &str) -> &mut SubjectAlternativeName {
-        self.names.push(format!("otherName:{}", other_name));
+        self.items
+            .push(RustGeneralName::Conf(format!("otherName:{}", other_name)));
+        self
+    }
+
+    /// Adds an `otherName` of type `oid` with the DER encoded `value`.
+    ///
+    /// `oid` is parsed when the extension is built, like the value of [`rid`].
+    ///
+    /// [`rid`]: #method.rid
+    pub fn other_name_der(&mut self, oid: &str, value: &[u8]) -> &mut SubjectAlternativeName {
+        self.items
+            .push(RustGeneralName::OtherName(oid.to_string(), value.to_vec()));
         self
     }
 
     /// Return a `SubjectAlternativeName` extension as an `X509Extension`.
     pub fn build(&self, ctx: &X509v3Context<'_>) -> Result<X509Extension, ErrorStack> {
-        let mut value = String::new();
-        let mut first = true;
-        append(&mut value, &mut first, self.critical, "critical");
-        for name in &self.names {
-            append(&mut value, &mut first, true, name);
+        let mut stack = Stack::new()?;
+        for item in &self.items {
+            let gn = match item {
+                RustGeneralName::Dns(s) => GeneralName::new_dns(s.as_bytes())?,
+                RustGeneralName::Email(s) => GeneralName::new_email(s.as_bytes())?,
+                RustGeneralName::Uri(s) => GeneralName::new_uri(s.as_bytes())?,
+                RustGeneralName::Ip(s) => GeneralName::new_ip_str(s)?,
+                RustGeneralName::IpAddr(ip) => GeneralName::new_ip(*ip)?,
+                RustGeneralName::Rid(s) => GeneralName::new_rid(Asn1Object::from_str(s)?)?,
+                RustGeneralName::OtherName(oid, value) => {
+                    GeneralName::new_other_name(Asn1Object::from_str(oid)?, value)?
+                }
+                RustGeneralName::Conf(s) => {
+                    for gn in conf_general_names(ctx, s)? {
+                        stack.push(gn)?;
+                    }
+                    continue;
+                }
+            };
+            stack.push(gn)?;
         }
-        X509Extension::new_nid(None, Some(ctx), Nid::SUBJECT_ALT_NAME, &value)
+        unsafe {
+            X509Extension::new_internal(
+                Nid::SUBJECT_ALT_NAME,
+                self.critical,
+                stack.as_ptr() as *mut _,
+            )
+        }
+    }
+}
+
+// Parses a single name written in OpenSSL's configuration syntax.
+fn conf_general_names(
+    ctx: &X509v3Context<'_>,
+    value: &str,
+) -> Result<Stack<GeneralName>, ErrorStack> {
+    let extension = X509Extension::new_nid(None, Some(ctx), Nid::SUBJECT_ALT_NAME, value)?;
+    unsafe {
+        let names = cvt_p(ffi::X509V3_EXT_d2i(extension.as_ptr()))?;
+        Ok(Stack::from_ptr(names as *mut _))
     }
 }
 
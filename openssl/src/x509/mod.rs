@@ -7,34 +7,47 @@
 //! Internet protocols, including SSL/TLS, which is the basis for HTTPS,
 //! the secure protocol for browsing the web.
 
+use bitflags::bitflags;
 use cfg_if::cfg_if;
-use foreign_types::{ForeignType, ForeignTypeRef};
-use libc::{c_int, c_long, c_uint};
+use foreign_types::{ForeignType, ForeignTypeRef, Opaque};
+use libc::{c_int, c_long, c_uint, c_ulong, c_void};
+use once_cell::sync::Lazy;
+use std::any::TypeId;
 use std::cmp::{self, Ordering};
+use std::collections::HashMap;
+use std::convert::TryFrom;
 use std::error::Error;
 use std::ffi::{CStr, CString};
 use std::fmt;
 use std::marker::PhantomData;
 use std::mem;
+use std::net::IpAddr;
 use std::path::Path;
 use std::ptr;
 use std::slice;
 use std::str;
+use std::sync::Mutex;
 
 use crate::asn1::{
-    Asn1BitStringRef, Asn1IntegerRef, Asn1ObjectRef, Asn1StringRef, Asn1TimeRef, Asn1Type,
+    Asn1BitString, Asn1BitStringRef, Asn1Integer, Asn1IntegerRef, Asn1Object, Asn1ObjectRef,
+    Asn1String, Asn1StringRef, Asn1TimeRef, Asn1Type,
 };
-use crate::bio::MemBioSlice;
+use crate::bio::{MemBio, MemBioSlice};
+use crate::bn::{BigNum, MsbOption};
 use crate::conf::ConfRef;
+#[cfg(ossl110)]
+use crate::ct::Sct;
 use crate::error::ErrorStack;
 use crate::ex_data::Index;
 use crate::hash::{DigestBytes, MessageDigest};
 use crate::nid::Nid;
 use crate::pkey::{HasPrivate, HasPublic, PKey, PKeyRef, Public};
-use crate::ssl::SslRef;
+use crate::ssl::{free_data_box, SslRef};
 use crate::stack::{Stack, StackRef, Stackable};
 use crate::string::OpensslString;
 use crate::util::{ForeignTypeExt, ForeignTypeRefExt};
+#[cfg(any(ossl102, libressl261))]
+use crate::x509::verify::{X509CheckFlags, X509VerifyParamRef};
 use crate::{cvt, cvt_n, cvt_p};
 use openssl_macros::corresponds;
 
@@ -58,6 +71,8 @@ foreign_type_and_impl_send_sync! {
     pub struct X509StoreContextRef;
 }
 
+static INDEXES: Lazy<Mutex<HashMap<TypeId, c_int>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
 impl X509StoreContext {
     /// Returns the index which can be used to obtain a reference to the `Ssl` associated with a
     /// context.
@@ -74,6 +89,56 @@ impl X509StoreContext {
             cvt_p(ffi::X509_STORE_CTX_new()).map(X509StoreContext)
         }
     }
+
+    /// Returns a new extra data index.
+    ///
+    /// Each invocation of this function is guaranteed to return a distinct index. These can be used
+    /// to store data in the context that can be retrieved later by callbacks, for example.
+    #[corresponds(X509_STORE_CTX_get_ex_new_index)]
+    pub fn new_ex_index<T>() -> Result<Index<X509StoreContext, T>, ErrorStack>
+    where
+        T: 'static + Sync + Send,
+    {
+        unsafe {
+            ffi::init();
+            #[cfg(boringssl)]
+            let idx = cvt_n(get_new_store_ctx_idx(Some(free_data_box::<T>)))?;
+            #[cfg(not(boringssl))]
+            let idx = cvt_n(get_new_store_ctx_idx(free_data_box::<T>))?;
+            Ok(Index::from_raw(idx))
+        }
+    }
+
+    fn cached_ex_index<T>() -> Index<X509StoreContext, T>
+    where
+        T: 'static + Sync + Send,
+    {
+        unsafe {
+            let idx = *INDEXES
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry(TypeId::of::<T>())
+                .or_insert_with(|| X509StoreContext::new_ex_index::<T>().unwrap().as_raw());
+            Index::from_raw(idx)
+        }
+    }
+}
+
+extern "C" fn raw_verify<F>(preverify_ok: c_int, x509_ctx: *mut ffi::X509_STORE_CTX) -> c_int
+where
+    F: Fn(bool, &mut X509StoreContextRef) -> bool + 'static + Sync + Send,
+{
+    unsafe {
+        let ctx = X509StoreContextRef::from_ptr_mut(x509_ctx);
+
+        // raw pointer shenanigans to break the borrow of ctx
+        // the callback can't mess with its own ex_data slot so this is safe
+        let verify = ctx
+            .ex_data(X509StoreContext::cached_ex_index::<F>())
+            .expect("BUG: verify callback missing") as *const F;
+
+        (*verify)(preverify_ok != 0, ctx) as c_int
+    }
 }
 
 impl X509StoreContextRef {
@@ -90,6 +155,37 @@ impl X509StoreContextRef {
         }
     }
 
+    /// Returns a mutable reference to application data pertaining to an `X509` store context.
+    #[corresponds(X509_STORE_CTX_get_ex_data)]
+    pub fn ex_data_mut<T>(&mut self, index: Index<X509StoreContext, T>) -> Option<&mut T> {
+        unsafe {
+            let data = ffi::X509_STORE_CTX_get_ex_data(self.as_ptr(), index.as_raw());
+            if data.is_null() {
+                None
+            } else {
+                Some(&mut *(data as *mut T))
+            }
+        }
+    }
+
+    /// Sets the extra data at the specified index.
+    ///
+    /// This can be used to provide data to callbacks registered with the context. Use the
+    /// `X509StoreContext::new_ex_index` method to create an `Index`.
+    ///
+    /// Extra data is discarded when the context is cleaned up at the end of `init`, so this should
+    /// be called from within its closure.
+    #[corresponds(X509_STORE_CTX_set_ex_data)]
+    pub fn set_ex_data<T>(&mut self, index: Index<X509StoreContext, T>, data: T) {
+        match self.ex_data_mut(index) {
+            Some(v) => *v = data,
+            None => unsafe {
+                let data = Box::into_raw(Box::new(data)) as *mut c_void;
+                ffi::X509_STORE_CTX_set_ex_data(self.as_ptr(), index.as_raw(), data);
+            },
+        }
+    }
+
     /// Returns the error code of the context.
     #[corresponds(X509_STORE_CTX_get_error)]
     pub fn error(&self) -> X509VerifyResult {
@@ -155,6 +251,27 @@ impl X509StoreContextRef {
         unsafe { cvt_n(ffi::X509_verify_cert(self.as_ptr())).map(|n| n != 0) }
     }
 
+    /// Registers a callback invoked as each certificate in the chain is checked.
+    ///
+    /// The callback is passed a boolean indicating if OpenSSL's internal verification of the
+    /// current certificate succeeded as well as a reference to the context, whose `error`,
+    /// `error_depth` and `current_cert` describe the check being made. It should return a boolean
+    /// indicating if verification should continue, so returning `true` on failure overrides that
+    /// error. Overridden errors are still reported by `error` once `verify_cert` returns.
+    ///
+    /// The callback is reset when the context is initialized, so this must be called from within
+    /// the closure passed to `init`.
+    #[corresponds(X509_STORE_CTX_set_verify_cb)]
+    pub fn set_verify_callback<F>(&mut self, callback: F)
+    where
+        F: Fn(bool, &mut X509StoreContextRef) -> bool + 'static + Sync + Send,
+    {
+        unsafe {
+            self.set_ex_data(X509StoreContext::cached_ex_index::<F>(), callback);
+            ffi::X509_STORE_CTX_set_verify_cb(self.as_ptr(), Some(raw_verify::<F>));
+        }
+    }
+
     /// Set the error code of the context.
     #[corresponds(X509_STORE_CTX_set_error)]
     pub fn set_error(&mut self, result: X509VerifyResult) {
@@ -182,6 +299,27 @@ impl X509StoreContextRef {
         unsafe { ffi::X509_STORE_CTX_get_error_depth(self.as_ptr()) as u32 }
     }
 
+    /// Returns a reference to the verification parameters used by the context.
+    ///
+    /// Requires OpenSSL 1.0.2 or LibreSSL 2.6.1 or newer.
+    #[corresponds(X509_STORE_CTX_get0_param)]
+    #[cfg(any(ossl102, libressl261))]
+    pub fn verify_param(&self) -> &X509VerifyParamRef {
+        unsafe { X509VerifyParamRef::from_ptr(ffi::X509_STORE_CTX_get0_param(self.as_ptr())) }
+    }
+
+    /// Returns a mutable reference to the verification parameters used by the context.
+    ///
+    /// The parameters are initialized from those of the store by `init`, and may be adjusted
+    /// before calling `verify_cert`.
+    ///
+    /// Requires OpenSSL 1.0.2 or LibreSSL 2.6.1 or newer.
+    #[corresponds(X509_STORE_CTX_get0_param)]
+    #[cfg(any(ossl102, libressl261))]
+    pub fn verify_param_mut(&mut self) -> &mut X509VerifyParamRef {
+        unsafe { X509VerifyParamRef::from_ptr_mut(ffi::X509_STORE_CTX_get0_param(self.as_ptr())) }
+    }
+
     /// Returns a reference to a complete valid `X509` certificate chain.
     #[corresponds(X509_STORE_CTX_get0_chain)]
     pub fn chain(&self) -> Option<&StackRef<X509>> {
@@ -195,11 +333,130 @@ impl X509StoreContextRef {
             }
         }
     }
+
+    /// Returns an owned copy of the certificate chain built by `verify_cert`.
+    ///
+    /// Unlike `chain`, the returned stack remains valid after the context has been cleaned up or
+    /// reused.
+    #[corresponds(X509_STORE_CTX_get1_chain)]
+    pub fn verified_chain(&self) -> Option<Stack<X509>> {
+        unsafe { Stack::from_ptr_opt(ffi::X509_STORE_CTX_get1_chain(self.as_ptr())) }
+    }
+
+    /// Returns the policy tree computed by `verify_cert`.
+    ///
+    /// The tree is only computed if policy checking was enabled with
+    /// `X509VerifyFlags::POLICY_CHECK`, and may be absent if every certificate of the chain
+    /// permits any policy.
+    #[corresponds(X509_STORE_CTX_get0_policy_tree)]
+    pub fn policy_tree(&self) -> Option<&X509PolicyTreeRef> {
+        unsafe {
+            X509PolicyTreeRef::from_const_ptr_opt(ffi::X509_STORE_CTX_get0_policy_tree(
+                self.as_ptr(),
+            ))
+        }
+    }
+
+    /// Determines if the chain verified by `verify_cert` requires an explicit policy.
+    #[corresponds(X509_STORE_CTX_get_explicit_policy)]
+    pub fn explicit_policy(&self) -> bool {
+        unsafe { ffi::X509_STORE_CTX_get_explicit_policy(self.as_ptr()) == 1 }
+    }
+}
+
+/// A reference to the policy tree computed during certificate chain verification.
+///
+/// The tree is owned by the `X509StoreContext` which computed it.
+pub struct X509PolicyTreeRef(Opaque);
+
+impl ForeignTypeRef for X509PolicyTreeRef {
+    type CType = ffi::X509_POLICY_TREE;
+}
+
+impl X509PolicyTreeRef {
+    /// Returns the policies which are valid for the chain.
+    ///
+    /// `None` is returned if the chain is valid for any policy.
+    #[corresponds(X509_policy_tree_get0_policies)]
+    pub fn policies(&self) -> Option<&StackRef<X509PolicyNode>> {
+        unsafe { StackRef::from_const_ptr_opt(ffi::X509_policy_tree_get0_policies(self.as_ptr())) }
+    }
+
+    /// Returns the policies which are valid for the chain and acceptable to the caller, as
+    /// configured with `X509VerifyParamRef::add_policy`.
+    ///
+    /// `None` is returned if the chain is valid for any policy.
+    #[corresponds(X509_policy_tree_get0_user_policies)]
+    pub fn user_policies(&self) -> Option<&StackRef<X509PolicyNode>> {
+        unsafe {
+            StackRef::from_const_ptr_opt(ffi::X509_policy_tree_get0_user_policies(self.as_ptr()))
+        }
+    }
+}
+
+/// A node of an `X509PolicyTreeRef`.
+///
+/// Nodes are owned by their tree, so this type is only used through references.
+pub struct X509PolicyNode(*mut ffi::X509_POLICY_NODE);
+
+impl ForeignType for X509PolicyNode {
+    type CType = ffi::X509_POLICY_NODE;
+    type Ref = X509PolicyNodeRef;
+
+    #[inline]
+    unsafe fn from_ptr(ptr: *mut ffi::X509_POLICY_NODE) -> X509PolicyNode {
+        X509PolicyNode(ptr)
+    }
+
+    #[inline]
+    fn as_ptr(&self) -> *mut ffi::X509_POLICY_NODE {
+        self.0
+    }
+}
+
+impl Stackable for X509PolicyNode {
+    type StackType = ffi::stack_st_X509_POLICY_NODE;
+}
+
+/// Reference to an `X509PolicyNode`.
+pub struct X509PolicyNodeRef(Opaque);
+
+impl ForeignTypeRef for X509PolicyNodeRef {
+    type CType = ffi::X509_POLICY_NODE;
+}
+
+impl X509PolicyNodeRef {
+    /// Returns the policy identifier of the node.
+    #[corresponds(X509_policy_node_get0_policy)]
+    pub fn policy(&self) -> &Asn1ObjectRef {
+        unsafe {
+            Asn1ObjectRef::from_const_ptr_opt(ffi::X509_policy_node_get0_policy(self.as_ptr()))
+                .expect("policy must not be null")
+        }
+    }
+
+    /// Returns the node of the issuing certificate's level from which this node was derived.
+    #[corresponds(X509_policy_node_get0_parent)]
+    pub fn parent(&self) -> Option<&X509PolicyNodeRef> {
+        unsafe {
+            X509PolicyNodeRef::from_const_ptr_opt(ffi::X509_policy_node_get0_parent(self.as_ptr()))
+        }
+    }
 }
 
 /// A builder used to construct an `X509`.
 pub struct X509Builder(X509);
 
+/// How extensions copied from a certificate request interact with those already present in a
+/// certificate.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum CopyExtensions {
+    /// Extensions already present in the certificate are kept, and the requested ones skipped.
+    Add,
+    /// Extensions already present in the certificate are replaced by the requested ones.
+    All,
+}
+
 impl X509Builder {
     /// Creates a new builder.
     #[corresponds(X509_new)]
@@ -210,13 +467,40 @@ impl X509Builder {
         }
     }
 
+    /// Creates a builder for the final certificate corresponding to a precertificate.
+    ///
+    /// The precertificate's contents are copied and its Certificate Transparency poison extension
+    /// is removed. The signed certificate timestamps obtained for the precertificate can then be
+    /// embedded in a `ct_precert_scts` extension before the certificate is signed again.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(X509_dup)]
+    #[cfg(ossl110)]
+    pub fn from_precertificate(precert: &X509Ref) -> Result<X509Builder, ErrorStack> {
+        unsafe {
+            let x509 = X509(cvt_p(ffi::X509_dup(precert.as_ptr()))?);
+            delete_extensions(x509.as_ptr(), Nid::CT_PRECERT_POISON);
+            Ok(X509Builder(x509))
+        }
+    }
+
     /// Sets the notAfter constraint on the certificate.
+    ///
+    /// The time is stored with its encoding unchanged. Use [`Asn1TimeRef::to_generalized_time`]
+    /// to force a GeneralizedTime encoding.
+    ///
+    /// [`Asn1TimeRef::to_generalized_time`]: ../asn1/struct.Asn1TimeRef.html#method.to_generalized_time
     #[corresponds(X509_set1_notAfter)]
     pub fn set_not_after(&mut self, not_after: &Asn1TimeRef) -> Result<(), ErrorStack> {
         unsafe { cvt(X509_set1_notAfter(self.0.as_ptr(), not_after.as_ptr())).map(|_| ()) }
     }
 
     /// Sets the notBefore constraint on the certificate.
+    ///
+    /// The time is stored with its encoding unchanged. Use [`Asn1TimeRef::to_generalized_time`]
+    /// to force a GeneralizedTime encoding.
+    ///
+    /// [`Asn1TimeRef::to_generalized_time`]: ../asn1/struct.Asn1TimeRef.html#method.to_generalized_time
     #[corresponds(X509_set1_notBefore)]
     pub fn set_not_before(&mut self, not_before: &Asn1TimeRef) -> Result<(), ErrorStack> {
         unsafe { cvt(X509_set1_notBefore(self.0.as_ptr(), not_before.as_ptr())).map(|_| ()) }
@@ -243,6 +527,19 @@ impl X509Builder {
         }
     }
 
+    /// Sets the serial number of the certificate to a random positive 128-bit value.
+    ///
+    /// The CA/Browser Forum Baseline Requirements call for serial numbers containing at least 64
+    /// bits of output from a CSPRNG. The most significant bit is always set so that every serial
+    /// number is encoded with the same length.
+    #[corresponds(BN_rand)]
+    pub fn set_random_serial_number(&mut self) -> Result<(), ErrorStack> {
+        let mut serial = BigNum::new()?;
+        serial.rand(128, MsbOption::ONE, false)?;
+        let serial = serial.to_asn1_integer()?;
+        self.set_serial_number(&serial)
+    }
+
     /// Sets the issuer name of the certificate.
     #[corresponds(X509_set_issuer_name)]
     pub fn set_issuer_name(&mut self, issuer_name: &X509NameRef) -> Result<(), ErrorStack> {
@@ -344,6 +641,120 @@ impl X509Builder {
         }
     }
 
+    /// Copies the extensions of a certificate request into the certificate.
+    ///
+    /// Only extensions for which `filter` returns `true` are copied. `policy` determines what
+    /// happens to extensions already present in the certificate, as with the `copy_extensions`
+    /// option of `openssl ca`. Care should be taken not to copy extensions such as
+    /// `basicConstraints` from untrusted requests.
+    #[corresponds(X509_REQ_get_extensions)]
+    pub fn append_extensions_from_req<F>(
+        &mut self,
+        req: &X509ReqRef,
+        policy: CopyExtensions,
+        mut filter: F,
+    ) -> Result<(), ErrorStack>
+    where
+        F: FnMut(&X509ExtensionRef) -> bool,
+    {
+        let extensions = unsafe {
+            let extensions = ffi::X509_REQ_get_extensions(req.as_ptr());
+            if extensions.is_null() {
+                // Older versions return NULL rather than an empty stack when there are none.
+                let errors = ErrorStack::get();
+                if errors.errors().is_empty() {
+                    return Ok(());
+                }
+                return Err(errors);
+            }
+            Stack::<X509Extension>::from_ptr(extensions)
+        };
+
+        for extension in &extensions {
+            if !filter(extension) {
+                continue;
+            }
+
+            unsafe {
+                let obj = ffi::X509_EXTENSION_get_object(extension.as_ptr());
+                let mut idx = ffi::X509_get_ext_by_OBJ(self.0.as_ptr(), obj, -1);
+                if idx != -1 {
+                    if policy == CopyExtensions::Add {
+                        continue;
+                    }
+                    while idx != -1 {
+                        X509Extension::from_ptr(ffi::X509_delete_ext(self.0.as_ptr(), idx));
+                        idx = ffi::X509_get_ext_by_OBJ(self.0.as_ptr(), obj, -1);
+                    }
+                }
+            }
+
+            self.append_extension2(extension)?;
+        }
+
+        Ok(())
+    }
+
+    /// Adds an extension with the given OID and DER-encoded value to the certificate.
+    ///
+    /// This allows extensions which OpenSSL does not know about to be added.
+    pub fn append_raw_extension(
+        &mut self,
+        oid: &Asn1ObjectRef,
+        critical: bool,
+        der: &[u8],
+    ) -> Result<(), ErrorStack> {
+        let extension = X509Extension::new_from_der(oid, critical, der)?;
+        self.append_extension2(&extension)
+    }
+
+    /// Adds the critical Certificate Transparency poison extension, making the certificate a
+    /// precertificate.
+    ///
+    /// A precertificate is submitted to Certificate Transparency logs in exchange for signed
+    /// certificate timestamps, but cannot itself be used. See [RFC 6962] for details.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    ///
+    /// [RFC 6962]: https://tools.ietf.org/html/rfc6962#section-3.1
+    #[corresponds(X509_EXTENSION_create_by_OBJ)]
+    #[cfg(ossl110)]
+    pub fn append_ct_poison(&mut self) -> Result<(), ErrorStack> {
+        let oid = unsafe { Asn1ObjectRef::from_ptr(ffi::OBJ_nid2obj(ffi::NID_ct_precert_poison)) };
+        // The extension's value is an ASN.1 NULL.
+        self.append_raw_extension(oid, true, &[0x05, 0x00])
+    }
+
+    /// Marks the certificate as trusted for the purpose identified by `obj`, typically an extended
+    /// key usage such as `serverAuth`.
+    ///
+    /// Trust settings are not part of the signed certificate and only affect its use as a trust
+    /// anchor.
+    #[corresponds(X509_add1_trust_object)]
+    pub fn add_trust_object(&mut self, obj: &Asn1ObjectRef) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::X509_add1_trust_object(self.0.as_ptr(), obj.as_ptr())).map(|_| ()) }
+    }
+
+    /// Marks the certificate as rejected for the purpose identified by `obj`.
+    ///
+    /// Rejections take precedence over trust settings.
+    #[corresponds(X509_add1_reject_object)]
+    pub fn add_reject_object(&mut self, obj: &Asn1ObjectRef) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::X509_add1_reject_object(self.0.as_ptr(), obj.as_ptr())).map(|_| ()) }
+    }
+
+    /// Removes all purposes the certificate is marked as trusted for.
+    #[corresponds(X509_trust_clear)]
+    pub fn clear_trust(&mut self) {
+        unsafe { ffi::X509_trust_clear(self.0.as_ptr()) }
+    }
+
+    /// Removes all purposes the certificate is marked as rejected for.
+    #[corresponds(X509_reject_clear)]
+    pub fn clear_reject(&mut self) {
+        unsafe { ffi::X509_reject_clear(self.0.as_ptr()) }
+    }
+
     /// Signs the certificate with a private key.
     #[corresponds(X509_sign)]
     pub fn sign<T>(&mut self, key: &PKeyRef<T>, hash: MessageDigest) -> Result<(), ErrorStack>
@@ -359,6 +770,17 @@ impl X509Builder {
     }
 }
 
+#[cfg(ossl110)]
+unsafe fn delete_extensions(x509: *mut ffi::X509, nid: Nid) {
+    loop {
+        let idx = ffi::X509_get_ext_by_NID(x509, nid.as_raw(), -1);
+        if idx < 0 {
+            break;
+        }
+        X509Extension::from_ptr(ffi::X509_delete_ext(x509, idx));
+    }
+}
+
 foreign_type_and_impl_send_sync! {
     type CType = ffi::X509;
     fn drop = ffi::X509_free;
@@ -433,96 +855,284 @@ impl X509Ref {
         }
     }
 
-    /// Returns this certificate's [`authority information access`] entries, if they exist.
+    /// Returns this certificate's subject key identifier, if it exists.
     ///
-    /// [`authority information access`]: https://tools.ietf.org/html/rfc5280#section-4.2.2.1
-    #[corresponds(X509_get_ext_d2i)]
-    pub fn authority_info(&self) -> Option<Stack<AccessDescription>> {
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(X509_get0_subject_key_id)]
+    #[cfg(ossl110)]
+    pub fn subject_key_id(&self) -> Option<&Asn1StringRef> {
         unsafe {
-            let stack = ffi::X509_get_ext_d2i(
-                self.as_ptr(),
-                ffi::NID_info_access,
-                ptr::null_mut(),
-                ptr::null_mut(),
-            );
-            Stack::from_ptr_opt(stack as *mut _)
+            let data = ffi::X509_get0_subject_key_id(self.as_ptr());
+            Asn1StringRef::from_const_ptr_opt(data as *const _)
         }
     }
 
-    #[corresponds(X509_get_pubkey)]
-    pub fn public_key(&self) -> Result<PKey<Public>, ErrorStack> {
+    /// Returns the key identifier of this certificate's authority key identifier, if it exists.
+    ///
+    /// Requires OpenSSL 1.1.1d or newer.
+    #[corresponds(X509_get0_authority_key_id)]
+    #[cfg(ossl111d)]
+    pub fn authority_key_id(&self) -> Option<&Asn1StringRef> {
         unsafe {
-            let pkey = cvt_p(ffi::X509_get_pubkey(self.as_ptr()))?;
-            Ok(PKey::from_ptr(pkey))
+            let data = ffi::X509_get0_authority_key_id(self.as_ptr());
+            Asn1StringRef::from_const_ptr_opt(data as *const _)
         }
     }
 
-    /// Returns a digest of the DER representation of the certificate.
-    #[corresponds(X509_digest)]
-    pub fn digest(&self, hash_type: MessageDigest) -> Result<DigestBytes, ErrorStack> {
+    /// Returns the issuer names of this certificate's authority key identifier, if they exist.
+    ///
+    /// Requires OpenSSL 1.1.1d or newer.
+    #[corresponds(X509_get0_authority_issuer)]
+    #[cfg(ossl111d)]
+    pub fn authority_issuer(&self) -> Option<&StackRef<GeneralName>> {
         unsafe {
-            let mut digest = DigestBytes {
-                buf: [0; ffi::EVP_MAX_MD_SIZE as usize],
-                len: ffi::EVP_MAX_MD_SIZE as usize,
-            };
-            let mut len = ffi::EVP_MAX_MD_SIZE as c_uint;
-            cvt(ffi::X509_digest(
-                self.as_ptr(),
-                hash_type.as_ptr(),
-                digest.buf.as_mut_ptr() as *mut _,
-                &mut len,
-            ))?;
-            digest.len = len as usize;
+            let stack = ffi::X509_get0_authority_issuer(self.as_ptr());
+            StackRef::from_const_ptr_opt(stack)
+        }
+    }
 
-            Ok(digest)
+    /// Returns the serial number of this certificate's authority key identifier, if it exists.
+    ///
+    /// Requires OpenSSL 1.1.1d or newer.
+    #[corresponds(X509_get0_authority_serial)]
+    #[cfg(ossl111d)]
+    pub fn authority_serial(&self) -> Option<&Asn1IntegerRef> {
+        unsafe {
+            let serial = ffi::X509_get0_authority_serial(self.as_ptr());
+            Asn1IntegerRef::from_const_ptr_opt(serial)
         }
     }
 
-    #[deprecated(since = "0.10.9", note = "renamed to digest")]
-    pub fn fingerprint(&self, hash_type: MessageDigest) -> Result<Vec<u8>, ErrorStack> {
-        self.digest(hash_type).map(|b| b.to_vec())
+    /// Returns the extension of this certificate identified by `oid`, if it exists.
+    ///
+    /// If the certificate contains several extensions with the same OID, the first is returned.
+    #[corresponds(X509_get_ext_by_OBJ)]
+    pub fn extension_by_oid(&self, oid: &Asn1ObjectRef) -> Option<&X509ExtensionRef> {
+        unsafe {
+            let idx = ffi::X509_get_ext_by_OBJ(self.as_ptr(), oid.as_ptr(), -1);
+            if idx < 0 {
+                return None;
+            }
+            X509ExtensionRef::from_const_ptr_opt(ffi::X509_get_ext(self.as_ptr(), idx))
+        }
     }
 
-    /// Returns the certificate's Not After validity period.
-    #[corresponds(X509_getm_notAfter)]
-    pub fn not_after(&self) -> &Asn1TimeRef {
+    /// Returns this certificate's CRL distribution points, if they exist.
+    #[corresponds(X509_get_ext_d2i)]
+    pub fn crl_distribution_points(&self) -> Option<Stack<DistPoint>> {
         unsafe {
-            let date = X509_getm_notAfter(self.as_ptr());
-            Asn1TimeRef::from_const_ptr_opt(date).expect("not_after must not be null")
+            let stack = ffi::X509_get_ext_d2i(
+                self.as_ptr(),
+                ffi::NID_crl_distribution_points,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            Stack::from_ptr_opt(stack as *mut _)
         }
     }
 
-    /// Returns the certificate's Not Before validity period.
-    #[corresponds(X509_getm_notBefore)]
-    pub fn not_before(&self) -> &Asn1TimeRef {
+    /// Returns the usages permitted by this certificate's key usage extension, if it exists.
+    #[corresponds(X509_get_ext_d2i)]
+    pub fn key_usage(&self) -> Option<X509KeyUsageFlags> {
         unsafe {
-            let date = X509_getm_notBefore(self.as_ptr());
-            Asn1TimeRef::from_const_ptr_opt(date).expect("not_before must not be null")
+            let bits = ffi::X509_get_ext_d2i(
+                self.as_ptr(),
+                ffi::NID_key_usage,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            X509KeyUsageFlags::from_bit_string(bits as *mut _)
         }
     }
 
-    /// Returns the certificate's signature
-    #[corresponds(X509_get0_signature)]
-    pub fn signature(&self) -> &Asn1BitStringRef {
+    /// Returns the purposes listed in this certificate's extended key usage extension, if it
+    /// exists.
+    #[corresponds(X509_get_ext_d2i)]
+    pub fn extended_key_usage(&self) -> Option<Stack<Asn1Object>> {
         unsafe {
-            let mut signature = ptr::null();
-            X509_get0_signature(&mut signature, ptr::null_mut(), self.as_ptr());
-            Asn1BitStringRef::from_const_ptr_opt(signature).expect("signature must not be null")
+            let stack = ffi::X509_get_ext_d2i(
+                self.as_ptr(),
+                ffi::NID_ext_key_usage,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            Stack::from_ptr_opt(stack as *mut _)
         }
     }
 
-    /// Returns the certificate's signature algorithm.
-    #[corresponds(X509_get0_signature)]
-    pub fn signature_algorithm(&self) -> &X509AlgorithmRef {
+    /// Returns the contents of this certificate's basic constraints extension, if it exists.
+    ///
+    /// `None` is also returned if the extension is malformed.
+    #[corresponds(X509_get_ext_d2i)]
+    pub fn basic_constraints(&self) -> Option<X509BasicConstraints> {
         unsafe {
-            let mut algor = ptr::null();
-            X509_get0_signature(ptr::null_mut(), &mut algor, self.as_ptr());
-            X509AlgorithmRef::from_const_ptr_opt(algor)
-                .expect("signature algorithm must not be null")
+            let bc = ffi::X509_get_ext_d2i(
+                self.as_ptr(),
+                ffi::NID_basic_constraints,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            X509BasicConstraints::from_ptr(bc as *mut _)
         }
     }
 
-    /// Returns the list of OCSP responder URLs specified in the certificate's Authority Information
+    /// Returns this certificate's certificate policies, if they exist.
+    #[corresponds(X509_get_ext_d2i)]
+    pub fn certificate_policies(&self) -> Option<Stack<PolicyInfo>> {
+        unsafe {
+            let stack = ffi::X509_get_ext_d2i(
+                self.as_ptr(),
+                ffi::NID_certificate_policies,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            Stack::from_ptr_opt(stack as *mut _)
+        }
+    }
+
+    /// Returns the signed certificate timestamps embedded in this certificate, if they exist.
+    ///
+    /// Embedded timestamps refer to the certificate's precertificate, so validating them requires
+    /// the certificate's issuer.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(X509_get_ext_d2i)]
+    #[cfg(ossl110)]
+    pub fn scts(&self) -> Option<Stack<Sct>> {
+        unsafe {
+            let stack = ffi::X509_get_ext_d2i(
+                self.as_ptr(),
+                ffi::NID_ct_precert_scts,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            Stack::from_ptr_opt(stack as *mut _)
+        }
+    }
+
+    /// Determines if this certificate is a precertificate, carrying the Certificate Transparency
+    /// poison extension.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(X509_get_ext_by_NID)]
+    #[cfg(ossl110)]
+    pub fn is_precertificate(&self) -> bool {
+        unsafe { ffi::X509_get_ext_by_NID(self.as_ptr(), ffi::NID_ct_precert_poison, -1) >= 0 }
+    }
+
+    /// Returns the DER-encoded `TBSCertificate` covered by the signed certificate timestamps of
+    /// this certificate or precertificate.
+    ///
+    /// This is the certificate's `TBSCertificate` with the poison and embedded timestamp extensions
+    /// removed, as used in the `PreCert` entry signed by Certificate Transparency logs. The entry
+    /// also contains the SHA-256 hash of the DER-encoded public key of the certificate's issuer.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(i2d_re_X509_tbs)]
+    #[cfg(ossl110)]
+    pub fn precertificate_tbs(&self) -> Result<Vec<u8>, ErrorStack> {
+        unsafe {
+            let x509 = X509(cvt_p(ffi::X509_dup(self.as_ptr()))?);
+            delete_extensions(x509.as_ptr(), Nid::CT_PRECERT_POISON);
+            delete_extensions(x509.as_ptr(), Nid::CT_PRECERT_SCTS);
+
+            let len = cvt(ffi::i2d_re_X509_tbs(x509.as_ptr(), ptr::null_mut()))?;
+            let mut buf = vec![0; len as usize];
+            cvt(ffi::i2d_re_X509_tbs(x509.as_ptr(), &mut buf.as_mut_ptr()))?;
+            Ok(buf)
+        }
+    }
+
+    /// Returns this certificate's [`authority information access`] entries, if they exist.
+    ///
+    /// [`authority information access`]: https://tools.ietf.org/html/rfc5280#section-4.2.2.1
+    #[corresponds(X509_get_ext_d2i)]
+    pub fn authority_info(&self) -> Option<Stack<AccessDescription>> {
+        unsafe {
+            let stack = ffi::X509_get_ext_d2i(
+                self.as_ptr(),
+                ffi::NID_info_access,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            Stack::from_ptr_opt(stack as *mut _)
+        }
+    }
+
+    #[corresponds(X509_get_pubkey)]
+    pub fn public_key(&self) -> Result<PKey<Public>, ErrorStack> {
+        unsafe {
+            let pkey = cvt_p(ffi::X509_get_pubkey(self.as_ptr()))?;
+            Ok(PKey::from_ptr(pkey))
+        }
+    }
+
+    /// Returns a digest of the DER representation of the certificate.
+    #[corresponds(X509_digest)]
+    pub fn digest(&self, hash_type: MessageDigest) -> Result<DigestBytes, ErrorStack> {
+        unsafe {
+            let mut digest = DigestBytes {
+                buf: [0; ffi::EVP_MAX_MD_SIZE as usize],
+                len: ffi::EVP_MAX_MD_SIZE as usize,
+            };
+            let mut len = ffi::EVP_MAX_MD_SIZE as c_uint;
+            cvt(ffi::X509_digest(
+                self.as_ptr(),
+                hash_type.as_ptr(),
+                digest.buf.as_mut_ptr() as *mut _,
+                &mut len,
+            ))?;
+            digest.len = len as usize;
+
+            Ok(digest)
+        }
+    }
+
+    #[deprecated(since = "0.10.9", note = "renamed to digest")]
+    pub fn fingerprint(&self, hash_type: MessageDigest) -> Result<Vec<u8>, ErrorStack> {
+        self.digest(hash_type).map(|b| b.to_vec())
+    }
+
+    /// Returns the certificate's Not After validity period.
+    #[corresponds(X509_getm_notAfter)]
+    pub fn not_after(&self) -> &Asn1TimeRef {
+        unsafe {
+            let date = X509_getm_notAfter(self.as_ptr());
+            Asn1TimeRef::from_const_ptr_opt(date).expect("not_after must not be null")
+        }
+    }
+
+    /// Returns the certificate's Not Before validity period.
+    #[corresponds(X509_getm_notBefore)]
+    pub fn not_before(&self) -> &Asn1TimeRef {
+        unsafe {
+            let date = X509_getm_notBefore(self.as_ptr());
+            Asn1TimeRef::from_const_ptr_opt(date).expect("not_before must not be null")
+        }
+    }
+
+    /// Returns the certificate's signature
+    #[corresponds(X509_get0_signature)]
+    pub fn signature(&self) -> &Asn1BitStringRef {
+        unsafe {
+            let mut signature = ptr::null();
+            X509_get0_signature(&mut signature, ptr::null_mut(), self.as_ptr());
+            Asn1BitStringRef::from_const_ptr_opt(signature).expect("signature must not be null")
+        }
+    }
+
+    /// Returns the certificate's signature algorithm.
+    #[corresponds(X509_get0_signature)]
+    pub fn signature_algorithm(&self) -> &X509AlgorithmRef {
+        unsafe {
+            let mut algor = ptr::null();
+            X509_get0_signature(ptr::null_mut(), &mut algor, self.as_ptr());
+            X509AlgorithmRef::from_const_ptr_opt(algor)
+                .expect("signature algorithm must not be null")
+        }
+    }
+
+    /// Returns the list of OCSP responder URLs specified in the certificate's Authority Information
     /// Access field.
     #[corresponds(X509_get1_ocsp)]
     pub fn ocsp_responders(&self) -> Result<Stack<OpensslString>, ErrorStack> {
@@ -530,6 +1140,15 @@ impl X509Ref {
     }
 
     /// Checks that this certificate issued `subject`.
+    ///
+    /// The issuer name, authority key identifier and key usage of the certificates are compared,
+    /// but the signature of `subject` is not verified; use [`verify`] for that. If this
+    /// certificate is not the issuer, the result identifies the first mismatch found, such as
+    /// [`X509VerifyResult::SUBJECT_ISSUER_MISMATCH`] or [`X509VerifyResult::AKID_SKID_MISMATCH`].
+    ///
+    /// [`verify`]: #method.verify
+    /// [`X509VerifyResult::SUBJECT_ISSUER_MISMATCH`]: struct.X509VerifyResult.html#associatedconstant.SUBJECT_ISSUER_MISMATCH
+    /// [`X509VerifyResult::AKID_SKID_MISMATCH`]: struct.X509VerifyResult.html#associatedconstant.AKID_SKID_MISMATCH
     #[corresponds(X509_check_issued)]
     pub fn issued(&self, subject: &X509Ref) -> X509VerifyResult {
         unsafe {
@@ -538,6 +1157,100 @@ impl X509Ref {
         }
     }
 
+    /// Checks whether the certificate matches the DNS hostname `host`.
+    ///
+    /// The subject alternative names are checked first, falling back to the subject's common name
+    /// as permitted by `flags`. An empty hostname never matches.
+    ///
+    /// Requires OpenSSL 1.0.2 or LibreSSL 2.6.1 or newer.
+    #[corresponds(X509_check_host)]
+    #[cfg(any(ossl102, libressl261))]
+    pub fn matches_hostname(&self, host: &str, flags: X509CheckFlags) -> Result<bool, ErrorStack> {
+        // A length of 0 makes OpenSSL call strlen on the name.
+        if host.is_empty() {
+            return Ok(false);
+        }
+        unsafe {
+            cvt_n(ffi::X509_check_host(
+                self.as_ptr(),
+                host.as_ptr() as *const _,
+                host.len(),
+                flags.bits(),
+                ptr::null_mut(),
+            ))
+            .map(|n| n != 0)
+        }
+    }
+
+    /// Checks whether the certificate matches the email address `email`.
+    ///
+    /// An empty address never matches.
+    ///
+    /// Requires OpenSSL 1.0.2 or LibreSSL 2.6.1 or newer.
+    #[corresponds(X509_check_email)]
+    #[cfg(any(ossl102, libressl261))]
+    pub fn matches_email(&self, email: &str, flags: X509CheckFlags) -> Result<bool, ErrorStack> {
+        // A length of 0 makes OpenSSL call strlen on the address.
+        if email.is_empty() {
+            return Ok(false);
+        }
+        unsafe {
+            cvt_n(ffi::X509_check_email(
+                self.as_ptr(),
+                email.as_ptr() as *const _,
+                email.len(),
+                flags.bits(),
+            ))
+            .map(|n| n != 0)
+        }
+    }
+
+    /// Checks whether the certificate matches the IP address `ip`.
+    ///
+    /// Requires OpenSSL 1.0.2 or LibreSSL 2.6.1 or newer.
+    #[corresponds(X509_check_ip)]
+    #[cfg(any(ossl102, libressl261))]
+    pub fn matches_ip(&self, ip: IpAddr, flags: X509CheckFlags) -> Result<bool, ErrorStack> {
+        let octets = match ip {
+            IpAddr::V4(addr) => addr.octets().to_vec(),
+            IpAddr::V6(addr) => addr.octets().to_vec(),
+        };
+        unsafe {
+            cvt_n(ffi::X509_check_ip(
+                self.as_ptr(),
+                octets.as_ptr(),
+                octets.len(),
+                flags.bits(),
+            ))
+            .map(|n| n != 0)
+        }
+    }
+
+    /// Checks whether the certificate can be used for `purpose`.
+    ///
+    /// The key usage, extended key usage and Netscape certificate type extensions are checked
+    /// against the purpose. If `ca` is `true`, the certificate is instead checked as an issuer of
+    /// certificates for that purpose, which additionally requires it to be a CA.
+    #[corresponds(X509_check_purpose)]
+    pub fn check_purpose(&self, purpose: X509PurposeId, ca: bool) -> bool {
+        unsafe { ffi::X509_check_purpose(self.as_ptr(), purpose.as_raw(), ca as c_int) > 0 }
+    }
+
+    /// Checks the trust settings of the certificate for `trust`.
+    ///
+    /// Trust settings are only present on certificates which have been configured with
+    /// [`X509Builder::add_trust_object`] or [`X509Builder::add_reject_object`]. Otherwise, only
+    /// self-signed certificates are considered trusted.
+    ///
+    /// [`X509Builder::add_trust_object`]: struct.X509Builder.html#method.add_trust_object
+    /// [`X509Builder::add_reject_object`]: struct.X509Builder.html#method.add_reject_object
+    #[corresponds(X509_check_trust)]
+    pub fn check_trust(&self, trust: X509TrustId) -> X509TrustResult {
+        unsafe {
+            X509TrustResult::from_raw(ffi::X509_check_trust(self.as_ptr(), trust.as_raw(), 0))
+        }
+    }
+
     /// Returns certificate version. If this certificate has no explicit version set, it defaults to
     /// version 1.
     ///
@@ -593,6 +1306,28 @@ impl X509Ref {
         to_text,
         ffi::X509_print
     }
+
+    /// Converts the certificate to human readable text, as printed by `openssl x509 -text`.
+    ///
+    /// `name_flags` controls how the issuer and subject names are formatted, and `flags` which
+    /// parts of the certificate are printed. `to_text` is equivalent to passing empty flags.
+    #[corresponds(X509_print_ex)]
+    pub fn to_text_with_flags(
+        &self,
+        name_flags: X509NameFlags,
+        flags: X509PrintFlags,
+    ) -> Result<Vec<u8>, ErrorStack> {
+        unsafe {
+            let bio = MemBio::new()?;
+            cvt(ffi::X509_print_ex(
+                bio.as_ptr(),
+                self.as_ptr(),
+                name_flags.bits(),
+                flags.bits(),
+            ))?;
+            Ok(bio.get_buf().to_owned())
+        }
+    }
 }
 
 impl ToOwned for X509Ref {
@@ -694,6 +1429,37 @@ impl X509 {
             Ok(certs)
         }
     }
+
+    /// Serializes a list of certificates to a PEM bundle.
+    ///
+    /// This is the counterpart to `stack_from_pem`, producing the concatenated PEM blocks of the
+    /// certificates in order, as found in `fullchain.pem` files.
+    #[corresponds(PEM_write_bio_X509)]
+    pub fn stack_to_pem(certs: &StackRef<X509>) -> Result<Vec<u8>, ErrorStack> {
+        X509::write_pem_bundle(certs.iter())
+    }
+
+    /// Serializes a slice of certificates to a PEM bundle.
+    ///
+    /// See `stack_to_pem`.
+    #[corresponds(PEM_write_bio_X509)]
+    pub fn to_pem_bundle(certs: &[X509]) -> Result<Vec<u8>, ErrorStack> {
+        X509::write_pem_bundle(certs.iter().map(|cert| &**cert))
+    }
+
+    fn write_pem_bundle<'a, I>(certs: I) -> Result<Vec<u8>, ErrorStack>
+    where
+        I: Iterator<Item = &'a X509Ref>,
+    {
+        unsafe {
+            ffi::init();
+            let bio = MemBio::new()?;
+            for cert in certs {
+                cvt(ffi::PEM_write_bio_X509(bio.as_ptr(), cert.as_ptr()))?;
+            }
+            Ok(bio.get_buf().to_owned())
+        }
+    }
 }
 
 impl Clone for X509 {
@@ -774,9 +1540,61 @@ impl PartialEq<X509Ref> for X509 {
 impl Eq for X509 {}
 
 /// A context object required to construct certain `X509` extension values.
+///
+/// The context links the subject of an extension with its issuer, which lets extensions such as
+/// `subjectKeyIdentifier = hash` and `authorityKeyIdentifier = keyid,issuer` be derived from
+/// them.
 pub struct X509v3Context<'a>(ffi::X509V3_CTX, PhantomData<(&'a X509Ref, &'a ConfRef)>);
 
 impl<'a> X509v3Context<'a> {
+    /// Returns a context for extensions of `subject`, a certificate issued by `issuer`.
+    ///
+    /// Use [`X509Builder::x509v3_context`] instead while the subject is still being built.
+    ///
+    /// [`X509Builder::x509v3_context`]: struct.X509Builder.html#method.x509v3_context
+    #[corresponds(X509V3_set_ctx)]
+    pub fn new(
+        issuer: &'a X509Ref,
+        subject: &'a X509Ref,
+        conf: Option<&'a ConfRef>,
+    ) -> X509v3Context<'a> {
+        unsafe {
+            let mut ctx = mem::zeroed();
+
+            ffi::X509V3_set_ctx(
+                &mut ctx,
+                issuer.as_ptr(),
+                subject.as_ptr(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                0,
+            );
+
+            // nodb case taken care of since we zeroed ctx above
+            if let Some(conf) = conf {
+                ffi::X509V3_set_nconf(&mut ctx, conf.as_ptr());
+            }
+
+            X509v3Context(ctx, PhantomData)
+        }
+    }
+
+    /// Sets the issuer's public key.
+    ///
+    /// The key identifier of an `authorityKeyIdentifier` extension is derived from this key when
+    /// no issuer certificate is available, such as for a self-signed certificate built from a
+    /// request.
+    ///
+    /// Requires OpenSSL 3.0.0 or newer.
+    #[corresponds(X509V3_set_issuer_pkey)]
+    #[cfg(ossl300)]
+    pub fn set_issuer_pkey<T>(&mut self, key: &'a PKeyRef<T>) -> Result<(), ErrorStack>
+    where
+        T: HasPublic,
+    {
+        unsafe { cvt(ffi::X509V3_set_issuer_pkey(&mut self.0, key.as_ptr())).map(|_| ()) }
+    }
+
     pub fn as_ptr(&self) -> *mut ffi::X509V3_CTX {
         &self.0 as *const _ as *mut _
     }
@@ -848,6 +1666,49 @@ impl X509Extension {
         }
     }
 
+    /// Constructs an X509 extension with the given OID from the DER encoding of its value.
+    ///
+    /// This allows extensions which OpenSSL does not know about to be created.
+    #[corresponds(X509_EXTENSION_create_by_OBJ)]
+    pub fn new_from_der(
+        oid: &Asn1ObjectRef,
+        critical: bool,
+        der: &[u8],
+    ) -> Result<X509Extension, ErrorStack> {
+        unsafe {
+            ffi::init();
+            assert!(der.len() <= c_int::max_value() as usize);
+            let data =
+                Asn1String::from_ptr(cvt_p(ffi::ASN1_STRING_type_new(ffi::V_ASN1_OCTET_STRING))?);
+            cvt(ffi::ASN1_STRING_set(
+                data.as_ptr(),
+                der.as_ptr() as *const c_void,
+                der.len() as c_int,
+            ))?;
+            cvt_p(ffi::X509_EXTENSION_create_by_OBJ(
+                ptr::null_mut(),
+                oid.as_ptr(),
+                critical as c_int,
+                data.as_ptr() as *mut ffi::ASN1_OCTET_STRING,
+            ))
+            .map(X509Extension)
+        }
+    }
+
+    /// Constructs an X509 extension from the internal OpenSSL representation of its value.
+    ///
+    /// # Safety
+    ///
+    /// `value` must point to the structure OpenSSL uses for extensions of type `nid`.
+    pub(crate) unsafe fn new_internal(
+        nid: Nid,
+        critical: bool,
+        value: *mut c_void,
+    ) -> Result<X509Extension, ErrorStack> {
+        ffi::init();
+        cvt_p(ffi::X509V3_EXT_i2d(nid.as_raw(), critical as c_int, value)).map(X509Extension)
+    }
+
     /// Adds an alias for an extension
     ///
     /// # Safety
@@ -858,25 +1719,155 @@ impl X509Extension {
         ffi::init();
         cvt(ffi::X509V3_EXT_add_alias(to.as_raw(), from.as_raw())).map(|_| ())
     }
+
+    from_der! {
+        /// Deserializes a DER-encoded X509 extension structure.
+        #[corresponds(d2i_X509_EXTENSION)]
+        from_der,
+        X509Extension,
+        ffi::d2i_X509_EXTENSION
+    }
 }
 
-/// A builder used to construct an `X509Name`.
-pub struct X509NameBuilder(X509Name);
+impl X509ExtensionRef {
+    /// Returns the object identifying the type of the extension.
+    #[corresponds(X509_EXTENSION_get_object)]
+    pub fn object(&self) -> &Asn1ObjectRef {
+        unsafe { Asn1ObjectRef::from_ptr(ffi::X509_EXTENSION_get_object(self.as_ptr())) }
+    }
 
-impl X509NameBuilder {
-    /// Creates a new builder.
-    pub fn new() -> Result<X509NameBuilder, ErrorStack> {
+    /// Returns `true` if the extension is marked as critical.
+    #[corresponds(X509_EXTENSION_get_critical)]
+    pub fn critical(&self) -> bool {
+        unsafe { ffi::X509_EXTENSION_get_critical(self.as_ptr()) > 0 }
+    }
+
+    /// Returns the DER-encoded value of the extension.
+    #[corresponds(X509_EXTENSION_get_data)]
+    pub fn data(&self) -> &Asn1StringRef {
         unsafe {
-            ffi::init();
-            cvt_p(ffi::X509_NAME_new()).map(|p| X509NameBuilder(X509Name(p)))
+            let data = ffi::X509_EXTENSION_get_data(self.as_ptr());
+            Asn1StringRef::from_ptr(data as *mut ffi::ASN1_STRING)
         }
     }
 
-    /// Add a field entry by str.
+    /// Decodes a key usage extension.
     ///
-    /// This corresponds to [`X509_NAME_add_entry_by_txt`].
+    /// `None` is returned if the extension is of another type or is malformed.
+    #[corresponds(X509V3_EXT_d2i)]
+    pub fn key_usage(&self) -> Option<X509KeyUsageFlags> {
+        if self.object().nid() != Nid::KEY_USAGE {
+            return None;
+        }
+        unsafe { X509KeyUsageFlags::from_bit_string(ffi::X509V3_EXT_d2i(self.as_ptr()) as *mut _) }
+    }
+
+    /// Decodes the purposes listed in an extended key usage extension.
     ///
-    /// [`X509_NAME_add_entry_by_txt`]: https://www.openssl.org/docs/man1.1.0/crypto/X509_NAME_add_entry_by_txt.html
+    /// `None` is returned if the extension is of another type or is malformed.
+    #[corresponds(X509V3_EXT_d2i)]
+    pub fn extended_key_usage(&self) -> Option<Stack<Asn1Object>> {
+        if self.object().nid() != Nid::EXT_KEY_USAGE {
+            return None;
+        }
+        unsafe { Stack::from_ptr_opt(ffi::X509V3_EXT_d2i(self.as_ptr()) as *mut _) }
+    }
+
+    /// Decodes a basic constraints extension.
+    ///
+    /// `None` is returned if the extension is of another type or is malformed.
+    #[corresponds(X509V3_EXT_d2i)]
+    pub fn basic_constraints(&self) -> Option<X509BasicConstraints> {
+        if self.object().nid() != Nid::BASIC_CONSTRAINTS {
+            return None;
+        }
+        unsafe { X509BasicConstraints::from_ptr(ffi::X509V3_EXT_d2i(self.as_ptr()) as *mut _) }
+    }
+
+    to_der! {
+        /// Serializes the extension into a DER-encoded X509 extension structure.
+        #[corresponds(i2d_X509_EXTENSION)]
+        to_der,
+        ffi::i2d_X509_EXTENSION
+    }
+}
+
+bitflags! {
+    /// The usages permitted by a key usage extension.
+    pub struct X509KeyUsageFlags: u32 {
+        const DIGITAL_SIGNATURE = ffi::X509v3_KU_DIGITAL_SIGNATURE;
+        const NON_REPUDIATION = ffi::X509v3_KU_NON_REPUDIATION;
+        const KEY_ENCIPHERMENT = ffi::X509v3_KU_KEY_ENCIPHERMENT;
+        const DATA_ENCIPHERMENT = ffi::X509v3_KU_DATA_ENCIPHERMENT;
+        const KEY_AGREEMENT = ffi::X509v3_KU_KEY_AGREEMENT;
+        const KEY_CERT_SIGN = ffi::X509v3_KU_KEY_CERT_SIGN;
+        const CRL_SIGN = ffi::X509v3_KU_CRL_SIGN;
+        const ENCIPHER_ONLY = ffi::X509v3_KU_ENCIPHER_ONLY;
+        const DECIPHER_ONLY = ffi::X509v3_KU_DECIPHER_ONLY;
+    }
+}
+
+impl X509KeyUsageFlags {
+    // Takes ownership of the decoded extension value.
+    unsafe fn from_bit_string(bits: *mut ffi::ASN1_BIT_STRING) -> Option<X509KeyUsageFlags> {
+        let bits = Asn1BitString::from_ptr_opt(bits)?;
+        // The first byte holds digitalSignature through encipherOnly, and the second decipherOnly.
+        let data = bits.as_slice();
+        let mut usage = u32::from(data.first().copied().unwrap_or(0));
+        usage |= u32::from(data.get(1).copied().unwrap_or(0)) << 8;
+        Some(X509KeyUsageFlags::from_bits_truncate(usage))
+    }
+}
+
+/// The contents of a basic constraints extension.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct X509BasicConstraints {
+    /// Whether the subject of the certificate is a CA.
+    pub ca: bool,
+    /// The maximum number of intermediate certificates which may follow the certificate in a
+    /// chain, if limited.
+    pub pathlen: Option<u32>,
+}
+
+impl X509BasicConstraints {
+    // Takes ownership of the decoded extension value.
+    unsafe fn from_ptr(bc: *mut ffi::BASIC_CONSTRAINTS) -> Option<X509BasicConstraints> {
+        if bc.is_null() {
+            return None;
+        }
+        let pathlen = (*bc).pathlen;
+        // A negative or out of range path length is malformed.
+        let constraints = if pathlen.is_null() {
+            Some(None)
+        } else {
+            u32::try_from(ffi::ASN1_INTEGER_get(pathlen)).ok().map(Some)
+        }
+        .map(|pathlen| X509BasicConstraints {
+            ca: (*bc).ca != 0,
+            pathlen,
+        });
+        ffi::BASIC_CONSTRAINTS_free(bc);
+        constraints
+    }
+}
+
+/// A builder used to construct an `X509Name`.
+pub struct X509NameBuilder(X509Name);
+
+impl X509NameBuilder {
+    /// Creates a new builder.
+    pub fn new() -> Result<X509NameBuilder, ErrorStack> {
+        unsafe {
+            ffi::init();
+            cvt_p(ffi::X509_NAME_new()).map(|p| X509NameBuilder(X509Name(p)))
+        }
+    }
+
+    /// Add a field entry by str.
+    ///
+    /// This corresponds to [`X509_NAME_add_entry_by_txt`].
+    ///
+    /// [`X509_NAME_add_entry_by_txt`]: https://www.openssl.org/docs/man1.1.0/crypto/X509_NAME_add_entry_by_txt.html
     pub fn append_entry_by_text(&mut self, field: &str, value: &str) -> Result<(), ErrorStack> {
         unsafe {
             let field = CString::new(field).unwrap();
@@ -968,6 +1959,97 @@ impl X509NameBuilder {
         }
     }
 
+    /// Add a field entry by object.
+    ///
+    /// This allows entries to be added for attribute types which have no `Nid`, such as those
+    /// defined by private OIDs.
+    #[corresponds(X509_NAME_add_entry_by_OBJ)]
+    pub fn append_entry_by_obj(
+        &mut self,
+        field: &Asn1ObjectRef,
+        value: &str,
+    ) -> Result<(), ErrorStack> {
+        self.append_entry_by_obj_with_type_inner(field, value, ffi::MBSTRING_UTF8)
+    }
+
+    /// Add a field entry by object with a specific type.
+    #[corresponds(X509_NAME_add_entry_by_OBJ)]
+    pub fn append_entry_by_obj_with_type(
+        &mut self,
+        field: &Asn1ObjectRef,
+        value: &str,
+        ty: Asn1Type,
+    ) -> Result<(), ErrorStack> {
+        self.append_entry_by_obj_with_type_inner(field, value, ty.as_raw())
+    }
+
+    fn append_entry_by_obj_with_type_inner(
+        &mut self,
+        field: &Asn1ObjectRef,
+        value: &str,
+        ty: c_int,
+    ) -> Result<(), ErrorStack> {
+        unsafe {
+            assert!(value.len() <= c_int::max_value() as usize);
+            cvt(ffi::X509_NAME_add_entry_by_OBJ(
+                self.0.as_ptr(),
+                field.as_ptr(),
+                ty,
+                value.as_ptr() as *mut _,
+                value.len() as c_int,
+                -1,
+                0,
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Add an entry as a new relative distinguished name at the end of the name.
+    ///
+    /// The entry is copied, so entries of an existing name can be used to reproduce it exactly.
+    #[corresponds(X509_NAME_add_entry)]
+    pub fn append_entry(&mut self, entry: &X509NameEntryRef) -> Result<(), ErrorStack> {
+        self.add_entry(entry, -1, 0)
+    }
+
+    /// Add an entry to the last relative distinguished name of the name, creating a multi-valued
+    /// RDN such as `CN=foo+UID=bar`.
+    ///
+    /// If the name is empty, the entry is added as a new RDN.
+    #[corresponds(X509_NAME_add_entry)]
+    pub fn append_multi_valued_entry(
+        &mut self,
+        entry: &X509NameEntryRef,
+    ) -> Result<(), ErrorStack> {
+        self.add_entry(entry, -1, -1)
+    }
+
+    /// Insert an entry as a new relative distinguished name before the entry at index `loc`.
+    ///
+    /// If `loc` is past the end of the name, the entry is added at the end.
+    #[corresponds(X509_NAME_add_entry)]
+    pub fn insert_entry(&mut self, loc: usize, entry: &X509NameEntryRef) -> Result<(), ErrorStack> {
+        assert!(loc <= c_int::max_value() as usize);
+        self.add_entry(entry, loc as c_int, 0)
+    }
+
+    fn add_entry(
+        &mut self,
+        entry: &X509NameEntryRef,
+        loc: c_int,
+        set: c_int,
+    ) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::X509_NAME_add_entry(
+                self.0.as_ptr(),
+                entry.as_ptr(),
+                loc,
+                set,
+            ))
+            .map(|_| ())
+        }
+    }
+
     /// Return an `X509Name`.
     pub fn build(self) -> X509Name {
         self.0
@@ -1014,6 +2096,103 @@ impl Stackable for X509Name {
     type StackType = ffi::stack_st_X509_NAME;
 }
 
+bitflags! {
+    /// Flags controlling how an `X509Name` is formatted as a string.
+    ///
+    /// Exactly one of the `SEP_*` flags should be set.
+    pub struct X509NameFlags: c_ulong {
+        /// Escape the characters required by RFC 2253.
+        const ESC_2253 = ffi::ASN1_STRFLGS_ESC_2253;
+        /// Escape control characters.
+        const ESC_CTRL = ffi::ASN1_STRFLGS_ESC_CTRL;
+        /// Escape characters with the most significant bit set.
+        const ESC_MSB = ffi::ASN1_STRFLGS_ESC_MSB;
+        /// Surround values containing special characters with quotes rather than escaping them.
+        const ESC_QUOTE = ffi::ASN1_STRFLGS_ESC_QUOTE;
+        /// Convert values to UTF-8 before escaping them.
+        const UTF8_CONVERT = ffi::ASN1_STRFLGS_UTF8_CONVERT;
+        /// Treat all values as raw bytes, ignoring their string type.
+        const IGNORE_TYPE = ffi::ASN1_STRFLGS_IGNORE_TYPE;
+        /// Prefix values with their string type.
+        const SHOW_TYPE = ffi::ASN1_STRFLGS_SHOW_TYPE;
+        /// Print all values as hex dumps.
+        const DUMP_ALL = ffi::ASN1_STRFLGS_DUMP_ALL;
+        /// Print values of unknown string types as hex dumps.
+        const DUMP_UNKNOWN = ffi::ASN1_STRFLGS_DUMP_UNKNOWN;
+        /// Hex dumps contain the full DER encoding of the value rather than just its content.
+        const DUMP_DER = ffi::ASN1_STRFLGS_DUMP_DER;
+        /// Separate RDNs with `,` and multi-valued RDN entries with `+`.
+        const SEP_COMMA_PLUS = ffi::XN_FLAG_SEP_COMMA_PLUS;
+        /// Separate RDNs with `, ` and multi-valued RDN entries with ` + `.
+        const SEP_CPLUS_SPC = ffi::XN_FLAG_SEP_CPLUS_SPC;
+        /// Separate RDNs with `; ` and multi-valued RDN entries with ` + `.
+        const SEP_SPLUS_SPC = ffi::XN_FLAG_SEP_SPLUS_SPC;
+        /// Print each entry on its own line.
+        const SEP_MULTILINE = ffi::XN_FLAG_SEP_MULTILINE;
+        /// Print the RDNs in reverse order, as required by RFC 2253.
+        const DN_REV = ffi::XN_FLAG_DN_REV;
+        /// Use the long names of fields rather than their short names.
+        const FN_LN = ffi::XN_FLAG_FN_LN;
+        /// Use the numeric OIDs of fields rather than their short names.
+        const FN_OID = ffi::XN_FLAG_FN_OID;
+        /// Omit field names.
+        const FN_NONE = ffi::XN_FLAG_FN_NONE;
+        /// Put spaces around the `=` between field names and values.
+        const SPC_EQ = ffi::XN_FLAG_SPC_EQ;
+        /// Print the values of fields with unknown names as hex dumps.
+        const DUMP_UNKNOWN_FIELDS = ffi::XN_FLAG_DUMP_UNKNOWN_FIELDS;
+        /// Pad field names to align values when used with `SEP_MULTILINE`.
+        const FN_ALIGN = ffi::XN_FLAG_FN_ALIGN;
+        /// The format specified by RFC 2253.
+        const RFC2253 = ffi::XN_FLAG_RFC2253;
+        /// A more readable single line format, as used by the `openssl` command line tools.
+        const ONELINE = ffi::XN_FLAG_ONELINE;
+        /// A format with one field per line, using long field names.
+        const MULTILINE = ffi::XN_FLAG_MULTILINE;
+    }
+}
+
+bitflags! {
+    /// Flags controlling how an `X509` is formatted as text.
+    ///
+    /// At most one of the `*_UNKNOWN_EXTENSIONS` flags should be set.
+    pub struct X509PrintFlags: c_ulong {
+        /// Omit the `Certificate:` and `Data:` headers.
+        const NO_HEADER = ffi::X509_FLAG_NO_HEADER;
+        /// Omit the version.
+        const NO_VERSION = ffi::X509_FLAG_NO_VERSION;
+        /// Omit the serial number.
+        const NO_SERIAL = ffi::X509_FLAG_NO_SERIAL;
+        /// Omit the signature algorithm.
+        const NO_SIGNAME = ffi::X509_FLAG_NO_SIGNAME;
+        /// Omit the issuer name.
+        const NO_ISSUER = ffi::X509_FLAG_NO_ISSUER;
+        /// Omit the validity period.
+        const NO_VALIDITY = ffi::X509_FLAG_NO_VALIDITY;
+        /// Omit the subject name.
+        const NO_SUBJECT = ffi::X509_FLAG_NO_SUBJECT;
+        /// Omit the public key.
+        const NO_PUBKEY = ffi::X509_FLAG_NO_PUBKEY;
+        /// Omit the extensions.
+        const NO_EXTENSIONS = ffi::X509_FLAG_NO_EXTENSIONS;
+        /// Omit the signature.
+        const NO_SIGDUMP = ffi::X509_FLAG_NO_SIGDUMP;
+        /// Omit the trust settings.
+        const NO_AUX = ffi::X509_FLAG_NO_AUX;
+        /// Omit the issuer and subject unique identifiers.
+        ///
+        /// Requires OpenSSL 1.1.0 or newer.
+        #[cfg(ossl110)]
+        const NO_IDS = ffi::X509_FLAG_NO_IDS;
+        /// Fail if an extension is not recognized.
+        const ERROR_UNKNOWN_EXTENSIONS = ffi::X509V3_EXT_ERROR_UNKNOWN;
+        /// Print unrecognized extensions as parsed ASN.1.
+        const PARSE_UNKNOWN_EXTENSIONS = ffi::X509V3_EXT_PARSE_UNKNOWN;
+        /// Print unrecognized extensions as hex dumps.
+        const DUMP_UNKNOWN_EXTENSIONS = ffi::X509V3_EXT_DUMP_UNKNOWN;
+    }
+}
+
 impl X509NameRef {
     /// Returns the name entries by the nid.
     pub fn entries_by_nid(&self, nid: Nid) -> X509NameEntries<'_> {
@@ -1033,6 +2212,107 @@ impl X509NameRef {
         }
     }
 
+    /// Returns the number of entries in the name.
+    ///
+    /// Entries are indexed in the order they are returned by `entries`.
+    #[corresponds(X509_NAME_entry_count)]
+    pub fn entry_count(&self) -> usize {
+        unsafe { ffi::X509_NAME_entry_count(self.as_ptr()) as usize }
+    }
+
+    /// Removes the entry at index `loc`, returning it.
+    ///
+    /// `None` is returned if `loc` is out of bounds.
+    #[corresponds(X509_NAME_delete_entry)]
+    pub fn delete_entry(&mut self, loc: usize) -> Option<X509NameEntry> {
+        if loc >= self.entry_count() {
+            return None;
+        }
+        unsafe {
+            X509NameEntry::from_ptr_opt(ffi::X509_NAME_delete_entry(self.as_ptr(), loc as c_int))
+        }
+    }
+
+    /// Removes all entries of type `nid`, returning the number of entries removed.
+    #[corresponds(X509_NAME_delete_entry)]
+    pub fn delete_entries_by_nid(&mut self, nid: Nid) -> usize {
+        let mut count = 0;
+        loop {
+            let loc = unsafe { ffi::X509_NAME_get_index_by_NID(self.as_ptr(), nid.as_raw(), -1) };
+            if loc < 0 {
+                return count;
+            }
+            self.delete_entry(loc as usize);
+            count += 1;
+        }
+    }
+
+    /// Replaces the value of the entry at index `loc`.
+    ///
+    /// The entry keeps its type and position, including its membership of a multi-valued RDN. As
+    /// with `X509NameBuilder::append_entry_by_nid`, the string type of the value is chosen based
+    /// on the type of the entry.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `loc` is out of bounds.
+    #[corresponds(X509_NAME_ENTRY_create_by_OBJ)]
+    #[cfg(ossl110)]
+    pub fn set_entry_data(&mut self, loc: usize, value: &str) -> Result<(), ErrorStack> {
+        let count = self.entry_count();
+        assert!(loc < count, "entry index out of bounds");
+        assert!(value.len() <= c_int::max_value() as usize);
+
+        unsafe {
+            let name = self.as_ptr();
+            let set_of =
+                |loc: usize| ffi::X509_NAME_ENTRY_set(ffi::X509_NAME_get_entry(name, loc as c_int));
+
+            let entry = ffi::X509_NAME_get_entry(name, loc as c_int);
+            let replacement = X509NameEntry::from_ptr(cvt_p(ffi::X509_NAME_ENTRY_create_by_OBJ(
+                ptr::null_mut(),
+                ffi::X509_NAME_ENTRY_get_object(entry),
+                ffi::MBSTRING_UTF8,
+                value.as_ptr(),
+                value.len() as c_int,
+            ))?);
+
+            // Reinsert the entry into the same RDN: merge it with the previous or next entry if
+            // they share it, or start a new one otherwise.
+            let set = set_of(loc);
+            let set = if loc > 0 && set_of(loc - 1) == set {
+                -1
+            } else if loc + 1 < count && set_of(loc + 1) == set {
+                1
+            } else {
+                0
+            };
+
+            self.delete_entry(loc);
+            cvt(ffi::X509_NAME_add_entry(
+                name,
+                replacement.as_ptr(),
+                loc as c_int,
+                set,
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Returns a copy of the name.
+    ///
+    /// The copy can be modified, for example to derive a new subject name from that of an
+    /// existing certificate.
+    ///
+    /// Requires OpenSSL 1.1.0 or LibreSSL 2.7.0 or newer.
+    #[corresponds(X509_NAME_dup)]
+    #[cfg(any(ossl110, libressl270))]
+    pub fn to_owned(&self) -> Result<X509Name, ErrorStack> {
+        unsafe { cvt_p(ffi::X509_NAME_dup(self.as_ptr())).map(|n| X509Name::from_ptr(n)) }
+    }
+
     /// Compare two names, like [`Ord`] but it may fail.
     ///
     /// With OpenSSL versions from 3.0.0 this may return an error if the underlying `X509_NAME_cmp`
@@ -1048,6 +2328,38 @@ impl X509NameRef {
         Ok(cmp.cmp(&0))
     }
 
+    /// Formats the name as a string according to `flags`.
+    ///
+    /// Values which are not valid UTF-8 are only produced if neither `ESC_MSB` nor `UTF8_CONVERT`
+    /// is set, and are replaced with `U+FFFD REPLACEMENT CHARACTER`.
+    #[corresponds(X509_NAME_print_ex)]
+    pub fn to_string_with_flags(&self, flags: X509NameFlags) -> Result<String, ErrorStack> {
+        unsafe {
+            let bio = MemBio::new()?;
+            cvt_n(ffi::X509_NAME_print_ex(
+                bio.as_ptr(),
+                self.as_ptr(),
+                0,
+                flags.bits(),
+            ))?;
+            Ok(String::from_utf8_lossy(bio.get_buf()).into_owned())
+        }
+    }
+
+    /// Formats the name as specified by RFC 2253, e.g. `CN=foo,O=bar,C=AU`.
+    ///
+    /// Special characters in values are escaped, so names formatted this way can be compared as
+    /// strings.
+    pub fn to_string_rfc2253(&self) -> Result<String, ErrorStack> {
+        self.to_string_with_flags(X509NameFlags::RFC2253)
+    }
+
+    /// Formats the name on a single line as the `openssl` command line tools do, e.g.
+    /// `C = AU, O = bar, CN = foo`.
+    pub fn to_string_oneline(&self) -> Result<String, ErrorStack> {
+        self.to_string_with_flags(X509NameFlags::ONELINE)
+    }
+
     to_der! {
         /// Serializes the certificate into a DER-encoded X509 name structure.
         ///
@@ -1112,6 +2424,34 @@ foreign_type_and_impl_send_sync! {
     pub struct X509NameEntryRef;
 }
 
+impl X509NameEntry {
+    /// Creates a new entry with the field `field` and string type `ty`.
+    ///
+    /// The entry can be added to a name with the methods of [`X509NameBuilder`], which allow it to
+    /// be placed at a specific position or in a multi-valued RDN.
+    ///
+    /// [`X509NameBuilder`]: struct.X509NameBuilder.html
+    #[corresponds(X509_NAME_ENTRY_create_by_OBJ)]
+    pub fn new(
+        field: &Asn1ObjectRef,
+        value: &str,
+        ty: Asn1Type,
+    ) -> Result<X509NameEntry, ErrorStack> {
+        unsafe {
+            ffi::init();
+            assert!(value.len() <= c_int::max_value() as usize);
+            cvt_p(ffi::X509_NAME_ENTRY_create_by_OBJ(
+                ptr::null_mut(),
+                field.as_ptr(),
+                ty.as_raw(),
+                value.as_ptr(),
+                value.len() as c_int,
+            ))
+            .map(X509NameEntry)
+        }
+    }
+}
+
 impl X509NameEntryRef {
     /// Returns the field value of an `X509NameEntry`.
     ///
@@ -1241,6 +2581,70 @@ impl X509ReqBuilder {
         }
     }
 
+    /// Adds an attribute to the request, such as `Nid::PKCS9_CHALLENGEPASSWORD`.
+    ///
+    /// The string type of the value is chosen based on the attribute.
+    #[corresponds(X509_REQ_add1_attr_by_NID)]
+    pub fn add_attribute_by_nid(&mut self, nid: Nid, value: &str) -> Result<(), ErrorStack> {
+        unsafe {
+            assert!(value.len() <= c_int::max_value() as usize);
+            cvt(ffi::X509_REQ_add1_attr_by_NID(
+                self.0.as_ptr(),
+                nid.as_raw(),
+                ffi::MBSTRING_UTF8,
+                value.as_ptr(),
+                value.len() as c_int,
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Adds an attribute to the request by object.
+    ///
+    /// This allows attributes to be added for types which have no `Nid`, such as those defined by
+    /// private OIDs. The value is encoded as a `UTF8String`.
+    #[corresponds(X509_REQ_add1_attr_by_OBJ)]
+    pub fn add_attribute_by_obj(
+        &mut self,
+        obj: &Asn1ObjectRef,
+        value: &str,
+    ) -> Result<(), ErrorStack> {
+        self.add_attribute_by_obj_inner(obj, value.as_bytes(), Asn1Type::UTF8STRING.as_raw())
+    }
+
+    /// Adds an attribute to the request by object with a specific type.
+    ///
+    /// For string types, `value` holds the contents of the string. For constructed types such as
+    /// `Asn1Type::SEQUENCE`, `value` holds the complete DER encoding of the value.
+    #[corresponds(X509_REQ_add1_attr_by_OBJ)]
+    pub fn add_attribute_by_obj_with_type(
+        &mut self,
+        obj: &Asn1ObjectRef,
+        value: &[u8],
+        ty: Asn1Type,
+    ) -> Result<(), ErrorStack> {
+        self.add_attribute_by_obj_inner(obj, value, ty.as_raw())
+    }
+
+    fn add_attribute_by_obj_inner(
+        &mut self,
+        obj: &Asn1ObjectRef,
+        value: &[u8],
+        ty: c_int,
+    ) -> Result<(), ErrorStack> {
+        unsafe {
+            assert!(value.len() <= c_int::max_value() as usize);
+            cvt(ffi::X509_REQ_add1_attr_by_OBJ(
+                self.0.as_ptr(),
+                obj.as_ptr(),
+                ty,
+                value.as_ptr(),
+                value.len() as c_int,
+            ))
+            .map(|_| ())
+        }
+    }
+
     /// Sign the request using a private key.
     ///
     /// This corresponds to [`X509_REQ_sign`].
@@ -1330,69 +2734,721 @@ impl X509ReqRef {
         ffi::i2d_X509_REQ
     }
 
-    to_pem! {
-        /// Converts the request to human readable text.
-        #[corresponds(X509_Req_print)]
-        to_text,
-        ffi::X509_REQ_print
+    to_pem! {
+        /// Converts the request to human readable text.
+        #[corresponds(X509_Req_print)]
+        to_text,
+        ffi::X509_REQ_print
+    }
+
+    /// Returns the numerical value of the version field of the certificate request.
+    ///
+    /// This corresponds to [`X509_REQ_get_version`]
+    ///
+    /// [`X509_REQ_get_version`]: https://www.openssl.org/docs/man1.1.0/crypto/X509_REQ_get_version.html
+    pub fn version(&self) -> i32 {
+        unsafe { X509_REQ_get_version(self.as_ptr()) as i32 }
+    }
+
+    /// Returns the subject name of the certificate request.
+    ///
+    /// This corresponds to [`X509_REQ_get_subject_name`]
+    ///
+    /// [`X509_REQ_get_subject_name`]: https://www.openssl.org/docs/man1.1.0/crypto/X509_REQ_get_subject_name.html
+    pub fn subject_name(&self) -> &X509NameRef {
+        unsafe {
+            let name = X509_REQ_get_subject_name(self.as_ptr());
+            X509NameRef::from_const_ptr_opt(name).expect("subject name must not be null")
+        }
+    }
+
+    /// Returns the public key of the certificate request.
+    ///
+    /// This corresponds to [`X509_REQ_get_pubkey"]
+    ///
+    /// [`X509_REQ_get_pubkey`]: https://www.openssl.org/docs/man1.1.0/crypto/X509_REQ_get_pubkey.html
+    pub fn public_key(&self) -> Result<PKey<Public>, ErrorStack> {
+        unsafe {
+            let key = cvt_p(ffi::X509_REQ_get_pubkey(self.as_ptr()))?;
+            Ok(PKey::from_ptr(key))
+        }
+    }
+
+    /// Check if the certificate request is signed using the given public key.
+    ///
+    /// Returns `true` if verification succeeds. Verifying a request against its own public key
+    /// confirms that the requester possesses the corresponding private key.
+    #[corresponds(X509_REQ_verify)]
+    pub fn verify<T>(&self, key: &PKeyRef<T>) -> Result<bool, ErrorStack>
+    where
+        T: HasPublic,
+    {
+        unsafe { cvt_n(ffi::X509_REQ_verify(self.as_ptr(), key.as_ptr())).map(|n| n != 0) }
+    }
+
+    /// Returns the extensions of the certificate request.
+    ///
+    /// This corresponds to [`X509_REQ_get_extensions"]
+    pub fn extensions(&self) -> Result<Stack<X509Extension>, ErrorStack> {
+        unsafe {
+            let extensions = cvt_p(ffi::X509_REQ_get_extensions(self.as_ptr()))?;
+            Ok(Stack::from_ptr(extensions))
+        }
+    }
+
+    /// Returns the subject alternative name entries requested, if they exist.
+    #[corresponds(X509V3_get_d2i)]
+    pub fn subject_alt_names(&self) -> Option<Stack<GeneralName>> {
+        let extensions = match self.extensions() {
+            Ok(extensions) => extensions,
+            Err(_) => return None,
+        };
+        unsafe {
+            let stack = ffi::X509V3_get_d2i(
+                extensions.as_ptr(),
+                ffi::NID_subject_alt_name,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            Stack::from_ptr_opt(stack as *mut _)
+        }
+    }
+
+    /// Returns an iterator over the attributes of the certificate request.
+    ///
+    /// Requested extensions are stored in an attribute of type `Nid::EXT_REQ`, and are more easily
+    /// accessed with the `extensions` method.
+    pub fn attributes(&self) -> X509ReqAttributes<'_> {
+        X509ReqAttributes { req: self, loc: 0 }
+    }
+
+    /// Returns the first attribute of the certificate request of type `nid`, if present.
+    #[corresponds(X509_REQ_get_attr_by_NID)]
+    pub fn attribute_by_nid(&self, nid: Nid) -> Option<&X509AttributeRef> {
+        unsafe {
+            let loc = ffi::X509_REQ_get_attr_by_NID(self.as_ptr(), nid.as_raw(), -1);
+            if loc < 0 {
+                return None;
+            }
+            X509AttributeRef::from_const_ptr_opt(ffi::X509_REQ_get_attr(self.as_ptr(), loc))
+        }
+    }
+
+    /// Returns the challenge password of the certificate request, if present.
+    pub fn challenge_password(&self) -> Option<&Asn1StringRef> {
+        self.attribute_by_nid(Nid::PKCS9_CHALLENGEPASSWORD)?
+            .value(0)
+    }
+}
+
+/// An iterator over the attributes of an `X509Req`.
+pub struct X509ReqAttributes<'a> {
+    req: &'a X509ReqRef,
+    loc: c_int,
+}
+
+impl<'a> Iterator for X509ReqAttributes<'a> {
+    type Item = &'a X509AttributeRef;
+
+    fn next(&mut self) -> Option<&'a X509AttributeRef> {
+        unsafe {
+            if self.loc >= ffi::X509_REQ_get_attr_count(self.req.as_ptr()) {
+                return None;
+            }
+            let attr = ffi::X509_REQ_get_attr(self.req.as_ptr(), self.loc);
+            self.loc += 1;
+            Some(X509AttributeRef::from_const_ptr_opt(attr).expect("attribute must not be null"))
+        }
+    }
+}
+
+foreign_type_and_impl_send_sync! {
+    type CType = ffi::X509_ATTRIBUTE;
+    fn drop = ffi::X509_ATTRIBUTE_free;
+
+    /// An attribute of an `X509Req`, consisting of a type and a set of values.
+    pub struct X509Attribute;
+    /// Reference to `X509Attribute`.
+    pub struct X509AttributeRef;
+}
+
+impl X509AttributeRef {
+    /// Returns the object identifying the type of the attribute.
+    #[corresponds(X509_ATTRIBUTE_get0_object)]
+    pub fn object(&self) -> &Asn1ObjectRef {
+        unsafe { Asn1ObjectRef::from_ptr(ffi::X509_ATTRIBUTE_get0_object(self.as_ptr())) }
+    }
+
+    /// Returns the number of values of the attribute.
+    #[corresponds(X509_ATTRIBUTE_count)]
+    pub fn value_count(&self) -> usize {
+        unsafe { ffi::X509_ATTRIBUTE_count(self.as_ptr()) as usize }
+    }
+
+    /// Returns the ASN.1 type of the value at index `idx`, if present.
+    #[corresponds(X509_ATTRIBUTE_get0_type)]
+    pub fn value_type(&self, idx: usize) -> Option<Asn1Type> {
+        unsafe {
+            let value = ffi::X509_ATTRIBUTE_get0_type(self.as_ptr(), idx as c_int);
+            if value.is_null() {
+                None
+            } else {
+                Some(Asn1Type::from_raw(ffi::ASN1_TYPE_get(value)))
+            }
+        }
+    }
+
+    /// Returns the value at index `idx`, if present.
+    ///
+    /// Values of constructed types such as `SEQUENCE` are returned in their DER encoding. `None` is
+    /// returned for `BOOLEAN`, `OBJECT` and `NULL` values, which are not represented as strings.
+    #[corresponds(X509_ATTRIBUTE_get0_data)]
+    pub fn value(&self, idx: usize) -> Option<&Asn1StringRef> {
+        let ty = self.value_type(idx)?;
+        if ty == Asn1Type::BOOLEAN || ty == Asn1Type::OBJECT || ty == Asn1Type::NULL {
+            return None;
+        }
+        unsafe {
+            let data = ffi::X509_ATTRIBUTE_get0_data(
+                self.as_ptr(),
+                idx as c_int,
+                ty.as_raw(),
+                ptr::null_mut(),
+            );
+            Asn1StringRef::from_const_ptr_opt(data as *mut ffi::ASN1_STRING)
+        }
+    }
+}
+
+/// An identifier of the purpose a certificate is used for.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct X509PurposeId(c_int);
+
+impl X509PurposeId {
+    pub const SSL_CLIENT: X509PurposeId = X509PurposeId(ffi::X509_PURPOSE_SSL_CLIENT);
+    pub const SSL_SERVER: X509PurposeId = X509PurposeId(ffi::X509_PURPOSE_SSL_SERVER);
+    pub const NS_SSL_SERVER: X509PurposeId = X509PurposeId(ffi::X509_PURPOSE_NS_SSL_SERVER);
+    pub const SMIME_SIGN: X509PurposeId = X509PurposeId(ffi::X509_PURPOSE_SMIME_SIGN);
+    pub const SMIME_ENCRYPT: X509PurposeId = X509PurposeId(ffi::X509_PURPOSE_SMIME_ENCRYPT);
+    pub const CRL_SIGN: X509PurposeId = X509PurposeId(ffi::X509_PURPOSE_CRL_SIGN);
+    pub const ANY: X509PurposeId = X509PurposeId(ffi::X509_PURPOSE_ANY);
+    pub const OCSP_HELPER: X509PurposeId = X509PurposeId(ffi::X509_PURPOSE_OCSP_HELPER);
+    pub const TIMESTAMP_SIGN: X509PurposeId = X509PurposeId(ffi::X509_PURPOSE_TIMESTAMP_SIGN);
+
+    /// Constructs an `X509PurposeId` from a raw OpenSSL value.
+    pub fn from_raw(raw: c_int) -> X509PurposeId {
+        X509PurposeId(raw)
+    }
+
+    /// Returns the raw OpenSSL value represented by this type.
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn as_raw(&self) -> c_int {
+        self.0
+    }
+}
+
+/// An identifier of the trust setting a certificate is checked against.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct X509TrustId(c_int);
+
+impl X509TrustId {
+    /// Uses the default trust setting of the purpose being checked.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[cfg(ossl110)]
+    pub const DEFAULT: X509TrustId = X509TrustId(ffi::X509_TRUST_DEFAULT);
+    pub const COMPAT: X509TrustId = X509TrustId(ffi::X509_TRUST_COMPAT);
+    pub const SSL_CLIENT: X509TrustId = X509TrustId(ffi::X509_TRUST_SSL_CLIENT);
+    pub const SSL_SERVER: X509TrustId = X509TrustId(ffi::X509_TRUST_SSL_SERVER);
+    pub const EMAIL: X509TrustId = X509TrustId(ffi::X509_TRUST_EMAIL);
+    pub const OBJECT_SIGN: X509TrustId = X509TrustId(ffi::X509_TRUST_OBJECT_SIGN);
+    pub const OCSP_SIGN: X509TrustId = X509TrustId(ffi::X509_TRUST_OCSP_SIGN);
+    pub const OCSP_REQUEST: X509TrustId = X509TrustId(ffi::X509_TRUST_OCSP_REQUEST);
+    pub const TSA: X509TrustId = X509TrustId(ffi::X509_TRUST_TSA);
+
+    /// Constructs an `X509TrustId` from a raw OpenSSL value.
+    pub fn from_raw(raw: c_int) -> X509TrustId {
+        X509TrustId(raw)
+    }
+
+    /// Returns the raw OpenSSL value represented by this type.
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn as_raw(&self) -> c_int {
+        self.0
+    }
+}
+
+/// The result of checking the trust settings of a certificate.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct X509TrustResult(c_int);
+
+impl X509TrustResult {
+    /// The certificate is explicitly trusted for the purpose.
+    pub const TRUSTED: X509TrustResult = X509TrustResult(ffi::X509_TRUST_TRUSTED);
+    /// The certificate is explicitly rejected for the purpose.
+    pub const REJECTED: X509TrustResult = X509TrustResult(ffi::X509_TRUST_REJECTED);
+    /// The certificate has no trust settings for the purpose.
+    pub const UNTRUSTED: X509TrustResult = X509TrustResult(ffi::X509_TRUST_UNTRUSTED);
+
+    /// Constructs an `X509TrustResult` from a raw OpenSSL value.
+    pub fn from_raw(raw: c_int) -> X509TrustResult {
+        X509TrustResult(raw)
+    }
+
+    /// Returns the raw OpenSSL value represented by this type.
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn as_raw(&self) -> c_int {
+        self.0
+    }
+}
+
+/// The reason a certificate was revoked.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct CrlReason(c_int);
+
+impl CrlReason {
+    pub const UNSPECIFIED: CrlReason = CrlReason(ffi::CRL_REASON_UNSPECIFIED);
+    pub const KEY_COMPROMISE: CrlReason = CrlReason(ffi::CRL_REASON_KEY_COMPROMISE);
+    pub const CA_COMPROMISE: CrlReason = CrlReason(ffi::CRL_REASON_CA_COMPROMISE);
+    pub const AFFILIATION_CHANGED: CrlReason = CrlReason(ffi::CRL_REASON_AFFILIATION_CHANGED);
+    pub const SUPERSEDED: CrlReason = CrlReason(ffi::CRL_REASON_SUPERSEDED);
+    pub const CESSATION_OF_OPERATION: CrlReason = CrlReason(ffi::CRL_REASON_CESSATION_OF_OPERATION);
+    pub const CERTIFICATE_HOLD: CrlReason = CrlReason(ffi::CRL_REASON_CERTIFICATE_HOLD);
+    pub const REMOVE_FROM_CRL: CrlReason = CrlReason(ffi::CRL_REASON_REMOVE_FROM_CRL);
+    pub const PRIVILEGE_WITHDRAWN: CrlReason = CrlReason(ffi::CRL_REASON_PRIVILEGE_WITHDRAWN);
+    pub const AA_COMPROMISE: CrlReason = CrlReason(ffi::CRL_REASON_AA_COMPROMISE);
+
+    /// Constructs a `CrlReason` from a raw OpenSSL value.
+    pub fn from_raw(raw: c_int) -> CrlReason {
+        CrlReason(raw)
+    }
+
+    /// Returns the raw OpenSSL value represented by this type.
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn as_raw(&self) -> c_int {
+        self.0
+    }
+}
+
+/// A builder used to construct an `X509Crl`.
+pub struct X509CrlBuilder(X509Crl);
+
+impl X509CrlBuilder {
+    /// Creates a new builder.
+    #[corresponds(X509_CRL_new)]
+    pub fn new() -> Result<X509CrlBuilder, ErrorStack> {
+        unsafe {
+            ffi::init();
+            cvt_p(ffi::X509_CRL_new()).map(|p| X509CrlBuilder(X509Crl(p)))
+        }
+    }
+
+    /// Sets the version of the CRL.
+    ///
+    /// Note that the version is zero-indexed; that is, a version 2 CRL, which is required for
+    /// CRLs with extensions, has the value 1.
+    #[corresponds(X509_CRL_set_version)]
+    pub fn set_version(&mut self, version: i32) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::X509_CRL_set_version(
+                self.0.as_ptr(),
+                version as c_long,
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Sets the issuer name of the CRL.
+    #[corresponds(X509_CRL_set_issuer_name)]
+    pub fn set_issuer_name(&mut self, issuer_name: &X509NameRef) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::X509_CRL_set_issuer_name(
+                self.0.as_ptr(),
+                issuer_name.as_ptr(),
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Sets the time at which the CRL was issued.
+    #[corresponds(X509_CRL_set1_lastUpdate)]
+    pub fn set_last_update(&mut self, last_update: &Asn1TimeRef) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(X509_CRL_set1_lastUpdate(
+                self.0.as_ptr(),
+                last_update.as_ptr(),
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Sets the time by which the next CRL will be issued.
+    #[corresponds(X509_CRL_set1_nextUpdate)]
+    pub fn set_next_update(&mut self, next_update: &Asn1TimeRef) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(X509_CRL_set1_nextUpdate(
+                self.0.as_ptr(),
+                next_update.as_ptr(),
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Adds a revoked certificate to the CRL.
+    ///
+    /// If `reason` is provided, it is recorded in a reason code extension of the entry.
+    #[corresponds(X509_CRL_add0_revoked)]
+    pub fn add_revoked(
+        &mut self,
+        serial_number: &Asn1IntegerRef,
+        revocation_date: &Asn1TimeRef,
+        reason: Option<CrlReason>,
+    ) -> Result<(), ErrorStack> {
+        unsafe {
+            let revoked = X509Revoked::from_ptr(cvt_p(ffi::X509_REVOKED_new())?);
+            cvt(ffi::X509_REVOKED_set_serialNumber(
+                revoked.as_ptr(),
+                serial_number.as_ptr(),
+            ))?;
+            cvt(ffi::X509_REVOKED_set_revocationDate(
+                revoked.as_ptr(),
+                revocation_date.as_ptr(),
+            ))?;
+
+            if let Some(reason) = reason {
+                let value = cvt_p(ffi::ASN1_ENUMERATED_new())?;
+                let r = cvt(ffi::ASN1_ENUMERATED_set(value, reason.as_raw() as c_long)).and_then(
+                    |_| {
+                        cvt(ffi::X509_REVOKED_add1_ext_i2d(
+                            revoked.as_ptr(),
+                            ffi::NID_crl_reason,
+                            value as *mut _,
+                            0,
+                            0,
+                        ))
+                    },
+                );
+                ffi::ASN1_ENUMERATED_free(value);
+                r?;
+            }
+
+            cvt(ffi::X509_CRL_add0_revoked(
+                self.0.as_ptr(),
+                revoked.as_ptr(),
+            ))?;
+            mem::forget(revoked);
+            Ok(())
+        }
+    }
+
+    /// Sets the CRL number extension of the CRL.
+    #[corresponds(X509_CRL_add1_ext_i2d)]
+    pub fn set_crl_number(&mut self, number: &Asn1IntegerRef) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::X509_CRL_add1_ext_i2d(
+                self.0.as_ptr(),
+                ffi::NID_crl_number,
+                number.as_ptr() as *mut _,
+                0,
+                ffi::X509V3_ADD_REPLACE,
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Adds an extension to the CRL.
+    #[corresponds(X509_CRL_add_ext)]
+    pub fn append_extension(&mut self, extension: &X509ExtensionRef) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::X509_CRL_add_ext(
+                self.0.as_ptr(),
+                extension.as_ptr(),
+                -1,
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Returns a context object which is needed to create certain X509 extension values, such as
+    /// the `authorityKeyIdentifier` of a CRL issued by `issuer`.
+    #[corresponds(X509V3_set_ctx)]
+    pub fn x509v3_context<'a>(
+        &'a self,
+        issuer: &'a X509Ref,
+        conf: Option<&'a ConfRef>,
+    ) -> X509v3Context<'a> {
+        unsafe {
+            let mut ctx = mem::zeroed();
+
+            ffi::X509V3_set_ctx(
+                &mut ctx,
+                issuer.as_ptr(),
+                ptr::null_mut(),
+                ptr::null_mut(),
+                self.0.as_ptr(),
+                0,
+            );
+
+            // nodb case taken care of since we zeroed ctx above
+            if let Some(conf) = conf {
+                ffi::X509V3_set_nconf(&mut ctx, conf.as_ptr());
+            }
+
+            X509v3Context(ctx, PhantomData)
+        }
+    }
+
+    /// Signs the CRL with a private key.
+    ///
+    /// The revoked certificates are sorted by serial number before the CRL is signed.
+    #[corresponds(X509_CRL_sign)]
+    pub fn sign<T>(&mut self, key: &PKeyRef<T>, hash: MessageDigest) -> Result<(), ErrorStack>
+    where
+        T: HasPrivate,
+    {
+        unsafe {
+            cvt(ffi::X509_CRL_sort(self.0.as_ptr()))?;
+            cvt(ffi::X509_CRL_sign(
+                self.0.as_ptr(),
+                key.as_ptr(),
+                hash.as_ptr(),
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Consumes the builder, returning the CRL.
+    pub fn build(self) -> X509Crl {
+        self.0
+    }
+}
+
+foreign_type_and_impl_send_sync! {
+    type CType = ffi::X509_CRL;
+    fn drop = ffi::X509_CRL_free;
+
+    /// An `X509` certificate revocation list.
+    pub struct X509Crl;
+    /// Reference to `X509Crl`.
+    pub struct X509CrlRef;
+}
+
+impl X509Crl {
+    /// Returns a new builder.
+    pub fn builder() -> Result<X509CrlBuilder, ErrorStack> {
+        X509CrlBuilder::new()
+    }
+
+    from_pem! {
+        /// Deserializes a PEM-encoded certificate revocation list.
+        ///
+        /// The input should have a header of `-----BEGIN X509 CRL-----`.
+        #[corresponds(PEM_read_bio_X509_CRL)]
+        from_pem,
+        X509Crl,
+        ffi::PEM_read_bio_X509_CRL
+    }
+
+    from_der! {
+        /// Deserializes a DER-encoded certificate revocation list.
+        #[corresponds(d2i_X509_CRL)]
+        from_der,
+        X509Crl,
+        ffi::d2i_X509_CRL
+    }
+}
+
+impl X509CrlRef {
+    to_pem! {
+        /// Serializes the certificate revocation list into a PEM-encoded structure.
+        ///
+        /// The output will have a header of `-----BEGIN X509 CRL-----`.
+        #[corresponds(PEM_write_bio_X509_CRL)]
+        to_pem,
+        ffi::PEM_write_bio_X509_CRL
+    }
+
+    to_der! {
+        /// Serializes the certificate revocation list into a DER-encoded structure.
+        #[corresponds(i2d_X509_CRL)]
+        to_der,
+        ffi::i2d_X509_CRL
+    }
+
+    /// Returns the issuer name of the CRL.
+    #[corresponds(X509_CRL_get_issuer)]
+    pub fn issuer_name(&self) -> &X509NameRef {
+        unsafe {
+            let name = X509_CRL_get_issuer(self.as_ptr());
+            X509NameRef::from_const_ptr_opt(name).expect("issuer name must not be null")
+        }
+    }
+
+    /// Returns the time at which the CRL was issued.
+    #[corresponds(X509_CRL_get0_lastUpdate)]
+    pub fn last_update(&self) -> &Asn1TimeRef {
+        unsafe {
+            let date = X509_CRL_get0_lastUpdate(self.as_ptr());
+            Asn1TimeRef::from_const_ptr_opt(date).expect("last update must not be null")
+        }
+    }
+
+    /// Returns the time by which the next CRL will be issued, if it is set.
+    #[corresponds(X509_CRL_get0_nextUpdate)]
+    pub fn next_update(&self) -> Option<&Asn1TimeRef> {
+        unsafe {
+            let date = X509_CRL_get0_nextUpdate(self.as_ptr());
+            Asn1TimeRef::from_const_ptr_opt(date)
+        }
     }
 
-    /// Returns the numerical value of the version field of the certificate request.
-    ///
-    /// This corresponds to [`X509_REQ_get_version`]
-    ///
-    /// [`X509_REQ_get_version`]: https://www.openssl.org/docs/man1.1.0/crypto/X509_REQ_get_version.html
-    pub fn version(&self) -> i32 {
-        unsafe { X509_REQ_get_version(self.as_ptr()) as i32 }
+    /// Returns the revoked certificates listed in the CRL, if there are any.
+    #[corresponds(X509_CRL_get_REVOKED)]
+    pub fn get_revoked(&self) -> Option<&StackRef<X509Revoked>> {
+        unsafe {
+            let revoked = X509_CRL_get_REVOKED(self.as_ptr());
+            StackRef::from_const_ptr_opt(revoked)
+        }
     }
 
-    /// Returns the subject name of the certificate request.
-    ///
-    /// This corresponds to [`X509_REQ_get_subject_name`]
-    ///
-    /// [`X509_REQ_get_subject_name`]: https://www.openssl.org/docs/man1.1.0/crypto/X509_REQ_get_subject_name.html
-    pub fn subject_name(&self) -> &X509NameRef {
+    /// Looks up the certificate with the serial number `serial` in the CRL.
+    #[corresponds(X509_CRL_get0_by_serial)]
+    pub fn get_by_serial<'a>(&'a self, serial: &Asn1IntegerRef) -> CrlStatus<'a> {
         unsafe {
-            let name = X509_REQ_get_subject_name(self.as_ptr());
-            X509NameRef::from_const_ptr_opt(name).expect("subject name must not be null")
+            let mut ret = ptr::null_mut();
+            let status = ffi::X509_CRL_get0_by_serial(self.as_ptr(), &mut ret, serial.as_ptr());
+            CrlStatus::from_ffi_status(status, ret)
         }
     }
 
-    /// Returns the public key of the certificate request.
+    /// Looks up `cert` in the CRL.
     ///
-    /// This corresponds to [`X509_REQ_get_pubkey"]
-    ///
-    /// [`X509_REQ_get_pubkey`]: https://www.openssl.org/docs/man1.1.0/crypto/X509_REQ_get_pubkey.html
-    pub fn public_key(&self) -> Result<PKey<Public>, ErrorStack> {
+    /// The certificate is matched by both its issuer name and its serial number.
+    #[corresponds(X509_CRL_get0_by_cert)]
+    pub fn get_by_cert<'a>(&'a self, cert: &X509Ref) -> CrlStatus<'a> {
         unsafe {
-            let key = cvt_p(ffi::X509_REQ_get_pubkey(self.as_ptr()))?;
-            Ok(PKey::from_ptr(key))
+            let mut ret = ptr::null_mut();
+            let status = ffi::X509_CRL_get0_by_cert(self.as_ptr(), &mut ret, cert.as_ptr());
+            CrlStatus::from_ffi_status(status, ret)
         }
     }
 
-    /// Check if the certificate request is signed using the given public key.
-    ///
-    /// Returns `true` if verification succeeds.
-    ///
-    /// This corresponds to [`X509_REQ_verify"].
-    ///
-    /// [`X509_REQ_verify`]: https://www.openssl.org/docs/man1.1.0/crypto/X509_REQ_verify.html
+    /// Returns the value of the CRL number extension, if present.
+    #[corresponds(X509_CRL_get_ext_d2i)]
+    pub fn crl_number(&self) -> Option<Asn1Integer> {
+        unsafe {
+            let number = ffi::X509_CRL_get_ext_d2i(
+                self.as_ptr(),
+                ffi::NID_crl_number,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            );
+            Asn1Integer::from_ptr_opt(number as *mut _)
+        }
+    }
+
+    /// Checks that the CRL is signed by the private key corresponding to `key`.
+    #[corresponds(X509_CRL_verify)]
     pub fn verify<T>(&self, key: &PKeyRef<T>) -> Result<bool, ErrorStack>
     where
         T: HasPublic,
     {
-        unsafe { cvt_n(ffi::X509_REQ_verify(self.as_ptr(), key.as_ptr())).map(|n| n != 0) }
+        unsafe { cvt_n(ffi::X509_CRL_verify(self.as_ptr(), key.as_ptr())).map(|n| n != 0) }
     }
+}
 
-    /// Returns the extensions of the certificate request.
+/// The status of a certificate in a certificate revocation list.
+pub enum CrlStatus<'a> {
+    /// The certificate is not listed.
+    NotRevoked,
+    /// The certificate is listed and revoked.
+    Revoked(&'a X509RevokedRef),
+    /// The certificate is listed with the `removeFromCRL` reason, so it is no longer revoked.
     ///
-    /// This corresponds to [`X509_REQ_get_extensions"]
-    pub fn extensions(&self) -> Result<Stack<X509Extension>, ErrorStack> {
+    /// This only occurs in delta CRLs.
+    RemoveFromCrl(&'a X509RevokedRef),
+}
+
+impl<'a> CrlStatus<'a> {
+    // Safety: `revoked` must be valid for the lifetime of the CRL if `status` is nonzero.
+    unsafe fn from_ffi_status(status: c_int, revoked: *mut ffi::X509_REVOKED) -> CrlStatus<'a> {
+        match status {
+            0 => CrlStatus::NotRevoked,
+            1 => CrlStatus::Revoked(X509RevokedRef::from_ptr(revoked)),
+            2 => CrlStatus::RemoveFromCrl(X509RevokedRef::from_ptr(revoked)),
+            _ => unreachable!("X509_CRL_get0_by_{{serial,cert}} returned {}", status),
+        }
+    }
+}
+
+foreign_type_and_impl_send_sync! {
+    type CType = ffi::X509_REVOKED;
+    fn drop = ffi::X509_REVOKED_free;
+
+    /// An entry of a certificate revocation list.
+    pub struct X509Revoked;
+    /// Reference to `X509Revoked`.
+    pub struct X509RevokedRef;
+}
+
+impl Stackable for X509Revoked {
+    type StackType = ffi::stack_st_X509_REVOKED;
+}
+
+impl X509Revoked {
+    from_der! {
+        /// Deserializes a DER-encoded X509 revoked certificate structure.
+        #[corresponds(d2i_X509_REVOKED)]
+        from_der,
+        X509Revoked,
+        ffi::d2i_X509_REVOKED
+    }
+}
+
+impl X509RevokedRef {
+    /// Returns the serial number of the revoked certificate.
+    #[corresponds(X509_REVOKED_get0_serialNumber)]
+    pub fn serial_number(&self) -> &Asn1IntegerRef {
         unsafe {
-            let extensions = cvt_p(ffi::X509_REQ_get_extensions(self.as_ptr()))?;
-            Ok(Stack::from_ptr(extensions))
+            let serial = X509_REVOKED_get0_serialNumber(self.as_ptr());
+            Asn1IntegerRef::from_const_ptr_opt(serial).expect("serial number must not be null")
+        }
+    }
+
+    /// Returns the time at which the certificate was revoked.
+    #[corresponds(X509_REVOKED_get0_revocationDate)]
+    pub fn revocation_date(&self) -> &Asn1TimeRef {
+        unsafe {
+            let date = X509_REVOKED_get0_revocationDate(self.as_ptr());
+            Asn1TimeRef::from_const_ptr_opt(date).expect("revocation date must not be null")
+        }
+    }
+
+    /// Returns the reason the certificate was revoked, if the entry records one.
+    #[corresponds(X509_REVOKED_get_ext_d2i)]
+    pub fn reason(&self) -> Option<CrlReason> {
+        unsafe {
+            let reason = ffi::X509_REVOKED_get_ext_d2i(
+                self.as_ptr(),
+                ffi::NID_crl_reason,
+                ptr::null_mut(),
+                ptr::null_mut(),
+            ) as *mut ffi::ASN1_ENUMERATED;
+            if reason.is_null() {
+                return None;
+            }
+            let raw = ffi::ASN1_ENUMERATED_get(reason);
+            ffi::ASN1_ENUMERATED_free(reason);
+            Some(CrlReason::from_raw(raw as c_int))
         }
     }
+
+    to_der! {
+        /// Serializes the entry into a DER-encoded X509 revoked certificate structure.
+        #[corresponds(i2d_X509_REVOKED)]
+        to_der,
+        ffi::i2d_X509_REVOKED
+    }
 }
 
 /// The result of peer certificate verification.
@@ -1453,6 +3509,91 @@ impl X509VerifyResult {
     /// Application verification failure.
     pub const APPLICATION_VERIFICATION: X509VerifyResult =
         X509VerifyResult(ffi::X509_V_ERR_APPLICATION_VERIFICATION);
+    /// The issuer certificate could not be found.
+    pub const UNABLE_TO_GET_ISSUER_CERT: X509VerifyResult =
+        X509VerifyResult(ffi::X509_V_ERR_UNABLE_TO_GET_ISSUER_CERT);
+    /// The issuer certificate of a certificate in the chain could not be found in the trusted
+    /// certificates.
+    pub const UNABLE_TO_GET_ISSUER_CERT_LOCALLY: X509VerifyResult =
+        X509VerifyResult(ffi::X509_V_ERR_UNABLE_TO_GET_ISSUER_CERT_LOCALLY);
+    /// The signature of the leaf certificate could not be verified because the chain contains
+    /// only that certificate and it is not self-signed.
+    pub const UNABLE_TO_VERIFY_LEAF_SIGNATURE: X509VerifyResult =
+        X509VerifyResult(ffi::X509_V_ERR_UNABLE_TO_VERIFY_LEAF_SIGNATURE);
+    /// The CRL of a certificate could not be found.
+    pub const UNABLE_TO_GET_CRL: X509VerifyResult =
+        X509VerifyResult(ffi::X509_V_ERR_UNABLE_TO_GET_CRL);
+    /// The certificate is not yet valid.
+    pub const CERT_NOT_YET_VALID: X509VerifyResult =
+        X509VerifyResult(ffi::X509_V_ERR_CERT_NOT_YET_VALID);
+    /// The certificate has expired.
+    pub const CERT_HAS_EXPIRED: X509VerifyResult =
+        X509VerifyResult(ffi::X509_V_ERR_CERT_HAS_EXPIRED);
+    /// The CRL is not yet valid.
+    pub const CRL_NOT_YET_VALID: X509VerifyResult =
+        X509VerifyResult(ffi::X509_V_ERR_CRL_NOT_YET_VALID);
+    /// The CRL has expired.
+    pub const CRL_HAS_EXPIRED: X509VerifyResult = X509VerifyResult(ffi::X509_V_ERR_CRL_HAS_EXPIRED);
+    /// The certificate is self-signed and not trusted.
+    pub const DEPTH_ZERO_SELF_SIGNED_CERT: X509VerifyResult =
+        X509VerifyResult(ffi::X509_V_ERR_DEPTH_ZERO_SELF_SIGNED_CERT);
+    /// The chain ends in a self-signed certificate which is not trusted.
+    pub const SELF_SIGNED_CERT_IN_CHAIN: X509VerifyResult =
+        X509VerifyResult(ffi::X509_V_ERR_SELF_SIGNED_CERT_IN_CHAIN);
+    /// The chain is longer than the maximum depth.
+    pub const CERT_CHAIN_TOO_LONG: X509VerifyResult =
+        X509VerifyResult(ffi::X509_V_ERR_CERT_CHAIN_TOO_LONG);
+    /// The certificate has been revoked.
+    pub const CERT_REVOKED: X509VerifyResult = X509VerifyResult(ffi::X509_V_ERR_CERT_REVOKED);
+    /// The certificate cannot be used for the requested purpose.
+    pub const INVALID_PURPOSE: X509VerifyResult = X509VerifyResult(ffi::X509_V_ERR_INVALID_PURPOSE);
+    /// The certificate does not match the expected hostname.
+    ///
+    /// Requires OpenSSL 1.0.2 or newer.
+    #[cfg(ossl102)]
+    pub const HOSTNAME_MISMATCH: X509VerifyResult =
+        X509VerifyResult(ffi::X509_V_ERR_HOSTNAME_MISMATCH);
+    /// The issuer name of the certificate does not match the subject name of the issuer.
+    pub const SUBJECT_ISSUER_MISMATCH: X509VerifyResult =
+        X509VerifyResult(ffi::X509_V_ERR_SUBJECT_ISSUER_MISMATCH);
+    /// The authority key identifier of the certificate does not match the subject key identifier
+    /// of the issuer.
+    pub const AKID_SKID_MISMATCH: X509VerifyResult =
+        X509VerifyResult(ffi::X509_V_ERR_AKID_SKID_MISMATCH);
+    /// The issuer name or serial number in the authority key identifier of the certificate does
+    /// not match the issuer.
+    pub const AKID_ISSUER_SERIAL_MISMATCH: X509VerifyResult =
+        X509VerifyResult(ffi::X509_V_ERR_AKID_ISSUER_SERIAL_MISMATCH);
+    /// The key usage of the issuer does not permit signing certificates.
+    pub const KEYUSAGE_NO_CERTSIGN: X509VerifyResult =
+        X509VerifyResult(ffi::X509_V_ERR_KEYUSAGE_NO_CERTSIGN);
+    /// A certificate contains an invalid policy extension.
+    pub const INVALID_POLICY_EXTENSION: X509VerifyResult =
+        X509VerifyResult(ffi::X509_V_ERR_INVALID_POLICY_EXTENSION);
+    /// An explicit policy was required, but the chain is not valid for any acceptable policy.
+    pub const NO_EXPLICIT_POLICY: X509VerifyResult =
+        X509VerifyResult(ffi::X509_V_ERR_NO_EXPLICIT_POLICY);
+}
+
+/// Returns a verification callback which overrides exactly the errors in `allowed`.
+///
+/// The callback accepts a certificate if OpenSSL's verification of it succeeded, or if it failed
+/// with one of the `allowed` errors, at any depth in the chain. Any other error fails
+/// verification. For example, passing `&[X509VerifyResult::CERT_HAS_EXPIRED]` accepts expired
+/// certificates, but still rejects those with an untrusted issuer.
+///
+/// The returned callback can be passed to [`SslContextBuilder::set_verify_callback`],
+/// [`SslRef::set_verify_callback`] or [`X509StoreContextRef::set_verify_callback`]. Overridden
+/// errors are still reported once verification completes.
+///
+/// [`SslContextBuilder::set_verify_callback`]: ../ssl/struct.SslContextBuilder.html#method.set_verify_callback
+/// [`SslRef::set_verify_callback`]: ../ssl/struct.SslRef.html#method.set_verify_callback
+/// [`X509StoreContextRef::set_verify_callback`]: struct.X509StoreContextRef.html#method.set_verify_callback
+pub fn allow_verify_errors(
+    allowed: &[X509VerifyResult],
+) -> impl Fn(bool, &mut X509StoreContextRef) -> bool + 'static + Sync + Send {
+    let allowed = allowed.to_vec();
+    move |preverify_ok, ctx| preverify_ok || allowed.contains(&ctx.error())
 }
 
 foreign_type_and_impl_send_sync! {
@@ -1465,6 +3606,123 @@ foreign_type_and_impl_send_sync! {
     pub struct GeneralNameRef;
 }
 
+impl GeneralName {
+    unsafe fn new(type_: c_int, asn1_type: c_int, value: &[u8]) -> Result<GeneralName, ErrorStack> {
+        ffi::init();
+        let gn = GeneralName::from_ptr(cvt_p(ffi::GENERAL_NAME_new())?);
+        let s = cvt_p(ffi::ASN1_STRING_type_new(asn1_type))?;
+        ffi::GENERAL_NAME_set0_value(gn.as_ptr(), type_, s as *mut _);
+        cvt(ffi::ASN1_STRING_set(
+            s,
+            value.as_ptr() as *const _,
+            value.len() as c_int,
+        ))?;
+        Ok(gn)
+    }
+
+    /// Creates an `rfc822Name` containing an email address.
+    pub fn new_email(email: &[u8]) -> Result<GeneralName, ErrorStack> {
+        unsafe { GeneralName::new(ffi::GEN_EMAIL, ffi::V_ASN1_IA5STRING, email) }
+    }
+
+    /// Creates a `dNSName`.
+    pub fn new_dns(dns: &[u8]) -> Result<GeneralName, ErrorStack> {
+        unsafe { GeneralName::new(ffi::GEN_DNS, ffi::V_ASN1_IA5STRING, dns) }
+    }
+
+    /// Creates a `uniformResourceIdentifier`.
+    pub fn new_uri(uri: &[u8]) -> Result<GeneralName, ErrorStack> {
+        unsafe { GeneralName::new(ffi::GEN_URI, ffi::V_ASN1_IA5STRING, uri) }
+    }
+
+    /// Creates an `iPAddress`.
+    pub fn new_ip(ip: IpAddr) -> Result<GeneralName, ErrorStack> {
+        match ip {
+            IpAddr::V4(addr) => unsafe {
+                GeneralName::new(ffi::GEN_IPADD, ffi::V_ASN1_OCTET_STRING, &addr.octets())
+            },
+            IpAddr::V6(addr) => unsafe {
+                GeneralName::new(ffi::GEN_IPADD, ffi::V_ASN1_OCTET_STRING, &addr.octets())
+            },
+        }
+    }
+
+    /// Parses `ip` with OpenSSL's own address syntax.
+    pub(crate) fn new_ip_str(ip: &str) -> Result<GeneralName, ErrorStack> {
+        let bad_ip = || ErrorStack::from_reason(ffi::ERR_LIB_X509V3, ffi::X509V3_R_BAD_IP_ADDRESS);
+        let ip = CString::new(ip).map_err(|_| bad_ip())?;
+        unsafe {
+            ffi::init();
+            let gn = GeneralName::from_ptr(cvt_p(ffi::GENERAL_NAME_new())?);
+            let s = cvt_p(ffi::a2i_IPADDRESS(ip.as_ptr())).map_err(|_| bad_ip())?;
+            ffi::GENERAL_NAME_set0_value(gn.as_ptr(), ffi::GEN_IPADD, s as *mut _);
+            Ok(gn)
+        }
+    }
+
+    /// Creates a `registeredID`.
+    pub fn new_rid(oid: Asn1Object) -> Result<GeneralName, ErrorStack> {
+        unsafe {
+            ffi::init();
+            let gn = GeneralName::from_ptr(cvt_p(ffi::GENERAL_NAME_new())?);
+            ffi::GENERAL_NAME_set0_value(gn.as_ptr(), ffi::GEN_RID, oid.as_ptr() as *mut _);
+            mem::forget(oid);
+            Ok(gn)
+        }
+    }
+
+    /// Creates a `directoryName` containing a copy of `name`.
+    ///
+    /// Requires OpenSSL 1.1.0 or LibreSSL 2.7.0 or newer.
+    #[cfg(any(ossl110, libressl270))]
+    pub fn new_dir_name(name: &X509NameRef) -> Result<GeneralName, ErrorStack> {
+        unsafe {
+            ffi::init();
+            let gn = GeneralName::from_ptr(cvt_p(ffi::GENERAL_NAME_new())?);
+            let name = cvt_p(ffi::X509_NAME_dup(name.as_ptr()))?;
+            ffi::GENERAL_NAME_set0_value(gn.as_ptr(), ffi::GEN_DIRNAME, name as *mut _);
+            Ok(gn)
+        }
+    }
+
+    /// Creates an `otherName` of type `oid`.
+    ///
+    /// `value` is the DER encoding of the name's value, such as a `UTF8String` for a Microsoft
+    /// user principal name. It must hold exactly one value.
+    #[corresponds(GENERAL_NAME_set0_othername)]
+    pub fn new_other_name(oid: Asn1Object, value: &[u8]) -> Result<GeneralName, ErrorStack> {
+        unsafe {
+            ffi::init();
+            let gn = GeneralName::from_ptr(cvt_p(ffi::GENERAL_NAME_new())?);
+            let len = cmp::min(value.len(), c_long::max_value() as usize) as c_long;
+            let mut p = value.as_ptr();
+            let end = value.as_ptr().add(value.len());
+            let value = cvt_p(ffi::d2i_ASN1_TYPE(ptr::null_mut(), &mut p, len))?;
+            if p != end {
+                ffi::ASN1_TYPE_free(value);
+                return Err(ErrorStack::from_reason(
+                    ffi::ERR_LIB_ASN1,
+                    ffi::ASN1_R_TOO_LONG,
+                ));
+            }
+            if ffi::GENERAL_NAME_set0_othername(gn.as_ptr(), oid.as_ptr(), value) <= 0 {
+                ffi::ASN1_TYPE_free(value);
+                return Err(ErrorStack::get());
+            }
+            mem::forget(oid);
+            Ok(gn)
+        }
+    }
+
+    from_der! {
+        /// Deserializes a DER-encoded `GeneralName`.
+        #[corresponds(d2i_GENERAL_NAME)]
+        from_der,
+        GeneralName,
+        ffi::d2i_GENERAL_NAME
+    }
+}
+
 impl GeneralNameRef {
     fn ia5_string(&self, ffi_type: c_int) -> Option<&str> {
         unsafe {
@@ -1520,6 +3778,70 @@ impl GeneralNameRef {
             Some(slice::from_raw_parts(ptr as *const u8, len as usize))
         }
     }
+
+    /// Returns the contents of this `GeneralName` if it is a `directoryName`.
+    pub fn directory_name(&self) -> Option<&X509NameRef> {
+        unsafe {
+            if (*self.as_ptr()).type_ != ffi::GEN_DIRNAME {
+                return None;
+            }
+
+            #[cfg(boringssl)]
+            let d = (*self.as_ptr()).d.ptr;
+            #[cfg(not(boringssl))]
+            let d = (*self.as_ptr()).d;
+
+            Some(X509NameRef::from_ptr(d as *mut _))
+        }
+    }
+
+    /// Returns the contents of this `GeneralName` if it is a `registeredID`.
+    pub fn registered_id(&self) -> Option<&Asn1ObjectRef> {
+        unsafe {
+            if (*self.as_ptr()).type_ != ffi::GEN_RID {
+                return None;
+            }
+
+            #[cfg(boringssl)]
+            let d = (*self.as_ptr()).d.ptr;
+            #[cfg(not(boringssl))]
+            let d = (*self.as_ptr()).d;
+
+            Some(Asn1ObjectRef::from_ptr(d as *mut _))
+        }
+    }
+
+    /// Returns the type and the DER encoded value of this `GeneralName` if it is an `otherName`.
+    #[corresponds(GENERAL_NAME_get0_otherName)]
+    pub fn other_name(&self) -> Option<(&Asn1ObjectRef, Vec<u8>)> {
+        unsafe {
+            let mut oid = ptr::null_mut();
+            let mut value = ptr::null_mut();
+            if ffi::GENERAL_NAME_get0_otherName(self.as_ptr(), &mut oid, &mut value) != 1 {
+                return None;
+            }
+
+            let len = ffi::i2d_ASN1_TYPE(value, ptr::null_mut());
+            if len <= 0 {
+                return None;
+            }
+            let mut buf = vec![0; len as usize];
+            let len = ffi::i2d_ASN1_TYPE(value, &mut buf.as_mut_ptr());
+            if len <= 0 {
+                return None;
+            }
+            buf.truncate(len as usize);
+
+            Some((Asn1ObjectRef::from_ptr(oid), buf))
+        }
+    }
+
+    to_der! {
+        /// Serializes the name into a DER-encoded `GeneralName`.
+        #[corresponds(i2d_GENERAL_NAME)]
+        to_der,
+        ffi::i2d_GENERAL_NAME
+    }
 }
 
 impl fmt::Debug for GeneralNameRef {
@@ -1533,6 +3855,12 @@ impl fmt::Debug for GeneralNameRef {
         } else if let Some(ipaddress) = self.ipaddress() {
             let result = String::from_utf8_lossy(ipaddress);
             formatter.write_str(&result)
+        } else if let Some(name) = self.directory_name() {
+            name.fmt(formatter)
+        } else if let Some(rid) = self.registered_id() {
+            rid.fmt(formatter)
+        } else if let Some((oid, _)) = self.other_name() {
+            oid.fmt(formatter)
         } else {
             formatter.write_str("(empty)")
         }
@@ -1569,6 +3897,77 @@ impl Stackable for AccessDescription {
     type StackType = ffi::stack_st_ACCESS_DESCRIPTION;
 }
 
+foreign_type_and_impl_send_sync! {
+    type CType = ffi::DIST_POINT;
+    fn drop = ffi::DIST_POINT_free;
+
+    /// A CRL distribution point of a certificate.
+    pub struct DistPoint;
+    /// Reference to `DistPoint`.
+    pub struct DistPointRef;
+}
+
+impl DistPointRef {
+    /// Returns the name of the distribution point, if present.
+    pub fn distpoint(&self) -> Option<&DistPointNameRef> {
+        unsafe { DistPointNameRef::from_const_ptr_opt((*self.as_ptr()).distpoint) }
+    }
+
+    /// Returns the names of the issuer of the CRL, if it differs from the certificate's issuer.
+    pub fn crl_issuer(&self) -> Option<&StackRef<GeneralName>> {
+        unsafe { StackRef::from_const_ptr_opt((*self.as_ptr()).CRLissuer) }
+    }
+}
+
+impl Stackable for DistPoint {
+    type StackType = ffi::stack_st_DIST_POINT;
+}
+
+foreign_type_and_impl_send_sync! {
+    type CType = ffi::DIST_POINT_NAME;
+    fn drop = ffi::DIST_POINT_NAME_free;
+
+    /// The name of a CRL distribution point.
+    pub struct DistPointName;
+    /// Reference to `DistPointName`.
+    pub struct DistPointNameRef;
+}
+
+impl DistPointNameRef {
+    /// Returns the full names of the distribution point, if it is named that way.
+    ///
+    /// These are typically `uniformResourceIdentifier`s from which the CRL can be fetched.
+    pub fn fullname(&self) -> Option<&StackRef<GeneralName>> {
+        unsafe {
+            if (*self.as_ptr()).type_ != ffi::DIST_POINT_NAME_FULLNAME {
+                return None;
+            }
+            StackRef::from_const_ptr_opt((*self.as_ptr()).name as *const _)
+        }
+    }
+}
+
+foreign_type_and_impl_send_sync! {
+    type CType = ffi::POLICYINFO;
+    fn drop = ffi::POLICYINFO_free;
+
+    /// A policy from a certificate's certificate policies extension.
+    pub struct PolicyInfo;
+    /// Reference to `PolicyInfo`.
+    pub struct PolicyInfoRef;
+}
+
+impl PolicyInfoRef {
+    /// Returns the OID identifying the policy.
+    pub fn policy_id(&self) -> &Asn1ObjectRef {
+        unsafe { Asn1ObjectRef::from_ptr((*self.as_ptr()).policyid) }
+    }
+}
+
+impl Stackable for PolicyInfo {
+    type StackType = ffi::stack_st_POLICYINFO;
+}
+
 foreign_type_and_impl_send_sync! {
     type CType = ffi::X509_ALGOR;
     fn drop = ffi::X509_ALGOR_free;
@@ -1694,6 +4093,66 @@ cfg_if! {
     }
 }
 
+cfg_if! {
+    if #[cfg(any(ossl110, boringssl, libressl281))] {
+        use ffi::{
+            X509_CRL_get0_lastUpdate, X509_CRL_get0_nextUpdate, X509_CRL_get_REVOKED,
+            X509_CRL_get_issuer,
+        };
+    } else {
+        #[allow(bad_style)]
+        unsafe fn X509_CRL_get0_lastUpdate(x: *const ffi::X509_CRL) -> *const ffi::ASN1_TIME {
+            (*(*x).crl).lastUpdate
+        }
+
+        #[allow(bad_style)]
+        unsafe fn X509_CRL_get0_nextUpdate(x: *const ffi::X509_CRL) -> *const ffi::ASN1_TIME {
+            (*(*x).crl).nextUpdate
+        }
+
+        #[allow(bad_style)]
+        unsafe fn X509_CRL_get_REVOKED(x: *mut ffi::X509_CRL) -> *mut ffi::stack_st_X509_REVOKED {
+            (*(*x).crl).revoked
+        }
+
+        #[allow(bad_style)]
+        unsafe fn X509_CRL_get_issuer(x: *const ffi::X509_CRL) -> *mut ffi::X509_NAME {
+            (*(*x).crl).issuer
+        }
+    }
+}
+
+cfg_if! {
+    if #[cfg(any(ossl110, boringssl, libressl270))] {
+        use ffi::{X509_REVOKED_get0_revocationDate, X509_REVOKED_get0_serialNumber};
+    } else {
+        #[allow(bad_style)]
+        unsafe fn X509_REVOKED_get0_serialNumber(
+            x: *const ffi::X509_REVOKED,
+        ) -> *const ffi::ASN1_INTEGER {
+            (*x).serialNumber
+        }
+
+        #[allow(bad_style)]
+        unsafe fn X509_REVOKED_get0_revocationDate(
+            x: *const ffi::X509_REVOKED,
+        ) -> *const ffi::ASN1_TIME {
+            (*x).revocationDate
+        }
+    }
+}
+
+cfg_if! {
+    if #[cfg(any(ossl110, boringssl, libressl270))] {
+        use ffi::{X509_CRL_set1_lastUpdate, X509_CRL_set1_nextUpdate};
+    } else {
+        use ffi::{
+            X509_CRL_set_lastUpdate as X509_CRL_set1_lastUpdate,
+            X509_CRL_set_nextUpdate as X509_CRL_set1_nextUpdate,
+        };
+    }
+}
+
 cfg_if! {
     if #[cfg(any(ossl110, boringssl, libressl270))] {
         use ffi::X509_OBJECT_get0_X509;
@@ -1722,3 +4181,36 @@ cfg_if! {
         }
     }
 }
+
+cfg_if! {
+    if #[cfg(ossl110)] {
+        unsafe fn get_new_store_ctx_idx(f: ffi::CRYPTO_EX_free) -> c_int {
+            ffi::CRYPTO_get_ex_new_index(
+                ffi::CRYPTO_EX_INDEX_X509_STORE_CTX,
+                0,
+                ptr::null_mut(),
+                None,
+                None,
+                Some(f),
+            )
+        }
+    } else {
+        use std::sync::Once;
+
+        unsafe fn get_new_store_ctx_idx(f: ffi::CRYPTO_EX_free) -> c_int {
+            // hack around https://rt.openssl.org/Ticket/Display.html?id=3710&user=guest&pass=guest
+            static ONCE: Once = Once::new();
+            ONCE.call_once(|| {
+                #[cfg(not(boringssl))]
+                ffi::X509_STORE_CTX_get_ex_new_index(0, ptr::null_mut(), None, None, None);
+                #[cfg(boringssl)]
+                ffi::X509_STORE_CTX_get_ex_new_index(0, ptr::null_mut(), ptr::null_mut(), None, None);
+            });
+
+            #[cfg(not(boringssl))]
+            return ffi::X509_STORE_CTX_get_ex_new_index(0, ptr::null_mut(), None, None, Some(f));
+            #[cfg(boringssl)]
+            return ffi::X509_STORE_CTX_get_ex_new_index(0, ptr::null_mut(), ptr::null_mut(), None, f);
+        }
+    }
+}
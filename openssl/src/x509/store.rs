@@ -43,6 +43,7 @@
 
 use cfg_if::cfg_if;
 use foreign_types::ForeignTypeRef;
+use libc::c_int;
 use std::mem;
 
 use crate::error::ErrorStack;
@@ -50,12 +51,14 @@ use crate::error::ErrorStack;
 use crate::ssl::SslFiletype;
 use crate::stack::StackRef;
 #[cfg(any(ossl102, libressl261))]
-use crate::x509::verify::X509VerifyFlags;
-use crate::x509::{X509Object, X509};
+use crate::x509::verify::{X509VerifyFlags, X509VerifyParamRef};
+use crate::x509::{X509Object, X509PurposeId, X509TrustId, X509};
 use crate::{cvt, cvt_p};
 use openssl_macros::corresponds;
 #[cfg(not(boringssl))]
 use std::ffi::CString;
+#[cfg(not(boringssl))]
+use std::path::Path;
 
 foreign_type_and_impl_send_sync! {
     type CType = ffi::X509_STORE;
@@ -116,12 +119,61 @@ impl X509StoreBuilderRef {
         cvt_p(lookup).map(|ptr| unsafe { X509LookupRef::from_ptr_mut(ptr) })
     }
 
+    /// Adds a lookup which loads certificates and CRLs on demand from a directory.
+    ///
+    /// The directory must contain PEM files named after the hash of the certificate's subject
+    /// name or the CRL's issuer name, as created by OpenSSL's `c_rehash` tool. Files are only
+    /// read when a lookup requires them.
+    #[corresponds(X509_LOOKUP_hash_dir)]
+    #[cfg(not(boringssl))]
+    pub fn add_lookup_hash_dir<P: AsRef<Path>>(&mut self, path: P) -> Result<(), ErrorStack> {
+        let path = path.as_ref().as_os_str().to_str().unwrap();
+        self.add_lookup(X509Lookup::hash_dir())?
+            .add_dir(path, SslFiletype::PEM)
+    }
+
+    /// Adds a lookup which loads all certificates and CRLs from a PEM file.
+    #[corresponds(X509_LOOKUP_file)]
+    #[cfg(not(boringssl))]
+    pub fn add_lookup_file<P: AsRef<Path>>(&mut self, path: P) -> Result<(), ErrorStack> {
+        self.add_lookup(X509Lookup::file())?.load_file(path)
+    }
+
     /// Sets certificate chain validation related flags.
     #[corresponds(X509_STORE_set_flags)]
     #[cfg(any(ossl102, libressl261))]
     pub fn set_flags(&mut self, flags: X509VerifyFlags) -> Result<(), ErrorStack> {
         unsafe { cvt(ffi::X509_STORE_set_flags(self.as_ptr(), flags.bits())).map(|_| ()) }
     }
+
+    /// Sets the maximum depth of chains verified against the store.
+    ///
+    /// The depth counts the intermediate certificates between the leaf and the trust anchor.
+    #[corresponds(X509_STORE_set_depth)]
+    pub fn set_depth(&mut self, depth: c_int) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::X509_STORE_set_depth(self.as_ptr(), depth)).map(|_| ()) }
+    }
+
+    /// Sets the purpose leaf certificates verified against the store must be valid for.
+    #[corresponds(X509_STORE_set_purpose)]
+    pub fn set_purpose(&mut self, purpose: X509PurposeId) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::X509_STORE_set_purpose(self.as_ptr(), purpose.as_raw())).map(|_| ()) }
+    }
+
+    /// Sets the trust setting trust anchors of the store are checked against.
+    #[corresponds(X509_STORE_set_trust)]
+    pub fn set_trust(&mut self, trust: X509TrustId) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::X509_STORE_set_trust(self.as_ptr(), trust.as_raw())).map(|_| ()) }
+    }
+
+    /// Sets the verification parameters used for chains verified against the store.
+    ///
+    /// Only the parameters which have been set in `param` override those of the store.
+    #[corresponds(X509_STORE_set1_param)]
+    #[cfg(any(ossl102, libressl261))]
+    pub fn set_param(&mut self, param: &X509VerifyParamRef) -> Result<(), ErrorStack> {
+        unsafe { cvt(ffi::X509_STORE_set1_param(self.as_ptr(), param.as_ptr())).map(|_| ()) }
+    }
 }
 
 generic_foreign_type_and_impl_send_sync! {
@@ -169,6 +221,77 @@ impl X509LookupRef<HashDir> {
     }
 }
 
+/// Marker type corresponding to the [`X509_LOOKUP_file`] lookup method.
+///
+/// [`X509_LOOKUP_file`]: https://www.openssl.org/docs/man1.1.1/man3/X509_LOOKUP_file.html
+pub struct File;
+
+impl X509Lookup<File> {
+    /// Lookup method that loads certificates and CRLs from files into the
+    /// store's in-memory cache when they are added to the lookup.
+    #[corresponds(X509_LOOKUP_file)]
+    pub fn file() -> &'static X509LookupMethodRef<File> {
+        unsafe { X509LookupMethodRef::from_ptr(ffi::X509_LOOKUP_file()) }
+    }
+}
+
+#[cfg(not(boringssl))]
+impl X509LookupRef<File> {
+    /// Loads all certificates and CRLs from a PEM file.
+    #[corresponds(X509_LOOKUP_load_file)]
+    pub fn load_file<P: AsRef<Path>>(&mut self, file: P) -> Result<(), ErrorStack> {
+        let file = CString::new(file.as_ref().as_os_str().to_str().unwrap()).unwrap();
+        unsafe {
+            cvt(ffi::X509_LOOKUP_load_file(
+                self.as_ptr(),
+                file.as_ptr(),
+                ffi::X509_FILETYPE_PEM,
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Loads the certificates from a file.
+    ///
+    /// A PEM file may contain several certificates, while a DER file contains exactly one.
+    #[corresponds(X509_load_cert_file)]
+    pub fn load_cert_file<P: AsRef<Path>>(
+        &mut self,
+        file: P,
+        file_type: SslFiletype,
+    ) -> Result<(), ErrorStack> {
+        let file = CString::new(file.as_ref().as_os_str().to_str().unwrap()).unwrap();
+        unsafe {
+            cvt(ffi::X509_load_cert_file(
+                self.as_ptr(),
+                file.as_ptr(),
+                file_type.as_raw(),
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Loads the CRLs from a file.
+    ///
+    /// A PEM file may contain several CRLs, while a DER file contains exactly one.
+    #[corresponds(X509_load_crl_file)]
+    pub fn load_crl_file<P: AsRef<Path>>(
+        &mut self,
+        file: P,
+        file_type: SslFiletype,
+    ) -> Result<(), ErrorStack> {
+        let file = CString::new(file.as_ref().as_os_str().to_str().unwrap()).unwrap();
+        unsafe {
+            cvt(ffi::X509_load_crl_file(
+                self.as_ptr(),
+                file.as_ptr(),
+                file_type.as_raw(),
+            ))
+            .map(|_| ())
+        }
+    }
+}
+
 generic_foreign_type_and_impl_send_sync! {
     type CType = ffi::X509_LOOKUP_METHOD;
     fn drop = X509_LOOKUP_meth_free;
@@ -1,8 +1,11 @@
 use std::cmp::Ordering;
+#[cfg(any(ossl102, libressl261))]
+use std::sync::{Arc, Mutex};
 
-use crate::asn1::Asn1Time;
+use crate::asn1::{Asn1Object, Asn1Time, Asn1Type};
 use crate::bn::{BigNum, MsbOption};
-use crate::hash::MessageDigest;
+use crate::conf::{Conf, ConfMethod};
+use crate::hash::{hash, MessageDigest};
 use crate::nid::Nid;
 use crate::pkey::{PKey, Private};
 use crate::rsa::Rsa;
@@ -11,12 +14,17 @@ use crate::x509::extension::{
     AuthorityKeyIdentifier, BasicConstraints, ExtendedKeyUsage, KeyUsage, SubjectAlternativeName,
     SubjectKeyIdentifier,
 };
-use crate::x509::store::X509StoreBuilder;
+use crate::x509::store::{X509StoreBuilder, X509StoreRef};
 #[cfg(any(ossl102, libressl261))]
-use crate::x509::verify::X509VerifyFlags;
+use crate::x509::verify::{X509CheckFlags, X509VerifyFlags, X509VerifyParam};
 #[cfg(ossl110)]
 use crate::x509::X509Builder;
-use crate::x509::{X509Name, X509Req, X509StoreContext, X509VerifyResult, X509};
+use crate::x509::{
+    allow_verify_errors, CopyExtensions, CrlReason, CrlStatus, GeneralName, X509BasicConstraints,
+    X509Crl, X509Extension, X509KeyUsageFlags, X509Name, X509NameEntry, X509NameFlags,
+    X509PrintFlags, X509PurposeId, X509Req, X509Revoked, X509StoreContext, X509TrustId,
+    X509TrustResult, X509VerifyResult, X509v3Context, X509,
+};
 use hex::{self, FromHex};
 
 fn pkey() -> PKey<Private> {
@@ -127,6 +135,56 @@ fn test_nameref_iterator() {
     }
 }
 
+#[test]
+#[cfg(any(ossl110, libressl270))]
+fn name_entry_deletion() {
+    let cert = include_bytes!("../../test/nid_test_cert.pem");
+    let cert = X509::from_pem(cert).unwrap();
+    let mut name = cert.subject_name().to_owned().unwrap();
+    assert_eq!(name.entry_count(), 3);
+
+    assert_eq!(name.delete_entries_by_nid(Nid::PKCS9_EMAILADDRESS), 1);
+    assert_eq!(name.delete_entries_by_nid(Nid::PKCS9_EMAILADDRESS), 0);
+    assert_eq!(name.entry_count(), 2);
+
+    let friendly = name.delete_entry(1).unwrap();
+    assert_eq!(friendly.object().nid(), Nid::FRIENDLYNAME);
+    assert!(name.delete_entry(1).is_none());
+
+    let name = X509Name::from_der(&name.to_der().unwrap()).unwrap();
+    let entries = name
+        .entries()
+        .map(|e| (e.object().nid(), e.data().as_slice().to_vec()))
+        .collect::<Vec<_>>();
+    assert_eq!(entries, [(Nid::COMMONNAME, b"example.com".to_vec())]);
+
+    // The original name is unchanged.
+    assert_eq!(cert.subject_name().entry_count(), 3);
+}
+
+#[test]
+#[cfg(ossl110)]
+fn name_entry_replacement() {
+    // C=US, CN=a+O=b
+    let der = [
+        0x30, 0x23, 0x31, 0x0b, 0x30, 0x09, 0x06, 0x03, 0x55, 0x04, 0x06, 0x13, 0x02, 0x55, 0x53,
+        0x31, 0x14, 0x30, 0x08, 0x06, 0x03, 0x55, 0x04, 0x03, 0x0c, 0x01, 0x61, 0x30, 0x08, 0x06,
+        0x03, 0x55, 0x04, 0x0a, 0x0c, 0x01, 0x62,
+    ];
+    let mut name = X509Name::from_der(&der).unwrap();
+    assert_eq!(name.to_string_rfc2253().unwrap(), "O=b+CN=a,C=US");
+
+    name.set_entry_data(2, "c").unwrap();
+    name.set_entry_data(1, "d").unwrap();
+    name.set_entry_data(0, "GB").unwrap();
+    let mut name = X509Name::from_der(&name.to_der().unwrap()).unwrap();
+    assert_eq!(name.to_string_rfc2253().unwrap(), "O=c+CN=d,C=GB");
+    assert_eq!(name.entries().next().unwrap().data().as_slice(), b"GB");
+
+    assert!(name.set_entry_data(0, "too long").is_err());
+    assert_eq!(name.to_string_rfc2253().unwrap(), "O=c+CN=d,C=GB");
+}
+
 #[test]
 fn test_nid_uid_value() {
     let cert = include_bytes!("../../test/nid_uid_test_cert.pem");
@@ -137,6 +195,36 @@ fn test_nid_uid_value() {
     assert_eq!(cn.data().as_slice(), b"this is the userId");
 }
 
+#[test]
+#[cfg(any(ossl102, libressl261))]
+fn matches_identities() {
+    let cert = include_bytes!("../../test/alt_name_cert.pem");
+    let cert = X509::from_pem(cert).unwrap();
+    let flags = X509CheckFlags::empty();
+
+    assert!(cert.matches_hostname("example.com", flags).unwrap());
+    assert!(cert.matches_hostname("EXAMPLE.com", flags).unwrap());
+    assert!(!cert.matches_hostname("www.example.com", flags).unwrap());
+    assert!(cert.matches_email("test@example.com", flags).unwrap());
+    assert!(!cert.matches_email("other@example.com", flags).unwrap());
+    assert!(!cert.matches_hostname("", flags).unwrap());
+    assert!(!cert.matches_email("", flags).unwrap());
+    assert!(cert
+        .matches_ip("127.0.0.1".parse().unwrap(), flags)
+        .unwrap());
+    assert!(cert.matches_ip("::1".parse().unwrap(), flags).unwrap());
+    assert!(!cert
+        .matches_ip("127.0.0.2".parse().unwrap(), flags)
+        .unwrap());
+
+    let cert = X509::from_pem(include_bytes!("../../test/cert.pem")).unwrap();
+    assert!(cert.matches_hostname("foobar.com", flags).unwrap());
+    #[cfg(ossl110)]
+    assert!(!cert
+        .matches_hostname("foobar.com", X509CheckFlags::NEVER_CHECK_SUBJECT)
+        .unwrap());
+}
+
 #[test]
 fn test_subject_alt_name() {
     let cert = include_bytes!("../../test/alt_name_cert.pem");
@@ -202,6 +290,412 @@ fn test_aia_ca_issuer() {
     assert!(cert.authority_info().is_none());
 }
 
+#[test]
+fn crl_distribution_points_and_policies() {
+    let pkey = pkey();
+
+    let mut builder = X509::builder().unwrap();
+    builder.set_version(2).unwrap();
+    builder.set_pubkey(&pkey).unwrap();
+    let extension = X509Extension::new_nid(
+        None,
+        None,
+        Nid::CRL_DISTRIBUTION_POINTS,
+        "URI:http://example.com/ca.crl,URI:ldap://example.com/ca",
+    )
+    .unwrap();
+    builder.append_extension(extension).unwrap();
+    // The certificate policies extension can only be created with a configuration database.
+    let conf = Conf::new(ConfMethod::default()).unwrap();
+    let extension = X509Extension::new_nid(
+        None,
+        Some(&builder.x509v3_context(None, Some(&conf))),
+        Nid::CERTIFICATE_POLICIES,
+        "2.23.140.1.2.1,2.999.1",
+    )
+    .unwrap();
+    builder.append_extension(extension).unwrap();
+    builder.sign(&pkey, MessageDigest::sha256()).unwrap();
+    let cert = builder.build();
+
+    let points = cert.crl_distribution_points().unwrap();
+    assert_eq!(points.len(), 2);
+    assert!(points[0].crl_issuer().is_none());
+    let uris = points
+        .iter()
+        .flat_map(|p| p.distpoint().unwrap().fullname().unwrap())
+        .map(|n| n.uri().unwrap())
+        .collect::<Vec<_>>();
+    assert_eq!(uris, ["http://example.com/ca.crl", "ldap://example.com/ca"]);
+
+    let policies = cert.certificate_policies().unwrap();
+    let oids = policies
+        .iter()
+        .map(|p| p.policy_id().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(oids, ["2.23.140.1.2.1", "2.999.1"]);
+
+    let cert = X509::from_pem(include_bytes!("../../test/cert.pem")).unwrap();
+    assert!(cert.crl_distribution_points().is_none());
+    assert!(cert.certificate_policies().is_none());
+}
+
+#[test]
+fn x509_builder_key_identifiers() {
+    let ca = X509::from_pem(include_bytes!("../../test/root-ca.pem")).unwrap();
+    let ca_key = include_bytes!("../../test/root-ca.key");
+    let ca_key = PKey::private_key_from_pem(ca_key).unwrap();
+    let pkey = pkey();
+
+    // The CA's subjectKeyIdentifier, and the SHA-1 hash of the new certificate's public key.
+    let ca_key_id = Vec::from_hex("6cd3a503ab0d5f2cc98d8a9c88a78877b837fd9a").unwrap();
+    let rsa = pkey.rsa().unwrap().public_key_to_der_pkcs1().unwrap();
+    let key_id = hash(MessageDigest::sha1(), &rsa).unwrap();
+    let contains =
+        |cert: &X509, id: &[u8]| cert.to_der().unwrap().windows(id.len()).any(|w| w == id);
+
+    let mut name = X509Name::builder().unwrap();
+    name.append_entry_by_nid(Nid::COMMONNAME, "foobar.com")
+        .unwrap();
+    let name = name.build();
+
+    let mut builder = X509::builder().unwrap();
+    builder.set_version(2).unwrap();
+    builder.set_subject_name(&name).unwrap();
+    builder.set_issuer_name(ca.subject_name()).unwrap();
+    builder.set_pubkey(&pkey).unwrap();
+    let subject_key_identifier = SubjectKeyIdentifier::new()
+        .build(&builder.x509v3_context(Some(&ca), None))
+        .unwrap();
+    builder.append_extension(subject_key_identifier).unwrap();
+    let authority_key_identifier = AuthorityKeyIdentifier::new()
+        .keyid(true)
+        .issuer(false)
+        .build(&builder.x509v3_context(Some(&ca), None))
+        .unwrap();
+    builder.append_extension(authority_key_identifier).unwrap();
+    builder.sign(&ca_key, MessageDigest::sha256()).unwrap();
+    let cert = builder.build();
+    assert!(contains(&cert, &ca_key_id));
+    assert!(contains(&cert, &key_id));
+
+    let context = X509v3Context::new(&ca, &cert, None);
+    let extension = AuthorityKeyIdentifier::new()
+        .keyid(true)
+        .build(&context)
+        .unwrap();
+    let mut builder = X509::builder().unwrap();
+    builder.set_version(2).unwrap();
+    builder.set_pubkey(&pkey).unwrap();
+    builder.append_extension(extension).unwrap();
+    builder.sign(&ca_key, MessageDigest::sha256()).unwrap();
+    let cert = builder.build();
+    assert!(contains(&cert, &ca_key_id));
+    assert!(!contains(&cert, &key_id));
+}
+
+#[test]
+#[cfg(ossl111d)]
+fn x509_key_identifiers() {
+    let ca = X509::from_pem(include_bytes!("../../test/root-ca.pem")).unwrap();
+    let ca_key = include_bytes!("../../test/root-ca.key");
+    let ca_key = PKey::private_key_from_pem(ca_key).unwrap();
+    let pkey = pkey();
+
+    let mut builder = X509::builder().unwrap();
+    builder.set_version(2).unwrap();
+    builder.set_issuer_name(ca.subject_name()).unwrap();
+    builder.set_pubkey(&pkey).unwrap();
+    let subject_key_identifier = SubjectKeyIdentifier::new()
+        .build(&builder.x509v3_context(Some(&ca), None))
+        .unwrap();
+    builder.append_extension(subject_key_identifier).unwrap();
+    let authority_key_identifier = AuthorityKeyIdentifier::new()
+        .keyid(true)
+        .issuer(false)
+        .build(&builder.x509v3_context(Some(&ca), None))
+        .unwrap();
+    builder.append_extension(authority_key_identifier).unwrap();
+    builder.sign(&ca_key, MessageDigest::sha256()).unwrap();
+    let cert = builder.build();
+
+    let ca_key_id = ca.subject_key_id().unwrap().as_slice();
+    assert_eq!(cert.authority_key_id().unwrap().as_slice(), ca_key_id);
+    assert_ne!(cert.subject_key_id().unwrap().as_slice(), ca_key_id);
+    assert!(cert.authority_issuer().is_none());
+    assert!(cert.authority_serial().is_none());
+
+    let context = X509v3Context::new(&ca, &cert, None);
+    let extension = AuthorityKeyIdentifier::new()
+        .issuer(true)
+        .build(&context)
+        .unwrap();
+    let mut builder = X509::builder().unwrap();
+    builder.set_version(2).unwrap();
+    builder.set_pubkey(&pkey).unwrap();
+    builder.append_extension(extension).unwrap();
+    builder.sign(&ca_key, MessageDigest::sha256()).unwrap();
+    let cert = builder.build();
+    assert!(cert.subject_key_id().is_none());
+    assert!(cert.authority_key_id().is_none());
+    let issuer = cert.authority_issuer().unwrap();
+    assert_eq!(issuer.len(), 1);
+    let issuer_name = issuer[0].directory_name().unwrap();
+    assert_eq!(
+        issuer_name.try_cmp(ca.subject_name()).unwrap(),
+        Ordering::Equal
+    );
+    assert_eq!(
+        cert.authority_serial().unwrap().to_bn().unwrap(),
+        ca.serial_number().to_bn().unwrap()
+    );
+}
+
+#[test]
+fn x509_builder_typed_extensions() {
+    let pkey = pkey();
+
+    let mut builder = X509::builder().unwrap();
+    builder.set_version(2).unwrap();
+    builder.set_pubkey(&pkey).unwrap();
+    let basic_constraints = BasicConstraints::new()
+        .critical()
+        .ca()
+        .pathlen(2)
+        .build()
+        .unwrap();
+    builder.append_extension(basic_constraints).unwrap();
+    let key_usage = KeyUsage::new()
+        .critical()
+        .key_cert_sign()
+        .crl_sign()
+        .decipher_only()
+        .build()
+        .unwrap();
+    builder.append_extension(key_usage).unwrap();
+    let ext_key_usage = ExtendedKeyUsage::new()
+        .server_auth()
+        .other("2.999.1")
+        .build()
+        .unwrap();
+    builder.append_extension(ext_key_usage).unwrap();
+    builder.sign(&pkey, MessageDigest::sha256()).unwrap();
+    let cert = builder.build();
+
+    let constraints = cert.basic_constraints().unwrap();
+    assert!(constraints.ca);
+    assert_eq!(constraints.pathlen, Some(2));
+    assert_eq!(
+        cert.key_usage().unwrap(),
+        X509KeyUsageFlags::KEY_CERT_SIGN
+            | X509KeyUsageFlags::CRL_SIGN
+            | X509KeyUsageFlags::DECIPHER_ONLY
+    );
+    let purposes = cert
+        .extended_key_usage()
+        .unwrap()
+        .iter()
+        .map(|o| o.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(purposes, ["TLS Web Server Authentication", "2.999.1"]);
+
+    let extension = cert
+        .extension_by_oid(&Asn1Object::from_str("2.5.29.15").unwrap())
+        .unwrap();
+    assert_eq!(extension.key_usage(), cert.key_usage());
+    assert!(extension.basic_constraints().is_none());
+    assert!(extension.extended_key_usage().is_none());
+
+    let extension = BasicConstraints::new().build().unwrap();
+    assert_eq!(
+        extension.basic_constraints(),
+        Some(X509BasicConstraints {
+            ca: false,
+            pathlen: None
+        })
+    );
+    assert!(extension.key_usage().is_none());
+
+    let cert = X509::from_pem(include_bytes!("../../test/cert.pem")).unwrap();
+    assert!(cert.key_usage().is_none());
+    assert!(cert.basic_constraints().is_none());
+    assert!(cert.extended_key_usage().is_none());
+}
+
+#[test]
+fn x509_basic_constraints_malformed_pathlen() {
+    let oid = Asn1Object::from_str("2.5.29.19").unwrap();
+    // cA TRUE with pathLenConstraint -1 and 2^32
+    let negative = [0x30, 0x06, 0x01, 0x01, 0xff, 0x02, 0x01, 0xff];
+    let too_large = [
+        0x30, 0x0a, 0x01, 0x01, 0xff, 0x02, 0x05, 0x01, 0x00, 0x00, 0x00, 0x00,
+    ];
+    for der in [&negative[..], &too_large[..]].iter() {
+        let extension = X509Extension::new_from_der(&oid, true, der).unwrap();
+        assert!(extension.basic_constraints().is_none());
+
+        let pkey = pkey();
+        let mut builder = X509::builder().unwrap();
+        builder.set_pubkey(&pkey).unwrap();
+        builder.append_extension(extension).unwrap();
+        builder.sign(&pkey, MessageDigest::sha256()).unwrap();
+        assert!(builder.build().basic_constraints().is_none());
+    }
+}
+
+#[test]
+fn x509_builder_subject_alt_names() {
+    let pkey = pkey();
+
+    let mut builder = X509::builder().unwrap();
+    builder.set_version(2).unwrap();
+    builder.set_pubkey(&pkey).unwrap();
+    let subject_alternative_name = SubjectAlternativeName::new()
+        .dns("example.com,DNS:evil.com")
+        .ip("127.0.0.1")
+        .ip_addr("::1".parse().unwrap())
+        .email("test@example.com")
+        .uri("http://www.example.com")
+        .build(&builder.x509v3_context(None, None))
+        .unwrap();
+    builder.append_extension(subject_alternative_name).unwrap();
+    builder.sign(&pkey, MessageDigest::sha256()).unwrap();
+    let x509 = builder.build();
+
+    let names = x509.subject_alt_names().unwrap();
+    assert_eq!(names.len(), 5);
+    assert_eq!(names[0].dnsname(), Some("example.com,DNS:evil.com"));
+    assert_eq!(names[1].ipaddress(), Some(&[127, 0, 0, 1][..]));
+    assert_eq!(
+        names[2].ipaddress(),
+        Some(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1][..])
+    );
+    assert_eq!(names[3].email(), Some("test@example.com"));
+    assert_eq!(names[4].uri(), Some("http://www.example.com"));
+
+    let builder = X509::builder().unwrap();
+    assert!(SubjectAlternativeName::new()
+        .ip("not an address")
+        .build(&builder.x509v3_context(None, None))
+        .is_err());
+    assert!(SubjectAlternativeName::new()
+        .ip("127.0.0.1\0")
+        .build(&builder.x509v3_context(None, None))
+        .is_err());
+}
+
+#[test]
+fn general_name_variants() {
+    let upn = b"\x0c\x10user@example.com";
+
+    let mut name = X509Name::builder().unwrap();
+    name.append_entry_by_nid(Nid::COMMONNAME, "foobar.com")
+        .unwrap();
+    let name = name.build();
+
+    #[cfg(any(ossl110, libressl270))]
+    {
+        let gn = GeneralName::new_dir_name(&name).unwrap();
+        assert_eq!(
+            gn.directory_name().unwrap().to_der().unwrap(),
+            name.to_der().unwrap()
+        );
+        assert!(gn.email().is_none());
+        assert!(gn.other_name().is_none());
+    }
+
+    let gn =
+        GeneralName::new_other_name(Asn1Object::from_str("1.3.6.1.4.1.311.20.2.3").unwrap(), upn)
+            .unwrap();
+    let (oid, value) = gn.other_name().unwrap();
+    assert_eq!(oid.to_string(), "Microsoft User Principal Name");
+    assert_eq!(value, upn);
+    assert!(gn.directory_name().is_none());
+
+    assert!(
+        GeneralName::new_other_name(Asn1Object::from_str("2.999.1").unwrap(), b"\x0c").is_err()
+    );
+    assert!(GeneralName::new_other_name(
+        Asn1Object::from_str("2.999.1").unwrap(),
+        b"\x0c\x01a\x0c\x01b"
+    )
+    .is_err());
+
+    let pkey = pkey();
+    let mut builder = X509::builder().unwrap();
+    builder.set_version(2).unwrap();
+    builder.set_pubkey(&pkey).unwrap();
+    let subject_alternative_name = SubjectAlternativeName::new()
+        .rid("2.999.1")
+        .other_name_der("1.3.6.1.4.1.311.20.2.3", upn)
+        .build(&builder.x509v3_context(None, None))
+        .unwrap();
+    builder.append_extension(subject_alternative_name).unwrap();
+    builder.sign(&pkey, MessageDigest::sha256()).unwrap();
+    let x509 = builder.build();
+
+    let names = x509.subject_alt_names().unwrap();
+    assert_eq!(names.len(), 2);
+    assert_eq!(names[0].registered_id().unwrap().to_string(), "2.999.1");
+    let (oid, value) = names[1].other_name().unwrap();
+    assert_eq!(oid.to_string(), "Microsoft User Principal Name");
+    assert_eq!(value, upn);
+
+    let builder = X509::builder().unwrap();
+    assert!(SubjectAlternativeName::new()
+        .other_name_der("1.3.6.1.4.1.311.20.2.3", &[&upn[..], b"\x05\x00"].concat())
+        .build(&builder.x509v3_context(None, None))
+        .is_err());
+}
+
+#[test]
+fn raw_extensions() {
+    let pkey = pkey();
+    let oid = Asn1Object::from_str("2.999.1").unwrap();
+    let value = b"\x0c\x05hello";
+
+    let mut builder = X509::builder().unwrap();
+    builder.set_version(2).unwrap();
+    builder.set_pubkey(&pkey).unwrap();
+    builder.append_raw_extension(&oid, true, value).unwrap();
+    let basic_constraints = BasicConstraints::new().build().unwrap();
+    builder.append_extension(basic_constraints).unwrap();
+    builder.sign(&pkey, MessageDigest::sha256()).unwrap();
+    let cert = builder.build();
+
+    let ext = cert.extension_by_oid(&oid).unwrap();
+    assert_eq!(ext.object().to_string(), "2.999.1");
+    assert!(ext.critical());
+    assert_eq!(ext.data().as_slice(), value);
+
+    let oid = Asn1Object::from_str("basicConstraints").unwrap();
+    let ext = cert.extension_by_oid(&oid).unwrap();
+    assert!(!ext.critical());
+    assert_eq!(ext.data().as_slice(), b"\x30\x00");
+
+    let oid = Asn1Object::from_str("2.999.2").unwrap();
+    assert!(cert.extension_by_oid(&oid).is_none());
+}
+
+#[test]
+fn extension_and_general_name_der() {
+    let gn = GeneralName::new_email(b"test@example.com").unwrap();
+    let der = gn.to_der().unwrap();
+    assert_eq!(&der[..2], &[0x81, 16]);
+    let gn = GeneralName::from_der(&der).unwrap();
+    assert_eq!(gn.email(), Some("test@example.com"));
+
+    let constraints = BasicConstraints::new().critical().ca().build().unwrap();
+    let der = constraints.to_der().unwrap();
+    let ext = X509Extension::from_der(&der).unwrap();
+    assert_eq!(ext.object().nid(), Nid::BASIC_CONSTRAINTS);
+    assert!(ext.critical());
+    assert_eq!(ext.data().as_slice(), constraints.data().as_slice());
+    assert_eq!(ext.to_der().unwrap(), der);
+
+    assert!(X509Extension::from_der(&der[..der.len() - 1]).is_err());
+}
+
 #[test]
 fn x509_builder() {
     let pkey = pkey();
@@ -275,6 +769,124 @@ fn x509_builder() {
     assert_eq!(serial, x509.serial_number().to_bn().unwrap());
 }
 
+#[test]
+fn x509_crl_builder() {
+    let ca = X509::from_pem(include_bytes!("../../test/root-ca.pem")).unwrap();
+    let ca_key = include_bytes!("../../test/root-ca.key");
+    let ca_key = PKey::private_key_from_pem(ca_key).unwrap();
+
+    let mut builder = X509Crl::builder().unwrap();
+    builder.set_version(1).unwrap();
+    builder.set_issuer_name(ca.subject_name()).unwrap();
+    builder
+        .set_last_update(&Asn1Time::days_from_now(0).unwrap())
+        .unwrap();
+    builder
+        .set_next_update(&Asn1Time::days_from_now(7).unwrap())
+        .unwrap();
+    let revocation_date = Asn1Time::days_from_now(0).unwrap();
+    let serial = BigNum::from_u32(3).unwrap().to_asn1_integer().unwrap();
+    builder
+        .add_revoked(&serial, &revocation_date, Some(CrlReason::KEY_COMPROMISE))
+        .unwrap();
+    let serial = BigNum::from_u32(2).unwrap().to_asn1_integer().unwrap();
+    builder
+        .add_revoked(&serial, &revocation_date, None)
+        .unwrap();
+    let number = BigNum::from_u32(1).unwrap().to_asn1_integer().unwrap();
+    builder.set_crl_number(&number).unwrap();
+    let authority_key_identifier = AuthorityKeyIdentifier::new()
+        .keyid(true)
+        .build(&builder.x509v3_context(&ca, None))
+        .unwrap();
+    builder.append_extension(&authority_key_identifier).unwrap();
+    builder.sign(&ca_key, MessageDigest::sha256()).unwrap();
+    let crl = builder.build();
+
+    assert!(crl.verify(&ca.public_key().unwrap()).unwrap());
+    assert!(!crl.verify(&pkey()).unwrap());
+
+    let pem = crl.to_pem().unwrap();
+    assert!(pem.starts_with(b"-----BEGIN X509 CRL-----"));
+    let crl = X509Crl::from_pem(&pem).unwrap();
+    let der = crl.to_der().unwrap();
+    assert_eq!(X509Crl::from_der(&der).unwrap().to_der().unwrap(), der);
+}
+
+#[test]
+fn x509_crl_inspection() {
+    let ca = X509::from_pem(include_bytes!("../../test/root-ca.pem")).unwrap();
+    let ca_key = include_bytes!("../../test/root-ca.key");
+    let ca_key = PKey::private_key_from_pem(ca_key).unwrap();
+
+    let mut builder = X509Crl::builder().unwrap();
+    builder.set_version(1).unwrap();
+    builder.set_issuer_name(ca.subject_name()).unwrap();
+    let last_update = Asn1Time::days_from_now(0).unwrap();
+    builder.set_last_update(&last_update).unwrap();
+    let revocation_date = Asn1Time::days_from_now(0).unwrap();
+    let serial = BigNum::from_u32(3).unwrap().to_asn1_integer().unwrap();
+    builder
+        .add_revoked(&serial, &revocation_date, Some(CrlReason::KEY_COMPROMISE))
+        .unwrap();
+    let serial = BigNum::from_u32(2).unwrap().to_asn1_integer().unwrap();
+    builder
+        .add_revoked(&serial, &revocation_date, None)
+        .unwrap();
+    let number = BigNum::from_u32(5).unwrap().to_asn1_integer().unwrap();
+    builder.set_crl_number(&number).unwrap();
+    builder.sign(&ca_key, MessageDigest::sha256()).unwrap();
+    let crl = X509Crl::from_der(&builder.build().to_der().unwrap()).unwrap();
+
+    assert_eq!(
+        crl.issuer_name().to_der().unwrap(),
+        ca.subject_name().to_der().unwrap()
+    );
+    assert_eq!(crl.last_update().to_string(), last_update.to_string());
+    assert!(crl.next_update().is_none());
+    assert_eq!(
+        crl.crl_number().unwrap().to_bn().unwrap(),
+        BigNum::from_u32(5).unwrap()
+    );
+
+    let revoked = crl.get_revoked().unwrap();
+    assert_eq!(revoked.len(), 2);
+    assert_eq!(
+        revoked[0].serial_number().to_bn().unwrap(),
+        BigNum::from_u32(2).unwrap()
+    );
+    assert_eq!(
+        revoked[0].revocation_date().to_string(),
+        revocation_date.to_string()
+    );
+    assert_eq!(revoked[0].reason(), None);
+    assert_eq!(revoked[1].reason(), Some(CrlReason::KEY_COMPROMISE));
+    let entry = X509Revoked::from_der(&revoked[1].to_der().unwrap()).unwrap();
+    assert_eq!(entry.reason(), Some(CrlReason::KEY_COMPROMISE));
+
+    let serial = BigNum::from_u32(3).unwrap().to_asn1_integer().unwrap();
+    match crl.get_by_serial(&serial) {
+        CrlStatus::Revoked(entry) => assert_eq!(entry.reason(), Some(CrlReason::KEY_COMPROMISE)),
+        _ => panic!("serial 3 should be revoked"),
+    }
+    let serial = BigNum::from_u32(4).unwrap().to_asn1_integer().unwrap();
+    assert!(matches!(crl.get_by_serial(&serial), CrlStatus::NotRevoked));
+
+    let pkey = pkey();
+    let mut builder = X509::builder().unwrap();
+    builder.set_version(2).unwrap();
+    builder.set_issuer_name(ca.subject_name()).unwrap();
+    builder.set_subject_name(ca.subject_name()).unwrap();
+    builder
+        .set_serial_number(&BigNum::from_u32(2).unwrap().to_asn1_integer().unwrap())
+        .unwrap();
+    builder.set_pubkey(&pkey).unwrap();
+    builder.sign(&ca_key, MessageDigest::sha256()).unwrap();
+    let cert = builder.build();
+    assert!(matches!(crl.get_by_cert(&cert), CrlStatus::Revoked(_)));
+    assert!(matches!(crl.get_by_cert(&ca), CrlStatus::NotRevoked));
+}
+
 #[test]
 fn x509_req_builder() {
     let pkey = pkey();
@@ -312,30 +924,347 @@ fn x509_req_builder() {
 }
 
 #[test]
-fn test_stack_from_pem() {
-    let certs = include_bytes!("../../test/certs.pem");
-    let certs = X509::stack_from_pem(certs).unwrap();
+fn x509_req_verify() {
+    let pkey = pkey();
 
-    assert_eq!(certs.len(), 2);
-    assert_eq!(
-        hex::encode(certs[0].digest(MessageDigest::sha1()).unwrap()),
-        "59172d9313e84459bcff27f967e79e6e9217e584"
-    );
-    assert_eq!(
-        hex::encode(certs[1].digest(MessageDigest::sha1()).unwrap()),
-        "c0cbdf7cdd03c9773e5468e1f6d2da7d5cbb1875"
-    );
-}
+    let mut name = X509Name::builder().unwrap();
+    name.append_entry_by_nid(Nid::COMMONNAME, "foobar.com")
+        .unwrap();
+    let name = name.build();
 
-#[test]
-fn issued() {
-    let cert = include_bytes!("../../test/cert.pem");
-    let cert = X509::from_pem(cert).unwrap();
+    let mut builder = X509Req::builder().unwrap();
+    builder.set_version(0).unwrap();
+    builder.set_subject_name(&name).unwrap();
+    builder.set_pubkey(&pkey).unwrap();
+    builder.sign(&pkey, MessageDigest::sha256()).unwrap();
+    let req = builder.build();
+
+    assert!(req.verify(&req.public_key().unwrap()).unwrap());
+
+    let other_pkey = PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap();
+    assert!(!req.verify(&other_pkey).unwrap());
+}
+
+#[test]
+fn x509_builder_validity_encoding_and_random_serial() {
+    let pkey = pkey();
+
+    let not_before = Asn1Time::days_from_now(0).unwrap();
+    let not_after = Asn1Time::days_from_now(365)
+        .unwrap()
+        .to_generalized_time()
+        .unwrap();
+
+    let mut builder = X509::builder().unwrap();
+    builder.set_version(2).unwrap();
+    builder.set_pubkey(&pkey).unwrap();
+    builder.set_not_before(&not_before).unwrap();
+    builder.set_not_after(&not_after).unwrap();
+    builder.set_random_serial_number().unwrap();
+    builder.sign(&pkey, MessageDigest::sha256()).unwrap();
+    let cert = X509::from_der(&builder.build().to_der().unwrap()).unwrap();
+
+    assert_eq!(cert.not_before().asn1_type(), Asn1Type::UTCTIME);
+    assert_eq!(cert.not_after().asn1_type(), Asn1Type::GENERALIZEDTIME);
+    assert_eq!(cert.not_after().to_string(), not_after.to_string());
+
+    let serial = cert.serial_number().to_bn().unwrap();
+    assert_eq!(serial.num_bits(), 128);
+    assert!(!serial.is_negative());
+
+    let mut builder = X509::builder().unwrap();
+    builder.set_random_serial_number().unwrap();
+    let other = builder.build();
+    assert_ne!(other.serial_number().to_bn().unwrap(), serial);
+}
+
+#[test]
+fn x509_builder_extensions_from_req() {
+    let pkey = pkey();
+
+    let mut builder = X509Req::builder().unwrap();
+    builder.set_pubkey(&pkey).unwrap();
+    let mut extensions = Stack::new().unwrap();
+    let extended_key_usage = ExtendedKeyUsage::new().server_auth().build().unwrap();
+    extensions.push(extended_key_usage).unwrap();
+    let subject_alternative_name = SubjectAlternativeName::new()
+        .dns("example.com")
+        .build(&builder.x509v3_context(None))
+        .unwrap();
+    extensions.push(subject_alternative_name).unwrap();
+    let basic_constraints = BasicConstraints::new().critical().ca().build().unwrap();
+    extensions.push(basic_constraints).unwrap();
+    builder.add_extensions(&extensions).unwrap();
+    builder.sign(&pkey, MessageDigest::sha256()).unwrap();
+    let req = builder.build();
+
+    let issue = |policy, copy_constraints| {
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_pubkey(&pkey).unwrap();
+        let extended_key_usage = ExtendedKeyUsage::new().client_auth().build().unwrap();
+        builder.append_extension(extended_key_usage).unwrap();
+        builder
+            .append_extensions_from_req(&req, policy, |ext| {
+                copy_constraints || ext.object().nid() != Nid::BASIC_CONSTRAINTS
+            })
+            .unwrap();
+        builder.sign(&pkey, MessageDigest::sha256()).unwrap();
+        builder.build()
+    };
+
+    let cert = issue(CopyExtensions::Add, false);
+    let names = cert.subject_alt_names().unwrap();
+    assert_eq!(names[0].dnsname(), Some("example.com"));
+    assert!(cert.check_purpose(X509PurposeId::SSL_CLIENT, false));
+    assert!(!cert.check_purpose(X509PurposeId::SSL_SERVER, false));
+    assert!(!cert.check_purpose(X509PurposeId::SSL_CLIENT, true));
+
+    let cert = issue(CopyExtensions::All, false);
+    assert_eq!(cert.subject_alt_names().unwrap().len(), 1);
+    assert!(!cert.check_purpose(X509PurposeId::SSL_CLIENT, false));
+    assert!(cert.check_purpose(X509PurposeId::SSL_SERVER, false));
+    assert!(!cert.check_purpose(X509PurposeId::SSL_SERVER, true));
+
+    let cert = issue(CopyExtensions::All, true);
+    assert!(cert.check_purpose(X509PurposeId::SSL_SERVER, true));
+
+    let mut builder = X509Req::builder().unwrap();
+    builder.set_pubkey(&pkey).unwrap();
+    builder.sign(&pkey, MessageDigest::sha256()).unwrap();
+    let req = builder.build();
+    let mut builder = X509::builder().unwrap();
+    builder
+        .append_extensions_from_req(&req, CopyExtensions::All, |_| true)
+        .unwrap();
+}
+
+#[test]
+fn x509_req_attributes() {
+    let pkey = pkey();
+
+    let mut builder = X509Req::builder().unwrap();
+    builder.set_version(0).unwrap();
+    builder.set_pubkey(&pkey).unwrap();
+
+    let mut extensions = Stack::new().unwrap();
+    let key_usage = KeyUsage::new()
+        .critical()
+        .digital_signature()
+        .build()
+        .unwrap();
+    extensions.push(key_usage).unwrap();
+    let subject_alternative_name = SubjectAlternativeName::new()
+        .dns("example.com")
+        .email("foo@example.com")
+        .build(&builder.x509v3_context(None))
+        .unwrap();
+    extensions.push(subject_alternative_name).unwrap();
+    builder.add_extensions(&extensions).unwrap();
+    builder
+        .add_attribute_by_nid(Nid::PKCS9_CHALLENGEPASSWORD, "secret")
+        .unwrap();
+    builder.sign(&pkey, MessageDigest::sha256()).unwrap();
+
+    let req = builder.build().to_der().unwrap();
+    let req = X509Req::from_der(&req).unwrap();
+
+    let extensions = req.extensions().unwrap();
+    assert_eq!(extensions[0].object().nid(), Nid::KEY_USAGE);
+    assert!(extensions[0].critical());
+    assert_eq!(extensions[0].data().as_slice(), [0x03, 0x02, 0x07, 0x80]);
+    assert_eq!(extensions[1].object().nid(), Nid::SUBJECT_ALT_NAME);
+    assert!(!extensions[1].critical());
+
+    let names = req.subject_alt_names().unwrap();
+    assert_eq!(names.len(), 2);
+    assert_eq!(names[0].dnsname(), Some("example.com"));
+    assert_eq!(names[1].email(), Some("foo@example.com"));
+
+    let attributes = req
+        .attributes()
+        .map(|a| a.object().nid())
+        .collect::<Vec<_>>();
+    assert_eq!(attributes, [Nid::PKCS9_CHALLENGEPASSWORD, Nid::EXT_REQ]);
+
+    let password = req.attribute_by_nid(Nid::PKCS9_CHALLENGEPASSWORD).unwrap();
+    assert_eq!(password.value_count(), 1);
+    assert!(password.value_type(0).is_some());
+    assert_eq!(password.value_type(1), None);
+    assert_eq!(req.challenge_password().unwrap().as_slice(), b"secret");
+}
+
+#[test]
+fn x509_req_custom_attributes() {
+    let pkey = pkey();
+
+    let mut builder = X509Req::builder().unwrap();
+    builder.set_pubkey(&pkey).unwrap();
+    builder
+        .add_attribute_by_nid(Nid::PKCS9_CHALLENGEPASSWORD, "secret")
+        .unwrap();
+    builder
+        .add_attribute_by_nid(Nid::PKCS9_UNSTRUCTUREDNAME, "device 42")
+        .unwrap();
+    let text = Asn1Object::from_str("2.999.1").unwrap();
+    builder.add_attribute_by_obj(&text, "hello").unwrap();
+    let octets = Asn1Object::from_str("2.999.2").unwrap();
+    builder
+        .add_attribute_by_obj_with_type(&octets, &[1, 2, 3], Asn1Type::OCTET_STRING)
+        .unwrap();
+    let sequence = Asn1Object::from_str("2.999.3").unwrap();
+    builder
+        .add_attribute_by_obj_with_type(
+            &sequence,
+            &[0x30, 0x03, 0x02, 0x01, 0x05],
+            Asn1Type::SEQUENCE,
+        )
+        .unwrap();
+    builder.sign(&pkey, MessageDigest::sha256()).unwrap();
+
+    let req = builder.build().to_der().unwrap();
+    let req = X509Req::from_der(&req).unwrap();
+
+    // Attributes are encoded as a SET, so they are sorted by their encoding.
+    let attribute = |oid: &str| {
+        req.attributes()
+            .find(|a| a.object().to_string() == oid)
+            .unwrap()
+    };
+    assert_eq!(req.attributes().count(), 5);
+    assert_eq!(req.challenge_password().unwrap().as_slice(), b"secret");
+
+    let name = req.attribute_by_nid(Nid::PKCS9_UNSTRUCTUREDNAME).unwrap();
+    assert_eq!(name.value(0).unwrap().as_slice(), b"device 42");
+
+    let text = attribute("2.999.1");
+    assert_eq!(text.value_type(0), Some(Asn1Type::UTF8STRING));
+    assert_eq!(text.value(0).unwrap().as_slice(), b"hello");
+    let octets = attribute("2.999.2");
+    assert_eq!(octets.value_type(0), Some(Asn1Type::OCTET_STRING));
+    assert_eq!(octets.value(0).unwrap().as_slice(), [1, 2, 3]);
+    let sequence = attribute("2.999.3");
+    assert_eq!(sequence.value_type(0), Some(Asn1Type::SEQUENCE));
+    assert_eq!(
+        sequence.value(0).unwrap().as_slice(),
+        [0x30, 0x03, 0x02, 0x01, 0x05]
+    );
+}
+
+#[test]
+fn verify_signature() {
+    let cert = X509::from_pem(include_bytes!("../../test/cert.pem")).unwrap();
+    let ca = X509::from_pem(include_bytes!("../../test/root-ca.pem")).unwrap();
+    let ca_key = ca.public_key().unwrap();
+
+    assert!(ca.verify(&ca_key).unwrap());
+    assert!(cert.verify(&ca_key).unwrap());
+    assert!(!cert.verify(&cert.public_key().unwrap()).unwrap());
+}
+
+#[test]
+fn test_stack_from_pem() {
+    let certs = include_bytes!("../../test/certs.pem");
+    let certs = X509::stack_from_pem(certs).unwrap();
+
+    assert_eq!(certs.len(), 2);
+    assert_eq!(
+        hex::encode(certs[0].digest(MessageDigest::sha1()).unwrap()),
+        "59172d9313e84459bcff27f967e79e6e9217e584"
+    );
+    assert_eq!(
+        hex::encode(certs[1].digest(MessageDigest::sha1()).unwrap()),
+        "c0cbdf7cdd03c9773e5468e1f6d2da7d5cbb1875"
+    );
+}
+
+#[test]
+fn test_stack_to_pem() {
+    let certs = include_bytes!("../../test/certs.pem");
+    let certs = X509::stack_from_pem(certs).unwrap();
+
+    let pem = X509::to_pem_bundle(&certs).unwrap();
+    let mut expected = certs[0].to_pem().unwrap();
+    expected.extend_from_slice(&certs[1].to_pem().unwrap());
+    assert_eq!(pem, expected);
+
+    let mut stack = Stack::new().unwrap();
+    for cert in &certs {
+        stack.push(cert.clone()).unwrap();
+    }
+    assert_eq!(X509::stack_to_pem(&stack).unwrap(), pem);
+
+    let parsed = X509::stack_from_pem(&pem).unwrap();
+    assert_eq!(parsed.len(), 2);
+    assert_eq!(parsed[1].to_der().unwrap(), certs[1].to_der().unwrap());
+
+    assert!(X509::to_pem_bundle(&[]).unwrap().is_empty());
+}
+
+#[test]
+fn issued() {
+    let cert = include_bytes!("../../test/cert.pem");
+    let cert = X509::from_pem(cert).unwrap();
     let ca = include_bytes!("../../test/root-ca.pem");
     let ca = X509::from_pem(ca).unwrap();
 
     assert_eq!(ca.issued(&cert), X509VerifyResult::OK);
-    assert_ne!(cert.issued(&cert), X509VerifyResult::OK);
+    assert_eq!(
+        cert.issued(&cert),
+        X509VerifyResult::SUBJECT_ISSUER_MISMATCH
+    );
+
+    // A certificate with the same subject as the CA, but a different key.
+    let pkey = pkey();
+    let impostor = |key_usage: Option<X509Extension>, key_id: bool| {
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_subject_name(ca.subject_name()).unwrap();
+        builder.set_issuer_name(ca.subject_name()).unwrap();
+        builder.set_pubkey(&pkey).unwrap();
+        if key_id {
+            let subject_key_identifier = SubjectKeyIdentifier::new()
+                .build(&builder.x509v3_context(None, None))
+                .unwrap();
+            builder.append_extension(subject_key_identifier).unwrap();
+        }
+        if let Some(key_usage) = key_usage {
+            builder.append_extension(key_usage).unwrap();
+        }
+        builder.sign(&pkey, MessageDigest::sha256()).unwrap();
+        builder.build()
+    };
+
+    let key_usage = KeyUsage::new().digital_signature().build().unwrap();
+    assert_eq!(
+        impostor(Some(key_usage), false).issued(&cert),
+        X509VerifyResult::KEYUSAGE_NO_CERTSIGN
+    );
+    let key_usage = KeyUsage::new().key_cert_sign().build().unwrap();
+    assert_eq!(
+        impostor(Some(key_usage), false).issued(&cert),
+        X509VerifyResult::OK
+    );
+
+    let ca_key = include_bytes!("../../test/root-ca.key");
+    let ca_key = PKey::private_key_from_pem(ca_key).unwrap();
+    let mut builder = X509::builder().unwrap();
+    builder.set_version(2).unwrap();
+    builder.set_issuer_name(ca.subject_name()).unwrap();
+    builder.set_pubkey(&pkey).unwrap();
+    let authority_key_identifier = AuthorityKeyIdentifier::new()
+        .keyid(true)
+        .build(&builder.x509v3_context(Some(&ca), None))
+        .unwrap();
+    builder.append_extension(authority_key_identifier).unwrap();
+    builder.sign(&ca_key, MessageDigest::sha256()).unwrap();
+    let leaf = builder.build();
+
+    assert_eq!(ca.issued(&leaf), X509VerifyResult::OK);
+    assert_eq!(
+        impostor(None, true).issued(&leaf),
+        X509VerifyResult::AKID_SKID_MISMATCH
+    );
 }
 
 #[test]
@@ -387,6 +1316,51 @@ fn test_verify_cert() {
         .unwrap());
 }
 
+#[test]
+#[cfg(ossl110)]
+fn test_verify_cert_with_lookups() {
+    let cert = include_bytes!("../../test/cert.pem");
+    let cert = X509::from_pem(cert).unwrap();
+    let ca = include_bytes!("../../test/root-ca.pem");
+    let ca = X509::from_pem(ca).unwrap();
+    let chain = Stack::new().unwrap();
+
+    let mut store_bldr = X509StoreBuilder::new().unwrap();
+    store_bldr.add_lookup_file("test/root-ca.pem").unwrap();
+    store_bldr
+        .set_flags(X509VerifyFlags::NO_CHECK_TIME)
+        .unwrap();
+    let store = store_bldr.build();
+
+    let mut context = X509StoreContext::new().unwrap();
+    assert!(context
+        .init(&store, &cert, &chain, |c| c.verify_cert())
+        .unwrap());
+
+    let dir = std::env::temp_dir().join(format!("openssl-hash-dir-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let name = format!("{:08x}.0", ca.subject_name_hash());
+    std::fs::write(dir.join(name), ca.to_pem().unwrap()).unwrap();
+
+    let mut store_bldr = X509StoreBuilder::new().unwrap();
+    store_bldr.add_lookup_hash_dir(&dir).unwrap();
+    store_bldr
+        .set_flags(X509VerifyFlags::NO_CHECK_TIME)
+        .unwrap();
+    let store = store_bldr.build();
+    assert!(store.objects().is_empty());
+
+    let verified = context
+        .init(&store, &cert, &chain, |c| c.verify_cert())
+        .unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    assert!(verified);
+    assert!(!store.objects().is_empty());
+
+    let mut store_bldr = X509StoreBuilder::new().unwrap();
+    assert!(store_bldr.add_lookup_file("test/nonexistent.pem").is_err());
+}
+
 #[test]
 fn test_verify_fails() {
     let cert = include_bytes!("../../test/cert.pem");
@@ -432,6 +1406,356 @@ fn test_verify_fails_with_crl_flag_set_and_no_crl() {
     )
 }
 
+#[test]
+#[cfg(any(ossl102, libressl261))]
+fn test_verify_param() {
+    let cert = include_bytes!("../../test/cert.pem");
+    let cert = X509::from_pem(cert).unwrap();
+    let ca = include_bytes!("../../test/root-ca.pem");
+    let ca = X509::from_pem(ca).unwrap();
+    let chain = Stack::new().unwrap();
+
+    // cert.pem is valid from 2016 to 2026, so pin the check time inside that window.
+    let mut param = X509VerifyParam::new().unwrap();
+    param.set_time(1_500_000_000);
+    param.set_depth(1);
+    param.set_purpose(X509PurposeId::SSL_SERVER).unwrap();
+    assert_eq!(param.depth(), 1);
+    #[cfg(ossl110)]
+    assert_eq!(param.time(), 1_500_000_000);
+
+    let mut store_bldr = X509StoreBuilder::new().unwrap();
+    store_bldr.add_cert(ca).unwrap();
+    store_bldr.set_param(&param).unwrap();
+    let store = store_bldr.build();
+
+    let mut context = X509StoreContext::new().unwrap();
+    assert!(context
+        .init(&store, &cert, &chain, |c| c.verify_cert())
+        .unwrap());
+
+    // Parameters set on the context override those inherited from the store.
+    let error = context
+        .init(&store, &cert, &chain, |c| {
+            c.verify_param_mut().set_purpose(X509PurposeId::ANY)?;
+            c.verify_param_mut().set_time(2_000_000_000);
+            c.verify_cert()?;
+            Ok(c.error())
+        })
+        .unwrap();
+    assert_eq!(error.as_raw(), ffi::X509_V_ERR_CERT_HAS_EXPIRED);
+}
+
+#[test]
+#[cfg(ossl110)]
+fn test_verify_store_settings() {
+    let cert = include_bytes!("../../test/cert.pem");
+    let cert = X509::from_pem(cert).unwrap();
+    let ca = include_bytes!("../../test/root-ca.pem");
+    let ca = X509::from_pem(ca).unwrap();
+    let chain = Stack::new().unwrap();
+
+    let verify = |configure: &dyn Fn(&mut X509StoreBuilder)| {
+        let mut store_bldr = X509StoreBuilder::new().unwrap();
+        store_bldr.add_cert(ca.clone()).unwrap();
+        store_bldr
+            .set_flags(X509VerifyFlags::NO_CHECK_TIME)
+            .unwrap();
+        configure(&mut store_bldr);
+        let store = store_bldr.build();
+
+        let mut context = X509StoreContext::new().unwrap();
+        context
+            .init(&store, &cert, &chain, |c| {
+                c.verify_cert()?;
+                Ok(c.error())
+            })
+            .unwrap()
+            .as_raw()
+    };
+
+    assert_eq!(verify(&|_| {}), ffi::X509_V_OK);
+    assert_eq!(
+        verify(&|b| b.set_purpose(X509PurposeId::SSL_SERVER).unwrap()),
+        ffi::X509_V_OK
+    );
+    assert_eq!(
+        verify(&|b| b.set_purpose(X509PurposeId::TIMESTAMP_SIGN).unwrap()),
+        ffi::X509_V_ERR_INVALID_PURPOSE
+    );
+    assert_eq!(
+        verify(&|b| b.set_trust(X509TrustId::SSL_SERVER).unwrap()),
+        ffi::X509_V_OK
+    );
+    // cert.pem is issued directly by the trust anchor, so no intermediates are needed.
+    assert_eq!(verify(&|b| b.set_depth(0).unwrap()), ffi::X509_V_OK);
+}
+
+#[test]
+#[cfg(ossl110)]
+fn test_verify_partial_chain() {
+    let cert = include_bytes!("../../test/cert.pem");
+    let cert = X509::from_pem(cert).unwrap();
+    let chain = Stack::new().unwrap();
+
+    let verify = |flags: X509VerifyFlags| {
+        // Trust the leaf itself rather than the root which issued it.
+        let mut store_bldr = X509StoreBuilder::new().unwrap();
+        store_bldr.add_cert(cert.clone()).unwrap();
+        store_bldr
+            .set_flags(X509VerifyFlags::NO_CHECK_TIME | flags)
+            .unwrap();
+        let store = store_bldr.build();
+
+        let mut context = X509StoreContext::new().unwrap();
+        context
+            .init(&store, &cert, &chain, |c| {
+                c.verify_cert()?;
+                Ok(c.error())
+            })
+            .unwrap()
+    };
+
+    assert_eq!(
+        verify(X509VerifyFlags::empty()),
+        X509VerifyResult::UNABLE_TO_GET_ISSUER_CERT_LOCALLY
+    );
+    assert_eq!(verify(X509VerifyFlags::PARTIAL_CHAIN), X509VerifyResult::OK);
+}
+
+#[test]
+#[cfg(ossl110)]
+fn test_verify_policies() {
+    let ca = X509::from_pem(include_bytes!("../../test/root-ca.pem")).unwrap();
+    let ca_key = include_bytes!("../../test/root-ca.key");
+    let ca_key = PKey::private_key_from_pem(ca_key).unwrap();
+    let pkey = pkey();
+
+    let mut name = X509Name::builder().unwrap();
+    name.append_entry_by_nid(Nid::COMMONNAME, "foobar.com")
+        .unwrap();
+    let name = name.build();
+
+    let mut builder = X509::builder().unwrap();
+    builder.set_version(2).unwrap();
+    builder.set_subject_name(&name).unwrap();
+    builder.set_issuer_name(ca.subject_name()).unwrap();
+    builder.set_pubkey(&pkey).unwrap();
+    builder
+        .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+        .unwrap();
+    builder
+        .set_not_after(&Asn1Time::days_from_now(365).unwrap())
+        .unwrap();
+    // certificatePolicies with the single policy 2.999.5.
+    let policies = Asn1Object::from_str("2.5.29.32").unwrap();
+    builder
+        .append_raw_extension(
+            &policies,
+            false,
+            &[0x30, 0x07, 0x30, 0x05, 0x06, 0x03, 0x88, 0x37, 0x05],
+        )
+        .unwrap();
+    builder.sign(&ca_key, MessageDigest::sha256()).unwrap();
+    let cert = builder.build();
+
+    let mut store_bldr = X509StoreBuilder::new().unwrap();
+    store_bldr.add_cert(ca).unwrap();
+    store_bldr
+        .set_flags(X509VerifyFlags::NO_CHECK_TIME)
+        .unwrap();
+    let store = store_bldr.build();
+    let chain = Stack::new().unwrap();
+
+    let verify = |policy: &str| {
+        let mut context = X509StoreContext::new().unwrap();
+        context
+            .init(&store, &cert, &chain, |c| {
+                let param = c.verify_param_mut();
+                param
+                    .add_policy(Asn1Object::from_str(policy).unwrap())
+                    .unwrap();
+                param.set_flags(X509VerifyFlags::EXPLICIT_POLICY).unwrap();
+                c.verify_cert()?;
+
+                let policies = c.policy_tree().and_then(|tree| tree.user_policies());
+                let policies = policies.map(|policies| {
+                    policies
+                        .iter()
+                        .map(|node| node.policy().to_string())
+                        .collect::<Vec<_>>()
+                });
+                Ok((c.error(), c.explicit_policy(), policies))
+            })
+            .unwrap()
+    };
+
+    assert_eq!(
+        verify("2.999.5"),
+        (
+            X509VerifyResult::OK,
+            true,
+            Some(vec!["2.999.5".to_string()])
+        )
+    );
+    let (error, _, _) = verify("2.999.6");
+    assert_eq!(error, X509VerifyResult::NO_EXPLICIT_POLICY);
+}
+
+#[test]
+#[cfg(any(ossl102, libressl261))]
+fn test_verify_callback() {
+    let cert = include_bytes!("../../test/cert.pem");
+    let cert = X509::from_pem(cert).unwrap();
+    let ca = include_bytes!("../../test/root-ca.pem");
+    let ca = X509::from_pem(ca).unwrap();
+    let chain = Stack::new().unwrap();
+
+    let mut store_bldr = X509StoreBuilder::new().unwrap();
+    store_bldr.add_cert(ca).unwrap();
+    let store = store_bldr.build();
+
+    let expired = Arc::new(Mutex::new(vec![]));
+    let expired2 = expired.clone();
+
+    let mut context = X509StoreContext::new().unwrap();
+    let (error, verified_chain) = context
+        .init(&store, &cert, &chain, |c| {
+            // Both certificates expire in 2026.
+            c.verify_param_mut().set_time(1_800_000_000);
+            c.set_verify_callback(move |preverify_ok, c| {
+                if preverify_ok || c.error().as_raw() != ffi::X509_V_ERR_CERT_HAS_EXPIRED {
+                    return preverify_ok;
+                }
+                // Panicking here would unwind through OpenSSL, so the results are checked later.
+                let has_common_name = match c.current_cert() {
+                    Some(cert) => cert.subject_name().entries_by_nid(Nid::COMMONNAME).count() == 1,
+                    None => false,
+                };
+                expired2
+                    .lock()
+                    .unwrap()
+                    .push((c.error_depth(), has_common_name));
+                true
+            });
+            assert!(c.verify_cert()?);
+            Ok((c.error(), c.verified_chain().unwrap()))
+        })
+        .unwrap();
+
+    let mut expired = expired.lock().unwrap().clone();
+    expired.sort_unstable();
+    assert_eq!(expired, [(0, true), (1, false)]);
+    assert_eq!(error.as_raw(), ffi::X509_V_ERR_CERT_HAS_EXPIRED);
+    assert_eq!(verified_chain.len(), 2);
+}
+
+#[test]
+#[cfg(any(ossl102, libressl261))]
+fn test_allow_verify_errors() {
+    let cert = include_bytes!("../../test/cert.pem");
+    let cert = X509::from_pem(cert).unwrap();
+    let ca = include_bytes!("../../test/root-ca.pem");
+    let ca = X509::from_pem(ca).unwrap();
+    let chain = Stack::new().unwrap();
+
+    let mut store_bldr = X509StoreBuilder::new().unwrap();
+    store_bldr.add_cert(ca).unwrap();
+    let store = store_bldr.build();
+    let empty_store = X509StoreBuilder::new().unwrap().build();
+
+    let verify = |store: &X509StoreRef, allowed: &[X509VerifyResult]| {
+        let mut context = X509StoreContext::new().unwrap();
+        context
+            .init(store, &cert, &chain, |c| {
+                // Both certificates expire in 2026.
+                c.verify_param_mut().set_time(1_800_000_000);
+                c.set_verify_callback(allow_verify_errors(allowed));
+                Ok((c.verify_cert()?, c.error()))
+            })
+            .unwrap()
+    };
+
+    assert_eq!(
+        verify(&store, &[X509VerifyResult::CERT_HAS_EXPIRED]),
+        (true, X509VerifyResult::CERT_HAS_EXPIRED)
+    );
+    assert_eq!(
+        verify(&store, &[X509VerifyResult::CERT_NOT_YET_VALID]),
+        (false, X509VerifyResult::CERT_HAS_EXPIRED)
+    );
+    assert_eq!(
+        verify(&store, &[]),
+        (false, X509VerifyResult::CERT_HAS_EXPIRED)
+    );
+    assert_eq!(
+        verify(&empty_store, &[X509VerifyResult::CERT_HAS_EXPIRED]),
+        (false, X509VerifyResult::UNABLE_TO_GET_ISSUER_CERT_LOCALLY)
+    );
+    assert!(
+        verify(
+            &empty_store,
+            &[
+                X509VerifyResult::CERT_HAS_EXPIRED,
+                X509VerifyResult::UNABLE_TO_GET_ISSUER_CERT_LOCALLY,
+                X509VerifyResult::UNABLE_TO_VERIFY_LEAF_SIGNATURE,
+            ]
+        )
+        .0
+    );
+}
+
+#[test]
+fn test_check_purpose_and_trust() {
+    let cert = X509::from_pem(include_bytes!("../../test/cert.pem")).unwrap();
+    let ca = X509::from_pem(include_bytes!("../../test/root-ca.pem")).unwrap();
+
+    assert!(cert.check_purpose(X509PurposeId::SSL_SERVER, false));
+    assert!(!cert.check_purpose(X509PurposeId::SSL_SERVER, true));
+    assert!(ca.check_purpose(X509PurposeId::SSL_SERVER, true));
+
+    assert_eq!(
+        cert.check_trust(X509TrustId::SSL_SERVER),
+        X509TrustResult::UNTRUSTED
+    );
+    assert_eq!(
+        ca.check_trust(X509TrustId::SSL_SERVER),
+        X509TrustResult::TRUSTED
+    );
+
+    let pkey = pkey();
+    let mut name = X509Name::builder().unwrap();
+    name.append_entry_by_nid(Nid::COMMONNAME, "foobar.com")
+        .unwrap();
+    let name = name.build();
+
+    let mut builder = X509::builder().unwrap();
+    builder.set_version(2).unwrap();
+    builder.set_subject_name(&name).unwrap();
+    builder.set_issuer_name(&name).unwrap();
+    builder.set_pubkey(&pkey).unwrap();
+    builder
+        .append_extension(ExtendedKeyUsage::new().client_auth().build().unwrap())
+        .unwrap();
+    let client_auth = Asn1Object::from_str("1.3.6.1.5.5.7.3.2").unwrap();
+    let server_auth = Asn1Object::from_str("1.3.6.1.5.5.7.3.1").unwrap();
+    builder.add_trust_object(&client_auth).unwrap();
+    builder.add_reject_object(&server_auth).unwrap();
+    builder.sign(&pkey, MessageDigest::sha256()).unwrap();
+    let cert = builder.build();
+
+    assert!(cert.check_purpose(X509PurposeId::SSL_CLIENT, false));
+    assert!(!cert.check_purpose(X509PurposeId::SSL_SERVER, false));
+    assert_eq!(
+        cert.check_trust(X509TrustId::SSL_CLIENT),
+        X509TrustResult::TRUSTED
+    );
+    assert_eq!(
+        cert.check_trust(X509TrustId::SSL_SERVER),
+        X509TrustResult::REJECTED
+    );
+}
+
 #[cfg(ossl110)]
 #[test]
 fn x509_ref_version() {
@@ -469,6 +1793,14 @@ fn test_save_subject_der() {
     assert!(!der.is_empty());
 }
 
+#[test]
+#[cfg(ossl102)]
+fn test_verify_flags_suiteb() {
+    assert_eq!(X509VerifyFlags::SUITEB_128_LOS_ONLY.bits(), 0x10000);
+    assert_eq!(X509VerifyFlags::SUITEB_192_LOS.bits(), 0x20000);
+    assert_eq!(X509VerifyFlags::SUITEB_128_LOS.bits(), 0x30000);
+}
+
 #[test]
 fn test_load_subject_der() {
     // The subject from ../../test/cert.pem
@@ -508,6 +1840,29 @@ fn test_convert_to_text() {
     }
 }
 
+#[test]
+fn test_convert_to_text_with_flags() {
+    let cert = include_bytes!("../../test/cert.pem");
+    let cert = X509::from_pem(cert).unwrap();
+
+    let text = cert
+        .to_text_with_flags(X509NameFlags::empty(), X509PrintFlags::empty())
+        .unwrap();
+    assert_eq!(text, cert.to_text().unwrap());
+
+    let text = cert
+        .to_text_with_flags(
+            X509NameFlags::RFC2253,
+            X509PrintFlags::NO_HEADER | X509PrintFlags::NO_PUBKEY | X509PrintFlags::NO_SIGDUMP,
+        )
+        .unwrap();
+    let text = String::from_utf8(text).unwrap();
+    assert!(!text.contains("Certificate:\n"));
+    assert!(!text.contains("Subject Public Key Info:"));
+    assert!(text.contains("Serial Number:"));
+    assert!(text.contains("Subject: CN=foobar.com,O=Internet Widgits Pty Ltd,ST=Some-State,C=AU\n"));
+}
+
 #[test]
 fn test_convert_req_to_text() {
     let csr = include_bytes!("../../test/csr.pem");
@@ -543,3 +1898,91 @@ fn test_name_cmp() {
     assert_eq!(Ordering::Equal, subject.try_cmp(subject).unwrap());
     assert_eq!(Ordering::Greater, subject.try_cmp(issuer).unwrap());
 }
+
+#[test]
+fn test_name_builder_entries() {
+    let custom = Asn1Object::from_str("2.999.1").unwrap();
+
+    let mut builder = X509Name::builder().unwrap();
+    builder
+        .append_entry_by_obj_with_type(&custom, "custom", Asn1Type::PRINTABLESTRING)
+        .unwrap();
+    let entry = X509NameEntry::new(
+        &Asn1Object::from_str("O").unwrap(),
+        "bar",
+        Asn1Type::UTF8STRING,
+    )
+    .unwrap();
+    builder.append_multi_valued_entry(&entry).unwrap();
+    let entry = X509NameEntry::new(
+        &Asn1Object::from_str("CN").unwrap(),
+        "foo",
+        Asn1Type::UTF8STRING,
+    )
+    .unwrap();
+    builder.insert_entry(0, &entry).unwrap();
+    let name = builder.build();
+
+    let entries = name.entries().collect::<Vec<_>>();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0].object().nid(), Nid::COMMONNAME);
+    assert_eq!(entries[1].object().to_string(), "2.999.1");
+    assert_eq!(entries[1].data().as_slice(), b"custom");
+    assert_eq!(entries[2].object().nid(), Nid::ORGANIZATIONNAME);
+
+    // The custom entry is a PrintableString sharing an RDN with O.
+    assert_eq!(
+        hex::encode(name.to_der().unwrap()),
+        "302b310c300a06035504030c03666f6f311b300a060355040a0c03626172300d06038837011306637573746f6d"
+    );
+
+    let cert = X509::from_pem(include_bytes!("../../test/cert.pem")).unwrap();
+    let mut builder = X509Name::builder().unwrap();
+    for entry in cert.subject_name().entries() {
+        builder.append_entry(entry).unwrap();
+    }
+    assert_eq!(
+        builder.build().to_der().unwrap(),
+        cert.subject_name().to_der().unwrap()
+    );
+}
+
+#[test]
+fn test_name_to_string() {
+    let cert = X509::from_pem(include_bytes!("../../test/cert.pem")).unwrap();
+    let subject = cert.subject_name();
+    assert_eq!(
+        subject.to_string_rfc2253().unwrap(),
+        "CN=foobar.com,O=Internet Widgits Pty Ltd,ST=Some-State,C=AU"
+    );
+    assert_eq!(
+        subject.to_string_oneline().unwrap(),
+        "C = AU, ST = Some-State, O = Internet Widgits Pty Ltd, CN = foobar.com"
+    );
+    assert_eq!(
+        subject
+            .to_string_with_flags(
+                X509NameFlags::SEP_SPLUS_SPC | X509NameFlags::FN_OID | X509NameFlags::DN_REV
+            )
+            .unwrap(),
+        "2.5.4.3=foobar.com; 2.5.4.10=Internet Widgits Pty Ltd; 2.5.4.8=Some-State; 2.5.4.6=AU"
+    );
+
+    let mut builder = X509Name::builder().unwrap();
+    builder.append_entry_by_nid(Nid::COMMONNAME, "a,b").unwrap();
+    let entry = X509NameEntry::new(
+        &Asn1Object::from_str("UID").unwrap(),
+        "c+d",
+        Asn1Type::UTF8STRING,
+    )
+    .unwrap();
+    builder.append_multi_valued_entry(&entry).unwrap();
+    builder
+        .append_entry_by_nid(Nid::ORGANIZATIONNAME, "Ünïcødé")
+        .unwrap();
+    let name = builder.build();
+    assert_eq!(
+        name.to_string_rfc2253().unwrap(),
+        "O=\\C3\\9Cn\\C3\\AFc\\C3\\B8d\\C3\\A9,UID=c\\+d+CN=a\\,b"
+    );
+}
@@ -1,10 +1,14 @@
 use bitflags::bitflags;
-use foreign_types::ForeignTypeRef;
-use libc::{c_uint, c_ulong};
+use foreign_types::{ForeignType, ForeignTypeRef};
+use libc::{c_int, c_uint, c_ulong, time_t};
+#[cfg(ossl102)]
+use std::ffi::CStr;
 use std::net::IpAddr;
 
-use crate::cvt;
+use crate::asn1::Asn1Object;
 use crate::error::ErrorStack;
+use crate::x509::{X509PurposeId, X509TrustId};
+use crate::{cvt, cvt_p};
 use openssl_macros::corresponds;
 
 bitflags! {
@@ -34,24 +38,46 @@ bitflags! {
         const IGNORE_CRITICAL = ffi::X509_V_FLAG_IGNORE_CRITICAL;
         const X509_STRICT = ffi::X509_V_FLAG_X509_STRICT;
         const ALLOW_PROXY_CERTS = ffi::X509_V_FLAG_ALLOW_PROXY_CERTS;
+        /// Enables certificate policy checking.
+        ///
+        /// The policy tree computed during verification is available from
+        /// `X509StoreContextRef::policy_tree`.
         const POLICY_CHECK = ffi::X509_V_FLAG_POLICY_CHECK;
+        /// Requires the chain to be valid for at least one acceptable policy, as if the
+        /// `requireExplicitPolicy` constraint was set. Implies `POLICY_CHECK`.
         const EXPLICIT_POLICY = ffi::X509_V_FLAG_EXPLICIT_POLICY;
+        /// Prevents the special `anyPolicy` identifier from matching other policies, as if the
+        /// `inhibitAnyPolicy` constraint was set. Implies `POLICY_CHECK`.
         const INHIBIT_ANY = ffi::X509_V_FLAG_INHIBIT_ANY;
+        /// Disables policy mapping, as if the `inhibitPolicyMapping` constraint was set. Implies
+        /// `POLICY_CHECK`.
         const INHIBIT_MAP = ffi::X509_V_FLAG_INHIBIT_MAP;
         const NOTIFY_POLICY = ffi::X509_V_FLAG_NOTIFY_POLICY;
         const EXTENDED_CRL_SUPPORT = ffi::X509_V_FLAG_EXTENDED_CRL_SUPPORT;
         const USE_DELTAS = ffi::X509_V_FLAG_USE_DELTAS;
         const CHECK_SS_SIGNATURE = ffi::X509_V_FLAG_CHECK_SS_SIGNATURE;
+        /// Looks for issuers in the trusted store before the untrusted chain.
+        ///
+        /// Requires OpenSSL 1.0.2 or newer. This is always enabled in OpenSSL 1.1.0 and newer.
         #[cfg(ossl102)]
         const TRUSTED_FIRST = ffi::X509_V_FLAG_TRUSTED_FIRST;
         #[cfg(ossl102)]
         const SUITEB_128_LOS_ONLY = ffi::X509_V_FLAG_SUITEB_128_LOS_ONLY;
         #[cfg(ossl102)]
-        const SUITEB_192_LOS = ffi::X509_V_FLAG_SUITEB_128_LOS;
+        const SUITEB_192_LOS = ffi::X509_V_FLAG_SUITEB_192_LOS;
         #[cfg(ossl102)]
-        const SUITEB_128_LOS = ffi::X509_V_FLAG_SUITEB_192_LOS;
+        const SUITEB_128_LOS = ffi::X509_V_FLAG_SUITEB_128_LOS;
+        /// Accepts a chain ending in any trusted certificate, rather than requiring a self-signed
+        /// root.
+        ///
+        /// This makes it possible to pin an intermediate or leaf certificate as a trust anchor.
+        ///
+        /// Requires OpenSSL 1.0.2 or newer.
         #[cfg(ossl102)]
         const PARTIAL_CHAIN = ffi::X509_V_FLAG_PARTIAL_CHAIN;
+        /// Stops looking for an alternative chain when the first chain built fails to verify.
+        ///
+        /// Requires OpenSSL 1.1.0 or newer.
         #[cfg(ossl110)]
         const NO_ALT_CHAINS = ffi::X509_V_FLAG_NO_ALT_CHAINS;
         #[cfg(ossl110)]
@@ -69,6 +95,17 @@ foreign_type_and_impl_send_sync! {
     pub struct X509VerifyParamRef;
 }
 
+impl X509VerifyParam {
+    /// Creates a new set of verification parameters with nothing set.
+    #[corresponds(X509_VERIFY_PARAM_new)]
+    pub fn new() -> Result<X509VerifyParam, ErrorStack> {
+        unsafe {
+            ffi::init();
+            cvt_p(ffi::X509_VERIFY_PARAM_new()).map(X509VerifyParam)
+        }
+    }
+}
+
 impl X509VerifyParamRef {
     /// Set the host flags.
     #[corresponds(X509_VERIFY_PARAM_set_hostflags)]
@@ -107,15 +144,172 @@ impl X509VerifyParamRef {
     #[corresponds(X509_VERIFY_PARAM_set1_host)]
     pub fn set_host(&mut self, host: &str) -> Result<(), ErrorStack> {
         unsafe {
+            // A length of 0 makes OpenSSL call strlen on the name.
+            let raw_host = if host.is_empty() { "\0" } else { host };
             cvt(ffi::X509_VERIFY_PARAM_set1_host(
                 self.as_ptr(),
-                host.as_ptr() as *const _,
+                raw_host.as_ptr() as *const _,
+                host.len(),
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Add a DNS hostname to the set of acceptable names.
+    ///
+    /// The peer's certificate is accepted if it matches any of the names set with
+    /// [`set_host`] or added with this method.
+    ///
+    /// Requires OpenSSL 1.0.2 or newer.
+    ///
+    /// [`set_host`]: #method.set_host
+    #[corresponds(X509_VERIFY_PARAM_add1_host)]
+    #[cfg(ossl102)]
+    pub fn add_host(&mut self, host: &str) -> Result<(), ErrorStack> {
+        unsafe {
+            // A length of 0 makes OpenSSL call strlen on the name.
+            let raw_host = if host.is_empty() { "\0" } else { host };
+            cvt(ffi::X509_VERIFY_PARAM_add1_host(
+                self.as_ptr(),
+                raw_host.as_ptr() as *const _,
                 host.len(),
             ))
             .map(|_| ())
         }
     }
 
+    /// Returns the name from the peer's certificate which matched an expected DNS hostname.
+    ///
+    /// This is only set once hostname verification has succeeded. If the match was made with a
+    /// wildcard, the wildcard pattern is returned rather than the hostname.
+    ///
+    /// Requires OpenSSL 1.0.2 or newer.
+    #[corresponds(X509_VERIFY_PARAM_get0_peername)]
+    #[cfg(ossl102)]
+    pub fn peername(&self) -> Option<&str> {
+        unsafe {
+            let ptr = ffi::X509_VERIFY_PARAM_get0_peername(self.as_ptr());
+            if ptr.is_null() {
+                None
+            } else {
+                CStr::from_ptr(ptr).to_str().ok()
+            }
+        }
+    }
+
+    /// Set the expected email address.
+    ///
+    /// Requires OpenSSL 1.0.2 or newer.
+    #[corresponds(X509_VERIFY_PARAM_set1_email)]
+    #[cfg(ossl102)]
+    pub fn set_email(&mut self, email: &str) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::X509_VERIFY_PARAM_set1_email(
+                self.as_ptr(),
+                email.as_ptr() as *const _,
+                email.len(),
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Set the time at which certificates are checked for validity, as seconds since the Unix
+    /// epoch.
+    ///
+    /// The current time is used by default. This also sets `X509VerifyFlags::USE_CHECK_TIME`.
+    #[corresponds(X509_VERIFY_PARAM_set_time)]
+    pub fn set_time(&mut self, time: time_t) {
+        unsafe { ffi::X509_VERIFY_PARAM_set_time(self.as_ptr(), time) }
+    }
+
+    /// Returns the time at which certificates are checked for validity.
+    ///
+    /// This is only meaningful if `X509VerifyFlags::USE_CHECK_TIME` is set.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(X509_VERIFY_PARAM_get_time)]
+    #[cfg(ossl110)]
+    pub fn time(&self) -> time_t {
+        unsafe { ffi::X509_VERIFY_PARAM_get_time(self.as_ptr()) }
+    }
+
+    /// Set the maximum depth of the certificate chain.
+    ///
+    /// The depth counts the intermediate certificates between the leaf and the trust anchor, so a
+    /// depth of 0 only permits self-signed or directly trusted certificates.
+    #[corresponds(X509_VERIFY_PARAM_set_depth)]
+    pub fn set_depth(&mut self, depth: c_int) {
+        unsafe { ffi::X509_VERIFY_PARAM_set_depth(self.as_ptr(), depth) }
+    }
+
+    /// Returns the maximum depth of the certificate chain, or -1 if it is not set.
+    #[corresponds(X509_VERIFY_PARAM_get_depth)]
+    pub fn depth(&self) -> c_int {
+        unsafe { ffi::X509_VERIFY_PARAM_get_depth(self.as_ptr()) }
+    }
+
+    /// Set the authentication security level.
+    ///
+    /// Certificates whose keys or signatures are weaker than the level permits are rejected. See
+    /// [`SslContextBuilder::set_security_level`] for the meaning of each level.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    ///
+    /// [`SslContextBuilder::set_security_level`]: ../../ssl/struct.SslContextBuilder.html#method.set_security_level
+    #[corresponds(X509_VERIFY_PARAM_set_auth_level)]
+    #[cfg(ossl110)]
+    pub fn set_auth_level(&mut self, lvl: c_int) {
+        unsafe { ffi::X509_VERIFY_PARAM_set_auth_level(self.as_ptr(), lvl) }
+    }
+
+    /// Returns the authentication security level, or -1 if it is not set.
+    ///
+    /// Requires OpenSSL 1.1.0 or newer.
+    #[corresponds(X509_VERIFY_PARAM_get_auth_level)]
+    #[cfg(ossl110)]
+    pub fn auth_level(&self) -> c_int {
+        unsafe { ffi::X509_VERIFY_PARAM_get_auth_level(self.as_ptr()) }
+    }
+
+    /// Set the purpose the certificate must be valid for.
+    #[corresponds(X509_VERIFY_PARAM_set_purpose)]
+    pub fn set_purpose(&mut self, purpose: X509PurposeId) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::X509_VERIFY_PARAM_set_purpose(
+                self.as_ptr(),
+                purpose.as_raw(),
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Set the trust setting the trust anchor is checked against.
+    #[corresponds(X509_VERIFY_PARAM_set_trust)]
+    pub fn set_trust(&mut self, trust: X509TrustId) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::X509_VERIFY_PARAM_set_trust(
+                self.as_ptr(),
+                trust.as_raw(),
+            ))
+            .map(|_| ())
+        }
+    }
+
+    /// Add a certificate policy to the set of acceptable policies.
+    ///
+    /// Policies are only checked if `X509VerifyFlags::POLICY_CHECK` is set.
+    #[corresponds(X509_VERIFY_PARAM_add0_policy)]
+    pub fn add_policy(&mut self, policy: Asn1Object) -> Result<(), ErrorStack> {
+        unsafe {
+            cvt(ffi::X509_VERIFY_PARAM_add0_policy(
+                self.as_ptr(),
+                policy.as_ptr(),
+            ))?;
+            std::mem::forget(policy);
+            Ok(())
+        }
+    }
+
     /// Set the expected IPv4 or IPv6 address.
     #[corresponds(X509_VERIFY_PARAM_set1_ip)]
     pub fn set_ip(&mut self, ip: IpAddr) -> Result<(), ErrorStack> {
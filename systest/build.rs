@@ -109,6 +109,10 @@ fn main() {
     cfg.skip_struct(|s| {
         s == "ProbeResult" || s == "X509_OBJECT_data" // inline union
     });
+    cfg.skip_const(|s| {
+        // not defined by OpenSSL
+        s.starts_with("DIST_POINT_NAME_")
+    });
     cfg.skip_fn(move |s| {
         s == "CRYPTO_memcmp" ||                 // uses volatile
 
@@ -127,6 +131,7 @@ fn main() {
     cfg.skip_field_type(|s, field| {
         (s == "EVP_PKEY" && field == "pkey") ||      // union
             (s == "GENERAL_NAME" && field == "d") || // union
+            (s == "DIST_POINT_NAME" && field == "name") || // union
             (s == "X509_OBJECT" && field == "data") // union
     });
     cfg.skip_signededness(|s| {
@@ -137,6 +142,9 @@ fn main() {
             || s == "PasswordCallback"
             || s.ends_with("_cb_func")
             || s.ends_with("_cb_ex")
+            // enum signedness is implementation-defined
+            || s == "sct_validation_status_t"
+            || s == "sct_source_t"
     });
     cfg.field_name(|_s, field| {
         if field == "type_" {